@@ -724,6 +724,43 @@ def sweep_table(points: list[dict]) -> str:
     )
 
 
+def sweep_points(sweep: dict | list | None) -> list[dict]:
+    """The latency points of a sweep.
+
+    `threadstone sweep --out` writes a document with its own provenance; older
+    sweeps were a bare array of points, and still render.
+    """
+    if isinstance(sweep, list):
+        return sweep
+    if isinstance(sweep, dict) and sweep.get("latency"):
+        return sweep["latency"]["points"]
+    return []
+
+
+def same_machine(report: dict, sweep: dict | list | None) -> bool:
+    """Whether a sweep document says it was measured on the result's machine.
+
+    A bare array says nothing either way and is taken on trust.
+    """
+    if not isinstance(sweep, dict):
+        return True
+    return sweep["system"].get("cpu_model") == report["system"].get("cpu_model")
+
+
+def levels_note(sweep: dict | list | None) -> str:
+    """One sentence naming the cache levels read off the sweep, if it has any."""
+    if not isinstance(sweep, dict) or not sweep.get("latency"):
+        return ""
+    bounded = [
+        f"{human_bytes(level['capacity_bytes'])} at {level['latency_ns']:.1f}&nbsp;ns"
+        for level in sweep["latency"]["levels"]
+        if level.get("capacity_bytes")
+    ]
+    if not bounded:
+        return ""
+    return " The plateaus read off the curve end at " + ", ".join(bounded) + "."
+
+
 def cache_markers(system: dict) -> list[tuple[int, str]]:
     """Cache capacities to annotate on the sweep, from the measured machine."""
     markers = []
@@ -760,7 +797,7 @@ def signature_note(report: dict) -> str:
     )
 
 
-def build(report: dict, sweep: list[dict] | None) -> str:
+def build(report: dict, sweep: dict | list | None) -> str:
     """Render the whole page."""
     system = report["system"]
     workloads = load_workloads(report)
@@ -797,8 +834,11 @@ def build(report: dict, sweep: list[dict] | None) -> str:
         for w in workloads
     )
 
+    points = sweep_points(sweep)
+    # Mark the caches of the machine the sweep says it ran on.
+    sweep_system = sweep["system"] if isinstance(sweep, dict) else system
     sweep_section = ""
-    if sweep:
+    if points:
         sweep_section = f"""
 <section id="memory">
   <h2>The memory hierarchy, measured</h2>
@@ -806,13 +846,13 @@ def build(report: dict, sweep: list[dict] | None) -> str:
   to 256&nbsp;MiB. Nothing can hide the miss, so each plateau is a cache level
   and each step is a boundary. This is the shape of the machine's memory system.</p>
   <figure>
-    {sweep_chart(sweep, cache_markers(system))}
+    {sweep_chart(points, cache_markers(sweep_system))}
     <figcaption>Latency per access against working-set size, log scale.
     Vertical lines mark this machine's reported cache capacities. The rise from
-    {sweep[0]['latency_ns']:.1f}&nbsp;ns to {sweep[-1]['latency_ns']:.0f}&nbsp;ns
-    is the whole cost of missing every level of cache.</figcaption>
+    {points[0]['latency_ns']:.1f}&nbsp;ns to {points[-1]['latency_ns']:.0f}&nbsp;ns
+    is the whole cost of missing every level of cache.{levels_note(sweep)}</figcaption>
   </figure>
-  {sweep_table(sweep)}
+  {sweep_table(points)}
 </section>"""
 
     # A complete document, doctype first: without it browsers fall back to
@@ -1029,6 +1069,14 @@ def main() -> int:
 
     report = json.loads(args.result.read_text())
     sweep = json.loads(args.sweep.read_text()) if args.sweep.exists() else None
+    if not same_machine(report, sweep):
+        print(
+            f"error: {args.sweep.name} was measured on "
+            f"{sweep['system'].get('cpu_model')}, not "
+            f"{report['system'].get('cpu_model')}",
+            file=sys.stderr,
+        )
+        return 1
 
     args.out.parent.mkdir(parents=True, exist_ok=True)
     args.out.write_text(build(report, sweep))
@@ -192,6 +192,34 @@ class ReportLoading(unittest.TestCase):
         self.assertIn("memory hierarchy", html)
         self.assertEqual(html.count("<svg"), 3)
 
+    def test_page_renders_a_sweep_document_with_its_levels(self):
+        points = [
+            {"bytes": 4096 << i, "latency_ns": ns}
+            for i, ns in enumerate([1.0, 1.0, 1.0, 4.0, 4.0, 4.0])
+        ]
+        sweep = {
+            "schema_version": 2,
+            "system": dict(self.report["system"], l1d_bytes=16384),
+            "latency": {
+                "points": points,
+                "levels": [
+                    {"from_bytes": 4096, "capacity_bytes": 16384, "latency_ns": 1.0},
+                    {"from_bytes": 32768, "latency_ns": 4.0},
+                ],
+            },
+        }
+        self.assertEqual(build.sweep_points(sweep), points)
+        self.assertTrue(build.same_machine(self.report, sweep))
+        html = build.build(self.report, sweep)
+        self.assertIn("L1d 16 KiB", html)
+        self.assertIn("end at 16 KiB at 1.0&nbsp;ns.", html)
+
+    def test_a_sweep_from_another_machine_is_refused(self):
+        sweep = {"system": {"cpu_model": "Some Other CPU"}, "bandwidth": {}}
+        self.assertFalse(build.same_machine(self.report, sweep))
+        self.assertEqual(build.sweep_points(sweep), [])
+        self.assertTrue(build.same_machine(self.report, []))
+
 
 class StabilityRendering(unittest.TestCase):
     def test_every_stability_verdict_has_a_colour(self):
@@ -0,0 +1,280 @@
+//! Interleaved A/B measurement of two `threadstone` binaries.
+//!
+//! Comparing `before.json` with `after.json` compares two stretches of time as
+//! much as two builds: the machine that ran the second had warmed up, or
+//! cooled down, or started indexing a disk in between. `threadstone ab` runs
+//! both binaries at once, each as a `threadstone child` serving pass requests
+//! (see [`crate::child`]), and alternates one-round passes between them. Each
+//! baseline round has a candidate round next to it, and
+//! [`compare::paired`] judges the pairs, so whatever happened to the machine
+//! happened to both sides of every pair and cancels.
+//!
+//! # Order
+//!
+//! Strict ABAB would still favour whichever side runs second in each pair on a
+//! machine that is steadily speeding up or slowing down. [`Order::Alternate`]
+//! therefore swaps the order every round (AB BA AB BA…), which cancels a
+//! linear trend exactly. [`Order::Random`] picks each pair's order by coin
+//! flip, for drift that is not linear; its seed is printed so the schedule can
+//! be replayed.
+//!
+//! Every round calibrates afresh in its own child, so each binary runs at the
+//! iteration count it would choose for itself, and the rates compare as they
+//! would between two reports.
+//!
+//! Workloads run at their default parameters only. A baseline built before
+//! workloads had parameters would ignore any in the request and measure the
+//! default size, and the comparison would look like a change of code.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use clap::ValueEnum;
+
+use threadstone_core::pages::PagePolicy;
+use threadstone_core::stats::{geometric_mean, Paired};
+use threadstone_core::{Kernel, Scaling, SuiteObserver};
+use threadstone_workloads::rng::Rng;
+
+use crate::child::{Child, ChildError, PassRequest};
+use crate::compare::{self, Comparison, Delta, Verdict};
+use crate::observer::Progress;
+use crate::Failure;
+
+/// Fewest rounds a paired test can call significant at 5%: with five pairs,
+/// even a clean sweep leaves p above it.
+pub const MIN_ROUNDS: u32 = 6;
+
+/// How the two sides take turns within each pair of rounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Order {
+    /// AB, BA, AB, BA…: the order swaps every round.
+    Alternate,
+    /// Each pair's order by coin flip.
+    Random,
+}
+
+/// What to measure, and how often.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Threads for the multi-thread pass.
+    pub threads: usize,
+    /// Paired rounds per pass.
+    pub rounds: u32,
+    /// Target duration of each round.
+    pub window: Duration,
+    /// Turn-taking within each pair.
+    pub order: Order,
+    /// Seed for [`Order::Random`].
+    pub seed: u64,
+    /// Whether to measure the single-thread pass.
+    pub single_thread: bool,
+    /// Whether to measure the multi-thread pass.
+    pub multi_thread: bool,
+}
+
+/// One of the two binaries, and the child it is currently serving through.
+struct Side<'a> {
+    role: &'static str,
+    program: &'a Path,
+    child: Option<Child>,
+}
+
+impl<'a> Side<'a> {
+    fn new(role: &'static str, program: &'a Path) -> Side<'a> {
+        Side {
+            role,
+            program,
+            child: None,
+        }
+    }
+
+    /// Start the child if there is none, and describe it.
+    fn start(&mut self, progress: &Progress) -> Result<&mut Child, ChildError> {
+        if self.child.is_none() {
+            let mut command = Command::new(self.program);
+            command.arg("child");
+            self.child = Some(Child::spawn(command, progress)?);
+        }
+        Ok(self.child.as_mut().expect("started above"))
+    }
+
+    fn label(&mut self, progress: &Progress) -> Result<String, Failure> {
+        let version = match self.start(progress) {
+            Ok(child) => child.tool_version.clone(),
+            Err(e) => return Err(self.failure(e)),
+        };
+        Ok(format!("{} ({version})", self.program.display()))
+    }
+
+    /// Measure one round. A child that is lost is replaced at the next.
+    fn round(
+        &mut self,
+        request: &PassRequest,
+        id: &str,
+        progress: &Progress,
+    ) -> Result<f64, ChildError> {
+        let result = self.start(progress)?.pass(request, id, progress);
+        if matches!(result, Err(ChildError::Lost(_))) {
+            self.child = None;
+        }
+        result.map(|pass| pass.value)
+    }
+
+    fn failure(&self, error: ChildError) -> Failure {
+        match error {
+            ChildError::Interrupted => "interrupted".into(),
+            ChildError::Failed(e) | ChildError::Lost(e) => {
+                format!("{} {}: {e}", self.role, self.program.display()).into()
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        if let Some(child) = self.child.take() {
+            child.finish();
+        }
+    }
+}
+
+/// Measure `kernels` under both binaries and compare them pair by pair.
+/// Returns the comparison with the two sides' labels.
+pub fn run(
+    baseline: &Path,
+    candidate: &Path,
+    kernels: &[Box<dyn Kernel>],
+    settings: &Settings,
+    progress: &Progress,
+) -> Result<(Comparison, String, String), Failure> {
+    let mut sides = [
+        Side::new("baseline", baseline),
+        Side::new("candidate", candidate),
+    ];
+    let labels = (sides[0].label(progress)?, sides[1].label(progress)?);
+    let result = measure(&mut sides, kernels, settings, progress);
+    for side in &mut sides {
+        side.finish();
+    }
+    Ok((result?, labels.0, labels.1))
+}
+
+fn measure(
+    sides: &mut [Side; 2],
+    kernels: &[Box<dyn Kernel>],
+    settings: &Settings,
+    progress: &Progress,
+) -> Result<Comparison, Failure> {
+    let mut rng = Rng::new(settings.seed);
+    let mut comparison = Comparison {
+        single: Vec::new(),
+        multi: Vec::new(),
+        single_score: None,
+        multi_score: None,
+        machine_mismatch: None,
+        params_mismatch: None,
+    };
+    let mut ratios = (Vec::new(), Vec::new());
+
+    for kernel in kernels {
+        let info = kernel.info();
+        let passes = [
+            (settings.single_thread, 1),
+            (
+                settings.multi_thread && info.scaling == Scaling::Scales && settings.threads > 1,
+                settings.threads,
+            ),
+        ];
+        for (multi, (enabled, threads)) in passes.into_iter().enumerate() {
+            if !enabled {
+                continue;
+            }
+            let request = PassRequest {
+                workload: info.id.to_string(),
+                threads,
+                samples: 1,
+                warmup: 0,
+                window: settings.window,
+                affinity: None,
+                counters: false,
+                precision: None,
+                pages: PagePolicy::Default,
+                params: BTreeMap::new(),
+            };
+            let mut values = [Vec::new(), Vec::new()];
+            let mut failed = None;
+            'rounds: for round in 0..settings.rounds {
+                let swap = match settings.order {
+                    Order::Alternate => round % 2 == 1,
+                    Order::Random => rng.below(2) == 1,
+                };
+                let turns = if swap { [1, 0] } else { [0, 1] };
+                for side in turns {
+                    let role = sides[side].role;
+                    progress.round(info.name, threads, round + 1, settings.rounds, role);
+                    match sides[side].round(&request, info.id, progress) {
+                        Ok(value) => values[side].push(value),
+                        Err(ChildError::Interrupted) => return Err("interrupted".into()),
+                        Err(e) => {
+                            failed = Some(sides[side].failure(e));
+                            break 'rounds;
+                        }
+                    }
+                }
+            }
+
+            let [base, cand] = &values;
+            let delta = match failed {
+                Some(error) => {
+                    progress.workload_failed(info.id, &error.to_string());
+                    Delta {
+                        id: info.id.to_string(),
+                        baseline: None,
+                        candidate: None,
+                        percent: None,
+                        verdict: Verdict::Missing,
+                    }
+                }
+                None => {
+                    let higher = info.unit.higher_is_better();
+                    if let Some(p) = Paired::new(base, cand) {
+                        let ratio = 1.0 + p.change;
+                        let ratios = if multi == 1 {
+                            &mut ratios.1
+                        } else {
+                            &mut ratios.0
+                        };
+                        ratios.push(if higher { ratio } else { 1.0 / ratio });
+                    }
+                    let delta = compare::paired(info.id, base, cand, higher);
+                    progress.paired(info.name, threads, &delta, info.unit);
+                    delta
+                }
+            };
+            if multi == 1 {
+                comparison.multi.push(delta);
+            } else {
+                comparison.single.push(delta);
+            }
+        }
+    }
+
+    // With the same workloads on both sides the reference values cancel, so
+    // the change in a score is the geometric mean of the changes in its parts.
+    let score = |r: &[f64]| geometric_mean(r).map(|g| (g - 1.0) * 100.0);
+    comparison.single_score = score(&ratios.0);
+    comparison.multi_score = score(&ratios.1);
+    Ok(comparison)
+}
+
+/// The run's schedule in words, for the header.
+pub fn describe(settings: &Settings) -> String {
+    match settings.order {
+        Order::Alternate => format!("{} rounds, alternating AB BA", settings.rounds),
+        Order::Random => format!(
+            "{} rounds in random order, seed {}",
+            settings.rounds, settings.seed
+        ),
+    }
+}
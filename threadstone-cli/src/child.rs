@@ -0,0 +1,706 @@
+//! Measuring passes in a child process, for `threadstone run --isolate`.
+//!
+//! A workload that segfaults, or allocates until the kernel's out-of-memory
+//! killer picks the benchmark, would otherwise take every result the run had
+//! gathered with it. Under `--isolate` the suite still runs in the parent —
+//! load sampling, the budget, scoring and signing stay there — but each pass
+//! is measured by `threadstone child`, this same binary re-executed, and a
+//! child that dies becomes that workload's error.
+//!
+//! # Protocol
+//!
+//! JSON lines in both directions. The child opens with a `hello` naming its
+//! [`PROTOCOL`] version. The parent then writes one [`PassRequest`] per pass
+//! to its stdin; for each, the child streams `calibrating`, `calibrated` and
+//! `sample` messages as the runner reports them, and ends with exactly one of
+//! `pass`, `failed` or `interrupted`. The child exits at end of input, so the
+//! parent decides how many passes one process measures: all of a workload's,
+//! or one.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::process::{ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use threadstone_core::affinity::Affinity;
+use threadstone_core::pages::PagePolicy;
+use threadstone_core::report::{Pass, PrecisionSettings};
+use threadstone_core::runner::{self, Measurement, Observer, Precision, RunConfig, RunError};
+use threadstone_core::suite::{Executor, Isolation};
+use threadstone_core::{Kernel, KernelInfo};
+
+use crate::Failure;
+
+/// Version of the message format. A parent refuses a child that speaks
+/// another.
+pub const PROTOCOL: u32 = 1;
+
+/// How often a parent waiting on its child checks for Ctrl-C.
+const POLL: Duration = Duration::from_millis(50);
+
+/// One pass to measure: a workload and the runner settings for it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PassRequest {
+    /// Workload identifier.
+    pub workload: String,
+    /// Threads for the pass.
+    pub threads: usize,
+    /// Measured rounds; the minimum under `precision`.
+    pub samples: u32,
+    /// Discarded rounds before measuring.
+    pub warmup: u32,
+    /// Target duration of each round.
+    pub window: Duration,
+    /// Where to bind worker threads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub affinity: Option<Affinity>,
+    /// Whether to count hardware events.
+    #[serde(default)]
+    pub counters: bool,
+    /// Adaptive-sampling target.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<PrecisionSettings>,
+    /// How large buffers are backed.
+    #[serde(default)]
+    pub pages: PagePolicy,
+    /// Workload parameters set away from their defaults, by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, u64>,
+}
+
+impl PassRequest {
+    /// The request for one pass of `kernel`, at its parameters, under `cfg`.
+    pub fn new(kernel: &dyn Kernel, cfg: &RunConfig) -> PassRequest {
+        PassRequest {
+            workload: kernel.info().id.to_string(),
+            threads: cfg.threads,
+            samples: cfg.samples,
+            warmup: cfg.warmup,
+            window: cfg.window,
+            affinity: cfg.affinity.clone(),
+            counters: cfg.counters,
+            precision: cfg.precision.map(PrecisionSettings::from),
+            pages: cfg.pages,
+            params: kernel
+                .params()
+                .into_iter()
+                .filter(|p| !p.is_default())
+                .map(|p| (p.name.to_string(), p.value))
+                .collect(),
+        }
+    }
+
+    /// The workload this asks for, at its parameters.
+    fn kernel(&self) -> Result<Box<dyn Kernel>, String> {
+        let mut kernel = threadstone_workloads::find(&self.workload)
+            .ok_or_else(|| format!("unknown workload '{}'", self.workload))?;
+        for (name, &value) in &self.params {
+            kernel.configure(name, value).map_err(|e| e.to_string())?;
+        }
+        Ok(kernel)
+    }
+
+    fn run_config(&self) -> RunConfig {
+        RunConfig {
+            threads: self.threads,
+            samples: self.samples,
+            warmup: self.warmup,
+            window: self.window,
+            affinity: self.affinity.clone(),
+            counters: self.counters,
+            precision: self.precision.as_ref().map(|p| Precision {
+                target_ci: p.target_ci,
+                max_samples: p.max_samples,
+                max_time: Duration::from_millis(p.max_time_ms),
+            }),
+            pages: self.pages,
+            memory_node: None,
+        }
+    }
+}
+
+/// Everything a child writes to its stdout.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Message {
+    /// First line of every child's output.
+    Hello {
+        /// The child's [`PROTOCOL`].
+        protocol: u32,
+        /// The child's `threadstone` version.
+        tool_version: String,
+    },
+    /// See [`Observer::calibrating`].
+    Calibrating {
+        /// Threads being calibrated.
+        threads: usize,
+    },
+    /// See [`Observer::calibrated`].
+    Calibrated {
+        /// Iterations per thread per round.
+        iters: u64,
+        /// Duration of a round at that count, in milliseconds.
+        window_ms: f64,
+    },
+    /// See [`Observer::sample`].
+    Sample {
+        /// Round just completed, from 1.
+        index: u32,
+        /// Rounds planned.
+        total: u32,
+        /// The round's rate.
+        rate: f64,
+    },
+    /// The pass completed.
+    Pass(Box<Pass>),
+    /// The pass could not be measured; the child is still serving.
+    Failed {
+        /// Why, as the runner put it.
+        error: String,
+    },
+    /// A signal stopped the pass; the child is still serving.
+    Interrupted,
+}
+
+// ---- Child side -------------------------------------------------------------
+
+/// Serve pass requests on stdin until it closes: `threadstone child`.
+pub fn serve(tool_version: &str) -> Result<(), Failure> {
+    crate::interrupt::install(false);
+    let relay = Relay(Mutex::new(std::io::stdout()));
+    relay.send(&Message::Hello {
+        protocol: PROTOCOL,
+        tool_version: tool_version.to_string(),
+    });
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: PassRequest =
+            serde_json::from_str(&line).map_err(|e| format!("malformed pass request: {e}"))?;
+        let reply = match request.kernel() {
+            Err(error) => Message::Failed { error },
+            Ok(kernel) => match runner::run(kernel.as_ref(), request.run_config(), &relay) {
+                Ok(m) => Message::Pass(Box::new(Pass::from_measurement(&m))),
+                Err(RunError::Interrupted { .. }) => Message::Interrupted,
+                Err(e) => Message::Failed {
+                    error: e.to_string(),
+                },
+            },
+        };
+        relay.send(&reply);
+    }
+    Ok(())
+}
+
+/// Forwards runner events to the parent as they happen.
+struct Relay(Mutex<std::io::Stdout>);
+
+impl Relay {
+    fn send(&self, message: &Message) {
+        let mut out = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        // A parent that has gone away cannot be told anything; the next read
+        // of stdin ends the loop.
+        if let Ok(line) = serde_json::to_string(message) {
+            let _ = writeln!(out, "{line}");
+            let _ = out.flush();
+        }
+    }
+}
+
+impl Observer for Relay {
+    fn calibrating(&self, _id: &str, threads: usize) {
+        self.send(&Message::Calibrating { threads });
+    }
+
+    fn calibrated(&self, _id: &str, iters: u64, window_ms: f64) {
+        self.send(&Message::Calibrated { iters, window_ms });
+    }
+
+    fn sample(&self, _id: &str, index: u32, total: u32, rate: f64) {
+        self.send(&Message::Sample { index, total, rate });
+    }
+
+    fn interrupted(&self) -> bool {
+        crate::interrupt::requested()
+    }
+}
+
+// ---- Parent side ------------------------------------------------------------
+
+/// Why a child produced no pass.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChildError {
+    /// The child reported an error and is still serving.
+    Failed(String),
+    /// The pass was interrupted, in the child or in the parent.
+    Interrupted,
+    /// The child is gone: it crashed, was killed, or broke the protocol.
+    Lost(String),
+}
+
+/// A running child process.
+pub struct Child {
+    process: std::process::Child,
+    stdin: Option<ChildStdin>,
+    replies: Receiver<String>,
+    stderr: Option<JoinHandle<String>>,
+    /// The child's version, from its `hello`.
+    pub tool_version: String,
+}
+
+impl Child {
+    /// Start `command` and wait for its `hello`.
+    pub fn spawn(mut command: Command, obs: &dyn Observer) -> Result<Child, ChildError> {
+        let mut process = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| ChildError::Lost(format!("cannot start a child process: {e}")))?;
+        let (tx, replies) = mpsc::channel();
+        let stdout = process.stdout.take().expect("stdout is piped");
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        let mut stderr = process.stderr.take().expect("stderr is piped");
+        let stderr = std::thread::spawn(move || {
+            let mut text = String::new();
+            let _ = stderr.read_to_string(&mut text);
+            text
+        });
+        let mut child = Child {
+            stdin: process.stdin.take(),
+            process,
+            replies,
+            stderr: Some(stderr),
+            tool_version: String::new(),
+        };
+        match child.next(obs)? {
+            Message::Hello {
+                protocol,
+                tool_version,
+            } if protocol == PROTOCOL => {
+                child.tool_version = tool_version;
+                Ok(child)
+            }
+            Message::Hello { protocol, .. } => {
+                Err(child.abandon(format!("child speaks protocol {protocol}, not {PROTOCOL}")))
+            }
+            other => Err(child.abandon(format!("child opened with {other:?}, not hello"))),
+        }
+    }
+
+    /// Measure one pass, relaying its progress to `obs` under `id`.
+    pub fn pass(
+        &mut self,
+        request: &PassRequest,
+        id: &str,
+        obs: &dyn Observer,
+    ) -> Result<Pass, ChildError> {
+        let line = serde_json::to_string(request).expect("a pass request serialises");
+        let sent = self
+            .stdin
+            .as_mut()
+            .map(|stdin| writeln!(stdin, "{line}").and_then(|_| stdin.flush()));
+        if !matches!(sent, Some(Ok(()))) {
+            return Err(self.lost());
+        }
+        loop {
+            match self.next(obs)? {
+                Message::Calibrating { threads } => obs.calibrating(id, threads),
+                Message::Calibrated { iters, window_ms } => obs.calibrated(id, iters, window_ms),
+                Message::Sample { index, total, rate } => obs.sample(id, index, total, rate),
+                Message::Pass(pass) => return Ok(*pass),
+                Message::Failed { error } => return Err(ChildError::Failed(error)),
+                Message::Interrupted => return Err(ChildError::Interrupted),
+                Message::Hello { .. } => {
+                    return Err(self.abandon("child said hello twice".to_string()))
+                }
+            }
+        }
+    }
+
+    /// Close the child's input and wait for it to exit.
+    pub fn finish(mut self) {
+        self.stdin = None;
+        let _ = self.process.wait();
+    }
+
+    /// The next message, waiting as long as the child is alive and the run
+    /// has not been interrupted.
+    fn next(&mut self, obs: &dyn Observer) -> Result<Message, ChildError> {
+        loop {
+            match self.replies.recv_timeout(POLL) {
+                Ok(line) => {
+                    return serde_json::from_str(&line).map_err(|e| {
+                        self.abandon(format!("child sent an unreadable message ({e}): {line}"))
+                    })
+                }
+                Err(RecvTimeoutError::Timeout) if obs.interrupted() => {
+                    // The pass in flight is lost either way; a child that did
+                    // not see the signal must not keep measuring.
+                    let _ = self.process.kill();
+                    let _ = self.process.wait();
+                    return Err(ChildError::Interrupted);
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) if obs.interrupted() => {
+                    let _ = self.process.wait();
+                    return Err(ChildError::Interrupted);
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(self.lost()),
+            }
+        }
+    }
+
+    /// Describe how the child ended, once its output has closed.
+    fn lost(&mut self) -> ChildError {
+        self.stdin = None;
+        let status = self.process.wait();
+        let stderr = self
+            .stderr
+            .take()
+            .and_then(|h| h.join().ok())
+            .unwrap_or_default();
+        ChildError::Lost(match status {
+            Ok(status) => describe(status, &stderr),
+            Err(e) => format!("lost track of the child process: {e}"),
+        })
+    }
+
+    /// Kill a child that broke the protocol.
+    fn abandon(&mut self, why: String) -> ChildError {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        ChildError::Lost(why)
+    }
+}
+
+/// How a child that stopped answering ended, with the last thing it said on
+/// stderr — for a panic, its message.
+fn describe(status: ExitStatus, stderr: &str) -> String {
+    let how = match exit_signal(status) {
+        Some(9) => {
+            "child process was killed by SIGKILL, most often the out-of-memory killer".to_string()
+        }
+        Some(signal) => format!("child process was killed by {}", signal_name(signal)),
+        None => match status.code() {
+            Some(0) => "child process exited before finishing the pass".to_string(),
+            Some(code) => format!("child process exited with status {code}"),
+            None => format!("child process ended: {status}"),
+        },
+    };
+    let last = stderr
+        .lines()
+        .map(str::trim)
+        .rev()
+        .find(|l| !l.is_empty() && !l.starts_with("note:"));
+    match last {
+        Some(line) => format!("{how}: {line}"),
+        None => how,
+    }
+}
+
+#[cfg(unix)]
+fn exit_signal(status: ExitStatus) -> Option<i32> {
+    std::os::unix::process::ExitStatusExt::signal(&status)
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: ExitStatus) -> Option<i32> {
+    None
+}
+
+fn signal_name(signal: i32) -> String {
+    match signal {
+        4 => "SIGILL".to_string(),
+        6 => "SIGABRT".to_string(),
+        7 => "SIGBUS".to_string(),
+        8 => "SIGFPE".to_string(),
+        11 => "SIGSEGV".to_string(),
+        15 => "SIGTERM".to_string(),
+        n => format!("signal {n}"),
+    }
+}
+
+/// Measures every pass in a child process: `threadstone run --isolate`.
+pub struct Isolated {
+    program: PathBuf,
+    args: Vec<String>,
+    mode: Isolation,
+    /// The child serving the current workload and its sub-results, under
+    /// [`Isolation::Workload`], with the workload's id.
+    current: RefCell<Option<(String, Child)>>,
+}
+
+impl Isolated {
+    /// Re-execute this binary as `threadstone child`.
+    pub fn new(mode: Isolation) -> Result<Isolated, Failure> {
+        let program = std::env::current_exe()
+            .map_err(|e| format!("--isolate: cannot find this executable to re-run: {e}"))?;
+        Ok(Isolated::with_command(
+            program,
+            vec!["child".to_string()],
+            mode,
+        ))
+    }
+
+    fn with_command(program: PathBuf, args: Vec<String>, mode: Isolation) -> Isolated {
+        Isolated {
+            program,
+            args,
+            mode,
+            current: RefCell::new(None),
+        }
+    }
+
+    fn spawn(&self, obs: &dyn Observer) -> Result<Child, ChildError> {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        Child::spawn(command, obs)
+    }
+}
+
+impl Executor for Isolated {
+    fn pass(
+        &self,
+        workload: &str,
+        kernel: &dyn Kernel,
+        run_cfg: RunConfig,
+        obs: &dyn Observer,
+    ) -> Result<Pass, RunError> {
+        let info = kernel.info();
+        let mut current = self.current.borrow_mut();
+        let mut child = match current.take() {
+            Some((served, child)) if served == workload => child,
+            other => {
+                // A workload's child serves only that workload and its
+                // sub-results, which the child looks up by their own ids.
+                if let Some((_, child)) = other {
+                    child.finish();
+                }
+                self.spawn(obs).map_err(|e| lost(&info, e))?
+            }
+        };
+        let result = child.pass(&PassRequest::new(kernel, &run_cfg), info.id, obs);
+        match (&result, self.mode) {
+            // Already reaped.
+            (Err(ChildError::Lost(_)), _) => {}
+            (Ok(_) | Err(ChildError::Failed(_)), Isolation::Workload) => {
+                *current = Some((workload.to_string(), child));
+            }
+            _ => child.finish(),
+        }
+        if let Ok(pass) = &result {
+            obs.finished(info.id, &measurement(&info, pass));
+        }
+        result.map_err(|e| lost(&info, e))
+    }
+
+    fn isolation(&self) -> Option<Isolation> {
+        Some(self.mode)
+    }
+}
+
+impl Drop for Isolated {
+    fn drop(&mut self) {
+        if let Some((_, child)) = self.current.get_mut().take() {
+            child.finish();
+        }
+    }
+}
+
+fn lost(info: &KernelInfo, error: ChildError) -> RunError {
+    match error {
+        ChildError::Interrupted => RunError::Interrupted { id: info.id },
+        ChildError::Failed(reason) | ChildError::Lost(reason) => {
+            // The child's own errors already name the workload.
+            let prefix = format!("workload '{}': ", info.id);
+            let reason = reason.strip_prefix(&prefix).unwrap_or(&reason).to_string();
+            RunError::Isolated {
+                id: info.id,
+                reason,
+            }
+        }
+    }
+}
+
+/// Enough of a [`Measurement`] for the progress line. The per-round clock
+/// readings stay in the child; the pass carries their summary.
+fn measurement(info: &KernelInfo, pass: &Pass) -> Measurement {
+    Measurement {
+        id: info.id,
+        unit: info.unit,
+        threads: pass.threads,
+        iters_per_thread: pass.iters_per_thread,
+        samples: pass.samples.clone(),
+        summary: pass.stats.clone(),
+        window_ms: pass.window_ms,
+        window_too_short: pass.window_too_short,
+        pinned: pass.pinned_cpus.clone(),
+        cpus: pass.cpus.clone(),
+        balance: pass.balance.clone(),
+        core_hz: Vec::new(),
+        counters: pass.counters.clone(),
+        counters_error: pass.counters_error.clone(),
+        precision: pass.precision.clone(),
+        huge_pages: pass.huge_pages,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+    use threadstone_core::runner::SilentObserver;
+
+    const HELLO: &str = r#"{"hello":{"protocol":1,"tool_version":"test"}}"#;
+
+    /// An executor whose "child" is a shell script.
+    fn scripted(script: &str, mode: Isolation) -> Isolated {
+        let args = vec!["-c".to_string(), script.to_string()];
+        Isolated::with_command(PathBuf::from("/bin/sh"), args, mode)
+    }
+
+    fn one_pass(executor: &Isolated) -> Result<Pass, RunError> {
+        let kernel = threadstone_workloads::by_id("sha256").unwrap();
+        let cfg = RunConfig {
+            threads: 1,
+            samples: 1,
+            warmup: 0,
+            window: Duration::from_millis(15),
+            affinity: None,
+            counters: false,
+            precision: None,
+            pages: PagePolicy::Default,
+            memory_node: None,
+        };
+        let id = kernel.info().id;
+        executor.pass(id, kernel.as_ref(), cfg, &SilentObserver)
+    }
+
+    #[test]
+    fn a_child_killed_mid_pass_becomes_the_workloads_error() {
+        let executor = scripted(
+            &format!("echo '{HELLO}'; read request; kill -9 $$"),
+            Isolation::Workload,
+        );
+        match one_pass(&executor) {
+            Err(RunError::Isolated { id, reason }) => {
+                assert_eq!(id, "sha256");
+                assert!(reason.contains("SIGKILL"), "got: {reason}");
+                assert!(reason.contains("out-of-memory"), "got: {reason}");
+            }
+            other => panic!("expected a lost child, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn a_workloads_sub_results_share_its_child() {
+        let dir = tempfile::tempdir().unwrap();
+        let spawns = dir.path().join("spawns");
+        // Refuses every pass, which keeps the child, and counts its starts.
+        let executor = scripted(
+            &format!(
+                r#"echo >> '{}'; echo '{HELLO}'; while read request; do echo '{{"failed":{{"error":"refused"}}}}'; done"#,
+                spawns.display()
+            ),
+            Isolation::Workload,
+        );
+        let stream = threadstone_workloads::by_id("stream").unwrap();
+        let copy = &stream.sub_results()[0];
+        let sha256 = threadstone_workloads::by_id("sha256").unwrap();
+        let cfg = RunConfig {
+            threads: 1,
+            ..RunConfig::default()
+        };
+        let started = || std::fs::read_to_string(&spawns).unwrap().lines().count();
+
+        for kernel in [stream.as_ref(), copy.as_ref(), stream.as_ref()] {
+            assert!(executor
+                .pass("stream", kernel, cfg.clone(), &SilentObserver)
+                .is_err());
+        }
+        assert_eq!(started(), 1, "stream and its sub-results share one child");
+        executor
+            .pass("sha256", sha256.as_ref(), cfg, &SilentObserver)
+            .unwrap_err();
+        assert_eq!(started(), 2, "the next workload gets a child of its own");
+    }
+
+    #[test]
+    fn a_request_carries_the_parameters_the_child_needs() {
+        let mut kernel = threadstone_workloads::by_id("stream").unwrap();
+        let cfg = RunConfig::default();
+        let request = PassRequest::new(kernel.as_ref(), &cfg);
+        assert!(request.params.is_empty());
+        assert!(!serde_json::to_string(&request).unwrap().contains("params"));
+
+        kernel.configure("mib", 128).unwrap();
+        let line = serde_json::to_string(&PassRequest::new(kernel.as_ref(), &cfg)).unwrap();
+        let request: PassRequest = serde_json::from_str(&line).unwrap();
+        let served = request.kernel().unwrap();
+        assert_eq!(served.params(), kernel.params());
+
+        // A sub-result is served at its workload's parameters too.
+        let sub = &kernel.sub_results()[0];
+        let served = PassRequest::new(sub.as_ref(), &cfg).kernel().unwrap();
+        assert_eq!(served.info().id, "stream-copy");
+        assert_eq!(served.params()[0].value, 128);
+
+        let mut bad = request;
+        bad.params.insert("n".into(), 1);
+        let err = bad.kernel().err().unwrap();
+        assert_eq!(err, "workload 'stream' has no parameter 'n'; it has mib");
+    }
+
+    #[test]
+    fn a_child_speaking_another_protocol_is_refused() {
+        let executor = scripted(
+            r#"echo '{"hello":{"protocol":99,"tool_version":"future"}}'; sleep 5"#,
+            Isolation::Pass,
+        );
+        let err = one_pass(&executor).unwrap_err().to_string();
+        assert!(err.contains("protocol 99"), "got: {err}");
+    }
+
+    #[test]
+    fn a_reported_failure_is_not_named_twice() {
+        let executor = scripted(
+            &format!(
+                r#"echo '{HELLO}'; read request; echo '{{"failed":{{"error":"workload '\''sha256'\'': every sample was non-finite"}}}}'"#
+            ),
+            Isolation::Pass,
+        );
+        let err = one_pass(&executor).unwrap_err().to_string();
+        assert_eq!(err, "workload 'sha256': every sample was non-finite");
+    }
+
+    #[test]
+    fn a_panicking_child_is_described_by_its_message() {
+        let stderr = "thread 'main' panicked at src/lib.rs:1:1:\nout of bounds\n\
+                      note: run with `RUST_BACKTRACE=1` to see a backtrace\n";
+        assert_eq!(
+            describe(ExitStatus::from_raw(101 << 8), stderr),
+            "child process exited with status 101: out of bounds"
+        );
+        assert_eq!(
+            describe(ExitStatus::from_raw(11), ""),
+            "child process was killed by SIGSEGV"
+        );
+    }
+}
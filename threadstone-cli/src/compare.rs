@@ -7,8 +7,13 @@
 //!
 //! So every delta here is reported against the combined uncertainty of both
 //! measurements, and only differences that clear it are called significant.
+//!
+//! Two files measured minutes apart also differ in everything that happened to
+//! the machine in between. `threadstone ab` measures both sides in alternating
+//! rounds instead, and [`paired`] judges the rounds pair by pair.
 
 use threadstone_core::report::{Pass, Report};
+use threadstone_core::stats::{Paired, Summary};
 
 /// How a measurement changed between two reports.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -65,6 +70,9 @@ pub struct Comparison {
     /// Set when the two reports came from different machines, in which case the
     /// comparison measures the machines rather than the change.
     pub machine_mismatch: Option<String>,
+    /// Set when a workload ran with different parameters in the two
+    /// reports, in which case its delta measures the change of problem size.
+    pub params_mismatch: Option<String>,
 }
 
 /// Relative uncertainty below which a measurement is treated as exact.
@@ -74,6 +82,9 @@ pub struct Comparison {
 /// this suite claims to be able to resolve.
 const MIN_RELATIVE_UNCERTAINTY: f64 = 0.005;
 
+/// p-value below which a paired difference is treated as real.
+const PAIRED_ALPHA: f64 = 0.05;
+
 /// Compare `candidate` against `baseline`.
 pub fn compare(baseline: &Report, candidate: &Report) -> Comparison {
     let machine_mismatch = describe_mismatch(baseline, candidate);
@@ -87,9 +98,33 @@ pub fn compare(baseline: &Report, candidate: &Report) -> Comparison {
         single_score: percent_change(baseline.score.single_core, candidate.score.single_core),
         multi_score: percent_change(baseline.score.multi_core, candidate.score.multi_core),
         machine_mismatch,
+        params_mismatch: describe_params_mismatch(baseline, candidate),
     }
 }
 
+/// The first workload whose parameters differ between the reports. A report
+/// written before workloads had parameters ran at the defaults.
+fn describe_params_mismatch(a: &Report, b: &Report) -> Option<String> {
+    let label = |t: Vec<String>| {
+        if t.is_empty() {
+            "defaults".to_string()
+        } else {
+            t.join(", ")
+        }
+    };
+    a.workloads.iter().find_map(|w| {
+        let other = b.workloads.iter().find(|o| o.id == w.id)?;
+        (w.tuned() != other.tuned()).then(|| {
+            format!(
+                "different {} parameters: {} vs {}",
+                w.id,
+                label(w.tuned()),
+                label(other.tuned())
+            )
+        })
+    })
+}
+
 fn describe_mismatch(a: &Report, b: &Report) -> Option<String> {
     let a_cpu = a.system.cpu_model.as_deref().unwrap_or("unknown");
     let b_cpu = b.system.cpu_model.as_deref().unwrap_or("unknown");
@@ -108,6 +143,27 @@ fn describe_mismatch(a: &Report, b: &Report) -> Option<String> {
             a.config.threads, b.config.threads
         ));
     }
+    if a.config.affinity != b.config.affinity {
+        let label = |r: &Report| {
+            r.config
+                .affinity
+                .as_ref()
+                .map_or_else(|| "unpinned".to_string(), |p| p.label())
+        };
+        return Some(format!(
+            "different thread placement: {} vs {}",
+            label(a),
+            label(b)
+        ));
+    }
+    if a.config.pages != b.config.pages {
+        let label = |r: &Report| r.config.pages.unwrap_or_default().label();
+        return Some(format!(
+            "different page backing: {} vs {}",
+            label(a),
+            label(b)
+        ));
+    }
     None
 }
 
@@ -174,13 +230,15 @@ fn signed_percent(baseline: f64, candidate: f64, higher_is_better: bool) -> f64
 /// Combines the two confidence intervals in quadrature, which is the standard
 /// treatment for independent uncertainties, and floors each at
 /// [`MIN_RELATIVE_UNCERTAINTY`] so a single-sample pass cannot claim infinite
-/// precision.
+/// precision. A pass from a `--repeat` run brings the interval across its
+/// invocations, which counts the variation between launches that one
+/// invocation's rounds cannot see.
 fn classify(baseline: &Pass, candidate: &Pass, percent: f64) -> Verdict {
     let relative = |p: &Pass| {
         if p.value == 0.0 || !p.value.is_finite() {
             return MIN_RELATIVE_UNCERTAINTY;
         }
-        (p.stats.ci95 / p.value).abs().max(MIN_RELATIVE_UNCERTAINTY)
+        (p.ci95() / p.value).abs().max(MIN_RELATIVE_UNCERTAINTY)
     };
     let combined = (relative(baseline).powi(2) + relative(candidate).powi(2)).sqrt() * 100.0;
 
@@ -193,6 +251,41 @@ fn classify(baseline: &Pass, candidate: &Pass, percent: f64) -> Verdict {
     }
 }
 
+/// One workload's change from rounds measured in interleaved pairs,
+/// `baseline[i]` next to `candidate[i]`.
+///
+/// The values shown are each side's median, the change is the median of the
+/// per-pair ratios, and the verdict comes from a signed-rank test on those
+/// ratios rather than from the two sides' intervals, which drift widens. A
+/// change within [`MIN_RELATIVE_UNCERTAINTY`] stays unchanged however
+/// consistent it is.
+pub fn paired(id: &str, baseline: &[f64], candidate: &[f64], higher_is_better: bool) -> Delta {
+    let median = |v: &[f64]| Summary::new(v).map(|s| s.median);
+    let test = Paired::new(baseline, candidate);
+    let (percent, verdict) = match &test {
+        Some(t) => {
+            let pct = signed_percent(1.0, 1.0 + t.change, higher_is_better);
+            let verdict =
+                if t.p_value >= PAIRED_ALPHA || pct.abs() <= MIN_RELATIVE_UNCERTAINTY * 100.0 {
+                    Verdict::Unchanged
+                } else if pct > 0.0 {
+                    Verdict::Faster
+                } else {
+                    Verdict::Slower
+                };
+            (Some(pct), verdict)
+        }
+        None => (None, Verdict::Missing),
+    };
+    Delta {
+        id: id.to_string(),
+        baseline: median(baseline),
+        candidate: median(candidate),
+        percent,
+        verdict,
+    }
+}
+
 fn percent_change(baseline: Option<f64>, candidate: Option<f64>) -> Option<f64> {
     let (b, c) = (baseline?, candidate?);
     if b == 0.0 || !b.is_finite() || !c.is_finite() {
@@ -212,6 +305,11 @@ pub fn render(comparison: &Comparison, baseline_label: &str, candidate_label: &s
             "warning: {warning}\n         this compares two machines, not two versions\n\n"
         ));
     }
+    if let Some(warning) = &comparison.params_mismatch {
+        out.push_str(&format!(
+            "warning: {warning}\n         this compares two problem sizes, not two versions\n\n"
+        ));
+    }
 
     for (title, deltas) in [
         ("single-thread", &comparison.single),
@@ -259,7 +357,6 @@ mod tests {
     use threadstone_core::kernel::Unit;
     use threadstone_core::report::{RunSettings, WorkloadReport};
     use threadstone_core::score::ScoreCard;
-    use threadstone_core::stats::Summary;
     use threadstone_core::sysinfo::SystemInfo;
 
     fn pass(value: f64, ci95: f64) -> Pass {
@@ -273,6 +370,15 @@ mod tests {
             stats,
             window_ms: 250.0,
             window_too_short: false,
+            pinned_cpus: vec![],
+            cpus: vec![],
+            balance: None,
+            clock: None,
+            counters: None,
+            counters_error: None,
+            precision: None,
+            huge_pages: None,
+            repeats: None,
         }
     }
 
@@ -292,6 +398,15 @@ mod tests {
                 samples: 7,
                 warmup: 2,
                 window_ms: 250,
+                affinity: None,
+                scaling: vec![],
+                counters: None,
+                precision: None,
+                budget: None,
+                require_idle: None,
+                isolate: None,
+                repeat: None,
+                pages: None,
             },
             workloads,
             score: ScoreCard {
@@ -301,6 +416,8 @@ mod tests {
                 single_core_components: vec![],
                 multi_core_components: vec![],
             },
+            load: vec![],
+            core_to_core: None,
             signature: None,
         }
     }
@@ -312,9 +429,13 @@ mod tests {
             summary: "test".into(),
             unit,
             reference: 1.0,
+            params: vec![],
             single_thread: single,
             multi_thread: None,
             scaling: None,
+            scaling_curve: None,
+            core_classes: vec![],
+            sub_results: vec![],
             excluded_from_multi_core: None,
             error: None,
         }
@@ -378,6 +499,31 @@ mod tests {
         assert_eq!(compare(&a, &b).single[0].verdict, Verdict::Faster);
     }
 
+    #[test]
+    fn between_run_variation_can_swallow_a_tight_runs_change() {
+        // Each invocation varies by 0.1%, but launches differ by several
+        // percent: the 2% change is inside that.
+        let repeated = |value: f64| {
+            let mut p = pass(value, 0.1);
+            p.repeats = Some(threadstone_core::report::Repeats {
+                medians: vec![value * 0.96, value, value * 1.04],
+                within_cv: 0.001,
+                between_cv: 0.04,
+                ci95: value * 0.045,
+            });
+            p
+        };
+        let a = report(
+            vec![workload("x", Unit::Gflops, Some(repeated(100.0)))],
+            None,
+        );
+        let b = report(
+            vec![workload("x", Unit::Gflops, Some(repeated(102.0)))],
+            None,
+        );
+        assert_eq!(compare(&a, &b).single[0].verdict, Verdict::Unchanged);
+    }
+
     #[test]
     fn latency_improvements_are_reported_as_positive() {
         // Lower nanoseconds is better, so the sign must flip.
@@ -450,6 +596,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_different_thread_placement_is_called_out() {
+        let a = report(vec![], None);
+        let mut b = report(vec![], None);
+        b.config.affinity = Some(threadstone_core::Affinity::Explicit(vec![0, 1, 2, 3]));
+        let warning = compare(&a, &b).machine_mismatch.unwrap();
+        assert_eq!(warning, "different thread placement: unpinned vs cpus 0-3");
+    }
+
+    #[test]
+    fn a_different_page_backing_is_called_out() {
+        use threadstone_core::pages::PagePolicy;
+
+        let a = report(vec![], None);
+        let mut b = report(vec![], None);
+        b.config.pages = Some(PagePolicy::Thp);
+        let warning = compare(&a, &b).machine_mismatch.unwrap();
+        assert_eq!(warning, "different page backing: default vs thp");
+    }
+
+    #[test]
+    fn a_workload_run_at_another_size_is_called_out() {
+        use threadstone_core::report::ParamSetting;
+
+        let sgemm = || workload("sgemm", Unit::Gflops, Some(pass(10.0, 0.1)));
+        let a = report(vec![sgemm()], None);
+        let mut b = report(vec![sgemm()], None);
+        b.workloads[0].params = vec![ParamSetting {
+            name: "n".into(),
+            value: 256,
+            default: 256,
+        }];
+        // A report from before parameters ran at the defaults too.
+        assert!(compare(&a, &b).params_mismatch.is_none());
+
+        b.workloads[0].params[0].value = 1024;
+        let comparison = compare(&a, &b);
+        assert!(comparison.machine_mismatch.is_none());
+        assert_eq!(
+            comparison.params_mismatch.as_deref(),
+            Some("different sgemm parameters: defaults vs n=1024")
+        );
+        assert!(render(&comparison, "a", "b").contains("two problem sizes"));
+    }
+
     #[test]
     fn score_change_is_a_plain_percentage() {
         let a = report(vec![], Some(1000.0));
@@ -457,6 +648,26 @@ mod tests {
         assert!((compare(&a, &b).single_score.unwrap() - 25.0).abs() < 1e-9);
     }
 
+    #[test]
+    fn paired_rounds_see_through_drift_that_swamps_the_intervals() {
+        // The machine slows 3% a round; the candidate is 2% ahead in every pair.
+        let base: Vec<f64> = (0..10).map(|i| 100.0 * 0.97f64.powi(i)).collect();
+        let cand: Vec<f64> = base.iter().map(|v| v * 1.02).collect();
+
+        let d = paired("x", &base, &cand, true);
+        assert_eq!(d.verdict, Verdict::Faster);
+        assert!((d.percent.unwrap() - 2.0).abs() < 1e-9);
+
+        // The same rounds in nanoseconds: higher is worse.
+        let d = paired("x", &base, &cand, false);
+        assert_eq!(d.verdict, Verdict::Slower);
+
+        // Consistent, but below what the suite claims to resolve.
+        let tiny: Vec<f64> = base.iter().map(|v| v * 1.002).collect();
+        assert_eq!(paired("x", &base, &tiny, true).verdict, Verdict::Unchanged);
+        assert_eq!(paired("x", &[], &[], true).verdict, Verdict::Missing);
+    }
+
     #[test]
     fn rendering_mentions_a_machine_mismatch() {
         let a = report(
@@ -0,0 +1,78 @@
+//! Ctrl-C and SIGTERM during a run.
+//!
+//! Killing a run outright throws away every workload it has already measured,
+//! which on a slow machine can be many minutes of data. So the first signal
+//! only raises a flag: the runner polls it between rounds, the pass in flight
+//! stops at its next barrier, and the suite writes and signs a report of what
+//! completed. A second signal means the user wants out now, and exits at once.
+//!
+//! A child measuring for `run --isolate` installs the same handler, quietly:
+//! Ctrl-C reaches the whole process group, and the parent already says so.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+/// Signals received since [`install`].
+static RECEIVED: AtomicU32 = AtomicU32::new(0);
+
+/// Whether the first signal prints a note to stderr.
+static ANNOUNCE: AtomicBool = AtomicBool::new(true);
+
+/// Exit status for a second signal, following the shell's 128 + SIGINT.
+const ABORTED: i32 = 130;
+
+/// Route SIGINT and SIGTERM to the flag, noting the first on stderr if
+/// `announce` is set. Elsewhere than Unix, signals keep their default
+/// behaviour.
+pub fn install(announce: bool) {
+    ANNOUNCE.store(announce, Ordering::Relaxed);
+    #[cfg(unix)]
+    imp::install();
+}
+
+/// Whether a signal has asked the run to stop.
+pub fn requested() -> bool {
+    RECEIVED.load(Ordering::Relaxed) > 0
+}
+
+#[cfg(unix)]
+mod imp {
+    use std::sync::atomic::Ordering;
+
+    use super::{ABORTED, ANNOUNCE, RECEIVED};
+
+    const SIGINT: i32 = 2;
+    const SIGTERM: i32 = 15;
+
+    extern "C" {
+        fn signal(signum: i32, handler: usize) -> usize;
+        fn write(fd: i32, buf: *const u8, count: usize) -> isize;
+        fn _exit(status: i32) -> !;
+    }
+
+    /// Only async-signal-safe work here: an atomic, `write`, and `_exit`.
+    extern "C" fn on_signal(_signum: i32) {
+        if RECEIVED.fetch_add(1, Ordering::Relaxed) == 0 {
+            if !ANNOUNCE.load(Ordering::Relaxed) {
+                return;
+            }
+            let note = b"\nthreadstone: interrupted; finishing the current round \
+                         and writing a partial report (signal again to abort)\n";
+            // SAFETY: `note` is a live buffer of the length passed, and fd 2
+            // is stderr. A failed write is harmless.
+            unsafe { write(2, note.as_ptr(), note.len()) };
+        } else {
+            // SAFETY: `_exit` ends the process without running anything that
+            // could be unsafe in a handler.
+            unsafe { _exit(ABORTED) }
+        }
+    }
+
+    pub fn install() {
+        let handler = on_signal as extern "C" fn(i32) as usize;
+        for signum in [SIGINT, SIGTERM] {
+            // SAFETY: `handler` is an `extern "C"` function with the signature
+            // `signal` expects, and it lives for the whole program.
+            unsafe { signal(signum, handler) };
+        }
+    }
+}
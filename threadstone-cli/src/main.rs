@@ -4,7 +4,11 @@
 
 #![warn(missing_docs)]
 
+mod ab;
+mod child;
 mod compare;
+mod interrupt;
+mod numa;
 mod observer;
 mod render;
 mod signing;
@@ -13,16 +17,29 @@ mod verify;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use clap::{Parser, Subcommand, ValueEnum};
 
-use threadstone_core::report::Report;
-use threadstone_core::{suite, SuiteConfig};
+use threadstone_core::affinity::{self, Affinity};
+use threadstone_core::coherence::CoreToCore;
+use threadstone_core::load::{self, RequireIdle};
+use threadstone_core::pages::{self, PagePolicy};
+use threadstone_core::repeat;
+use threadstone_core::report::{Report, Signature, SCHEMA_VERSION};
+use threadstone_core::runner::{defaults, Precision};
+use threadstone_core::suite::{Isolation, ThreadCounts};
+use threadstone_core::sweep::{
+    BandwidthSweep, LatencySweep, LoadedSweep, MlpSweep, SweepReport, TlbProbe, TlbSweep,
+};
+use threadstone_core::{suite, SuiteConfig, SuiteObserver};
 
 /// Boxed error, so every failure path can use `?` without a dependency.
 type Failure = Box<dyn std::error::Error>;
 
+/// Minimum measurement time per core pair, in milliseconds.
+const C2C_MIN_MS: u64 = 20;
+
 /// Version reported by `--version` and stamped into results.
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -42,21 +59,21 @@ enum Command {
     /// Describe the available workloads and what each one measures.
     List,
 
-    /// Check a result file's structure and signature.
+    /// Check a result or sweep file's structure and signature.
     Verify {
-        /// Result file to check.
+        /// Result or sweep file to check.
         file: PathBuf,
         /// Fail if the file carries no signature at all.
         #[arg(long)]
         require_signature: bool,
     },
 
-    /// Sign an existing result file with an Ed25519 key.
+    /// Sign an existing result or sweep file with an Ed25519 key.
     ///
     /// Signing at run time covers the common case; this covers signing a result
     /// after the fact, or re-signing one with a different key.
     Sign {
-        /// Result file to sign.
+        /// Result or sweep file to sign.
         file: PathBuf,
         /// PKCS#8 Ed25519 private key, as written by `keygen`.
         #[arg(short, long, value_name = "PATH")]
@@ -74,9 +91,13 @@ enum Command {
         candidate: PathBuf,
     },
 
-    /// Re-render a saved result file.
+    /// Measure two threadstone binaries in alternating rounds and compare
+    /// them pair by pair, so drift on the machine cancels.
+    Ab(AbArgs),
+
+    /// Re-render a saved result or sweep file.
     Report {
-        /// Result file to render.
+        /// Result or sweep file to render.
         file: PathBuf,
         /// Output format.
         #[arg(long, value_enum, default_value_t = Format::Table)]
@@ -88,13 +109,117 @@ enum Command {
         /// Minimum measurement time per size, in milliseconds.
         #[arg(long, default_value_t = 120)]
         min_ms: u64,
+        /// Measure read and read-modify-write bandwidth instead of latency, at
+        /// one thread and at all threads.
+        #[arg(long)]
+        bandwidth: bool,
+        /// Chase one node per page instead, at 4 KiB and at 2 MiB pages, to
+        /// find the TLBs' reach and the cost of a page walk (Linux only).
+        #[arg(long, conflicts_with = "bandwidth")]
+        tlb: bool,
+        /// Largest ratio between neighbouring working-set sizes, or page counts
+        /// for `--tlb`: 1.25 cuts each octave into quarters, 1.5 into halves, 2
+        /// keeps powers of two. At least 1.01.
+        #[arg(long, default_value_t = 2.0, conflicts_with = "bandwidth")]
+        step: f64,
+        /// Timed windows per size, sharing its `--min-ms`.
+        #[arg(long, default_value_t = 5, conflicts_with = "bandwidth")]
+        samples: usize,
+        /// Largest working set to measure, in MiB. Defaults to 256, or for
+        /// `--bandwidth` to four times the last-level cache if that is more.
+        /// For `--tlb`, the most address space a probe spans.
+        #[arg(long, value_name = "MIB")]
+        max_mib: Option<u64>,
+        /// Write the sweep as JSON to this path instead of a table.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Sign the sweep with this PKCS#8 Ed25519 private key.
+        #[arg(long, value_name = "PATH", requires = "out")]
+        sign_key: Option<PathBuf>,
+    },
+
+    /// Measure latency and bandwidth from every NUMA node's CPUs to every
+    /// node's memory.
+    Numa {
+        /// Measured rounds per cell.
+        #[arg(long, default_value_t = 3)]
+        samples: u32,
+        /// Target duration of each round, in milliseconds.
+        #[arg(long, default_value_t = 250)]
+        window_ms: u64,
         /// Write results as JSON to this path instead of a table.
         #[arg(short, long)]
         out: Option<PathBuf>,
+        /// Suppress progress output.
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Measure memory latency while the other cores load memory with
+    /// bandwidth traffic, from idle to saturation. Not scored.
+    LoadedLatency {
+        /// Threads injecting traffic. Defaults to every CPU but the one
+        /// running the chase.
+        #[arg(long)]
+        injectors: Option<usize>,
+        /// Minimum measurement time per step, in milliseconds.
+        #[arg(long, default_value_t = 200)]
+        min_ms: u64,
+        /// Page size for the chase and the injected traffic (Linux only).
+        #[arg(long, value_enum, default_value_t = Pages::Default)]
+        pages: Pages,
+        /// Write the sweep as JSON to this path instead of a table.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Sign the sweep with this PKCS#8 Ed25519 private key.
+        #[arg(long, value_name = "PATH", requires = "out")]
+        sign_key: Option<PathBuf>,
+    },
+
+    /// Measure how many misses one core keeps in flight, by interleaving
+    /// independent pointer chases on one thread. Not scored.
+    Mlp {
+        /// Minimum measurement time per chain count, in milliseconds.
+        #[arg(long, default_value_t = 200)]
+        min_ms: u64,
+        /// Timed windows per chain count, sharing its `--min-ms`.
+        #[arg(long, default_value_t = 5)]
+        samples: usize,
+        /// Page size for the chase (Linux only).
+        #[arg(long, value_enum, default_value_t = Pages::Default)]
+        pages: Pages,
+        /// Write the sweep as JSON to this path instead of a table.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Sign the sweep with this PKCS#8 Ed25519 private key.
+        #[arg(long, value_name = "PATH", requires = "out")]
+        sign_key: Option<PathBuf>,
     },
 
-    /// Print the JSON Schema for result files.
+    /// Measure the one-way cache-line latency between every pair of cores.
+    /// Not scored.
+    C2c {
+        /// Measure exactly these CPUs, e.g. `0-7,64-71`. Defaults to one CPU
+        /// per physical core.
+        #[arg(long, value_name = "LIST")]
+        cpus: Option<String>,
+        /// Minimum measurement time per pair, in milliseconds.
+        #[arg(long, default_value_t = C2C_MIN_MS)]
+        min_ms: u64,
+        /// Write the matrix as JSON to this path instead of a table.
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+        /// Suppress progress output.
+        #[arg(short, long)]
+        quiet: bool,
+    },
+
+    /// Print the JSON Schema for result files, or for sweep files.
     Schema {
+        /// Describe the sweep document `threadstone sweep --out` writes
+        /// instead of the result.
+        #[arg(long)]
+        sweep: bool,
         /// Write to this path instead of stdout.
         #[arg(short, long)]
         out: Option<PathBuf>,
@@ -106,11 +231,18 @@ enum Command {
         #[arg(short, long, default_value = ".")]
         dir: PathBuf,
     },
+
+    /// Measure passes requested on stdin, for `run --isolate`.
+    #[command(hide = true)]
+    Child,
 }
 
 #[derive(clap::Args)]
 struct RunArgs {
     /// Workload to run; repeat for several. Defaults to all of them.
+    ///
+    /// ID:NAME=VALUE[,NAME=VALUE] sets its parameters, as `list` shows them.
+    /// A workload run away from its defaults is recorded but not scored.
     #[arg(short, long, value_name = "ID")]
     workload: Vec<String>,
 
@@ -118,14 +250,50 @@ struct RunArgs {
     #[arg(short, long, default_value_t = 0)]
     threads: usize,
 
-    /// Measured rounds per pass.
+    /// Measured rounds per pass; the minimum with --target-ci.
     #[arg(short, long, default_value_t = threadstone_core::runner::defaults::SAMPLES)]
     samples: u32,
 
+    /// Keep measuring each pass until its 95% confidence interval is within
+    /// this fraction of the median, e.g. `0.5%`.
+    #[arg(long, value_name = "PERCENT", value_parser = parse_percent)]
+    target_ci: Option<f64>,
+
+    /// With --target-ci, stop a pass after this many measured rounds even if
+    /// the target is unmet [default: 50].
+    #[arg(long, value_name = "N", requires = "target_ci")]
+    max_samples: Option<u32>,
+
+    /// With --target-ci, stop a pass after this long even if the target is
+    /// unmet, e.g. `30s` or `2m` [default: 30s].
+    #[arg(long, value_name = "DURATION", requires = "target_ci", value_parser = parse_duration)]
+    max_time: Option<Duration>,
+
     /// Discarded rounds before measuring.
     #[arg(long, default_value_t = threadstone_core::runner::defaults::WARMUP)]
     warmup: u32,
 
+    /// Fit the whole run into this long, e.g. `90s` or `5m`, choosing the
+    /// window, warmup and samples from a quick estimate of each workload.
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        conflicts_with_all = ["samples", "warmup", "window_ms", "target_ci"]
+    )]
+    budget: Option<Duration>,
+
+    /// Start no pass while more than this fraction of the machine's CPU time
+    /// goes to other work, e.g. `5%`; wait for it to settle, and stop the run
+    /// if it does not (Linux only).
+    #[arg(long, value_name = "PERCENT", value_parser = parse_percent)]
+    require_idle: Option<f64>,
+
+    /// With --require-idle, how long to wait for the machine to settle, e.g.
+    /// `30s` [default: 60s].
+    #[arg(long, value_name = "DURATION", requires = "require_idle", value_parser = parse_duration)]
+    idle_wait: Option<Duration>,
+
     /// Target duration of each measurement round, in milliseconds.
     #[arg(long, default_value_t = 250)]
     window_ms: u64,
@@ -138,6 +306,61 @@ struct RunArgs {
     #[arg(long, conflicts_with = "single_only")]
     multi_only: bool,
 
+    /// Also measure these thread counts and report the scaling curve, e.g.
+    /// `1,2,4,8,16,all`, or `auto` for powers of two up to every logical core.
+    #[arg(long, value_name = "COUNTS", value_parser = ThreadCounts::parse)]
+    scaling: Option<ThreadCounts>,
+
+    /// Also measure a single-thread pass pinned to a performance core and to
+    /// an efficiency core (Linux hybrid CPUs).
+    #[arg(long)]
+    core_classes: bool,
+
+    /// Also measure the one-way cache-line latency between every pair of
+    /// physical cores, summarised within and between clusters (Linux only).
+    /// Not scored.
+    #[arg(long)]
+    core_to_core: bool,
+
+    /// Bind worker threads to CPUs using this placement policy (Linux only).
+    #[arg(long, value_enum, conflicts_with = "cpus")]
+    affinity: Option<Placement>,
+
+    /// Bind worker threads to exactly these CPUs, e.g. `0-7,16-23` (Linux
+    /// only). Thread i runs on the i-th listed CPU.
+    #[arg(long, value_name = "LIST", conflicts_with = "affinity")]
+    cpus: Option<String>,
+
+    /// Page size for the large working sets of `stream` and `latency`
+    /// (Linux only). The TLB misses small pages cost can move latency by
+    /// tens of percent.
+    #[arg(long, value_enum, default_value_t = Pages::Default)]
+    pages: Pages,
+
+    /// Count hardware events (IPC, cache misses, branch mispredicts) in every
+    /// pass (Linux perf_event_open). Where access is denied the run continues
+    /// without them and the result records why.
+    #[arg(long)]
+    counters: bool,
+
+    /// Measure each workload in a child process, so that one that crashes or
+    /// runs out of memory is recorded as that workload's error instead of
+    /// ending the run. `--isolate pass` starts a fresh process for every pass.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "PER",
+        num_args = 0..=1,
+        default_missing_value = "workload"
+    )]
+    isolate: Option<Per>,
+
+    /// Run the whole suite this many times, each in fresh child processes,
+    /// and report them combined, with the variation between runs next to the
+    /// variation within them.
+    #[arg(long, value_name = "N", default_value_t = 1, conflicts_with = "budget")]
+    repeat: u32,
+
     /// Write the result document to this path.
     #[arg(short, long)]
     out: Option<PathBuf>,
@@ -155,6 +378,86 @@ struct RunArgs {
     quiet: bool,
 }
 
+#[derive(clap::Args)]
+struct AbArgs {
+    /// The binary to compare against.
+    #[arg(long, value_name = "PATH")]
+    baseline: PathBuf,
+
+    /// The binary being evaluated.
+    #[arg(long, value_name = "PATH")]
+    candidate: PathBuf,
+
+    /// Workload to compare; repeat for several. Defaults to all of them.
+    #[arg(short, long, value_name = "ID")]
+    workload: Vec<String>,
+
+    /// Threads for the multi-core pass. 0 uses every logical core.
+    #[arg(short, long, default_value_t = 0)]
+    threads: usize,
+
+    /// Paired rounds per pass: each is one round of each binary.
+    #[arg(long, default_value_t = 10)]
+    rounds: u32,
+
+    /// Target duration of each measurement round, in milliseconds.
+    #[arg(long, default_value_t = 250)]
+    window_ms: u64,
+
+    /// Which binary goes first in each pair of rounds.
+    #[arg(long, value_enum, default_value_t = ab::Order::Alternate)]
+    order: ab::Order,
+
+    /// Seed for `--order random` [default: from the clock].
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Compare only the single-thread pass.
+    #[arg(long, conflicts_with = "multi_only")]
+    single_only: bool,
+
+    /// Compare only the multi-thread pass.
+    #[arg(long, conflicts_with = "single_only")]
+    multi_only: bool,
+
+    /// Suppress progress output.
+    #[arg(short, long)]
+    quiet: bool,
+}
+
+/// Named thread-placement policies. See [`Affinity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Placement {
+    /// Fill each physical core's SMT siblings before the next core.
+    Compact,
+    /// Spread across sockets, then cores, then SMT siblings.
+    Scatter,
+    /// One thread per physical core before any SMT sibling.
+    PhysicalFirst,
+}
+
+/// How large working sets are backed. See [`PagePolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Pages {
+    /// Whatever the kernel's transparent-huge-page mode gives.
+    Default,
+    /// Transparent 2 MiB pages, requested with madvise.
+    Thp,
+    /// 2 MiB pages from the reserved hugetlbfs pool, or THP if it is empty.
+    Hugetlb,
+    /// 4 KiB pages only.
+    Small,
+}
+
+/// How much of a run each `--isolate` child measures. See [`Isolation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Per {
+    /// One process for all of a workload's passes.
+    Workload,
+    /// A fresh process for every pass.
+    Pass,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 enum Format {
     /// Aligned terminal table.
@@ -189,14 +492,92 @@ fn dispatch(command: Command) -> Result<(), Failure> {
             baseline,
             candidate,
         } => compare_files(&baseline, &candidate),
+        Command::Ab(args) => ab(args),
         Command::Report { file, format } => report_file(&file, format),
-        Command::Sweep { min_ms, out } => sweep(min_ms, out.as_deref()),
-        Command::Schema { out } => schema(out.as_deref()),
+        Command::Sweep {
+            min_ms,
+            bandwidth: false,
+            tlb: false,
+            step,
+            samples,
+            max_mib,
+            out,
+            sign_key,
+        } => sweep(
+            min_ms,
+            step,
+            samples,
+            max_mib,
+            out.as_deref(),
+            sign_key.as_deref(),
+        ),
+        Command::Sweep {
+            min_ms,
+            bandwidth: true,
+            max_mib,
+            out,
+            sign_key,
+            ..
+        } => bandwidth_sweep(min_ms, max_mib, out.as_deref(), sign_key.as_deref()),
+        Command::Sweep {
+            min_ms,
+            tlb: true,
+            step,
+            samples,
+            max_mib,
+            out,
+            sign_key,
+            ..
+        } => tlb_sweep(
+            min_ms,
+            step,
+            samples,
+            max_mib,
+            out.as_deref(),
+            sign_key.as_deref(),
+        ),
+        Command::Numa {
+            samples,
+            window_ms,
+            out,
+            quiet,
+        } => numa_matrix(samples, window_ms, out.as_deref(), quiet),
+        Command::LoadedLatency {
+            injectors,
+            min_ms,
+            pages,
+            out,
+            sign_key,
+        } => loaded_latency(
+            injectors,
+            min_ms,
+            pages,
+            out.as_deref(),
+            sign_key.as_deref(),
+        ),
+        Command::Mlp {
+            min_ms,
+            samples,
+            pages,
+            out,
+            sign_key,
+        } => mlp(min_ms, samples, pages, out.as_deref(), sign_key.as_deref()),
+        Command::C2c {
+            cpus,
+            min_ms,
+            out,
+            quiet,
+        } => c2c(cpus.as_deref(), min_ms, out.as_deref(), quiet),
+        Command::Schema { sweep, out } => schema(sweep, out.as_deref()),
         Command::Keygen { dir } => keygen(&dir),
+        Command::Child => child::serve(VERSION),
     }
 }
 
 fn run(args: RunArgs) -> Result<(), Failure> {
+    // First, so that a signal arriving while the run is still being set up
+    // stops it like any other rather than killing the process.
+    interrupt::install(true);
     let kernels = select_workloads(&args.workload)?;
 
     let cfg = SuiteConfig {
@@ -206,18 +587,117 @@ fn run(args: RunArgs) -> Result<(), Failure> {
         window: Duration::from_millis(args.window_ms),
         single_thread: !args.multi_only,
         multi_thread: !args.single_only,
+        affinity: affinity_plan(args.affinity, args.cpus.as_deref())?,
+        scaling: args.scaling,
+        core_classes: args.core_classes,
+        counters: args.counters,
+        precision: args.target_ci.map(|target_ci| Precision {
+            target_ci,
+            max_samples: args.max_samples.unwrap_or(defaults::MAX_SAMPLES),
+            max_time: args.max_time.unwrap_or(defaults::MAX_TIME),
+        }),
+        budget: args.budget,
+        require_idle: args.require_idle.map(|max_busy| RequireIdle {
+            max_busy,
+            wait: args.idle_wait.unwrap_or(load::IDLE_WAIT),
+        }),
+        pages: page_policy(args.pages)?,
     };
+    if cfg.require_idle.is_some() && !load::supported() {
+        return Err(
+            "--require-idle: cannot sample the machine's load on this platform \
+                    (supported on Linux)"
+                .into(),
+        );
+    }
     if cfg.samples == 0 {
         return Err("--samples must be at least 1".into());
     }
+    if args.repeat == 0 {
+        return Err("--repeat must be at least 1".into());
+    }
+    if let Some(p) = &cfg.precision {
+        if p.max_samples < cfg.samples {
+            return Err(format!(
+                "--max-samples {} is below --samples {}, the minimum",
+                p.max_samples, cfg.samples
+            )
+            .into());
+        }
+    }
     if args.window_ms == 0 {
         return Err("--window-ms must be at least 1".into());
     }
+    if cfg.core_classes {
+        let system = threadstone_core::SystemInfo::detect();
+        if system.performance_cpus.is_empty() || system.efficiency_cpus.is_empty() {
+            return Err(
+                "--core-classes: cannot tell performance from efficiency cores \
+                        on this machine (supported on Linux hybrid CPUs)"
+                    .into(),
+            );
+        }
+    }
+
+    let c2c_cpus = if args.core_to_core {
+        Some(
+            threadstone_workloads::c2c::default_cpus()
+                .filter(|cpus| cpus.len() >= 2)
+                .ok_or(
+                    "--core-to-core: needs two physical cores to pin threads to \
+                     (supported on Linux)",
+                )?,
+        )
+    } else {
+        None
+    };
 
     // Progress goes to stderr so that `--format json > file` stays clean.
     let quiet = args.quiet || args.format == Format::Json;
     let progress = observer::Progress::new(quiet);
-    let mut report = suite::run(&kernels, cfg, VERSION, &progress);
+    // Repeats run in fresh processes, or launch-to-launch variation — the
+    // point of repeating — would be shared by every invocation.
+    let isolate = args
+        .isolate
+        .or((args.repeat > 1).then_some(Per::Workload))
+        .map(|per| match per {
+            Per::Workload => Isolation::Workload,
+            Per::Pass => Isolation::Pass,
+        });
+    let mut runs = Vec::new();
+    for invocation in 1..=args.repeat {
+        if args.repeat > 1 {
+            progress.invocation(invocation, args.repeat);
+        }
+        let report = match isolate {
+            Some(isolation) => {
+                let executor = child::Isolated::new(isolation)?;
+                suite::run_with(&kernels, cfg.clone(), VERSION, &progress, &executor)
+            }
+            None => suite::run(&kernels, cfg.clone(), VERSION, &progress),
+        };
+        runs.push(report);
+        if interrupt::requested() {
+            break;
+        }
+    }
+    // An interrupted invocation is combined only if it is all there is: its
+    // missing workloads would otherwise rest on fewer runs than the others.
+    if runs.len() > 1 && runs.last().is_some_and(Report::is_partial) {
+        runs.pop();
+    }
+    let mut report = if args.repeat > 1 {
+        repeat::combine(runs)
+    } else {
+        runs.pop()
+    }
+    .ok_or("no run completed")?;
+    if let Some(cpus) = c2c_cpus.filter(|_| !interrupt::requested()) {
+        match core_to_core(&cpus, C2C_MIN_MS, &progress) {
+            Ok(matrix) => report.core_to_core = Some(matrix),
+            Err(e) => progress.workload_failed("core-to-core", &e.to_string()),
+        }
+    }
     progress.finish();
 
     if let Some(key_path) = &args.sign_key {
@@ -231,10 +711,43 @@ fn run(args: RunArgs) -> Result<(), Failure> {
     }
 
     print!("{}", format_report(&report, args.format)?);
+    // The partial report is written and signed like any other, but the exit
+    // status still says the run did not finish.
+    if report.is_partial() {
+        return Err("interrupted; the report covers only the workloads that completed".into());
+    }
+    if interrupt::requested() {
+        return Err(format!(
+            "interrupted; the report combines only the {} run(s) that completed",
+            report.config.repeat.unwrap_or(1)
+        )
+        .into());
+    }
     Ok(())
 }
 
-/// Resolve requested workload ids, or return all of them.
+/// Turn `--affinity` / `--cpus` into a plan, checking it against this machine
+/// before any workload runs rather than failing each one in turn.
+fn affinity_plan(
+    placement: Option<Placement>,
+    cpus: Option<&str>,
+) -> Result<Option<Affinity>, Failure> {
+    let plan = match (placement, cpus) {
+        (Some(Placement::Compact), _) => Affinity::Compact,
+        (Some(Placement::Scatter), _) => Affinity::Scatter,
+        (Some(Placement::PhysicalFirst), _) => Affinity::PhysicalFirst,
+        (None, Some(list)) => {
+            Affinity::Explicit(affinity::parse_cpu_list(list).map_err(|e| format!("--cpus: {e}"))?)
+        }
+        (None, None) => return Ok(None),
+    };
+    plan.resolve(1)
+        .map_err(|e| format!("cannot pin threads ({}): {e}", plan.label()))?;
+    Ok(Some(plan))
+}
+
+/// Resolve requested workloads, each an id optionally followed by parameters
+/// as `id:name=value[,name=value]`, or return all of them.
 fn select_workloads(
     requested: &[String],
 ) -> Result<Vec<Box<dyn threadstone_core::Kernel>>, Failure> {
@@ -242,24 +755,38 @@ fn select_workloads(
         return Ok(threadstone_workloads::all());
     }
     let mut kernels = Vec::with_capacity(requested.len());
-    for id in requested {
-        let kernel = threadstone_workloads::by_id(id).ok_or_else(|| {
+    for spec in requested {
+        let (id, params) = spec.split_once(':').unwrap_or((spec, ""));
+        let mut kernel = threadstone_workloads::by_id(id).ok_or_else(|| {
             format!(
                 "unknown workload '{id}'; available: {}",
                 threadstone_workloads::ids().join(", ")
             )
         })?;
+        for setting in params.split(',').filter(|s| !s.is_empty()) {
+            let (name, value) = setting
+                .split_once('=')
+                .ok_or_else(|| format!("'{spec}': expected NAME=VALUE, found '{setting}'"))?;
+            let value: u64 = value
+                .parse()
+                .map_err(|_| format!("'{spec}': {name} must be a whole number, not '{value}'"))?;
+            kernel.configure(name, value)?;
+        }
         kernels.push(kernel);
     }
     Ok(kernels)
 }
 
 fn sign_report(report: &mut Report, key_path: &Path) -> Result<(), Failure> {
+    report.signature = Some(signature(&report.signing_bytes()?, key_path)?);
+    Ok(())
+}
+
+/// Sign a document's canonical bytes with the key at `key_path`.
+fn signature(message: &[u8], key_path: &Path) -> Result<Signature, Failure> {
     let pkcs8 = std::fs::read(key_path)
         .map_err(|e| format!("cannot read signing key {}: {e}", key_path.display()))?;
-    let message = report.signing_bytes()?;
-    report.signature = Some(signing::sign(&message, &pkcs8)?);
-    Ok(())
+    Ok(signing::sign(message, &pkcs8)?)
 }
 
 fn format_report(report: &Report, format: Format) -> Result<String, Failure> {
@@ -290,12 +817,27 @@ fn list() -> Result<(), Failure> {
                     "single-thread only (excluded from the multi-core score)",
             },
         );
+        let subs: Vec<&str> = kernel.sub_results().iter().map(|k| k.info().name).collect();
+        if !subs.is_empty() {
+            println!("             also measures {}, unscored", subs.join(", "));
+        }
+        for p in kernel.params() {
+            let multiple = match p.multiple_of {
+                0 | 1 => String::new(),
+                m => format!(", a multiple of {m}"),
+            };
+            println!(
+                "             {}={} · {}; {} to {}{multiple}",
+                p.name, p.default, p.summary, p.min, p.max
+            );
+        }
         println!();
     }
     println!(
         "Reference values define {}; a machine matching it scores 1000.",
         threadstone_core::score::REFERENCE_NAME
     );
+    println!("-w ID:NAME=VALUE sets a parameter; a workload away from its defaults is not scored.");
     Ok(())
 }
 
@@ -311,19 +853,25 @@ fn verify_file(path: &Path, require_signature: bool) -> Result<(), Failure> {
 }
 
 fn sign_file(file: &Path, key: &Path, out: Option<&Path>) -> Result<(), Failure> {
-    let mut report: Report =
-        serde_json::from_str(&read_file(file)?).map_err(|e| format!("{}: {e}", file.display()))?;
+    let text = read_file(file)?;
+    let parse_error = |e: serde_json::Error| format!("{}: {e}", file.display());
 
     // Any existing signature is dropped first: `signing_bytes` excludes the
     // field, so leaving a stale one in place would be harmless but confusing.
-    report.signature = None;
-    sign_report(&mut report, key)?;
+    let json = if verify::is_sweep(&text) {
+        let mut sweep: SweepReport = serde_json::from_str(&text).map_err(parse_error)?;
+        sweep.signature = None;
+        sweep.signature = Some(signature(&sweep.signing_bytes()?, key)?);
+        serde_json::to_string_pretty(&sweep)?
+    } else {
+        let mut report: Report = serde_json::from_str(&text).map_err(parse_error)?;
+        report.signature = None;
+        sign_report(&mut report, key)?;
+        serde_json::to_string_pretty(&report)?
+    };
 
     let destination = out.unwrap_or(file);
-    write_file(
-        destination,
-        serde_json::to_string_pretty(&report)?.as_bytes(),
-    )?;
+    write_file(destination, json.as_bytes())?;
     println!("signed {}", destination.display());
     Ok(())
 }
@@ -345,46 +893,486 @@ fn compare_files(baseline: &Path, candidate: &Path) -> Result<(), Failure> {
     Ok(())
 }
 
+fn ab(args: AbArgs) -> Result<(), Failure> {
+    let kernels = select_workloads(&args.workload)?;
+    if let Some(k) = kernels.iter().find(|k| !k.is_canonical()) {
+        return Err(format!(
+            "ab runs workloads at their defaults only, and {} has parameters set: a \
+             baseline built before parameters would ignore them",
+            k.info().id
+        )
+        .into());
+    }
+    if args.rounds < ab::MIN_ROUNDS {
+        return Err(format!(
+            "--rounds must be at least {}: fewer pairs can never show a significant change",
+            ab::MIN_ROUNDS
+        )
+        .into());
+    }
+    if args.window_ms == 0 {
+        return Err("--window-ms must be at least 1".into());
+    }
+    let settings = ab::Settings {
+        threads: match args.threads {
+            0 => threadstone_core::SystemInfo::detect().default_threads(),
+            n => n,
+        },
+        rounds: args.rounds,
+        window: Duration::from_millis(args.window_ms),
+        order: args.order,
+        seed: args.seed.unwrap_or_else(|| {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.as_nanos() as u64)
+        }),
+        single_thread: !args.multi_only,
+        multi_thread: !args.single_only,
+    };
+
+    let progress = observer::Progress::new(args.quiet);
+    interrupt::install(true);
+    let result = ab::run(
+        &args.baseline,
+        &args.candidate,
+        &kernels,
+        &settings,
+        &progress,
+    );
+    progress.finish();
+    let (comparison, baseline, candidate) = result?;
+    println!("{}\n", ab::describe(&settings));
+    print!("{}", compare::render(&comparison, &baseline, &candidate));
+    Ok(())
+}
+
 fn report_file(path: &Path, format: Format) -> Result<(), Failure> {
+    let text = read_file(path)?;
+    if verify::is_sweep(&text) {
+        let sweep: SweepReport =
+            serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+        match format {
+            Format::Table => print!("{}", render::sweep(&sweep)),
+            Format::Json => println!("{}", serde_json::to_string_pretty(&sweep)?),
+            Format::Markdown => return Err("a sweep renders as a table or JSON".into()),
+        }
+        return Ok(());
+    }
     let report: Report =
-        serde_json::from_str(&read_file(path)?).map_err(|e| format!("{}: {e}", path.display()))?;
+        serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
     print!("{}", format_report(&report, format)?);
     Ok(())
 }
 
-fn sweep(min_ms: u64, out: Option<&Path>) -> Result<(), Failure> {
+fn sweep(
+    min_ms: u64,
+    step: f64,
+    samples: usize,
+    max_mib: Option<u64>,
+    out: Option<&Path>,
+    sign_key: Option<&Path>,
+) -> Result<(), Failure> {
+    use threadstone_core::hierarchy;
     use threadstone_workloads::latency;
 
-    let sizes = latency::default_sweep_sizes();
+    check_sampling(step, samples)?;
+    let sizes = up_to(latency::sweep_sizes(step), max_mib)?;
     eprintln!(
-        "Measuring pointer-chase latency across {} working-set sizes…",
+        "Measuring pointer-chase latency across {} working-set sizes, {samples} windows each…",
         sizes.len()
     );
 
-    let points = latency::sweep(&sizes, min_ms);
+    let started = Instant::now();
+    let system = threadstone_core::SystemInfo::detect();
+    let progress = observer::Progress::new(false);
+    let points = latency::sweep(&sizes, min_ms, samples, &mut |bytes| {
+        progress.chase_point(&human_bytes(bytes))
+    });
+    progress.finish();
 
-    if let Some(path) = out {
-        let json: Vec<serde_json::Value> = points
+    let levels = hierarchy::levels(&points);
+    let largest = sizes.last().copied().unwrap_or(0);
+    let disagreements = hierarchy::cross_check(&levels, &system, largest);
+    let latency = LatencySweep {
+        min_ms,
+        step,
+        points,
+        levels,
+        disagreements,
+    };
+    let mut document = sweep_report(started, system);
+    document.latency = Some(latency);
+    write_sweep(document, out, sign_key)
+}
+
+/// Refuse a `--step` or `--samples` that cannot be swept.
+fn check_sampling(step: f64, samples: usize) -> Result<(), Failure> {
+    use threadstone_workloads::latency::MIN_STEP;
+
+    if step.is_nan() || step < MIN_STEP {
+        return Err(format!("--step must be at least {MIN_STEP}").into());
+    }
+    if samples == 0 {
+        return Err("--samples must be at least 1".into());
+    }
+    Ok(())
+}
+
+/// The provenance of a sweep timed from `started`, with no sweep in it yet.
+fn sweep_report(started: Instant, system: threadstone_core::SystemInfo) -> SweepReport {
+    SweepReport {
+        schema_version: SCHEMA_VERSION,
+        tool_version: VERSION.to_string(),
+        generated_at: threadstone_core::report::now_rfc3339(),
+        duration_secs: started.elapsed().as_secs_f64(),
+        system,
+        latency: None,
+        bandwidth: None,
+        tlb: None,
+        mlp: None,
+        loaded: None,
+        signature: None,
+    }
+}
+
+/// Sign a sweep if asked, then write it to `out` or print it as tables.
+fn write_sweep(
+    mut document: SweepReport,
+    out: Option<&Path>,
+    sign_key: Option<&Path>,
+) -> Result<(), Failure> {
+    if let Some(key_path) = sign_key {
+        document.signature = Some(signature(&document.signing_bytes()?, key_path)?);
+    }
+    match out {
+        Some(path) => {
+            write_file(path, serde_json::to_string_pretty(&document)?.as_bytes())?;
+            eprintln!("wrote {}", path.display());
+        }
+        None => print!("{}", render::sweep(&document)),
+    }
+    Ok(())
+}
+
+/// `--max-mib` in bytes, refused if it does not fit in an address.
+fn max_bytes(mib: u64) -> Result<usize, Failure> {
+    usize::try_from(mib)
+        .ok()
+        .and_then(|mib| mib.checked_mul(1 << 20))
+        .ok_or_else(|| format!("--max-mib {mib} is more than this machine can address").into())
+}
+
+/// Drop the sweep sizes above `--max-mib`.
+fn up_to(sizes: Vec<usize>, max_mib: Option<u64>) -> Result<Vec<usize>, Failure> {
+    let Some(max) = max_mib else {
+        return Ok(sizes);
+    };
+    let max = max_bytes(max)?;
+    let sizes: Vec<usize> = sizes.into_iter().filter(|&s| s <= max).collect();
+    if sizes.is_empty() {
+        return Err("--max-mib leaves no working set to measure".into());
+    }
+    Ok(sizes)
+}
+
+fn bandwidth_sweep(
+    min_ms: u64,
+    max_mib: Option<u64>,
+    out: Option<&Path>,
+    sign_key: Option<&Path>,
+) -> Result<(), Failure> {
+    use threadstone_workloads::bandwidth;
+
+    let started = Instant::now();
+    let system = threadstone_core::SystemInfo::detect();
+    let sizes = up_to(bandwidth::default_sizes(system.l3_bytes), max_mib)?;
+    let mut threads = vec![1, system.default_threads()];
+    threads.dedup();
+    eprintln!(
+        "Measuring read and read-modify-write bandwidth across {} working-set sizes at {}…",
+        sizes.len(),
+        threads
             .iter()
-            .map(|p| serde_json::json!({ "bytes": p.bytes, "latency_ns": p.latency_ns }))
-            .collect();
-        write_file(path, serde_json::to_string_pretty(&json)?.as_bytes())?;
+            .map(|n| format!("{n} thread{}", if *n == 1 { "" } else { "s" }))
+            .collect::<Vec<_>>()
+            .join(" and ")
+    );
+
+    let progress = observer::Progress::new(false);
+    let points = bandwidth::sweep(&sizes, &threads, min_ms, &mut |bytes, access, n| {
+        progress.sweep_point(access.id(), &human_bytes(bytes), n)
+    });
+    progress.finish();
+
+    let bandwidth = BandwidthSweep {
+        min_ms,
+        threads,
+        points,
+    };
+    let mut document = sweep_report(started, system);
+    document.bandwidth = Some(bandwidth);
+    write_sweep(document, out, sign_key)
+}
+
+fn tlb_sweep(
+    min_ms: u64,
+    step: f64,
+    samples: usize,
+    max_mib: Option<u64>,
+    out: Option<&Path>,
+    sign_key: Option<&Path>,
+) -> Result<(), Failure> {
+    use threadstone_core::hierarchy;
+    use threadstone_workloads::tlb;
+
+    if !pages::supported() {
+        return Err("--tlb: cannot choose page sizes on this platform (supported on Linux)".into());
+    }
+    check_sampling(step, samples)?;
+    let span = max_mib.map_or(Ok(256 << 20), max_bytes)?;
+    let counts: Vec<(usize, Vec<usize>)> = tlb::PAGE_SIZES
+        .iter()
+        .map(|&page_bytes| (page_bytes, tlb::page_counts(page_bytes, step, span)))
+        .filter(|(_, counts)| !counts.is_empty())
+        .collect();
+    if counts.is_empty() {
+        return Err("--max-mib leaves no page count to measure".into());
+    }
+    eprintln!(
+        "Measuring a page-stride chase against its baseline at {} page counts, {samples} windows each…",
+        counts.iter().map(|(_, c)| c.len()).sum::<usize>()
+    );
+
+    let started = Instant::now();
+    let system = threadstone_core::SystemInfo::detect();
+    let progress = observer::Progress::new(false);
+    let mut probes = Vec::new();
+    for (page_bytes, counts) in counts {
+        let page = human_bytes(page_bytes);
+        let points = tlb::probe(page_bytes, &counts, min_ms, samples, &mut |n| {
+            progress.tlb_point(&page, n)
+        });
+        match points {
+            Some(points) => probes.push(TlbProbe {
+                page_bytes,
+                reach: hierarchy::tlb_reach(&points, page_bytes),
+                points,
+            }),
+            None => eprintln!("note: {page} pages were not granted; that probe is left out"),
+        }
+    }
+    progress.finish();
+
+    let mut document = sweep_report(started, system);
+    document.tlb = Some(TlbSweep {
+        min_ms,
+        step,
+        probes,
+    });
+    write_sweep(document, out, sign_key)
+}
+
+fn loaded_latency(
+    injectors: Option<usize>,
+    min_ms: u64,
+    pages: Pages,
+    out: Option<&Path>,
+    sign_key: Option<&Path>,
+) -> Result<(), Failure> {
+    use threadstone_workloads::loaded;
+
+    let pages = page_policy(pages)?;
+    let started = Instant::now();
+    let system = threadstone_core::SystemInfo::detect();
+    let injectors = match injectors {
+        Some(0) => return Err("--injectors must be at least 1".into()),
+        Some(n) => n,
+        None => match system.default_threads() - 1 {
+            0 => {
+                return Err("loaded-latency needs a second CPU to inject traffic from; \
+                            pass --injectors to share one"
+                    .into())
+            }
+            n => n,
+        },
+    };
+    let delays = loaded::default_delays();
+    eprintln!(
+        "Measuring pointer-chase latency under {} levels of load from {injectors} injector(s)…",
+        delays.len() + 1
+    );
+
+    let points = loaded::measure(injectors, &delays, min_ms, pages);
+
+    let mut document = sweep_report(started, system);
+    document.loaded = Some(LoadedSweep {
+        min_ms,
+        injectors,
+        pages,
+        points,
+    });
+    write_sweep(document, out, sign_key)
+}
+
+fn mlp(
+    min_ms: u64,
+    samples: usize,
+    pages: Pages,
+    out: Option<&Path>,
+    sign_key: Option<&Path>,
+) -> Result<(), Failure> {
+    use threadstone_workloads::mlp;
+
+    let pages = page_policy(pages)?;
+    if samples == 0 {
+        return Err("--samples must be at least 1".into());
+    }
+    eprintln!(
+        "Measuring {} pointer chases interleaved on one thread, {samples} windows each…",
+        mlp::CHAINS.map(|n| n.to_string()).join(", ")
+    );
+
+    let started = Instant::now();
+    let system = threadstone_core::SystemInfo::detect();
+    let progress = observer::Progress::new(false);
+    let points = mlp::measure(&mlp::CHAINS, min_ms, samples, pages, &mut |n| {
+        progress.mlp_point(n)
+    });
+    progress.finish();
+
+    let mut document = sweep_report(started, system);
+    document.mlp = Some(MlpSweep {
+        min_ms,
+        pages,
+        points,
+    });
+    write_sweep(document, out, sign_key)
+}
+
+fn c2c(cpus: Option<&str>, min_ms: u64, out: Option<&Path>, quiet: bool) -> Result<(), Failure> {
+    let cpus = match cpus {
+        Some(list) => {
+            let cpus = affinity::parse_cpu_list(list).map_err(|e| format!("--cpus: {e}"))?;
+            if let Some(cpu) = cpus
+                .iter()
+                .enumerate()
+                .find_map(|(i, c)| cpus[..i].contains(c).then_some(c))
+            {
+                return Err(format!("--cpus: CPU {cpu} is listed twice").into());
+            }
+            cpus
+        }
+        None => threadstone_workloads::c2c::default_cpus().unwrap_or_default(),
+    };
+    let progress = observer::Progress::new(quiet);
+    interrupt::install(true);
+    let matrix = core_to_core(&cpus, min_ms, &progress);
+    progress.finish();
+    let matrix = matrix?;
+
+    if let Some(path) = out {
+        write_file(path, serde_json::to_string_pretty(&matrix)?.as_bytes())?;
         eprintln!("wrote {}", path.display());
         return Ok(());
     }
+    print!("{}", render::core_to_core(&matrix, render::Color::detect()));
+    Ok(())
+}
 
-    println!("{:>12}  {:>10}", "working set", "latency");
-    for point in &points {
-        println!(
-            "{:>12}  {:>7.1} ns",
-            human_bytes(point.bytes),
-            point.latency_ns
-        );
+/// Measure every pair of `cpus`, showing which pair is in progress.
+fn core_to_core(
+    cpus: &[usize],
+    min_ms: u64,
+    progress: &observer::Progress,
+) -> Result<CoreToCore, Failure> {
+    let mut show = |pair, done, total| progress.core_pair(pair, done, total);
+    let matrix =
+        threadstone_workloads::c2c::measure(cpus, min_ms, &mut show, &interrupt::requested);
+    if interrupt::requested() {
+        return Err("interrupted".into());
     }
-    println!("\nPlateaus mark cache levels; each step up is a level boundary.");
+    Ok(matrix.map_err(|e| format!("core to core: {e}"))?)
+}
+
+fn numa_matrix(
+    samples: u32,
+    window_ms: u64,
+    out: Option<&Path>,
+    quiet: bool,
+) -> Result<(), Failure> {
+    if samples == 0 {
+        return Err("--samples must be at least 1".into());
+    }
+    if window_ms == 0 {
+        return Err("--window-ms must be at least 1".into());
+    }
+    let settings = numa::Settings {
+        samples,
+        window: Duration::from_millis(window_ms),
+    };
+    let progress = observer::Progress::new(quiet);
+    interrupt::install(true);
+    let matrix = numa::run(&settings, &progress);
+    progress.finish();
+    let matrix = matrix?;
+
+    if let Some(path) = out {
+        write_file(path, serde_json::to_string_pretty(&matrix)?.as_bytes())?;
+        eprintln!("wrote {}", path.display());
+        return Ok(());
+    }
+    print!("{}", numa::render(&matrix));
     Ok(())
 }
 
+/// The page policy `--pages` names, if this platform can honour it.
+fn page_policy(pages: Pages) -> Result<PagePolicy, Failure> {
+    let policy = match pages {
+        Pages::Default => PagePolicy::Default,
+        Pages::Thp => PagePolicy::Thp,
+        Pages::Hugetlb => PagePolicy::Hugetlb,
+        Pages::Small => PagePolicy::Small,
+    };
+    if policy != PagePolicy::Default && !pages::supported() {
+        return Err(
+            "--pages: cannot choose page sizes on this platform (supported on Linux)".into(),
+        );
+    }
+    Ok(policy)
+}
+
+/// Parse a percentage such as `0.5%` (or `0.5`) into a fraction.
+fn parse_percent(text: &str) -> Result<f64, String> {
+    let number = text.trim().trim_end_matches('%');
+    match number.parse::<f64>() {
+        Ok(p) if p > 0.0 && p < 100.0 => Ok(p / 100.0),
+        Ok(_) => Err("must be between 0% and 100%, exclusive".to_string()),
+        Err(_) => Err(format!("'{text}' is not a percentage")),
+    }
+}
+
+/// Parse a duration such as `90s`, `2m`, `1.5h` or `500ms`. A bare number is
+/// seconds.
+fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let scale = match unit.trim() {
+        "ms" => 1e-3,
+        "" | "s" => 1.0,
+        "m" | "min" => 60.0,
+        "h" => 3600.0,
+        other => return Err(format!("unknown unit '{other}': use ms, s, m or h")),
+    };
+    match number.parse::<f64>() {
+        Ok(n) if n.is_finite() && n > 0.0 => Duration::try_from_secs_f64(n * scale)
+            .map_err(|_| format!("'{text}' is too long a duration")),
+        _ => Err(format!("'{text}' is not a positive duration")),
+    }
+}
+
 fn human_bytes(bytes: usize) -> String {
     const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
     let mut value = bytes as f64;
@@ -393,11 +1381,18 @@ fn human_bytes(bytes: usize) -> String {
         value /= 1024.0;
         unit += 1;
     }
-    format!("{value:.0} {}", UNITS[unit])
+    // Finer sweeps step between powers of two, to sizes such as 1.25 MiB.
+    let value = format!("{value:.2}");
+    let value = value.trim_end_matches('0').trim_end_matches('.');
+    format!("{value} {}", UNITS[unit])
 }
 
-fn schema(out: Option<&Path>) -> Result<(), Failure> {
-    let schema = schemars::schema_for!(Report);
+fn schema(sweep: bool, out: Option<&Path>) -> Result<(), Failure> {
+    let schema = if sweep {
+        schemars::schema_for!(SweepReport)
+    } else {
+        schemars::schema_for!(Report)
+    };
     let json = serde_json::to_string_pretty(&schema)?;
     match out {
         Some(path) => {
@@ -488,6 +1483,29 @@ mod tests {
         assert_eq!(ids, vec!["sgemm", "stream"]);
     }
 
+    #[test]
+    fn workload_parameters_follow_the_id() {
+        let picked = select_workloads(&["sgemm:n=64".into(), "stream".into()]).unwrap();
+        assert_eq!(picked[0].params()[0].value, 64);
+        assert!(!picked[0].is_canonical());
+        assert!(picked[1].is_canonical());
+        // A trailing colon sets nothing.
+        assert!(select_workloads(&["sort:".into()]).unwrap()[0].is_canonical());
+
+        let err = |spec: &str| match select_workloads(&[spec.into()]) {
+            Ok(_) => panic!("'{spec}' must be refused"),
+            Err(e) => e.to_string(),
+        };
+        assert_eq!(err("sgemm:n"), "'sgemm:n': expected NAME=VALUE, found 'n'");
+        assert_eq!(
+            err("sgemm:n=big"),
+            "'sgemm:n=big': n must be a whole number, not 'big'"
+        );
+        assert!(err("sgemm:n=8192").contains("out of range"));
+        assert!(err("sgemm:m=64").contains("has no parameter 'm'; it has n"));
+        assert!(err("dhrystone:n=1").contains("has no parameters"));
+    }
+
     #[test]
     fn an_unknown_workload_lists_the_valid_ones() {
         // `Box<dyn Kernel>` is not `Debug`, so unwrap the error by hand.
@@ -502,11 +1520,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn no_placement_flags_means_no_pinning() {
+        assert_eq!(affinity_plan(None, None).unwrap(), None);
+    }
+
+    #[test]
+    fn a_malformed_cpu_list_is_rejected() {
+        let err = affinity_plan(None, Some("7-3")).unwrap_err().to_string();
+        assert!(err.contains("--cpus"), "got: {err}");
+    }
+
+    #[test]
+    fn percentages_parse_with_or_without_the_sign() {
+        assert_eq!(parse_percent("0.5%"), Ok(0.005));
+        assert_eq!(parse_percent("2"), Ok(0.02));
+        assert!(parse_percent("0%").is_err());
+        assert!(parse_percent("half").is_err());
+    }
+
+    #[test]
+    fn durations_parse_in_common_units() {
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(parse_duration("10 parsecs").is_err());
+        assert!(parse_duration("0s").is_err());
+        assert_eq!(
+            parse_duration("99999999999999999999h"),
+            Err("'99999999999999999999h' is too long a duration".to_string())
+        );
+    }
+
+    #[test]
+    fn max_mib_is_refused_past_what_fits_in_an_address() {
+        assert_eq!(up_to(vec![1 << 20, 2 << 20], Some(1)).unwrap(), [1 << 20]);
+        let err = up_to(vec![1 << 20], Some(u64::MAX)).unwrap_err();
+        assert!(err.to_string().contains("more than this machine"), "{err}");
+        assert!(up_to(vec![2 << 20], Some(1)).is_err());
+    }
+
     #[test]
     fn human_bytes_uses_binary_units() {
         assert_eq!(human_bytes(512), "512 B");
         assert_eq!(human_bytes(4096), "4 KiB");
         assert_eq!(human_bytes(1 << 20), "1 MiB");
         assert_eq!(human_bytes(256 << 20), "256 MiB");
+        assert_eq!(human_bytes(1280 << 10), "1.25 MiB");
+        assert_eq!(human_bytes(6 << 10), "6 KiB");
     }
 }
@@ -0,0 +1,331 @@
+//! Latency and bandwidth between every pair of NUMA nodes.
+//!
+//! A suite run lets first touch place memory, so on a multi-socket machine it
+//! measures local access only, and only if the threads stayed put. Capacity
+//! planning needs the other half: what a thread pays when its data lives on the
+//! far socket. `threadstone numa` measures it on purpose. For each node with
+//! CPUs and each node with memory, it pins threads to the first and binds
+//! their memory to the second (see [`threadstone_core::numa`]), then runs
+//!
+//! * the `latency` chase on the node's last CPU, and
+//! * the `stream` triad on every CPU of the node,
+//!
+//! giving an N×N matrix of each. The diagonal is local access; everything off
+//! it crosses the interconnect. The chase takes the last CPU rather than the
+//! first for the reason the suite's core-class passes do: node 0's first CPU
+//! is CPU 0, which takes more than its share of interrupts, and would make
+//! that node's row noisier than the rest.
+
+use serde::Serialize;
+
+use threadstone_core::affinity::{self, format_cpu_list, Affinity};
+use threadstone_core::numa::{self, NumaNode};
+use threadstone_core::pages::PagePolicy;
+use threadstone_core::runner::{self, RunConfig, RunError};
+use threadstone_core::stats::geometric_mean;
+use threadstone_core::{Kernel, SuiteObserver};
+
+use crate::observer::Progress;
+use crate::Failure;
+
+/// How long to measure each cell.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Measured rounds per cell.
+    pub samples: u32,
+    /// Target duration of each round.
+    pub window: std::time::Duration,
+}
+
+/// Every cell of the matrix, with the nodes it spans.
+#[derive(Debug, Clone, Serialize)]
+pub struct Matrix {
+    /// The machine's nodes, as detected.
+    pub nodes: Vec<NumaNode>,
+    /// One entry per pair of CPU node and memory node, row by row.
+    pub cells: Vec<Cell>,
+}
+
+/// Access from one node's CPUs to another node's memory.
+#[derive(Debug, Clone, Serialize)]
+pub struct Cell {
+    /// Node the threads ran on.
+    pub cpu_node: usize,
+    /// Node the memory was bound to.
+    pub memory_node: usize,
+    /// Median load-to-use latency of the chase, in nanoseconds. `None` if the
+    /// pass failed.
+    pub latency_ns: Option<f64>,
+    /// Median triad bandwidth across the node's CPUs, in GiB/s. `None` if the
+    /// pass failed.
+    pub bandwidth_gib_s: Option<f64>,
+}
+
+/// Measure the matrix.
+pub fn run(settings: &Settings, progress: &Progress) -> Result<Matrix, Failure> {
+    if !numa::supported() {
+        return Err(
+            "numa: cannot bind memory to nodes on this platform (supported on Linux)".into(),
+        );
+    }
+    let nodes = numa::detect();
+    if nodes.is_empty() {
+        return Err("numa: cannot read the node layout from /sys/devices/system/node".into());
+    }
+    let allowed: Vec<usize> = affinity::Topology::detect()
+        .map(|t| t.cpus.iter().map(|c| c.cpu).collect())
+        .unwrap_or_default();
+    let latency = threadstone_workloads::by_id("latency").expect("latency is built in");
+    let stream = threadstone_workloads::by_id("stream").expect("stream is built in");
+
+    let mut cells = Vec::new();
+    for from in &nodes {
+        // CPUs outside this process's set cannot be pinned to, so a node
+        // fenced off by `taskset` or a cpuset is left out.
+        let cpus: Vec<usize> = from
+            .cpus
+            .iter()
+            .copied()
+            .filter(|cpu| allowed.contains(cpu))
+            .collect();
+        if cpus.is_empty() {
+            continue;
+        }
+        for to in nodes.iter().filter(|n| n.has_memory()) {
+            cells.push(Cell {
+                cpu_node: from.id,
+                memory_node: to.id,
+                latency_ns: cell(
+                    latency.as_ref(),
+                    &cpus[cpus.len() - 1..],
+                    (from.id, to.id),
+                    settings,
+                    progress,
+                )?,
+                bandwidth_gib_s: cell(
+                    stream.as_ref(),
+                    &cpus,
+                    (from.id, to.id),
+                    settings,
+                    progress,
+                )?,
+            });
+        }
+    }
+    Ok(Matrix { nodes, cells })
+}
+
+/// One pass with threads on `cpus`, which belong to `cpu_node`, and memory on
+/// `memory_node`. A failed pass is a gap in the matrix; an interrupted
+/// one ends the command.
+fn cell(
+    kernel: &dyn Kernel,
+    cpus: &[usize],
+    (cpu_node, memory_node): (usize, usize),
+    settings: &Settings,
+    progress: &Progress,
+) -> Result<Option<f64>, Failure> {
+    let info = kernel.info();
+    progress.cell(info.name, cpus.len(), cpu_node, memory_node);
+    let cfg = RunConfig {
+        threads: cpus.len(),
+        samples: settings.samples,
+        warmup: 1,
+        window: settings.window,
+        affinity: Some(Affinity::Explicit(cpus.to_vec())),
+        counters: false,
+        precision: None,
+        pages: PagePolicy::Default,
+        memory_node: Some(memory_node),
+    };
+    match runner::run(kernel, cfg, progress) {
+        Ok(m) => Ok(Some(m.value())),
+        Err(RunError::Interrupted { .. }) => Err("interrupted".into()),
+        Err(e) => {
+            progress.workload_failed(info.id, &e.to_string());
+            Ok(None)
+        }
+    }
+}
+
+/// One of a cell's two figures.
+type Figure = fn(&Cell) -> Option<f64>;
+
+impl Matrix {
+    /// Node ids that have rows, and those that have columns, in order.
+    fn axes(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut rows: Vec<usize> = self.cells.iter().map(|c| c.cpu_node).collect();
+        let mut columns: Vec<usize> = self.cells.iter().map(|c| c.memory_node).collect();
+        for axis in [&mut rows, &mut columns] {
+            axis.sort_unstable();
+            axis.dedup();
+        }
+        (rows, columns)
+    }
+
+    fn get(&self, cpu_node: usize, memory_node: usize) -> Option<&Cell> {
+        self.cells
+            .iter()
+            .find(|c| c.cpu_node == cpu_node && c.memory_node == memory_node)
+    }
+
+    /// How much worse remote access is than local: the geometric mean, over
+    /// every off-diagonal cell, of its figure against the local figure in the
+    /// same row. Returned as (extra latency, lost bandwidth), both fractions.
+    /// `None` without a remote cell to compare.
+    pub fn remote_penalty(&self) -> Option<(f64, f64)> {
+        let ratios = |figure: Figure| {
+            let ratios: Vec<f64> = self
+                .cells
+                .iter()
+                .filter(|c| c.cpu_node != c.memory_node)
+                .filter_map(|c| {
+                    let local = figure(self.get(c.cpu_node, c.cpu_node)?)?;
+                    Some(figure(c)? / local)
+                })
+                .collect();
+            geometric_mean(&ratios)
+        };
+        let latency = ratios(|c| c.latency_ns)?;
+        let bandwidth = ratios(|c| c.bandwidth_gib_s)?;
+        Some((latency - 1.0, 1.0 - bandwidth))
+    }
+}
+
+/// The node layout and both matrices, as a terminal table.
+pub fn render(matrix: &Matrix) -> String {
+    let mut out = String::from("NUMA nodes\n");
+    for node in &matrix.nodes {
+        let memory = node
+            .memory_bytes
+            .map_or_else(|| "—".to_string(), |b| crate::human_bytes(b as usize));
+        let distances = node
+            .distances
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.push_str(&format!(
+            "  node {:<3} cpus {:<14} {:>9}   distances {}\n",
+            node.id,
+            if node.cpus.is_empty() {
+                "none".to_string()
+            } else {
+                format_cpu_list(&node.cpus)
+            },
+            memory,
+            if distances.is_empty() {
+                "—"
+            } else {
+                &distances
+            },
+        ));
+    }
+
+    let (rows, columns) = matrix.axes();
+    let tables: [(&str, Figure); 2] = [
+        (
+            "Latency (ns), one thread on the row's node, memory on the column's",
+            |c| c.latency_ns,
+        ),
+        (
+            "Bandwidth (GiB/s), every CPU of the row's node, memory on the column's",
+            |c| c.bandwidth_gib_s,
+        ),
+    ];
+    for (title, figure) in tables {
+        out.push_str(&format!("\n{title}\n{:>10}", ""));
+        for column in &columns {
+            out.push_str(&format!("{:>10}", format!("mem {column}")));
+        }
+        out.push('\n');
+        for &row in &rows {
+            out.push_str(&format!("{:>10}", format!("cpus {row}")));
+            for &column in &columns {
+                let value = matrix.get(row, column).and_then(figure);
+                out.push_str(&format!(
+                    "{:>10}",
+                    value.map_or_else(|| "—".to_string(), |v| format!("{v:.1}"))
+                ));
+            }
+            out.push('\n');
+        }
+    }
+
+    out.push('\n');
+    match matrix.remote_penalty() {
+        Some((latency, bandwidth)) => out.push_str(&format!(
+            "Remote memory costs {:+.0}% latency and {:.0}% of bandwidth against local.\n",
+            latency * 100.0,
+            bandwidth * 100.0
+        )),
+        None if columns.len() < 2 => {
+            out.push_str("One memory node: every access is local, so there is no remote penalty.\n")
+        }
+        None => out.push_str("No local and remote pair both measured; no remote penalty.\n"),
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: usize, cpus: Vec<usize>) -> NumaNode {
+        NumaNode {
+            id,
+            cpus,
+            memory_bytes: Some(64 << 30),
+            distances: if id == 0 { vec![10, 21] } else { vec![21, 10] },
+        }
+    }
+
+    fn cell(cpu_node: usize, memory_node: usize, latency: f64, bandwidth: f64) -> Cell {
+        Cell {
+            cpu_node,
+            memory_node,
+            latency_ns: Some(latency),
+            bandwidth_gib_s: Some(bandwidth),
+        }
+    }
+
+    #[test]
+    fn the_remote_penalty_compares_each_row_with_its_own_diagonal() {
+        let matrix = Matrix {
+            nodes: vec![node(0, vec![0, 1]), node(1, vec![2, 3])],
+            cells: vec![
+                cell(0, 0, 100.0, 40.0),
+                cell(0, 1, 150.0, 20.0),
+                // A slower node still costs +50% and half its bandwidth
+                // against its own local figure.
+                cell(1, 0, 180.0, 15.0),
+                cell(1, 1, 120.0, 30.0),
+            ],
+        };
+        let (latency, bandwidth) = matrix.remote_penalty().unwrap();
+        assert!((latency - 0.5).abs() < 1e-9, "{latency}");
+        assert!((bandwidth - 0.5).abs() < 1e-9, "{bandwidth}");
+
+        let text = render(&matrix);
+        assert!(text.contains("node 0   cpus 0-1"), "{text}");
+        assert!(text.contains("distances 10 21"), "{text}");
+        assert!(text.contains("     mem 0     mem 1"), "{text}");
+        assert!(text.contains("    cpus 1     180.0     120.0"), "{text}");
+        assert!(text.contains("+50% latency and 50% of bandwidth"), "{text}");
+    }
+
+    #[test]
+    fn one_node_has_no_remote_penalty_and_a_failed_cell_is_a_gap() {
+        let mut only = cell(0, 0, 90.0, 30.0);
+        only.bandwidth_gib_s = None;
+        let matrix = Matrix {
+            nodes: vec![node(0, vec![0])],
+            cells: vec![only],
+        };
+        assert!(matrix.remote_penalty().is_none());
+        let text = render(&matrix);
+        assert!(text.contains("    cpus 0      90.0\n"), "{text}");
+        assert!(text.contains("    cpus 0         —\n"), "{text}");
+        assert!(text.contains("every access is local"), "{text}");
+    }
+}
@@ -11,9 +11,14 @@
 use std::io::{IsTerminal, Write};
 use std::sync::Mutex;
 
+use threadstone_core::budget::BudgetPlan;
+use threadstone_core::kernel::Unit;
+use threadstone_core::load::LoadSample;
 use threadstone_core::runner::{Measurement, Observer};
 use threadstone_core::suite::SuiteObserver;
 
+use crate::compare::Delta;
+
 /// Writes single-line progress to stderr.
 pub struct Progress {
     /// Suppresses all output.
@@ -43,6 +48,73 @@ impl Progress {
         }
     }
 
+    /// Announce invocation `n` of a `--repeat` run.
+    pub fn invocation(&self, n: u32, of: u32) {
+        self.emit(&format!("run {n} of {of}"));
+    }
+
+    /// Label the passes of round `round` of `threadstone ab` for one side.
+    pub fn round(&self, name: &str, threads: usize, round: u32, rounds: u32, side: &str) {
+        self.set_label(format!(
+            "{name} ({}) · round {round} of {rounds}, {side}",
+            thread_count(threads)
+        ));
+    }
+
+    /// Label the pass for one cell of the `threadstone numa` matrix.
+    pub fn cell(&self, name: &str, threads: usize, cpu_node: usize, memory_node: usize) {
+        self.set_label(format!(
+            "{name} ({}) · cpus {cpu_node}, memory {memory_node}",
+            thread_count(threads)
+        ));
+    }
+
+    /// Show the core pair `threadstone c2c` is measuring.
+    pub fn core_pair(&self, (a, b): (usize, usize), done: usize, total: usize) {
+        self.draw(&format!(
+            "  core to core · CPUs {a} and {b} · {done} of {total} pairs"
+        ));
+    }
+
+    /// Show the size `threadstone sweep` is measuring.
+    pub fn chase_point(&self, size: &str) {
+        self.draw(&format!("  latency · chase over {size}"));
+    }
+
+    /// Show the chain count `threadstone mlp` is measuring.
+    pub fn mlp_point(&self, chains: usize) {
+        self.draw(&format!("  mlp · {chains} chains"));
+    }
+
+    /// Show the page count `threadstone sweep --tlb` is measuring.
+    pub fn tlb_point(&self, page: &str, pages: usize) {
+        self.draw(&format!("  tlb · chase over {pages} pages of {page}"));
+    }
+
+    /// Show the point `threadstone sweep --bandwidth` is measuring.
+    pub fn sweep_point(&self, kernel: &str, size: &str, threads: usize) {
+        self.draw(&format!(
+            "  bandwidth · {kernel} over {size} ({})",
+            thread_count(threads)
+        ));
+    }
+
+    /// Report one workload's paired rounds once `threadstone ab` has them all.
+    pub fn paired(&self, name: &str, threads: usize, delta: &Delta, unit: Unit) {
+        let side = |v: Option<f64>| v.map_or_else(|| "—".to_string(), crate::render::si);
+        self.emit(&format!(
+            "  {:<28} {:>10} → {:>10} {}  {} {}",
+            format!("{name} ({})", thread_count(threads)),
+            side(delta.baseline),
+            side(delta.candidate),
+            unit.label(),
+            delta.verdict.glyph(),
+            delta
+                .percent
+                .map_or_else(|| "—".to_string(), |p| format!("{p:+.1}%")),
+        ));
+    }
+
     /// Clear any in-progress line. Call once the run is complete.
     pub fn finish(&self) {
         if self.quiet {
@@ -133,21 +205,57 @@ impl Observer for Progress {
             m.summary.cv * 100.0,
         ));
     }
+
+    fn interrupted(&self) -> bool {
+        crate::interrupt::requested()
+    }
 }
 
 impl SuiteObserver for Progress {
     fn workload_start(&self, _id: &str, name: &str, threads: usize) {
-        let suffix = if threads == 1 {
-            "1 thread".to_string()
-        } else {
-            format!("{threads} threads")
-        };
-        self.set_label(format!("{name} ({suffix})"));
+        self.set_label(format!("{name} ({})", thread_count(threads)));
     }
 
     fn workload_failed(&self, id: &str, error: &str) {
         self.emit(&format!("  {id}: FAILED — {error}"));
     }
+
+    fn probing(&self, _id: &str, name: &str, threads: usize) {
+        self.draw(&format!("  estimating {name} ({})…", thread_count(threads)));
+    }
+
+    fn waiting_for_idle(&self, sample: &LoadSample) {
+        self.draw(&format!(
+            "  waiting for the machine to settle before {}: {:.0}% busy, {} runnable…",
+            sample.before,
+            sample.contention() * 100.0,
+            sample.run_queue
+        ));
+    }
+
+    fn planned(&self, plan: &BudgetPlan) {
+        let mut line = format!(
+            "  budget {:.0} s: {} ms windows, {} warmup, {} samples, about {:.0} s",
+            plan.budget_ms as f64 / 1e3,
+            plan.window_ms,
+            plan.warmup,
+            plan.samples,
+            plan.estimated_ms as f64 / 1e3,
+        );
+        if !plan.dropped.is_empty() {
+            line.push_str(&format!("; left out {}", plan.dropped.join(", ")));
+        }
+        self.emit(&line);
+    }
+}
+
+/// `1 thread`, `14 threads`.
+fn thread_count(threads: usize) -> String {
+    if threads == 1 {
+        "1 thread".to_string()
+    } else {
+        format!("{threads} threads")
+    }
 }
 
 #[cfg(test)]
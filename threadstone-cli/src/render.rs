@@ -1,12 +1,22 @@
-//! Human-readable rendering of a [`Report`].
+//! Human-readable rendering of a [`Report`], and of a [`SweepReport`].
 //!
 //! Two audiences, two formats: a terminal table for someone who just ran the
 //! benchmark, and Markdown for pasting into an issue or a README. Both show the
 //! same facts, including the ones a benchmark tool is tempted to hide — how
 //! variable each measurement was, and which numbers are not to be trusted.
 
-use threadstone_core::report::{Pass, Report, WorkloadReport};
+use threadstone_core::coherence::CoreToCore;
+use threadstone_core::counters::{CounterReport, CounterStatus};
+use threadstone_core::hierarchy::Disagreement;
+use threadstone_core::pages::{HugePages, PagePolicy};
+use threadstone_core::report::{Pass, Report, ScalingCurve, WorkloadReport};
+use threadstone_core::runner::Stop;
 use threadstone_core::stats::Stability;
+use threadstone_core::sweep::{
+    Access, BandwidthSweep, LatencySweep, LoadedSweep, MlpSweep, SweepReport, TlbProbe, TlbSweep,
+};
+use threadstone_core::sysinfo::{CoreClass, SystemInfo};
+use threadstone_workloads::mlp;
 
 /// Whether to emit ANSI colour.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -132,11 +142,15 @@ pub fn table(report: &Report, color: Color) -> String {
         report.system.describe()
     )));
     out.push_str(&color.dim(&format!(
-        "{} · {} samples of {} ms after {} warmup · {:.1}s total\n\n",
+        "{} · {} of {} ms after {} warmup{}{}{}{} · {:.1}s total\n\n",
         report.generated_at,
-        report.config.samples,
+        sample_plan(report),
         report.config.window_ms,
         report.config.warmup,
+        placement(report),
+        pages(report),
+        isolation(report),
+        budget(report),
         report.duration_secs,
     )));
 
@@ -162,10 +176,731 @@ pub fn table(report: &Report, color: Color) -> String {
 
     out.push_str(&color.dim(&format!("{}\n", "─".repeat(68))));
     out.push_str(&score_line(report, color));
+    out.push_str(&sub_results(report, color));
+    out.push_str(&scaling_curves(report, color));
+    out.push_str(&clocks(report, color));
+    out.push_str(&repeats(report, color));
+    out.push_str(&counters(report, color));
+    out.push_str(&core_classes(report, color));
+    if let Some(matrix) = &report.core_to_core {
+        out.push('\n');
+        out.push_str(&core_to_core(matrix, color));
+    }
     out.push_str(&caveats(report, color));
     out
 }
 
+/// Unscored companion figures, under the workload they were measured beside.
+fn sub_results(report: &Report, color: Color) -> String {
+    const W_NAME: usize = 20;
+    const W_UNIT: usize = 9;
+    const W_VALUE: usize = 12;
+
+    let rows: Vec<&WorkloadReport> = report
+        .workloads
+        .iter()
+        .filter(|w| !w.sub_results.is_empty())
+        .collect();
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from("\n");
+    out.push_str(&color.bold("Sub-results"));
+    out.push_str(&color.dim(" · unscored, measured beside their workload\n"));
+    for w in rows {
+        for sub in &w.sub_results {
+            let value = |p: &Option<Pass>| p.as_ref().map_or("—".to_string(), |p| si(p.value));
+            out.push_str(&format!(
+                "{}{}{}{}\n",
+                pad(&sub.name, W_NAME),
+                pad(w.unit.label(), W_UNIT),
+                rpad(&value(&sub.single_thread), W_VALUE),
+                rpad(&value(&sub.multi_thread), W_VALUE),
+            ));
+        }
+    }
+    out
+}
+
+/// Single-thread value on each class of core, and how far apart they are.
+fn core_classes(report: &Report, color: Color) -> String {
+    const W_NAME: usize = 20;
+    const W_VALUE: usize = 12;
+
+    let rows: Vec<&WorkloadReport> = report
+        .workloads
+        .iter()
+        .filter(|w| !w.core_classes.is_empty())
+        .collect();
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from("\n");
+    out.push_str(&color.bold("Core classes"));
+    out.push_str(&color.dim(" · one thread pinned to each\n"));
+    let mut header = pad("Workload", W_NAME);
+    for class in CoreClass::ALL {
+        header.push_str(&rpad(class.label(), W_VALUE));
+    }
+    header.push_str(&rpad("E/P", W_VALUE));
+    out.push_str(&color.dim(&format!("{header}\n")));
+
+    for w in rows {
+        let value = |class| {
+            w.core_classes
+                .iter()
+                .find(|c| c.class == class)
+                .map(|c| c.pass.value)
+        };
+        let mut row = pad(&w.name, W_NAME);
+        for class in CoreClass::ALL {
+            row.push_str(&rpad(&value(class).map_or("—".to_string(), si), W_VALUE));
+        }
+        let relative = match (value(CoreClass::Performance), value(CoreClass::Efficiency)) {
+            (Some(p), Some(e)) => class_ratio(p, e, w.unit.higher_is_better()),
+            _ => "—".to_string(),
+        };
+        row.push_str(&rpad(&relative, W_VALUE));
+        out.push_str(&format!("{row}\n"));
+    }
+    out
+}
+
+/// One-way latency between every pair of cores, as a heatmap: one character
+/// per pair, shaded from the fastest pair to the slowest.
+pub fn core_to_core(matrix: &CoreToCore, color: Color) -> String {
+    const SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+
+    let mut out = color.bold("Core to core");
+    out.push_str(&color.dim(&format!(
+        " · one-way cache-line latency · {}\n",
+        c2c_summary(matrix)
+    )));
+    let values: Vec<f64> = matrix
+        .latency_ns
+        .iter()
+        .flatten()
+        .flatten()
+        .copied()
+        .collect();
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let label = matrix
+        .cpus
+        .iter()
+        .map(|c| c.to_string().len())
+        .max()
+        .unwrap_or(1);
+
+    for (i, row) in matrix.latency_ns.iter().enumerate() {
+        let mut line = format!("  {:>label$} ", matrix.cpus[i]);
+        for (j, value) in row.iter().enumerate() {
+            line.push(match value {
+                _ if i == j => '·',
+                None => ' ',
+                Some(v) if max > min => {
+                    let step = ((v - min) / (max - min) * SHADES.len() as f64) as usize;
+                    SHADES[step.min(SHADES.len() - 1)]
+                }
+                Some(_) => SHADES[0],
+            });
+        }
+        out.push_str(&format!("{line}\n"));
+    }
+    if !values.is_empty() {
+        out.push_str(&color.dim(&format!(
+            "  {} {min:.0} ns  …  {} {max:.0} ns\n",
+            SHADES[0],
+            SHADES[SHADES.len() - 1]
+        )));
+    }
+    out
+}
+
+/// Intra-cluster and inter-cluster figures, with what they were taken over.
+fn c2c_summary(matrix: &CoreToCore) -> String {
+    let ns = |v: Option<f64>| v.map_or("—".to_string(), |v| format!("{v:.0} ns"));
+    format!(
+        "intra-cluster {} · inter-cluster {} ({} cores, {} clusters)",
+        ns(matrix.intra_cluster_ns),
+        ns(matrix.inter_cluster_ns),
+        matrix.cpus.len(),
+        matrix.cluster_count()
+    )
+}
+
+/// A sweep document as tables: the latency curve with the levels read off it,
+/// or bandwidth by size, kernel and thread count.
+pub fn sweep(document: &SweepReport) -> String {
+    let mut out = String::new();
+    if let Some(latency) = &document.latency {
+        out.push_str(&latency_sweep(latency, &document.system));
+    }
+    if let Some(bandwidth) = &document.bandwidth {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&bandwidth_sweep(bandwidth));
+    }
+    if let Some(tlb) = &document.tlb {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&tlb_sweep(tlb));
+    }
+    if let Some(mlp) = &document.mlp {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&mlp_sweep(mlp));
+    }
+    if let Some(loaded) = &document.loaded {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&loaded_sweep(loaded));
+    }
+    out
+}
+
+fn latency_sweep(sweep: &LatencySweep, system: &SystemInfo) -> String {
+    let mut out = format!("{:>12}  {:>10}  {:>6}\n", "working set", "latency", "cv");
+    for point in &sweep.points {
+        out.push_str(&format!(
+            "{:>12}  {:>7.1} ns  {:>5.1}%\n",
+            crate::human_bytes(point.bytes),
+            point.latency_ns,
+            point.stats.cv * 100.0
+        ));
+    }
+
+    if sweep.levels.is_empty() {
+        out.push_str(
+            "\nNo plateau spans an octave; a finer or longer sweep may find the levels.\n",
+        );
+        return out;
+    }
+    out.push_str("\nLevels read off the curve:\n");
+    for level in &sweep.levels {
+        let extent = match level.capacity_bytes {
+            Some(bytes) => format!("up to {}", crate::human_bytes(bytes)),
+            None => format!(
+                "from {}, past the sweep",
+                crate::human_bytes(level.from_bytes)
+            ),
+        };
+        out.push_str(&format!("  {extent:<28} {:>7.1} ns\n", level.latency_ns));
+    }
+
+    if [system.l1d_bytes, system.l2_bytes, system.l3_bytes]
+        .iter()
+        .all(Option::is_none)
+    {
+        out.push_str("\nThe system reports no cache sizes to check these against.\n");
+    } else if sweep.disagreements.is_empty() {
+        out.push_str("\nEvery reported cache size agrees with a plateau.\n");
+    } else {
+        out.push_str("\nAgainst the reported cache sizes:\n");
+        for d in &sweep.disagreements {
+            out.push_str(&format!("  ! {}\n", disagreement(d)));
+        }
+    }
+    out
+}
+
+/// One line on where a latency sweep and the reported cache sizes part.
+pub fn disagreement(d: &Disagreement) -> String {
+    let bytes = |b: u64| crate::human_bytes(b as usize);
+    match d {
+        Disagreement::Misplaced {
+            cache,
+            reported_bytes,
+            inferred_bytes,
+        } => format!(
+            "{cache} is reported as {}, but the nearest plateau ends at {}",
+            bytes(*reported_bytes),
+            crate::human_bytes(*inferred_bytes)
+        ),
+        Disagreement::Missing {
+            cache,
+            reported_bytes,
+        } => format!(
+            "{cache} is reported as {}, but no plateau is left to match it",
+            bytes(*reported_bytes)
+        ),
+        Disagreement::Unreported {
+            capacity_bytes,
+            latency_ns,
+        } => format!(
+            "a plateau ends at {} ({latency_ns:.1} ns) where no reported cache does",
+            crate::human_bytes(*capacity_bytes)
+        ),
+    }
+}
+
+fn mlp_sweep(sweep: &MlpSweep) -> String {
+    let Some(single) = sweep.points.first().map(|p| p.ns_per_access) else {
+        return "No chain count was measured.\n".to_string();
+    };
+    let mut out = format!(
+        "{:>8}  {:>12}  {:>8}  {:>6}\n",
+        "chains", "per access", "speedup", "cv"
+    );
+    for point in &sweep.points {
+        out.push_str(&format!(
+            "{:>8}  {:>9.1} ns  {:>7.1}×  {:>5.1}%\n",
+            point.chains,
+            point.ns_per_access,
+            single / point.ns_per_access,
+            point.stats.cv * 100.0
+        ));
+    }
+    if let (Some(saturated), Some(in_flight)) = (
+        mlp::saturation(&sweep.points),
+        mlp::in_flight(&sweep.points),
+    ) {
+        out.push_str(&format!(
+            "\nSaturates at {} chains, {:.1} ns an access: about {in_flight:.1} misses in flight.\n",
+            saturated.chains, saturated.ns_per_access
+        ));
+    }
+    if sweep.pages != PagePolicy::Default {
+        out.push_str(&format!("Chased on {} pages.\n", sweep.pages.label()));
+    }
+    out.push_str("Not scored: the suite's `latency` figure is always one chain.\n");
+    out
+}
+
+fn loaded_sweep(sweep: &LoadedSweep) -> String {
+    let Some(idle) = sweep.points.first() else {
+        return "No load level was measured.\n".to_string();
+    };
+    let mut out = format!("{:>8}  {:>12}  {:>10}\n", "delay", "bandwidth", "latency");
+    for point in &sweep.points {
+        out.push_str(&format!(
+            "{:>8}  {:>7.1} GiB/s  {:>7.1} ns\n",
+            point
+                .delay
+                .map_or_else(|| "idle".to_string(), |d| d.to_string()),
+            point.bandwidth_gib_s,
+            point.latency_ns
+        ));
+    }
+    if let Some(peak) = sweep
+        .points
+        .iter()
+        .max_by(|a, b| a.bandwidth_gib_s.total_cmp(&b.bandwidth_gib_s))
+        .filter(|p| p.delay.is_some())
+    {
+        out.push_str(&format!(
+            "\nLatency rose from {:.1} ns idle to {:.1} ns at {:.1} GiB/s ({:+.0}%), \
+             {} injector(s).\n",
+            idle.latency_ns,
+            peak.latency_ns,
+            peak.bandwidth_gib_s,
+            (peak.latency_ns / idle.latency_ns - 1.0) * 100.0,
+            sweep.injectors
+        ));
+    }
+    if sweep.pages != PagePolicy::Default {
+        out.push_str(&format!("Measured on {} pages.\n", sweep.pages.label()));
+    }
+    out.push_str("Not scored: the suite's `latency` figure is always measured idle.\n");
+    out
+}
+
+fn tlb_sweep(sweep: &TlbSweep) -> String {
+    if sweep.probes.is_empty() {
+        return "No page size could be probed.\n".to_string();
+    }
+    let probes: Vec<String> = sweep.probes.iter().map(tlb_probe).collect();
+    probes.join("\n")
+}
+
+fn tlb_probe(probe: &TlbProbe) -> String {
+    let page = crate::human_bytes(probe.page_bytes);
+    let mut out = format!(
+        "{page} pages\n{:>8}  {:>10}  {:>10}  {:>10}  {:>11}\n",
+        "pages", "span", "latency", "baseline", "translation"
+    );
+    for point in &probe.points {
+        out.push_str(&format!(
+            "{:>8}  {:>10}  {:>7.1} ns  {:>7.1} ns  {:>+8.1} ns\n",
+            point.pages,
+            crate::human_bytes(point.pages * probe.page_bytes),
+            point.latency_ns,
+            point.baseline_ns,
+            point.translation_ns()
+        ));
+    }
+
+    let reach = &probe.reach;
+    let Some(l1) = reach.l1_pages else {
+        out.push_str("\nNo plateau spans an octave; a finer or longer probe may find the TLBs.\n");
+        return out;
+    };
+    let pages = |n: usize| format!("{n} pages ({})", crate::human_bytes(n * probe.page_bytes));
+    out.push_str(&format!("\n  L1 DTLB reach   {}\n", pages(l1)));
+    match reach.l2_pages {
+        Some(l2) => out.push_str(&format!("  STLB reach      {}\n", pages(l2))),
+        None => out.push_str("  STLB reach      past the probe\n"),
+    }
+    match (reach.l2_pages, reach.walk_ns) {
+        (_, Some(walk)) => out.push_str(&format!("  page walk       {walk:+.1} ns per access\n")),
+        (Some(_), None) => out.push_str("  page walk       no plateau past the STLB\n"),
+        (None, None) => {}
+    }
+    out
+}
+
+fn bandwidth_sweep(sweep: &BandwidthSweep) -> String {
+    let columns: Vec<(Access, usize)> = sweep
+        .threads
+        .iter()
+        .flat_map(|&n| Access::ALL.map(|a| (a, n)))
+        .collect();
+    let mut sizes: Vec<usize> = sweep.points.iter().map(|p| p.bytes).collect();
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    let mut out = format!("{:>12}", "working set");
+    for (access, n) in &columns {
+        out.push_str(&format!("  {:>10}", format!("{} {n}t", access.id())));
+    }
+    out.push('\n');
+    for bytes in sizes {
+        out.push_str(&format!("{:>12}", crate::human_bytes(bytes)));
+        for &(access, n) in &columns {
+            let point = sweep
+                .points
+                .iter()
+                .find(|p| p.bytes == bytes && p.access == access && p.threads == n);
+            match point {
+                Some(p) => out.push_str(&format!("  {:>10.1}", p.bandwidth_gib_s)),
+                None => out.push_str(&format!("  {:>10}", "—")),
+            }
+        }
+        out.push('\n');
+    }
+    out.push_str("\nGiB/s. Plateaus mark cache levels; each step down is a level boundary.\n");
+    out
+}
+
+/// Effective core clock of each pass, and the headline figure per cycle.
+fn clocks(report: &Report, color: Color) -> String {
+    const W_NAME: usize = 20;
+    const W_UNIT: usize = 12;
+    const W_VALUE: usize = 10;
+
+    let threads = report.config.threads;
+    let rows: Vec<&WorkloadReport> = report
+        .workloads
+        .iter()
+        .filter(|w| passes(w).any(|p| p.clock.is_some()))
+        .collect();
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from("\n");
+    out.push_str(&color.bold("Clock"));
+    out.push_str(&color.dim(" · effective GHz and each result per clock, per thread\n"));
+    out.push_str(&color.dim(&format!(
+        "{}{}{}{}{}{}\n",
+        pad("Workload", W_NAME),
+        pad("Per clock", W_UNIT),
+        rpad("1t GHz", W_VALUE),
+        rpad("1t", W_VALUE),
+        rpad(&format!("{threads}t GHz"), W_VALUE),
+        rpad(&format!("{threads}t"), W_VALUE),
+    )));
+
+    for w in rows {
+        let unit = passes(w)
+            .find_map(|p| p.clock.as_ref())
+            .map_or("", |c| c.per_clock_unit.as_str());
+        let mut row = pad(&w.name, W_NAME) + &pad(unit, W_UNIT);
+        for pass in [&w.single_thread, &w.multi_thread] {
+            let clock = pass.as_ref().and_then(|p| p.clock.as_ref());
+            row.push_str(&rpad(
+                &clock.map_or("—".to_string(), |c| format!("{:.2}", c.ghz)),
+                W_VALUE,
+            ));
+            row.push_str(&rpad(
+                &clock.map_or("—".to_string(), |c| si(c.per_clock)),
+                W_VALUE,
+            ));
+        }
+        out.push_str(&format!("{row}\n"));
+    }
+    out
+}
+
+/// Spread within and between the invocations of a `--repeat` run.
+fn repeats(report: &Report, color: Color) -> String {
+    const W_NAME: usize = 20;
+    const W_VALUE: usize = 10;
+
+    let threads = report.config.threads;
+    let rows: Vec<&WorkloadReport> = report
+        .workloads
+        .iter()
+        .filter(|w| passes(w).any(|p| p.repeats.is_some()))
+        .collect();
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from("\n");
+    out.push_str(&color.bold("Between runs"));
+    out.push_str(&color.dim(&format!(
+        " · cv within one run and between {} runs, and the combined ±95%\n",
+        report.config.repeat.unwrap_or(0)
+    )));
+    let mut header = pad("Workload", W_NAME);
+    for t in ["1t".to_string(), format!("{threads}t")] {
+        header.push_str(&rpad(&format!("{t} within"), W_VALUE));
+        header.push_str(&rpad("between", W_VALUE));
+        header.push_str(&rpad("±95%", W_VALUE));
+    }
+    out.push_str(&color.dim(&format!("{header}\n")));
+
+    for w in rows {
+        let mut row = pad(&w.name, W_NAME);
+        for pass in [&w.single_thread, &w.multi_thread] {
+            let cells = match pass.as_ref().and_then(|p| Some((p, p.repeats.as_ref()?))) {
+                Some((p, r)) => [
+                    format!("{:.1}%", r.within_cv * 100.0),
+                    format!("{:.1}%", r.between_cv * 100.0),
+                    format!("{:.1}%", r.ci95 / p.value * 100.0),
+                ],
+                None => ["—".to_string(), "—".to_string(), "—".to_string()],
+            };
+            for cell in cells {
+                row.push_str(&rpad(&cell, W_VALUE));
+            }
+        }
+        out.push_str(&format!("{row}\n"));
+    }
+    out
+}
+
+/// Hardware counter figures for every scored pass that has them.
+fn counters(report: &Report, color: Color) -> String {
+    const W_NAME: usize = 20;
+    const W_THREADS: usize = 8;
+    const W_VALUE: usize = 10;
+
+    let rows: Vec<(&WorkloadReport, &Pass, &CounterReport)> = report
+        .workloads
+        .iter()
+        .flat_map(|w| passes(w).filter_map(move |p| p.counters.as_ref().map(|c| (w, p, c))))
+        .collect();
+    if rows.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from("\n");
+    out.push_str(&color.bold("Counters"));
+    out.push_str(&color.dim(" · over the measured rounds, user space only\n"));
+    out.push_str(&color.dim(&format!(
+        "{}{}{}{}{}{}\n",
+        pad("Workload", W_NAME),
+        rpad("threads", W_THREADS),
+        rpad("IPC", W_VALUE),
+        rpad("br-miss", W_VALUE),
+        rpad("L1D MPKI", W_VALUE),
+        rpad("LLC MPKI", W_VALUE),
+    )));
+    for (w, pass, c) in rows {
+        let cells = counter_cells(c);
+        let mut row = pad(&w.name, W_NAME) + &rpad(&pass.threads.to_string(), W_THREADS);
+        for cell in cells {
+            row.push_str(&rpad(&cell, W_VALUE));
+        }
+        out.push_str(&format!("{row}\n"));
+    }
+    out
+}
+
+/// IPC, branch-miss rate, L1D MPKI and LLC MPKI, formatted, with a dash for
+/// each figure whose events the PMU did not offer.
+fn counter_cells(c: &CounterReport) -> [String; 4] {
+    let mpki = |m: Option<f64>| m.map_or("—".to_string(), |m| format!("{m:.2}"));
+    [
+        format!("{:.2}", c.ipc),
+        c.branch_miss_rate
+            .map_or("—".to_string(), |r| format!("{:.2}%", r * 100.0)),
+        mpki(c.l1d_mpki),
+        mpki(c.llc_mpki),
+    ]
+}
+
+/// The scored passes of a workload that ran.
+fn passes(w: &WorkloadReport) -> impl Iterator<Item = &Pass> {
+    [&w.single_thread, &w.multi_thread].into_iter().flatten()
+}
+
+/// E-core performance as a fraction of P-core performance, direction-corrected
+/// so that 0.5 always means "half as fast".
+fn class_ratio(performance: f64, efficiency: f64, higher_is_better: bool) -> String {
+    let ratio = if higher_is_better {
+        efficiency / performance
+    } else {
+        performance / efficiency
+    };
+    if ratio.is_finite() {
+        format!("{ratio:.2}")
+    } else {
+        "—".to_string()
+    }
+}
+
+/// Speedup at every measured thread count, one row per workload with a curve.
+fn scaling_curves(report: &Report, color: Color) -> String {
+    const W_NAME: usize = 20;
+    const W_POINT: usize = 8;
+
+    let counts = &report.config.scaling;
+    let curves: Vec<(&WorkloadReport, &ScalingCurve)> = report
+        .workloads
+        .iter()
+        .filter_map(|w| w.scaling_curve.as_ref().map(|c| (w, c)))
+        .collect();
+    if curves.is_empty() {
+        return String::new();
+    }
+
+    let mut out = String::from("\n");
+    out.push_str(&color.bold("Scaling curve"));
+    out.push_str(&color.dim(" · speedup over 1 thread\n"));
+    let mut header = pad("Threads", W_NAME);
+    for n in counts {
+        header.push_str(&rpad(&n.to_string(), W_POINT));
+    }
+    header.push_str(&rpad("serial", W_POINT + 1));
+    header.push_str(&rpad("knee", W_POINT - 2));
+    out.push_str(&color.dim(&format!("{header}\n")));
+
+    for (w, curve) in curves {
+        let mut row = pad(&w.name, W_NAME);
+        for n in counts {
+            let point = curve.points.iter().find(|p| p.threads == *n);
+            let cell = point.map_or("—".to_string(), |p| format!("{:.1}x", p.speedup));
+            // Points past the knee are dimmed: the extra threads there buy
+            // less than the threshold efficiency.
+            let past_knee = point.is_some_and(|p| p.efficiency < curve.knee_efficiency);
+            let cell = rpad(&cell, W_POINT);
+            row.push_str(&if past_knee { color.dim(&cell) } else { cell });
+        }
+        let serial = curve
+            .serial_fraction
+            .map_or("—".to_string(), |s| format!("{:.1}%", s * 100.0));
+        row.push_str(&rpad(&serial, W_POINT + 1));
+        let knee = curve
+            .knee_threads
+            .map_or("—".to_string(), |k| k.to_string());
+        row.push_str(&rpad(&knee, W_POINT - 2));
+        out.push_str(&format!("{row}\n"));
+    }
+    out
+}
+
+/// `7 samples`, or `7–50 samples to ±0.5%` under adaptive sampling.
+fn sample_plan(report: &Report) -> String {
+    let min = report.config.samples;
+    let plan = match &report.config.precision {
+        Some(p) => format!(
+            "{min}–{} samples to ±{}%",
+            p.max_samples,
+            p.target_ci * 100.0
+        ),
+        None => format!("{min} samples"),
+    };
+    match report.config.repeat {
+        Some(runs) => format!("{plan} × {runs} runs"),
+        None => plan,
+    }
+}
+
+/// Scored passes that stopped before reaching their adaptive-sampling target,
+/// described as `"<workload> at <n> threads: ±0.9% after 50 rounds (…)"`.
+pub fn missed_precision(report: &Report) -> Vec<String> {
+    let mut out = Vec::new();
+    for w in &report.workloads {
+        for pass in passes(w) {
+            let Some(p) = pass.precision.as_ref().filter(|p| !p.met) else {
+                continue;
+            };
+            out.push(format!(
+                "{} at {} thread{}: ±{:.2}% after {} rounds ({})",
+                w.id,
+                pass.threads,
+                if pass.threads == 1 { "" } else { "s" },
+                p.achieved_ci * 100.0,
+                pass.samples.len(),
+                match p.stopped {
+                    Stop::Target => "target",
+                    Stop::MaxSamples => "round budget spent",
+                    Stop::MaxTime => "time budget spent",
+                }
+            ));
+        }
+    }
+    out
+}
+
+/// Scored passes that ran without the counters the run enabled, described as
+/// `"<workload> at <n> threads: <why>"`.
+pub fn missing_counters(report: &Report) -> Vec<String> {
+    let mut out = Vec::new();
+    for w in &report.workloads {
+        for pass in passes(w) {
+            if let Some(why) = &pass.counters_error {
+                out.push(format!(
+                    "{} at {} thread{}: {why}",
+                    w.id,
+                    pass.threads,
+                    if pass.threads == 1 { "" } else { "s" }
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// ` · pinned <plan>` when threads were bound, so a pinned result is never
+/// mistaken for a default one. Empty otherwise.
+fn placement(report: &Report) -> String {
+    report
+        .config
+        .affinity
+        .as_ref()
+        .map_or_else(String::new, |a| format!(" · pinned {}", a.label()))
+}
+
+fn pages(report: &Report) -> String {
+    report
+        .config
+        .pages
+        .map_or_else(String::new, |p| format!(" · {} pages", p.label()))
+}
+
+fn isolation(report: &Report) -> String {
+    report
+        .config
+        .isolate
+        .map_or_else(String::new, |i| format!(" · isolated {}", i.label()))
+}
+
+fn budget(report: &Report) -> String {
+    report.config.budget.as_ref().map_or_else(String::new, |b| {
+        format!(" · planned for {:.0} s", b.budget_ms as f64 / 1e3)
+    })
+}
+
 fn workload_row(
     w: &WorkloadReport,
     color: Color,
@@ -223,6 +958,40 @@ fn score_line(report: &Report, color: Color) -> String {
     )
 }
 
+/// The workload a signal interrupted, if the run was cut short.
+pub fn interrupted(report: &Report) -> Option<&WorkloadReport> {
+    report.workloads.iter().find(|w| w.interrupted())
+}
+
+/// Huge pages that were asked for and never obtained, which leaves the large
+/// working sets on whatever the fallback gave.
+pub fn page_shortfall(report: &Report) -> Option<String> {
+    let policy = report.config.pages?;
+    let recorded: Vec<HugePages> = report
+        .workloads
+        .iter()
+        .flat_map(|w| [&w.single_thread, &w.multi_thread])
+        .flatten()
+        .filter_map(|p| p.huge_pages)
+        .collect();
+    if recorded.is_empty() {
+        return None;
+    }
+    match policy {
+        PagePolicy::Hugetlb if recorded.iter().all(|h| h.hugetlb_kib == 0) => Some(
+            "--pages hugetlb: the hugetlbfs pool gave no pages (see vm.nr_hugepages), so \
+             the large working sets fell back to transparent huge pages"
+                .to_string(),
+        ),
+        PagePolicy::Thp if recorded.iter().all(|h| h.total_kib() == 0) => Some(
+            "--pages thp: no huge pages were obtained (transparent huge pages disabled, \
+             or memory too fragmented), so the large working sets ran on 4 KiB pages"
+                .to_string(),
+        ),
+        _ => None,
+    }
+}
+
 /// Warnings a reader needs in order to interpret the numbers correctly.
 ///
 /// Printed unconditionally when they apply. A benchmark that quietly reports an
@@ -231,6 +1000,14 @@ fn score_line(report: &Report, color: Color) -> String {
 fn caveats(report: &Report, color: Color) -> String {
     let mut notes: Vec<String> = Vec::new();
 
+    if let Some(w) = interrupted(report) {
+        notes.push(format!(
+            "interrupted during {}: only the workloads above it completed, so the \
+             scores do not compare with a full run",
+            w.id
+        ));
+    }
+
     if report.system.build_profile.debug_assertions {
         notes.push(
             "built with debug assertions: these numbers do not describe an \
@@ -257,6 +1034,28 @@ fn caveats(report: &Report, color: Color) -> String {
         ));
     }
 
+    let unsettled: Vec<&str> = report
+        .workloads
+        .iter()
+        .filter(|w| {
+            passes(w)
+                .filter_map(|p| p.repeats.as_ref())
+                .any(|r| r.between_cv > 2.0 * r.within_cv.max(0.005))
+        })
+        .map(|w| w.id.as_str())
+        .collect();
+    if !unsettled.is_empty() {
+        notes.push(format!(
+            "launches differ more than rounds do in {}: a single run of these \
+             overstates its own precision",
+            unsettled.join(", ")
+        ));
+    }
+
+    if let Some(note) = page_shortfall(report) {
+        notes.push(note);
+    }
+
     let short: Vec<&str> = report
         .workloads
         .iter()
@@ -275,6 +1074,74 @@ fn caveats(report: &Report, color: Color) -> String {
         ));
     }
 
+    if let Some(CounterStatus::Unavailable { reason }) = &report.config.counters {
+        notes.push(format!("hardware counters unavailable: {reason}"));
+    }
+    let uncounted = missing_counters(report);
+    if !uncounted.is_empty() {
+        notes.push(format!("no counters for {}", uncounted.join("; ")));
+    }
+
+    if let Some(plan) = report
+        .config
+        .budget
+        .as_ref()
+        .filter(|p| !p.dropped.is_empty())
+    {
+        notes.push(format!(
+            "{} left out to fit the {:.0} s budget: the scores cover the rest",
+            plan.dropped.join(", "),
+            plan.budget_ms as f64 / 1e3
+        ));
+    }
+
+    let missed = missed_precision(report);
+    if !missed.is_empty() {
+        let target = report
+            .config
+            .precision
+            .as_ref()
+            .map_or(0.0, |p| p.target_ci);
+        notes.push(format!(
+            "confidence target ±{}% not reached in {}",
+            target * 100.0,
+            missed.join("; ")
+        ));
+    }
+
+    let drifts = drifts(report);
+    if !drifts.is_empty() {
+        notes.push(format!(
+            "samples drifted steadily, as thermal throttling does, so the median \
+             depends on when the pass stopped: {}",
+            drifts.join("; ")
+        ));
+    }
+
+    let stragglers = stragglers(report);
+    if !stragglers.is_empty() {
+        notes.push(format!(
+            "one thread consistently finished last, setting the pass's rate: {}",
+            stragglers.join("; ")
+        ));
+    }
+
+    let loaded = loaded(report);
+    if !loaded.is_empty() {
+        notes.push(format!(
+            "other work was using the machine, so these numbers understate it: {}",
+            loaded.join("; ")
+        ));
+    }
+
+    let tuned = tuned(report);
+    if !tuned.is_empty() {
+        notes.push(format!(
+            "run away from the scored defaults, and left out of the scores: {}",
+            tuned.join("; ")
+        ));
+    }
+
     let excluded: Vec<&str> = report
         .workloads
         .iter()
@@ -298,6 +1165,100 @@ fn caveats(report: &Report, color: Color) -> String {
     out
 }
 
+/// Workloads with parameters set away from their defaults, described as
+/// `"sgemm n=1024"`.
+pub fn tuned(report: &Report) -> Vec<String> {
+    report
+        .workloads
+        .iter()
+        .filter(|w| !w.is_canonical())
+        .map(|w| format!("{} {}", w.id, w.tuned().join(", ")))
+        .collect()
+}
+
+/// Load samples taken while other work was using the machine, described as
+/// `"before <workload> at <n> threads: 34% busy, 2% stolen, 3 runnable"`.
+pub fn loaded(report: &Report) -> Vec<String> {
+    report
+        .load
+        .iter()
+        .filter(|s| s.is_loaded())
+        .map(|s| {
+            let before = if s.before == "start" {
+                "at the start".to_string()
+            } else {
+                format!("before {}", s.before)
+            };
+            format!(
+                "{before}: {:.0}% busy, {:.0}% stolen, {} runnable",
+                s.busy * 100.0,
+                s.steal * 100.0,
+                s.run_queue
+            )
+        })
+        .collect()
+}
+
+/// Passes in which one thread was consistently the straggler, described as
+/// `"<workload> at <n> threads: thread <i> (…)"`.
+pub fn stragglers(report: &Report) -> Vec<String> {
+    let mut out = Vec::new();
+    for w in &report.workloads {
+        let curve = w
+            .scaling_curve
+            .iter()
+            .flat_map(|c| c.points.iter().map(|p| &p.pass));
+        let mut seen = Vec::new();
+        for pass in [&w.single_thread, &w.multi_thread]
+            .into_iter()
+            .flatten()
+            .chain(curve)
+        {
+            // A curve point may repeat a scored pass; report each count once.
+            if seen.contains(&pass.threads) {
+                continue;
+            }
+            seen.push(pass.threads);
+            if let Some(b) = pass.balance.as_ref().filter(|b| b.straggler) {
+                out.push(format!(
+                    "{} at {} threads: thread {} (last in {:.0}% of rounds, {:.2}× the mean)",
+                    w.id,
+                    pass.threads,
+                    b.slowest_thread,
+                    b.slowest_share * 100.0,
+                    b.imbalance
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// Scored passes whose samples trended significantly across the pass,
+/// described as `"<workload> at <n> threads: slowed 2.4% across the pass (…)"`.
+pub fn drifts(report: &Report) -> Vec<String> {
+    let mut out = Vec::new();
+    for w in &report.workloads {
+        for pass in passes(w) {
+            let Some(d) = pass.stats.drift.as_ref().filter(|d| d.significant) else {
+                continue;
+            };
+            // Rising samples are an improvement only where higher is better.
+            let slowed = (d.change < 0.0) == w.unit.higher_is_better();
+            out.push(format!(
+                "{} at {} thread{}: {} {:.1}% across the pass (p = {:.3})",
+                w.id,
+                pass.threads,
+                if pass.threads == 1 { "" } else { "s" },
+                if slowed { "slowed" } else { "sped up" },
+                d.change.abs() * 100.0,
+                d.p_value
+            ));
+        }
+    }
+    out
+}
+
 /// Render a report as a Markdown table, for pasting into issues and READMEs.
 pub fn markdown(report: &Report) -> String {
     let threads = report.config.threads;
@@ -312,6 +1273,20 @@ pub fn markdown(report: &Report) -> String {
             report.score.reference
         ));
     }
+    if let Some(w) = interrupted(report) {
+        out.push_str(&format!(
+            "> **Partial run:** interrupted during `{}`; only the workloads \
+             before it completed.\n\n",
+            w.id
+        ));
+    }
+    let tuned = tuned(report);
+    if !tuned.is_empty() {
+        out.push_str(&format!(
+            "> **Tuned:** {}, away from the defaults and so unscored.\n\n",
+            tuned.join("; ")
+        ));
+    }
 
     out.push_str(&format!(
         "| Workload | Unit | 1 thread | {threads} threads | Scaling | CV |\n"
@@ -345,9 +1320,170 @@ pub fn markdown(report: &Report) -> String {
         ));
     }
 
+    let curves: Vec<(&WorkloadReport, &ScalingCurve)> = report
+        .workloads
+        .iter()
+        .filter_map(|w| w.scaling_curve.as_ref().map(|c| (w, c)))
+        .collect();
+    if !curves.is_empty() {
+        let counts = &report.config.scaling;
+        out.push_str("\n**Scaling curve** (speedup over 1 thread)\n\n| Workload |");
+        for n in counts {
+            out.push_str(&format!(" {n} |"));
+        }
+        out.push_str(" Serial fraction | Knee |\n|---|");
+        out.push_str(&"---:|".repeat(counts.len() + 2));
+        out.push('\n');
+        for (w, curve) in curves {
+            out.push_str(&format!("| {} |", w.name));
+            for n in counts {
+                let cell = curve
+                    .points
+                    .iter()
+                    .find(|p| p.threads == *n)
+                    .map_or("—".to_string(), |p| format!("{:.1}×", p.speedup));
+                out.push_str(&format!(" {cell} |"));
+            }
+            let serial = curve
+                .serial_fraction
+                .map_or("—".to_string(), |s| format!("{:.1}%", s * 100.0));
+            let knee = curve
+                .knee_threads
+                .map_or("—".to_string(), |k| k.to_string());
+            out.push_str(&format!(" {serial} | {knee} |\n"));
+        }
+    }
+
+    if report
+        .workloads
+        .iter()
+        .any(|w| passes(w).any(|p| p.clock.is_some()))
+    {
+        out.push_str(&format!(
+            "\n**Clock** (effective GHz and each result per clock, per thread)\n\n\
+             | Workload | Per clock | 1 thread GHz | 1 thread | {threads} threads GHz | {threads} threads |\n\
+             |---|---|---:|---:|---:|---:|\n"
+        ));
+        for w in report
+            .workloads
+            .iter()
+            .filter(|w| passes(w).any(|p| p.clock.is_some()))
+        {
+            let unit = passes(w)
+                .find_map(|p| p.clock.as_ref())
+                .map_or("", |c| c.per_clock_unit.as_str());
+            out.push_str(&format!("| {} | {unit} |", w.name));
+            for pass in [&w.single_thread, &w.multi_thread] {
+                match pass.as_ref().and_then(|p| p.clock.as_ref()) {
+                    Some(c) => out.push_str(&format!(" {:.2} | {} |", c.ghz, si(c.per_clock))),
+                    None => out.push_str(" — | — |"),
+                }
+            }
+            out.push('\n');
+        }
+    }
+
+    let counted: Vec<(&WorkloadReport, &Pass, &CounterReport)> = report
+        .workloads
+        .iter()
+        .flat_map(|w| passes(w).filter_map(move |p| p.counters.as_ref().map(|c| (w, p, c))))
+        .collect();
+    if !counted.is_empty() {
+        out.push_str(
+            "\n**Counters** (measured rounds, user space only)\n\n\
+             | Workload | Threads | IPC | Branch miss | L1D MPKI | LLC MPKI |\n\
+             |---|---:|---:|---:|---:|---:|\n",
+        );
+        for (w, pass, c) in counted {
+            let [ipc, branch, l1d, llc] = counter_cells(c);
+            out.push_str(&format!(
+                "| {} | {} | {ipc} | {branch} | {l1d} | {llc} |\n",
+                w.name, pass.threads
+            ));
+        }
+    }
+    if let Some(CounterStatus::Unavailable { reason }) = &report.config.counters {
+        out.push_str(&format!("\n> **Counters unavailable:** {reason}\n"));
+    }
+    for uncounted in missing_counters(report) {
+        out.push_str(&format!("\n> **No counters:** {uncounted}\n"));
+    }
+
+    if report.workloads.iter().any(|w| !w.sub_results.is_empty()) {
+        out.push_str(&format!(
+            "\n**Sub-results** (unscored)\n\n\
+             | Result | Unit | 1 thread | {} threads |\n|---|---|---:|---:|\n",
+            report.config.threads
+        ));
+        for w in &report.workloads {
+            for sub in &w.sub_results {
+                let value = |p: &Option<Pass>| p.as_ref().map_or("—".to_string(), |p| si(p.value));
+                out.push_str(&format!(
+                    "| {} | {} | {} | {} |\n",
+                    sub.name,
+                    w.unit.label(),
+                    value(&sub.single_thread),
+                    value(&sub.multi_thread),
+                ));
+            }
+        }
+    }
+
+    if report.workloads.iter().any(|w| !w.core_classes.is_empty()) {
+        out.push_str("\n**Core classes** (one thread pinned to each)\n\n");
+        out.push_str("| Workload | P-core | E-core | E/P |\n|---|---:|---:|---:|\n");
+        for w in report
+            .workloads
+            .iter()
+            .filter(|w| !w.core_classes.is_empty())
+        {
+            let value = |class| {
+                w.core_classes
+                    .iter()
+                    .find(|c| c.class == class)
+                    .map(|c| c.pass.value)
+            };
+            let (p, e) = (value(CoreClass::Performance), value(CoreClass::Efficiency));
+            let relative = match (p, e) {
+                (Some(p), Some(e)) => class_ratio(p, e, w.unit.higher_is_better()),
+                _ => "—".to_string(),
+            };
+            out.push_str(&format!(
+                "| {} | {} | {} | {relative} |\n",
+                w.name,
+                p.map_or("—".to_string(), si),
+                e.map_or("—".to_string(), si),
+            ));
+        }
+    }
+
+    if let Some(matrix) = &report.core_to_core {
+        out.push_str(&format!("\n**Core to core:** {}\n", c2c_summary(matrix)));
+    }
+
+    for missed in missed_precision(report) {
+        out.push_str(&format!("\n> **Confidence target missed:** {missed}\n"));
+    }
+    for drift in drifts(report) {
+        out.push_str(&format!("\n> **Drift:** {drift}\n"));
+    }
+    for straggler in stragglers(report) {
+        out.push_str(&format!("\n> **Straggler:** {straggler}\n"));
+    }
+    for load in loaded(report) {
+        out.push_str(&format!("\n> **Loaded machine:** {load}\n"));
+    }
+
     out.push_str(&format!(
-        "\n<sub>{} · {} samples of {} ms · generated {}</sub>\n",
-        report.system.target, report.config.samples, report.config.window_ms, report.generated_at,
+        "\n<sub>{} · {} of {} ms{}{}{}{} · generated {}</sub>\n",
+        report.system.target,
+        sample_plan(report),
+        report.config.window_ms,
+        placement(report),
+        pages(report),
+        isolation(report),
+        budget(report),
+        report.generated_at,
     ));
     out
 }
@@ -405,6 +1541,34 @@ mod tests {
             .contains('\x1b'));
     }
 
+    #[test]
+    fn class_ratio_reads_as_relative_speed_in_either_direction() {
+        assert_eq!(class_ratio(100.0, 60.0, true), "0.60");
+        // Latency: an E-core at 150 ns against a P-core at 90 ns is 0.6× as fast.
+        assert_eq!(class_ratio(90.0, 150.0, false), "0.60");
+        assert_eq!(class_ratio(0.0, 1.0, true), "—");
+    }
+
+    #[test]
+    fn the_heatmap_shades_from_fastest_to_slowest_pair() {
+        let (near, far) = (Some(20.0), Some(100.0));
+        let matrix = CoreToCore::new(
+            vec![0, 8, 16],
+            vec![0, 0, 1],
+            vec![
+                vec![None, near, far],
+                vec![near, None, None],
+                vec![far, None, None],
+            ],
+        );
+        let text = core_to_core(&matrix, Color::Never);
+        assert!(text.contains("intra-cluster 20 ns · inter-cluster 100 ns (3 cores, 2 clusters)"));
+        assert!(text.contains("\n   0 ·░█\n"), "{text}");
+        assert!(text.contains("\n   8 ░· \n"), "{text}");
+        assert!(text.contains("\n  16 █ ·\n"), "{text}");
+        assert!(text.contains("░ 20 ns  …  █ 100 ns"), "{text}");
+    }
+
     #[test]
     fn color_always_emits_escapes() {
         assert!(Color::Always.bold("x").contains('\x1b'));
@@ -16,7 +16,16 @@
 
 use std::path::Path;
 
-use threadstone_core::report::{Report, SCHEMA_VERSION};
+use threadstone_core::budget::BudgetPlan;
+use threadstone_core::coherence::CoreToCore;
+use threadstone_core::counters::{CounterReport, CounterStatus};
+use threadstone_core::hierarchy;
+use threadstone_core::pages::PagePolicy;
+use threadstone_core::report::{Pass, Repeats, Report, ScalingCurve, SCHEMA_VERSION};
+use threadstone_core::runner::{defaults, PrecisionReport, Stop};
+use threadstone_core::stats::{Stability, Summary};
+use threadstone_core::sweep::SweepReport;
+use threadstone_workloads::{loaded, mlp, tlb};
 
 use crate::signing;
 
@@ -59,6 +68,10 @@ pub fn check(text: &str, signature_required: bool) -> Outcome {
         signature_required,
     };
 
+    if is_sweep(text) {
+        return check_sweep(text, outcome);
+    }
+
     let report: Report = match serde_json::from_str(text) {
         Ok(r) => r,
         Err(e) => {
@@ -80,6 +93,41 @@ pub fn check(text: &str, signature_required: bool) -> Outcome {
     outcome
 }
 
+/// Whether `text` is a sweep document rather than a result: it has a sweep
+/// section and, unlike every result, no workloads.
+pub fn is_sweep(text: &str) -> bool {
+    let Ok(serde_json::Value::Object(map)) = serde_json::from_str(text) else {
+        return false;
+    };
+    !map.contains_key("workloads")
+        && ["latency", "bandwidth", "tlb", "mlp", "loaded"]
+            .iter()
+            .any(|key| map.contains_key(*key))
+}
+
+/// [`check`], for a sweep document.
+fn check_sweep(text: &str, mut outcome: Outcome) -> Outcome {
+    let sweep: SweepReport = match serde_json::from_str(text) {
+        Ok(s) => s,
+        Err(e) => {
+            outcome.parse_error = Some(e.to_string());
+            return outcome;
+        }
+    };
+
+    outcome.problems.extend(sweep_problems(&sweep));
+    outcome.notes.extend(sweep_observations(&sweep));
+
+    if let Some(sig) = &sweep.signature {
+        outcome.signature = Some(match sweep.signing_bytes() {
+            Ok(message) => signing::verify(&message, sig).map_err(|e| e.to_string()),
+            Err(e) => Err(format!("cannot re-serialise for verification: {e}")),
+        });
+    }
+
+    outcome
+}
+
 /// Checks that a schema cannot express: relationships between fields.
 fn semantic_problems(report: &Report) -> Vec<String> {
     let mut problems = Vec::new();
@@ -100,6 +148,13 @@ fn semantic_problems(report: &Report) -> Vec<String> {
         problems.push(format!("implausible duration {}", report.duration_secs));
     }
 
+    if let Some(plan) = &report.config.budget {
+        problems.extend(budget_problems(plan, report));
+    }
+    problems.extend(load_problems(report));
+
+    let counters_enabled = matches!(report.config.counters, Some(CounterStatus::Enabled { .. }));
+
     for w in &report.workloads {
         for (label, pass) in [
             ("single-thread", &w.single_thread),
@@ -128,6 +183,31 @@ fn semantic_problems(report: &Report) -> Vec<String> {
                     pass.value, pass.stats.min, pass.stats.max
                 ));
             }
+            if let Some(p) = &pass.precision {
+                problems.extend(precision_problems(&where_, pass, p, report));
+            }
+            if let Some(r) = &pass.repeats {
+                problems.extend(repeats_problems(&where_, r, report));
+            }
+            if let Some(h) = &pass.huge_pages {
+                // Only a `MAP_HUGETLB` mapping, which nothing but that policy
+                // makes, draws from the pool.
+                if h.hugetlb_kib > 0 && report.config.pages != Some(PagePolicy::Hugetlb) {
+                    problems.push(format!(
+                        "{where_}: hugetlbfs pages recorded, but the run did not ask for them"
+                    ));
+                }
+            }
+            if let Some(d) = &pass.stats.drift {
+                if !(0.0..=1.0).contains(&d.p_value) || !d.change.is_finite() {
+                    problems.push(format!("{where_}: implausible drift figures"));
+                }
+                if d.significant && pass.stats.stability == Stability::Stable {
+                    problems.push(format!(
+                        "{where_}: a significant drift cannot leave the result stable"
+                    ));
+                }
+            }
             if pass.stats.min > pass.stats.max {
                 problems.push(format!("{where_}: min exceeds max"));
             }
@@ -138,16 +218,589 @@ fn semantic_problems(report: &Report) -> Vec<String> {
                     pass.samples.len()
                 ));
             }
+            if let Some(b) = &pass.balance {
+                if b.thread_ms.len() != pass.threads || b.slowest_thread >= pass.threads {
+                    problems.push(format!(
+                        "{where_}: thread balance does not match {} threads",
+                        pass.threads
+                    ));
+                }
+                if !(0.0..=1.0).contains(&b.slowest_share) || b.imbalance < 1.0 {
+                    problems.push(format!("{where_}: implausible thread balance figures"));
+                }
+            }
+            if let Some(c) = &pass.clock {
+                let figures = [c.ghz, c.min_ghz, c.max_ghz, c.cycles_per_iter, c.per_clock];
+                if figures.iter().any(|x| !x.is_finite() || *x <= 0.0)
+                    || c.ghz < c.min_ghz
+                    || c.ghz > c.max_ghz
+                {
+                    problems.push(format!("{where_}: implausible clock figures"));
+                }
+            }
+            if let Some(c) = &pass.counters {
+                problems.extend(counter_problems(&where_, c, counters_enabled));
+            }
+            for (field, cpus) in [("pinned", &pass.pinned_cpus), ("observed", &pass.cpus)] {
+                if !cpus.is_empty() && cpus.len() != pass.threads {
+                    problems.push(format!(
+                        "{where_}: {} {field} CPUs recorded for {} threads",
+                        cpus.len(),
+                        pass.threads
+                    ));
+                }
+            }
+        }
+
+        if let Some(curve) = &w.scaling_curve {
+            problems.extend(curve_problems(&w.id, curve));
+        }
+
+        for c in &w.core_classes {
+            let where_ = format!("{} {} pass", w.id, c.class.label());
+            if c.pass.threads != 1 {
+                problems.push(format!("{where_}: ran {} threads, not 1", c.pass.threads));
+            }
+            if !c.pass.value.is_finite() || c.pass.value <= 0.0 {
+                problems.push(format!("{where_}: non-positive value {}", c.pass.value));
+            }
+            // The pass must have been pinned to a core of the class it claims.
+            let class_cpus = report.system.cpus_of(c.class);
+            if c.pass
+                .pinned_cpus
+                .iter()
+                .any(|cpu| !class_cpus.contains(cpu))
+                || c.pass.pinned_cpus.is_empty()
+            {
+                problems.push(format!(
+                    "{where_}: not pinned to a CPU the system lists in that class"
+                ));
+            }
+        }
+
+        // A sub-result is measured only beside a pass of its workload, at
+        // that pass's thread count.
+        for sub in &w.sub_results {
+            for (label, pass, scored) in [
+                ("single-thread", &sub.single_thread, &w.single_thread),
+                ("multi-thread", &sub.multi_thread, &w.multi_thread),
+            ] {
+                let Some(pass) = pass else { continue };
+                let where_ = format!("{} {} {label}", w.id, sub.id);
+                match scored {
+                    Some(s) if s.threads == pass.threads => {}
+                    Some(s) => problems.push(format!(
+                        "{where_}: ran {} threads beside a {}-thread pass",
+                        pass.threads, s.threads
+                    )),
+                    None => {
+                        problems.push(format!("{where_}: has no pass of its workload beside it"))
+                    }
+                }
+                if !pass.value.is_finite() || pass.value <= 0.0 {
+                    problems.push(format!("{where_}: non-positive value {}", pass.value));
+                }
+                if pass.stats.n > 0 && (pass.value < pass.stats.min || pass.value > pass.stats.max)
+                {
+                    problems.push(format!(
+                        "{where_}: value {} outside its own range",
+                        pass.value
+                    ));
+                }
+            }
         }
 
         if w.single_thread.is_none() && w.multi_thread.is_none() && w.error.is_none() {
             problems.push(format!("{}: no passes and no error explaining why", w.id));
         }
+
+        // A reference describes the default problem, so only a workload at
+        // every default may have a ratio against it.
+        let scored = report
+            .score
+            .single_core_components
+            .iter()
+            .chain(&report.score.multi_core_components)
+            .any(|c| c.id == w.id);
+        if scored && !w.is_canonical() {
+            problems.push(format!(
+                "{}: scored, but ran with {} rather than its defaults",
+                w.id,
+                w.tuned().join(", ")
+            ));
+        }
+    }
+
+    // An interrupted run stops at the workload in flight, so nothing can
+    // follow the one marked interrupted.
+    if let Some(i) = report.workloads.iter().position(|w| w.interrupted()) {
+        if i + 1 != report.workloads.len() {
+            problems.push(format!(
+                "{}: marked interrupted, but workloads follow it",
+                report.workloads[i].id
+            ));
+        }
+    }
+
+    if let Some(matrix) = &report.core_to_core {
+        problems.extend(core_to_core_problems(matrix));
+    }
+
+    problems
+}
+
+/// Load samples must hold fractions, and under `--require-idle` no pass may
+/// have started above the threshold: a run that could not settle stops there.
+fn load_problems(report: &Report) -> Vec<String> {
+    let mut problems = Vec::new();
+    for s in &report.load {
+        let shares = [s.busy, s.iowait, s.steal];
+        if shares.iter().any(|v| !(0.0..=1.0).contains(v))
+            || s.busy + s.iowait + s.steal > 1.0 + 1e-9
+        {
+            problems.push(format!(
+                "load sample {}: CPU shares outside [0, 1]",
+                s.before
+            ));
+        }
+    }
+    if let Some(idle) = &report.config.require_idle {
+        let over = report
+            .load
+            .iter()
+            .filter(|s| s.contention() > idle.max_busy)
+            .count();
+        if over > 1 || (over == 1 && !report.is_partial()) {
+            problems.push(format!(
+                "a pass started above the required idle threshold of {:.0}%",
+                idle.max_busy * 100.0
+            ));
+        }
+    }
+    problems
+}
+
+/// A budget plan must be the one the run used, within the window floor, and
+/// must not have dropped a workload that was then measured.
+fn budget_problems(plan: &BudgetPlan, report: &Report) -> Vec<String> {
+    let mut problems = Vec::new();
+    let config = &report.config;
+    if (plan.window_ms, plan.warmup, plan.samples)
+        != (config.window_ms, config.warmup, config.samples)
+    {
+        problems.push("budget plan differs from the settings the run recorded".to_string());
+    }
+    if (plan.window_ms as u128) < defaults::MIN_WINDOW.as_millis() {
+        problems.push(format!(
+            "budget plan chose a {} ms window, below the {} ms floor",
+            plan.window_ms,
+            defaults::MIN_WINDOW.as_millis()
+        ));
+    }
+    for id in &plan.dropped {
+        if report.workloads.iter().any(|w| &w.id == id) {
+            problems.push(format!("{id}: dropped by the budget plan, yet measured"));
+        }
+        if !plan.workloads.iter().any(|c| &c.id == id) {
+            problems.push(format!(
+                "{id}: dropped by the budget plan, but never priced"
+            ));
+        }
+    }
+    problems
+}
+
+/// An adaptive pass must have stopped for the reason it gives, within the
+/// budgets the run recorded.
+fn precision_problems(
+    where_: &str,
+    pass: &Pass,
+    p: &PrecisionReport,
+    report: &Report,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(settings) = &report.config.precision else {
+        return vec![format!(
+            "{where_}: records a confidence target, but the run set none"
+        )];
+    };
+    let rounds = pass.samples.len();
+    if p.met != (p.achieved_ci <= p.target_ci) || (p.stopped == Stop::Target && !p.met) {
+        problems.push(format!(
+            "{where_}: confidence target verdict contradicts its figures"
+        ));
+    }
+    // Adaptive sampling never takes fewer than two rounds.
+    let min = report.config.samples.max(2);
+    if rounds > settings.max_samples.max(min) as usize || rounds < min as usize {
+        problems.push(format!(
+            "{where_}: {rounds} rounds is outside the recorded {}–{} budget",
+            min,
+            settings.max_samples.max(min)
+        ));
+    }
+    // The achieved interval must be the one the recorded statistics give.
+    let ci = (pass.stats.ci95 / pass.stats.median).abs();
+    if (ci - p.achieved_ci).abs() > 1e-9 * ci.max(1.0) {
+        problems.push(format!(
+            "{where_}: achieved confidence interval does not match the samples"
+        ));
+    }
+    problems
+}
+
+/// A pass combined from repeated invocations must come from a run that
+/// repeated, with one median per invocation at most.
+fn repeats_problems(where_: &str, r: &Repeats, report: &Report) -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(runs) = report.config.repeat else {
+        return vec![format!(
+            "{where_}: combines several runs, but the run was not repeated"
+        )];
+    };
+    if r.medians.len() < 2 || r.medians.len() > runs as usize {
+        problems.push(format!(
+            "{where_}: {} run medians recorded for {runs} runs",
+            r.medians.len()
+        ));
+    }
+    if r.medians.iter().any(|m| !m.is_finite() || *m <= 0.0) {
+        problems.push(format!("{where_}: contains a non-positive run median"));
+    }
+    let figures = [r.within_cv, r.between_cv, r.ci95];
+    if figures.iter().any(|x| !x.is_finite() || *x < 0.0) {
+        problems.push(format!("{where_}: implausible between-run figures"));
+    }
+    problems
+}
+
+/// Counter figures must follow from the totals they are derived from, and
+/// exist only in a run that enabled counters.
+fn counter_problems(where_: &str, c: &CounterReport, enabled: bool) -> Vec<String> {
+    let mut problems = Vec::new();
+    if !enabled {
+        problems.push(format!(
+            "{where_}: carries counter figures, but the run did not enable counters"
+        ));
+    }
+    let ipc = c.instructions as f64 / c.cycles as f64;
+    if c.cycles == 0 || (c.ipc - ipc).abs() > ipc * 1e-9 {
+        problems.push(format!("{where_}: IPC does not match the counted totals"));
+    }
+    let mut rates = [c.l1d_mpki, c.llc_mpki].into_iter().flatten();
+    if c.branch_miss_rate
+        .is_some_and(|r| !(0.0..=1.0).contains(&r))
+        || rates.any(|m| !m.is_finite() || m < 0.0)
+        || !(c.coverage > 0.0 && c.coverage <= 1.0)
+    {
+        problems.push(format!("{where_}: implausible counter figures"));
+    }
+    problems
+}
+
+/// A scaling curve's points must be the passes they claim to summarise.
+fn curve_problems(id: &str, curve: &ScalingCurve) -> Vec<String> {
+    let mut problems = Vec::new();
+    let where_ = format!("{id} scaling curve");
+
+    if curve.points.first().map(|p| p.threads) != Some(1) {
+        problems.push(format!("{where_}: does not start at 1 thread"));
+    }
+    if curve
+        .points
+        .windows(2)
+        .any(|w| w[0].threads >= w[1].threads)
+    {
+        problems.push(format!("{where_}: thread counts are not ascending"));
+    }
+    for p in &curve.points {
+        if p.pass.threads != p.threads {
+            problems.push(format!(
+                "{where_}: point at {} threads holds a {}-thread pass",
+                p.threads, p.pass.threads
+            ));
+        }
+        if !p.pass.value.is_finite() || p.pass.value <= 0.0 {
+            problems.push(format!(
+                "{where_}: non-positive value {} at {} threads",
+                p.pass.value, p.threads
+            ));
+        }
+        let expected = p.speedup / p.threads.max(1) as f64;
+        if (p.efficiency - expected).abs() > 1e-9 * expected.abs().max(1.0) {
+            problems.push(format!(
+                "{where_}: efficiency {} at {} threads does not match its speedup",
+                p.efficiency, p.threads
+            ));
+        }
+    }
+    if curve
+        .serial_fraction
+        .is_some_and(|s| !(0.0..=1.0).contains(&s))
+    {
+        problems.push(format!("{where_}: serial fraction outside [0, 1]"));
+    }
+    problems
+}
+
+/// A core-to-core matrix must be square over its CPUs, and its summary must
+/// be the one its cells give.
+fn core_to_core_problems(matrix: &CoreToCore) -> Vec<String> {
+    let mut problems = Vec::new();
+    let n = matrix.cpus.len();
+    if matrix.clusters.len() != n
+        || matrix.latency_ns.len() != n
+        || matrix.latency_ns.iter().any(|row| row.len() != n)
+    {
+        problems.push(format!("core to core: matrix does not match its {n} CPUs"));
+        return problems;
+    }
+    let values = matrix.latency_ns.iter().flatten().flatten();
+    if values.clone().any(|v| !v.is_finite() || *v <= 0.0) {
+        problems.push("core to core: contains a non-positive latency".to_string());
+    }
+    if (matrix.intra_cluster_ns, matrix.inter_cluster_ns) != matrix.summary() {
+        problems.push("core to core: cluster figures do not match the matrix".to_string());
+    }
+    problems
+}
+
+/// A sweep's points must be plausible, and whatever it says about them — the
+/// levels and how they compare with the reported caches — must be what the
+/// points and the system give.
+fn sweep_problems(sweep: &SweepReport) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if sweep.schema_version > SCHEMA_VERSION {
+        problems.push(format!(
+            "schema version {} is newer than this build understands ({SCHEMA_VERSION})",
+            sweep.schema_version
+        ));
+    }
+    if sweep.system.logical_cores == 0 {
+        problems.push("system reports zero logical cores".to_string());
+    }
+    if !sweep.duration_secs.is_finite() || sweep.duration_secs < 0.0 {
+        problems.push(format!("implausible duration {}", sweep.duration_secs));
+    }
+    if sweep.latency.is_none()
+        && sweep.bandwidth.is_none()
+        && sweep.tlb.is_none()
+        && sweep.mlp.is_none()
+        && sweep.loaded.is_none()
+    {
+        problems.push(
+            "neither a latency, a bandwidth, a TLB, an MLP nor a loaded-latency sweep".to_string(),
+        );
+    }
+
+    if let Some(latency) = &sweep.latency {
+        let before = problems.len();
+        if latency.step.is_nan() || latency.step <= 1.0 {
+            problems.push(format!(
+                "latency sweep: step {} is not above 1",
+                latency.step
+            ));
+        }
+        if latency.points.is_empty() {
+            problems.push("latency sweep: no points".to_string());
+        }
+        if latency.points.windows(2).any(|w| w[1].bytes <= w[0].bytes) {
+            problems.push("latency sweep: sizes are not in ascending order".to_string());
+        }
+        for p in &latency.points {
+            let where_ = format!("latency sweep at {} bytes", p.bytes);
+            chase_problems(&where_, p.latency_ns, &p.samples, &p.stats, &mut problems);
+        }
+        // Only worth recomputing from points that passed the checks above.
+        if problems.len() == before {
+            let levels = hierarchy::levels(&latency.points);
+            if levels != latency.levels {
+                problems.push("latency sweep: levels do not follow from its points".to_string());
+            }
+            let largest = latency.points.last().map_or(0, |p| p.bytes);
+            if hierarchy::cross_check(&levels, &sweep.system, largest) != latency.disagreements {
+                problems.push(
+                    "latency sweep: disagreements do not follow from its levels and the \
+                     reported cache sizes"
+                        .to_string(),
+                );
+            }
+        }
+    }
+
+    if let Some(bandwidth) = &sweep.bandwidth {
+        if bandwidth.threads.is_empty() || bandwidth.threads.contains(&0) {
+            problems.push("bandwidth sweep: no thread counts, or a zero".to_string());
+        }
+        for p in &bandwidth.points {
+            let where_ = format!(
+                "bandwidth sweep {} at {} bytes, {} threads",
+                p.access.id(),
+                p.bytes,
+                p.threads
+            );
+            if !bandwidth.threads.contains(&p.threads) {
+                problems.push(format!("{where_}: thread count not among those swept"));
+            }
+            if !p.bandwidth_gib_s.is_finite() || p.bandwidth_gib_s <= 0.0 {
+                problems.push(format!(
+                    "{where_}: non-positive bandwidth {}",
+                    p.bandwidth_gib_s
+                ));
+            }
+        }
+    }
+
+    if let Some(tlb) = &sweep.tlb {
+        if tlb.step.is_nan() || tlb.step <= 1.0 {
+            problems.push(format!("TLB sweep: step {} is not above 1", tlb.step));
+        }
+        for probe in &tlb.probes {
+            let before = problems.len();
+            let page = probe.page_bytes;
+            if !tlb::PAGE_SIZES.contains(&page) {
+                problems.push(format!("TLB sweep: {page}-byte pages are not probed"));
+            }
+            if probe.points.is_empty() {
+                problems.push(format!("TLB sweep of {page}-byte pages: no points"));
+            }
+            if probe.points.windows(2).any(|w| w[1].pages <= w[0].pages) {
+                problems.push(format!(
+                    "TLB sweep of {page}-byte pages: page counts are not in ascending order"
+                ));
+            }
+            for p in &probe.points {
+                let where_ = format!("TLB sweep at {} pages of {page} bytes", p.pages);
+                chase_problems(&where_, p.latency_ns, &p.samples, &p.stats, &mut problems);
+                if !p.baseline_ns.is_finite() || p.baseline_ns <= 0.0 {
+                    problems.push(format!("{where_}: non-positive baseline {}", p.baseline_ns));
+                }
+            }
+            if problems.len() == before && hierarchy::tlb_reach(&probe.points, page) != probe.reach
+            {
+                problems.push(format!(
+                    "TLB sweep of {page}-byte pages: reach does not follow from its points"
+                ));
+            }
+        }
+    }
+
+    if let Some(mlp) = &sweep.mlp {
+        if mlp.points.is_empty() {
+            problems.push("MLP sweep: no points".to_string());
+        }
+        if mlp.points.windows(2).any(|w| w[1].chains <= w[0].chains) {
+            problems.push("MLP sweep: chain counts are not in ascending order".to_string());
+        }
+        for p in &mlp.points {
+            let where_ = format!("MLP sweep at {} chains", p.chains);
+            if !mlp::CHAINS.contains(&p.chains) {
+                problems.push(format!("{where_}: not a chain count that is measured"));
+            }
+            chase_problems(
+                &where_,
+                p.ns_per_access,
+                &p.samples,
+                &p.stats,
+                &mut problems,
+            );
+        }
+    }
+
+    if let Some(sweep) = &sweep.loaded {
+        if sweep.injectors == 0 {
+            problems.push("loaded-latency sweep: no injectors".to_string());
+        }
+        match sweep.points.first() {
+            None => problems.push("loaded-latency sweep: no points".to_string()),
+            Some(first) if first.delay.is_some() => {
+                problems.push("loaded-latency sweep: does not start idle".to_string())
+            }
+            Some(_) => {}
+        }
+        if sweep.points.iter().skip(1).any(|p| p.delay.is_none()) {
+            problems.push("loaded-latency sweep: more than one idle point".to_string());
+        }
+        let delays: Vec<u32> = sweep.points.iter().filter_map(|p| p.delay).collect();
+        if delays.windows(2).any(|w| w[1] >= w[0]) {
+            problems.push("loaded-latency sweep: delays are not in descending order".to_string());
+        }
+        let measured = loaded::default_delays();
+        for p in &sweep.points {
+            let where_ = match p.delay {
+                Some(delay) => format!("loaded-latency sweep at delay {delay}"),
+                None => "loaded-latency sweep idle".to_string(),
+            };
+            if p.delay.is_some_and(|d| !measured.contains(&d)) {
+                problems.push(format!("{where_}: not a delay that is measured"));
+            }
+            if !p.latency_ns.is_finite() || p.latency_ns <= 0.0 {
+                problems.push(format!("{where_}: implausible latency {}", p.latency_ns));
+            }
+            if !p.bandwidth_gib_s.is_finite() || p.bandwidth_gib_s < 0.0 {
+                problems.push(format!(
+                    "{where_}: implausible bandwidth {}",
+                    p.bandwidth_gib_s
+                ));
+            }
+        }
     }
 
     problems
 }
 
+/// Problems with one chase point: its latency, its samples and the summary of
+/// them.
+fn chase_problems(
+    where_: &str,
+    latency_ns: f64,
+    samples: &[f64],
+    stats: &Summary,
+    problems: &mut Vec<String>,
+) {
+    if !latency_ns.is_finite() || latency_ns <= 0.0 {
+        problems.push(format!("{where_}: non-positive latency {latency_ns}"));
+    }
+    if samples.is_empty() || samples.iter().any(|v| !v.is_finite()) {
+        problems.push(format!("{where_}: no samples, or a non-finite one"));
+    }
+    if latency_ns != stats.median || stats.min > stats.max {
+        problems.push(format!(
+            "{where_}: latency is not the median of its samples"
+        ));
+    }
+    if stats.n + stats.outliers > samples.len() {
+        problems.push(format!(
+            "{where_}: statistics count {} samples but only {} are recorded",
+            stats.n + stats.outliers,
+            samples.len()
+        ));
+    }
+}
+
+/// Things a reader of a sweep should know that are not defects.
+fn sweep_observations(sweep: &SweepReport) -> Vec<String> {
+    let mut notes = Vec::new();
+    if sweep.system.build_profile.debug_assertions {
+        notes.push(
+            "produced by a build with debug assertions; the numbers do not \
+             describe an optimised binary"
+                .to_string(),
+        );
+    }
+    if let Some(latency) = &sweep.latency {
+        for d in &latency.disagreements {
+            notes.push(format!("cache levels: {}", crate::render::disagreement(d)));
+        }
+    }
+    notes
+}
+
+/// Highest-to-lowest clock ratio within a pass above which the rounds are
+/// noted as taken at different frequencies.
+const CLOCK_DRIFT: f64 = 1.1;
+
 /// Things a reader should know that are not defects.
 fn observations(report: &Report) -> Vec<String> {
     let mut notes = Vec::new();
@@ -184,45 +837,169 @@ fn observations(report: &Report) -> Vec<String> {
         ));
     }
 
-    let failed: Vec<&str> = report
+    // Binding is requested, not guaranteed: a cgroup change mid-run can move a
+    // thread. The data is still valid, but not for the placement it claims.
+    let strayed: Vec<&str> = report
         .workloads
         .iter()
-        .filter(|w| w.error.is_some())
+        .filter(|w| {
+            [&w.single_thread, &w.multi_thread]
+                .into_iter()
+                .flatten()
+                .any(|p| !p.pinned_cpus.is_empty() && !p.cpus.is_empty() && p.cpus != p.pinned_cpus)
+        })
         .map(|w| w.id.as_str())
         .collect();
-    if !failed.is_empty() {
+    if !strayed.is_empty() {
         notes.push(format!(
-            "workloads that failed to run: {}",
-            failed.join(", ")
+            "threads ran off their pinned CPUs in {}",
+            strayed.join(", ")
         ));
     }
 
-    notes
-}
-
-/// Render an outcome for the terminal.
-pub fn render(outcome: &Outcome, path: &Path) -> String {
-    let name = path.display();
-    let mut out = String::new();
+    // A clock that moved within a pass means the rounds were not all taken at
+    // the same operating point, so the spread is partly frequency, not code.
+    let drifting: Vec<&str> = report
+        .workloads
+        .iter()
+        .filter(|w| {
+            [&w.single_thread, &w.multi_thread]
+                .into_iter()
+                .flatten()
+                .filter_map(|p| p.clock.as_ref())
+                .any(|c| c.max_ghz > c.min_ghz * CLOCK_DRIFT)
+        })
+        .map(|w| w.id.as_str())
+        .collect();
+    if !drifting.is_empty() {
+        notes.push(format!(
+            "core clock moved by more than {:.0}% between rounds in {}",
+            (CLOCK_DRIFT - 1.0) * 100.0,
+            drifting.join(", ")
+        ));
+    }
 
-    if let Some(error) = &outcome.parse_error {
-        return format!("FAIL {name}\n  not a valid result document: {error}\n");
+    if let Some(CounterStatus::Unavailable { reason }) = &report.config.counters {
+        notes.push(format!(
+            "hardware counters were requested but unavailable: {reason}"
+        ));
+    }
+    let uncounted = crate::render::missing_counters(report);
+    if !uncounted.is_empty() {
+        notes.push(format!("no counters for {}", uncounted.join("; ")));
     }
 
-    let verdict = if outcome.is_ok() { "OK  " } else { "FAIL" };
-    out.push_str(&format!("{verdict} {name}\n"));
-    out.push_str("  structure   valid\n");
+    let missed = crate::render::missed_precision(report);
+    if !missed.is_empty() {
+        notes.push(format!(
+            "confidence target not reached in {}",
+            missed.join("; ")
+        ));
+    }
 
-    if outcome.problems.is_empty() {
-        out.push_str("  contents    coherent\n");
-    } else {
-        out.push_str("  contents    INVALID\n");
-        for problem in &outcome.problems {
-            out.push_str(&format!("    - {problem}\n"));
-        }
+    let drifts = crate::render::drifts(report);
+    if !drifts.is_empty() {
+        notes.push(format!("significant drift in {}", drifts.join("; ")));
     }
 
-    match &outcome.signature {
+    let loaded = crate::render::loaded(report);
+    if !loaded.is_empty() {
+        notes.push(format!(
+            "produced on a loaded machine: {}",
+            loaded.join("; ")
+        ));
+    }
+
+    let stragglers = crate::render::stragglers(report);
+    if !stragglers.is_empty() {
+        notes.push(format!(
+            "consistent straggler thread in {}",
+            stragglers.join("; ")
+        ));
+    }
+
+    if let Some(plan) = &report.config.budget {
+        if !plan.dropped.is_empty() {
+            notes.push(format!(
+                "left out to fit the budget: {}",
+                plan.dropped.join(", ")
+            ));
+        }
+        if report.duration_secs * 1e3 > plan.budget_ms as f64 {
+            notes.push(format!(
+                "ran {:.0} s against a {:.0} s budget",
+                report.duration_secs,
+                plan.budget_ms as f64 / 1e3
+            ));
+        }
+    }
+
+    if let Some(note) = crate::render::page_shortfall(report) {
+        notes.push(note);
+    }
+
+    if let Some(w) = crate::render::interrupted(report) {
+        notes.push(format!(
+            "partial run: interrupted during {}, after {} workload(s) completed",
+            w.id,
+            report.workloads.len() - 1
+        ));
+    }
+
+    if let Some(runs) = report.config.repeat {
+        let widest = report
+            .workloads
+            .iter()
+            .flat_map(|w| [&w.single_thread, &w.multi_thread])
+            .flatten()
+            .filter_map(|p| p.repeats.as_ref())
+            .map(|r| r.between_cv)
+            .fold(0.0, f64::max);
+        notes.push(format!(
+            "combines {runs} runs; largest between-run cv {:.1}%",
+            widest * 100.0
+        ));
+    }
+
+    let failed: Vec<&str> = report
+        .workloads
+        .iter()
+        .filter(|w| w.error.is_some() && !w.interrupted())
+        .map(|w| w.id.as_str())
+        .collect();
+    if !failed.is_empty() {
+        notes.push(format!(
+            "workloads that failed to run: {}",
+            failed.join(", ")
+        ));
+    }
+
+    notes
+}
+
+/// Render an outcome for the terminal.
+pub fn render(outcome: &Outcome, path: &Path) -> String {
+    let name = path.display();
+    let mut out = String::new();
+
+    if let Some(error) = &outcome.parse_error {
+        return format!("FAIL {name}\n  not a valid result document: {error}\n");
+    }
+
+    let verdict = if outcome.is_ok() { "OK  " } else { "FAIL" };
+    out.push_str(&format!("{verdict} {name}\n"));
+    out.push_str("  structure   valid\n");
+
+    if outcome.problems.is_empty() {
+        out.push_str("  contents    coherent\n");
+    } else {
+        out.push_str("  contents    INVALID\n");
+        for problem in &outcome.problems {
+            out.push_str(&format!("    - {problem}\n"));
+        }
+    }
+
+    match &outcome.signature {
         Some(Ok(())) => out.push_str("  signature   verified\n"),
         Some(Err(e)) => out.push_str(&format!("  signature   INVALID: {e}\n")),
         None if outcome.signature_required => {
@@ -241,7 +1018,8 @@ pub fn render(outcome: &Outcome, path: &Path) -> String {
 mod tests {
     use super::*;
     use threadstone_core::kernel::Unit;
-    use threadstone_core::report::{Pass, RunSettings, WorkloadReport};
+    use threadstone_core::pages::HugePages;
+    use threadstone_core::report::{ClockReport, PrecisionSettings, RunSettings, WorkloadReport};
     use threadstone_core::score::ScoreCard;
     use threadstone_core::stats::Summary;
     use threadstone_core::sysinfo::SystemInfo;
@@ -260,6 +1038,15 @@ mod tests {
                 samples: 5,
                 warmup: 2,
                 window_ms: 250,
+                affinity: None,
+                scaling: vec![],
+                counters: None,
+                precision: None,
+                budget: None,
+                require_idle: None,
+                isolate: None,
+                repeat: None,
+                pages: None,
             },
             workloads: vec![WorkloadReport {
                 id: "sgemm".into(),
@@ -267,6 +1054,7 @@ mod tests {
                 summary: "test".into(),
                 unit: Unit::Gflops,
                 reference: 30.0,
+                params: vec![],
                 single_thread: Some(Pass {
                     threads: 1,
                     iters_per_thread: 100,
@@ -275,13 +1063,27 @@ mod tests {
                     stats,
                     window_ms: 250.0,
                     window_too_short: false,
+                    pinned_cpus: vec![],
+                    cpus: vec![],
+                    balance: None,
+                    clock: None,
+                    counters: None,
+                    counters_error: None,
+                    precision: None,
+                    huge_pages: None,
+                    repeats: None,
                 }),
                 multi_thread: None,
                 scaling: None,
+                scaling_curve: None,
+                core_classes: vec![],
+                sub_results: vec![],
                 excluded_from_multi_core: None,
                 error: None,
             }],
             score: ScoreCard::new(vec![], vec![]),
+            load: vec![],
+            core_to_core: None,
             signature: None,
         }
     }
@@ -356,6 +1158,425 @@ mod tests {
         );
     }
 
+    #[test]
+    fn between_run_figures_need_a_repeated_run() {
+        let mut report = valid_report();
+        report.workloads[0].single_thread.as_mut().unwrap().repeats = Some(Repeats {
+            medians: vec![99.0, 101.0],
+            within_cv: 0.01,
+            between_cv: 0.014,
+            ci95: 2.5,
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(
+            outcome.problems.iter().any(|p| p.contains("not repeated")),
+            "{:?}",
+            outcome.problems
+        );
+
+        report.config.repeat = Some(2);
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{:?}", outcome.problems);
+        assert!(outcome.notes.iter().any(|n| n.contains("combines 2 runs")));
+
+        report.config.repeat = Some(1);
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.problems.iter().any(|p| p.contains("2 run medians")));
+    }
+
+    #[test]
+    fn pooled_huge_pages_need_the_policy_that_asks_for_them() {
+        let mut report = valid_report();
+        report.workloads[0]
+            .single_thread
+            .as_mut()
+            .unwrap()
+            .huge_pages = Some(HugePages {
+            anon_kib: 0,
+            hugetlb_kib: 262_144,
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.problems.iter().any(|p| p.contains("hugetlbfs")));
+
+        report.config.pages = Some(PagePolicy::Hugetlb);
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{:?}", outcome.problems);
+        assert!(!outcome.notes.iter().any(|n| n.contains("pool")));
+
+        // An empty pool is a caveat, not a defect.
+        report.workloads[0]
+            .single_thread
+            .as_mut()
+            .unwrap()
+            .huge_pages = Some(HugePages {
+            anon_kib: 262_144,
+            hugetlb_kib: 0,
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok());
+        assert!(outcome
+            .notes
+            .iter()
+            .any(|n| n.contains("pool gave no pages")));
+    }
+
+    #[test]
+    fn a_thread_that_left_its_pinned_cpu_is_noted() {
+        let mut report = valid_report();
+        let pass = report.workloads[0].single_thread.as_mut().unwrap();
+        pass.pinned_cpus = vec![2];
+        pass.cpus = vec![2];
+        let notes = check(&json_of(&report), false).notes;
+        assert!(
+            !notes.iter().any(|n| n.contains("pinned CPUs")),
+            "{notes:?}"
+        );
+
+        report.workloads[0].single_thread.as_mut().unwrap().cpus = vec![5];
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "a moved thread is a caveat, not a defect");
+        assert!(outcome.notes.iter().any(|n| n.contains("pinned CPUs")));
+
+        report.workloads[0].single_thread.as_mut().unwrap().cpus = vec![5, 6];
+        assert!(!check(&json_of(&report), false).is_ok());
+    }
+
+    #[test]
+    fn clock_figures_must_be_consistent_and_drift_is_noted() {
+        let mut report = valid_report();
+        report.workloads[0].single_thread.as_mut().unwrap().clock = Some(ClockReport {
+            ghz: 3.0,
+            min_ghz: 2.9,
+            max_ghz: 3.1,
+            cycles_per_iter: 120.0,
+            per_clock: 4.0,
+            per_clock_unit: "FLOP/cycle".to_string(),
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{:?}", outcome.problems);
+        assert!(!outcome.notes.iter().any(|n| n.contains("core clock")));
+
+        fn clock(r: &mut Report) -> &mut ClockReport {
+            let pass = r.workloads[0].single_thread.as_mut().unwrap();
+            pass.clock.as_mut().unwrap()
+        }
+        let mut drifted = report.clone();
+        clock(&mut drifted).min_ghz = 2.0;
+        let outcome = check(&json_of(&drifted), false);
+        assert!(outcome.is_ok());
+        assert!(outcome.notes.iter().any(|n| n.contains("core clock")));
+
+        clock(&mut report).ghz = 5.0;
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.problems.iter().any(|p| p.contains("clock")));
+    }
+
+    #[test]
+    fn counter_figures_must_match_their_totals_and_a_run_that_enabled_them() {
+        let mut report = valid_report();
+        report.workloads[0].single_thread.as_mut().unwrap().counters = Some(CounterReport {
+            instructions: 3_000,
+            cycles: 1_000,
+            ipc: 3.0,
+            branch_miss_rate: Some(0.01),
+            l1d_mpki: None,
+            llc_mpki: Some(0.5),
+            coverage: 1.0,
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p.contains("did not enable")),
+            "{:?}",
+            outcome.problems
+        );
+
+        report.config.counters = Some(CounterStatus::Enabled { events: vec![] });
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{:?}", outcome.problems);
+
+        let pass = report.workloads[0].single_thread.as_mut().unwrap();
+        pass.counters.as_mut().unwrap().ipc = 4.0;
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.problems.iter().any(|p| p.contains("IPC")));
+    }
+
+    #[test]
+    fn unavailable_counters_are_noted_with_their_reason() {
+        let mut report = valid_report();
+        report.config.counters = Some(CounterStatus::Unavailable {
+            reason: "no PMU".to_string(),
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok());
+        assert!(outcome.notes.iter().any(|n| n.contains("no PMU")));
+
+        // Enabled for the run, but one pass's workers could not open theirs.
+        report.config.counters = Some(CounterStatus::Enabled { events: vec![] });
+        report.workloads[0]
+            .single_thread
+            .as_mut()
+            .unwrap()
+            .counters_error = Some("perf_event_open failed in a container".to_string());
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{:?}", outcome.problems);
+        assert!(outcome
+            .notes
+            .iter()
+            .any(|n| n.contains("no counters for sgemm at 1 thread: perf_event_open failed")));
+    }
+
+    #[test]
+    fn a_drifting_pass_is_named_and_must_not_claim_stability() {
+        let mut report = valid_report();
+        let samples: Vec<f64> = (0..7).map(|i| 100.0 - 0.5 * i as f64).collect();
+        let pass = report.workloads[0].single_thread.as_mut().unwrap();
+        pass.stats = Summary::new(&samples).unwrap();
+        pass.value = pass.stats.median;
+        pass.samples = samples;
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{:?}", outcome.problems);
+        assert!(
+            outcome
+                .notes
+                .iter()
+                .any(|n| n.contains("drift") && n.contains("sgemm at 1 thread: slowed")),
+            "{:?}",
+            outcome.notes
+        );
+
+        let pass = report.workloads[0].single_thread.as_mut().unwrap();
+        pass.stats.stability = Stability::Stable;
+        assert!(!check(&json_of(&report), false).is_ok());
+    }
+
+    #[test]
+    fn an_adaptive_pass_must_match_its_budget_and_statistics() {
+        let mut report = valid_report();
+        let pass = report.workloads[0].single_thread.as_mut().unwrap();
+        let achieved_ci = (pass.stats.ci95 / pass.stats.median).abs();
+        pass.precision = Some(PrecisionReport {
+            target_ci: 0.001,
+            achieved_ci,
+            met: false,
+            stopped: Stop::MaxSamples,
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(
+            outcome.problems.iter().any(|p| p.contains("set none")),
+            "{:?}",
+            outcome.problems
+        );
+
+        report.config.precision = Some(PrecisionSettings {
+            target_ci: 0.001,
+            max_samples: 5,
+            max_time_ms: 30_000,
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{:?}", outcome.problems);
+        assert!(outcome
+            .notes
+            .iter()
+            .any(|n| n.contains("confidence target not reached")));
+
+        let pass = report.workloads[0].single_thread.as_mut().unwrap();
+        pass.precision.as_mut().unwrap().met = true;
+        assert!(!check(&json_of(&report), false).is_ok());
+    }
+
+    #[test]
+    fn a_tampered_scaling_curve_is_caught() {
+        let mut report = valid_report();
+        let base = report.workloads[0].single_thread.clone().unwrap();
+        let mut double = base.clone();
+        double.threads = 2;
+        double.value *= 2.0;
+        report.workloads[0].scaling_curve = ScalingCurve::compute(vec![base, double], Unit::Gflops);
+        assert!(check(&json_of(&report), false).is_ok());
+
+        report.workloads[0].scaling_curve.as_mut().unwrap().points[1].speedup = 3.0;
+        let outcome = check(&json_of(&report), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p.contains("does not match")),
+            "{:?}",
+            outcome.problems
+        );
+    }
+
+    #[test]
+    fn a_core_to_core_summary_must_follow_from_its_matrix() {
+        let mut report = valid_report();
+        report.core_to_core = Some(CoreToCore::new(
+            vec![0, 1],
+            vec![0, 0],
+            vec![vec![None, Some(30.0)], vec![Some(30.0), None]],
+        ));
+        assert!(check(&json_of(&report), false).is_ok());
+
+        report.core_to_core.as_mut().unwrap().intra_cluster_ns = Some(15.0);
+        let outcome = check(&json_of(&report), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p.contains("cluster figures do not match")),
+            "{:?}",
+            outcome.problems
+        );
+
+        report.core_to_core.as_mut().unwrap().clusters.pop();
+        assert!(!check(&json_of(&report), false).is_ok());
+    }
+
+    #[test]
+    fn a_measured_matrix_verifies_after_a_round_trip() {
+        // Symmetric, as measured, so each median averages two latencies: the
+        // kind of double that comes back from a file one ULP off unless
+        // serde_json reads exactly.
+        let mut report = valid_report();
+        for seed in 0..200u32 {
+            let ns = |i: usize, j: usize| {
+                let (lo, hi) = (i.min(j) as f64, i.max(j) as f64);
+                18.0 + 0.37 * lo + 0.013 * hi * hi + f64::from(seed) * 0.071
+            };
+            let cpus = 6;
+            let latency_ns = (0..cpus)
+                .map(|i| (0..cpus).map(|j| (i != j).then(|| ns(i, j))).collect())
+                .collect();
+            report.core_to_core = Some(CoreToCore::new(
+                (0..cpus).collect(),
+                vec![0, 0, 0, 1, 1, 1],
+                latency_ns,
+            ));
+            let outcome = check(&json_of(&report), false);
+            assert!(outcome.is_ok(), "seed {seed}: {:?}", outcome.problems);
+        }
+    }
+
+    #[test]
+    fn a_sub_result_must_sit_beside_a_pass_of_its_workload() {
+        use threadstone_core::report::SubResult;
+
+        let mut report = valid_report();
+        let pass = report.workloads[0].single_thread.clone().unwrap();
+        report.workloads[0].sub_results = vec![SubResult {
+            id: "companion".into(),
+            name: "Companion".into(),
+            single_thread: Some(pass.clone()),
+            multi_thread: None,
+        }];
+        assert!(check(&json_of(&report), false).is_ok());
+
+        report.workloads[0].sub_results[0].multi_thread = Some(pass);
+        let outcome = check(&json_of(&report), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p.contains("companion multi-thread: has no pass")),
+            "{:?}",
+            outcome.problems
+        );
+    }
+
+    #[test]
+    fn a_core_class_pass_must_run_on_its_class() {
+        use threadstone_core::report::CoreClassPass;
+        use threadstone_core::sysinfo::CoreClass;
+
+        let mut report = valid_report();
+        report.system.performance_cpus = vec![0, 1];
+        report.system.efficiency_cpus = vec![2, 3];
+        let mut pass = report.workloads[0].single_thread.clone().unwrap();
+        pass.pinned_cpus = vec![3];
+        report.workloads[0].core_classes = vec![CoreClassPass {
+            class: CoreClass::Efficiency,
+            pass,
+        }];
+        assert!(check(&json_of(&report), false).is_ok());
+
+        report.workloads[0].core_classes[0].class = CoreClass::Performance;
+        let outcome = check(&json_of(&report), false);
+        assert!(
+            outcome.problems.iter().any(|p| p.contains("P-core pass")),
+            "{:?}",
+            outcome.problems
+        );
+    }
+
+    #[test]
+    fn a_consistent_straggler_is_noted_but_not_a_defect() {
+        use threadstone_core::stats::ThreadBalance;
+
+        let mut report = valid_report();
+        let mut pass = report.workloads[0].single_thread.clone().unwrap();
+        pass.threads = 4;
+        let rounds: Vec<Vec<f64>> = (0..5).map(|_| vec![10.0, 10.0, 10.0, 14.0]).collect();
+        pass.balance = ThreadBalance::new(&rounds);
+        report.workloads[0].multi_thread = Some(pass);
+
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{:?}", outcome.problems);
+        assert!(
+            outcome.notes.iter().any(|n| n.contains("thread 3")),
+            "{:?}",
+            outcome.notes
+        );
+
+        let balance = report.workloads[0]
+            .multi_thread
+            .as_mut()
+            .unwrap()
+            .balance
+            .as_mut()
+            .unwrap();
+        balance.slowest_thread = 9;
+        assert!(!check(&json_of(&report), false).is_ok());
+    }
+
+    #[test]
+    fn a_tuned_workload_must_not_be_scored() {
+        use threadstone_core::report::ParamSetting;
+        use threadstone_core::score::ScoreComponent;
+
+        let mut report = valid_report();
+        report.workloads[0].params = vec![ParamSetting {
+            name: "n".into(),
+            value: 1024,
+            default: 256,
+        }];
+        assert!(check(&json_of(&report), false).is_ok());
+
+        let measured = report.workloads[0].single_thread.as_ref().unwrap().value;
+        report.score = ScoreCard::new(
+            vec![ScoreComponent {
+                id: "sgemm".into(),
+                measured,
+                reference: 30.0,
+                ratio: measured / 30.0,
+            }],
+            vec![],
+        );
+        let outcome = check(&json_of(&report), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p == "sgemm: scored, but ran with n=1024 rather than its defaults"),
+            "{:?}",
+            outcome.problems
+        );
+
+        report.workloads[0].params[0].value = 256;
+        assert!(check(&json_of(&report), false).is_ok());
+    }
+
     #[test]
     fn a_workload_with_no_passes_needs_an_explanation() {
         let mut report = valid_report();
@@ -367,6 +1588,108 @@ mod tests {
         assert!(check(&json_of(&report), false).is_ok());
     }
 
+    #[test]
+    fn an_interrupted_report_is_partial_rather_than_broken() {
+        let mut report = valid_report();
+        let mut second = report.workloads[0].clone();
+        second.id = "second".into();
+        second.multi_thread = None;
+        second.error = Some("interrupted before this workload completed".into());
+        report.workloads.push(second);
+
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{outcome:?}");
+        assert!(
+            outcome.notes.iter().any(|n| n.contains("partial run")),
+            "{:?}",
+            outcome.notes
+        );
+        assert!(!outcome.notes.iter().any(|n| n.contains("failed to run")));
+
+        // Nothing can have run after the interruption.
+        report.workloads.swap(0, 1);
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome
+            .problems
+            .iter()
+            .any(|p| p.contains("workloads follow it")));
+    }
+
+    #[test]
+    fn a_budget_plan_must_match_the_run_it_planned() {
+        use threadstone_core::budget::{BudgetPlan, WorkloadCost};
+
+        let mut report = valid_report();
+        let plan = BudgetPlan {
+            budget_ms: 90_000,
+            probe_ms: 1_200,
+            estimated_ms: 80_000,
+            window_ms: report.config.window_ms,
+            warmup: report.config.warmup,
+            samples: report.config.samples,
+            workloads: vec![WorkloadCost {
+                id: "stream".into(),
+                passes: 2,
+                setup_ms: 400.0,
+            }],
+            dropped: vec!["stream".into()],
+        };
+        report.config.budget = Some(plan.clone());
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{outcome:?}");
+        assert!(outcome.notes.iter().any(|n| n.contains("left out")));
+
+        report.config.budget = Some(BudgetPlan {
+            window_ms: 5,
+            ..plan.clone()
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.problems.iter().any(|p| p.contains("differs")));
+        assert!(outcome.problems.iter().any(|p| p.contains("floor")));
+
+        report.config.budget = Some(BudgetPlan {
+            dropped: vec![report.workloads[0].id.clone()],
+            ..plan
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.problems.iter().any(|p| p.contains("yet measured")));
+    }
+
+    #[test]
+    fn a_loaded_machine_is_observed_and_an_ignored_threshold_rejected() {
+        use threadstone_core::load::LoadSample;
+        use threadstone_core::report::IdleSettings;
+
+        let mut report = valid_report();
+        report.load = vec![LoadSample {
+            before: "sgemm at 1 thread".into(),
+            loadavg: 6.0,
+            run_queue: 5,
+            busy: 0.4,
+            iowait: 0.0,
+            steal: 0.0,
+            waited_ms: 0,
+        }];
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome.is_ok(), "{outcome:?}");
+        assert!(
+            outcome.notes.iter().any(|n| n.contains("loaded machine")),
+            "{:?}",
+            outcome.notes
+        );
+
+        // A complete run cannot have started a pass above its own threshold.
+        report.config.require_idle = Some(IdleSettings {
+            max_busy: 0.05,
+            wait_ms: 60_000,
+        });
+        let outcome = check(&json_of(&report), false);
+        assert!(outcome
+            .problems
+            .iter()
+            .any(|p| p.contains("idle threshold")));
+    }
+
     #[test]
     fn a_valid_signature_verifies() {
         let key = signing::generate().unwrap();
@@ -417,6 +1740,426 @@ mod tests {
         assert!(check(&compact, true).is_ok());
     }
 
+    #[test]
+    fn a_sweep_must_say_what_its_points_and_system_give() {
+        use threadstone_core::sweep::{
+            Access, BandwidthPoint, BandwidthSweep, LatencySweep, SweepPoint,
+        };
+
+        // 1 ns to 32 KiB, 5 ns from 128 KiB to the end, a climb between.
+        let points: Vec<SweepPoint> = [1.0, 1.0, 1.0, 1.0, 3.0, 5.0, 5.0, 5.0, 5.0]
+            .iter()
+            .enumerate()
+            .map(|(i, &ns)| SweepPoint {
+                bytes: 4096 << i,
+                latency_ns: ns,
+                samples: vec![ns; 3],
+                stats: Summary::new(&[ns; 3]).unwrap(),
+            })
+            .collect();
+        let system = SystemInfo {
+            logical_cores: 4,
+            l1d_bytes: Some(32 << 10),
+            ..SystemInfo::default()
+        };
+        let levels = hierarchy::levels(&points);
+        assert_eq!(levels.len(), 2);
+        let mut sweep = SweepReport {
+            schema_version: SCHEMA_VERSION,
+            tool_version: "2.0.0".into(),
+            generated_at: "2026-08-10T12:00:00Z".into(),
+            duration_secs: 3.0,
+            system,
+            latency: Some(LatencySweep {
+                min_ms: 120,
+                step: 2.0,
+                points,
+                levels,
+                disagreements: vec![],
+            }),
+            bandwidth: None,
+            tlb: None,
+            mlp: None,
+            loaded: None,
+            signature: None,
+        };
+        let key = signing::generate().unwrap();
+        sweep.signature = Some(signing::sign(&sweep.signing_bytes().unwrap(), &key.pkcs8).unwrap());
+        let text = serde_json::to_string_pretty(&sweep).unwrap();
+        assert!(is_sweep(&text) && !is_sweep(&json_of(&valid_report())));
+        let outcome = check(&text, true);
+        assert!(outcome.is_ok(), "{outcome:?}");
+        sweep.signature = None;
+
+        let mut edited = sweep.clone();
+        edited.latency.as_mut().unwrap().levels[0].latency_ns = 0.5;
+        let outcome = check(&serde_json::to_string(&edited).unwrap(), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p.contains("levels do not follow")),
+            "{outcome:?}"
+        );
+
+        // A 256 KiB L1d puts the 32 KiB plateau out of place.
+        let mut moved = sweep.clone();
+        moved.system.l1d_bytes = Some(256 << 10);
+        let outcome = check(&serde_json::to_string(&moved).unwrap(), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p.contains("disagreements do not follow")),
+            "{outcome:?}"
+        );
+
+        sweep.latency = None;
+        sweep.bandwidth = Some(BandwidthSweep {
+            min_ms: 120,
+            threads: vec![1, 4],
+            points: vec![BandwidthPoint {
+                bytes: 4096,
+                access: Access::Read,
+                threads: 2,
+                bandwidth_gib_s: 100.0,
+            }],
+        });
+        let outcome = check(&serde_json::to_string(&sweep).unwrap(), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p.contains("not among those swept")),
+            "{outcome:?}"
+        );
+    }
+
+    #[test]
+    fn a_measured_latency_sweep_verifies_after_a_round_trip() {
+        use threadstone_core::hierarchy::Disagreement;
+        use threadstone_core::sweep::{LatencySweep, SweepPoint};
+
+        // Plateaus at 32 KiB and 512 KiB with only the first reported, so the
+        // second comes back as an unreported level carrying its latency: both
+        // are medians of noisy samples, recomputed on every verify.
+        let system = SystemInfo {
+            logical_cores: 4,
+            l1d_bytes: Some(32 << 10),
+            ..SystemInfo::default()
+        };
+        for seed in 0..100u32 {
+            let scale = 1.0 + f64::from(seed) * 0.0137;
+            let points: Vec<SweepPoint> = (0..12)
+                .map(|i| {
+                    let bytes = 4096usize << i;
+                    let plateau = match bytes {
+                        0..=32768 => 1.1,
+                        32769..=524288 => 4.7,
+                        _ => 83.9,
+                    };
+                    let ns = plateau * scale + 0.0031 * i as f64;
+                    let samples = vec![ns * 0.993, ns, ns * 1.007];
+                    let stats = Summary::new(&samples).unwrap();
+                    SweepPoint {
+                        bytes,
+                        latency_ns: stats.median,
+                        samples,
+                        stats,
+                    }
+                })
+                .collect();
+            let levels = hierarchy::levels(&points);
+            let largest = points.last().unwrap().bytes;
+            let disagreements = hierarchy::cross_check(&levels, &system, largest);
+            assert!(
+                matches!(disagreements[..], [Disagreement::Unreported { .. }]),
+                "seed {seed}: {disagreements:?}"
+            );
+            let sweep = SweepReport {
+                schema_version: SCHEMA_VERSION,
+                tool_version: "2.0.0".into(),
+                generated_at: "2026-08-10T12:00:00Z".into(),
+                duration_secs: 3.0,
+                system: system.clone(),
+                latency: Some(LatencySweep {
+                    min_ms: 120,
+                    step: 2.0,
+                    points,
+                    levels,
+                    disagreements,
+                }),
+                bandwidth: None,
+                tlb: None,
+                mlp: None,
+                loaded: None,
+                signature: None,
+            };
+            let outcome = check(&serde_json::to_string_pretty(&sweep).unwrap(), false);
+            assert!(outcome.problems.is_empty(), "seed {seed}: {outcome:?}");
+        }
+    }
+
+    #[test]
+    fn a_measured_tlb_probe_verifies_after_a_round_trip() {
+        use threadstone_core::sweep::{TlbPoint, TlbProbe, TlbSweep};
+
+        // Medians of noisy samples, less a baseline: the kind of doubles that
+        // come back from a file one ULP off unless serde_json reads exactly.
+        for seed in 0..100u32 {
+            let scale = 1.0 + f64::from(seed) * 0.0137;
+            let points: Vec<TlbPoint> = (2..=14)
+                .map(|shift| {
+                    let pages = 1usize << shift;
+                    let plateau = match pages {
+                        0..=64 => 1.1,
+                        65..=1024 => 3.3,
+                        _ => 21.7,
+                    };
+                    let ns = plateau * scale + 0.0031 * f64::from(shift);
+                    let samples = vec![ns * 0.993, ns, ns * 1.007];
+                    let stats = Summary::new(&samples).unwrap();
+                    TlbPoint {
+                        pages,
+                        latency_ns: stats.median,
+                        samples,
+                        stats,
+                        baseline_ns: 1.03 * scale,
+                    }
+                })
+                .collect();
+            let reach = hierarchy::tlb_reach(&points, 4096);
+            let sweep = SweepReport {
+                schema_version: SCHEMA_VERSION,
+                tool_version: "2.0.0".into(),
+                generated_at: "2026-08-10T12:00:00Z".into(),
+                duration_secs: 3.0,
+                system: SystemInfo {
+                    logical_cores: 4,
+                    ..SystemInfo::default()
+                },
+                latency: None,
+                bandwidth: None,
+                tlb: Some(TlbSweep {
+                    min_ms: 120,
+                    step: 2.0,
+                    probes: vec![TlbProbe {
+                        page_bytes: 4096,
+                        points,
+                        reach,
+                    }],
+                }),
+                mlp: None,
+                loaded: None,
+                signature: None,
+            };
+            let outcome = check(&serde_json::to_string_pretty(&sweep).unwrap(), false);
+            assert!(outcome.problems.is_empty(), "seed {seed}: {outcome:?}");
+        }
+    }
+
+    #[test]
+    fn a_tlb_probe_must_report_the_reach_its_points_give() {
+        use threadstone_core::sweep::{TlbPoint, TlbProbe, TlbSweep};
+
+        // Translation is free to 64 pages, 2 ns to 1024 and 20 ns beyond.
+        let points: Vec<TlbPoint> = (2..=14)
+            .map(|shift| {
+                let pages = 1usize << shift;
+                let ns = match pages {
+                    0..=64 => 1.0,
+                    65..=1024 => 3.0,
+                    _ => 21.0,
+                };
+                TlbPoint {
+                    pages,
+                    latency_ns: ns,
+                    samples: vec![ns; 3],
+                    stats: Summary::new(&[ns; 3]).unwrap(),
+                    baseline_ns: 1.0,
+                }
+            })
+            .collect();
+        let reach = hierarchy::tlb_reach(&points, 4096);
+        assert_eq!((reach.l1_pages, reach.l2_pages), (Some(64), Some(1024)));
+        let mut sweep = SweepReport {
+            schema_version: SCHEMA_VERSION,
+            tool_version: "2.0.0".into(),
+            generated_at: "2026-08-10T12:00:00Z".into(),
+            duration_secs: 3.0,
+            system: SystemInfo {
+                logical_cores: 4,
+                ..SystemInfo::default()
+            },
+            latency: None,
+            bandwidth: None,
+            tlb: Some(TlbSweep {
+                min_ms: 120,
+                step: 2.0,
+                probes: vec![TlbProbe {
+                    page_bytes: 4096,
+                    points,
+                    reach,
+                }],
+            }),
+            mlp: None,
+            loaded: None,
+            signature: None,
+        };
+        let text = serde_json::to_string(&sweep).unwrap();
+        assert!(is_sweep(&text));
+        let outcome = check(&text, false);
+        assert!(outcome.problems.is_empty(), "{outcome:?}");
+
+        let probe = &mut sweep.tlb.as_mut().unwrap().probes[0];
+        probe.reach.walk_ns = Some(5.0);
+        probe.points[3].baseline_ns = 0.0;
+        let outcome = check(&serde_json::to_string(&sweep).unwrap(), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p.contains("non-positive baseline")),
+            "{outcome:?}"
+        );
+        sweep.tlb.as_mut().unwrap().probes[0].points[3].baseline_ns = 1.0;
+        let outcome = check(&serde_json::to_string(&sweep).unwrap(), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p.contains("reach does not follow from its points")),
+            "{outcome:?}"
+        );
+    }
+
+    #[test]
+    fn an_mlp_sweep_must_chase_the_chain_counts_it_names() {
+        use threadstone_core::sweep::{MlpPoint, MlpSweep};
+
+        let points: Vec<MlpPoint> = [(1, 90.0), (2, 45.5), (4, 23.1), (8, 12.7)]
+            .into_iter()
+            .map(|(chains, ns)| MlpPoint {
+                chains,
+                ns_per_access: ns,
+                samples: vec![ns; 3],
+                stats: Summary::new(&[ns; 3]).unwrap(),
+            })
+            .collect();
+        let mut sweep = SweepReport {
+            schema_version: SCHEMA_VERSION,
+            tool_version: "2.0.0".into(),
+            generated_at: "2026-08-10T12:00:00Z".into(),
+            duration_secs: 3.0,
+            system: SystemInfo {
+                logical_cores: 4,
+                ..SystemInfo::default()
+            },
+            latency: None,
+            bandwidth: None,
+            tlb: None,
+            mlp: Some(MlpSweep {
+                min_ms: 200,
+                pages: PagePolicy::Default,
+                points,
+            }),
+            loaded: None,
+            signature: None,
+        };
+        let key = signing::generate().unwrap();
+        sweep.signature = Some(signing::sign(&sweep.signing_bytes().unwrap(), &key.pkcs8).unwrap());
+        let text = serde_json::to_string_pretty(&sweep).unwrap();
+        assert!(is_sweep(&text));
+        let outcome = check(&text, true);
+        assert!(outcome.is_ok(), "{outcome:?}");
+        sweep.signature = None;
+
+        let points = &mut sweep.mlp.as_mut().unwrap().points;
+        points.swap(1, 2);
+        points[3].chains = 3;
+        points[0].ns_per_access = 80.0;
+        let outcome = check(&serde_json::to_string(&sweep).unwrap(), false);
+        for expected in [
+            "chain counts are not in ascending order",
+            "MLP sweep at 3 chains: not a chain count that is measured",
+            "MLP sweep at 1 chains: latency is not the median of its samples",
+        ] {
+            assert!(
+                outcome.problems.iter().any(|p| p.contains(expected)),
+                "{expected}: {outcome:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn a_loaded_latency_sweep_must_step_from_idle_through_the_delays_it_names() {
+        use threadstone_core::sweep::{LoadedPoint, LoadedSweep};
+
+        let points: Vec<LoadedPoint> = [(None, 0.0, 90.0), (Some(5000), 4.2, 95.5)]
+            .into_iter()
+            .chain([(Some(100), 18.9, 140.0), (Some(0), 21.3, 210.0)])
+            .map(|(delay, bandwidth_gib_s, latency_ns)| LoadedPoint {
+                delay,
+                bandwidth_gib_s,
+                latency_ns,
+            })
+            .collect();
+        let mut sweep = SweepReport {
+            schema_version: SCHEMA_VERSION,
+            tool_version: "2.0.0".into(),
+            generated_at: "2026-08-10T12:00:00Z".into(),
+            duration_secs: 3.0,
+            system: SystemInfo {
+                logical_cores: 4,
+                ..SystemInfo::default()
+            },
+            latency: None,
+            bandwidth: None,
+            tlb: None,
+            mlp: None,
+            loaded: Some(LoadedSweep {
+                min_ms: 200,
+                injectors: 3,
+                pages: PagePolicy::Default,
+                points,
+            }),
+            signature: None,
+        };
+        let key = signing::generate().unwrap();
+        sweep.signature = Some(signing::sign(&sweep.signing_bytes().unwrap(), &key.pkcs8).unwrap());
+        let text = serde_json::to_string_pretty(&sweep).unwrap();
+        assert!(is_sweep(&text));
+        let outcome = check(&text, true);
+        assert!(outcome.is_ok(), "{outcome:?}");
+        sweep.signature = None;
+
+        let points = &mut sweep.loaded.as_mut().unwrap().points;
+        points.swap(1, 2);
+        points[3].delay = Some(7);
+        points[0].latency_ns = -1.0;
+        let outcome = check(&serde_json::to_string(&sweep).unwrap(), false);
+        for expected in [
+            "delays are not in descending order",
+            "loaded-latency sweep at delay 7: not a delay that is measured",
+            "loaded-latency sweep idle: implausible latency",
+        ] {
+            assert!(
+                outcome.problems.iter().any(|p| p.contains(expected)),
+                "{expected}: {outcome:?}"
+            );
+        }
+
+        sweep.loaded.as_mut().unwrap().points.rotate_left(1);
+        let outcome = check(&serde_json::to_string(&sweep).unwrap(), false);
+        assert!(
+            outcome
+                .problems
+                .iter()
+                .any(|p| p.contains("does not start idle")),
+            "{outcome:?}"
+        );
+    }
+
     #[test]
     fn rendering_names_each_check() {
         let text = render(
@@ -493,8 +493,12 @@ fn sweep_maps_the_cache_hierarchy() {
         .success();
 
     let text = std::fs::read_to_string(&out).unwrap();
-    let points: Vec<serde_json::Value> = serde_json::from_str(&text).unwrap();
+    let sweep: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(sweep["schema_version"], 2);
+    assert!(sweep["system"]["logical_cores"].as_u64().unwrap() > 0);
+    let points = sweep["latency"]["points"].as_array().unwrap();
     assert!(points.len() >= 10);
+    assert!(sweep["latency"]["levels"].is_array());
 
     let first = points.first().unwrap()["latency_ns"].as_f64().unwrap();
     let last = points.last().unwrap()["latency_ns"].as_f64().unwrap();
@@ -503,3 +507,795 @@ fn sweep_maps_the_cache_hierarchy() {
         "a 256 MiB chase ({last:.1}ns) must be far slower than a 4 KiB one ({first:.1}ns)"
     );
 }
+
+#[test]
+fn finer_sweeps_take_repeated_samples_at_every_size() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("sweep.json");
+
+    threadstone()
+        .args(["sweep", "--step", "1.25", "--samples", "3", "--min-ms", "3"])
+        .args(["--max-mib", "1", "--out", out.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let text = std::fs::read_to_string(&out).unwrap();
+    let sweep: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(sweep["latency"]["step"], 1.25);
+    let points = sweep["latency"]["points"].as_array().unwrap();
+    // Quarter steps over the eight octaves from 4 KiB to 1 MiB.
+    assert_eq!(points.len(), 8 * 4 + 1);
+    assert_eq!(points[1]["bytes"], 5120);
+    for p in points {
+        assert_eq!(p["samples"].as_array().unwrap().len(), 3);
+        assert_eq!(p["latency_ns"], p["stats"]["median"]);
+    }
+
+    for step in ["1", "1.001"] {
+        threadstone()
+            .args(["sweep", "--step", step])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("--step must be at least 1.01"));
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn a_tlb_sweep_strides_a_page_per_node_and_verifies() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("tlb.json");
+
+    threadstone()
+        .args(["sweep", "--tlb", "--min-ms", "2", "--samples", "2"])
+        .args(["--max-mib", "16", "--out", out.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let text = std::fs::read_to_string(&out).unwrap();
+    let sweep: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let probes = sweep["tlb"]["probes"].as_array().unwrap();
+    assert_eq!(probes[0]["page_bytes"], 4096);
+    let points = probes[0]["points"].as_array().unwrap();
+    // Four pages to the 4096 that span 16 MiB.
+    assert_eq!(points.len(), 11);
+    assert_eq!(points[0]["pages"], 4);
+    for p in points {
+        assert!(p["baseline_ns"].as_f64().unwrap() > 0.0);
+    }
+    // 2 MiB pages are probed only where they were granted.
+    for probe in &probes[1..] {
+        assert_eq!(probe["page_bytes"], 2 << 20);
+    }
+
+    threadstone()
+        .args(["verify", out.to_str().unwrap()])
+        .assert()
+        .success();
+    threadstone()
+        .args(["report", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4 KiB pages"));
+
+    threadstone()
+        .args(["sweep", "--tlb", "--bandwidth"])
+        .assert()
+        .failure();
+    threadstone()
+        .args(["sweep", "--tlb", "--step", "1.001"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--step must be at least 1.01"));
+    threadstone()
+        .args(["sweep", "--tlb", "--max-mib", "18446744073709551615"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "more than this machine can address",
+        ));
+}
+
+#[test]
+fn a_sweep_is_signed_and_verified_like_a_result() {
+    let dir = TempDir::new().unwrap();
+    let key = dir.path().join("threadstone.key");
+    let out = dir.path().join("sweep.json");
+
+    threadstone()
+        .args(["keygen", "--dir", dir.path().to_str().unwrap()])
+        .assert()
+        .success();
+    threadstone()
+        .args(["sweep", "--min-ms", "2", "--samples", "2", "--max-mib", "1"])
+        .args(["--out", out.to_str().unwrap()])
+        .args(["--sign-key", key.to_str().unwrap()])
+        .assert()
+        .success();
+
+    threadstone()
+        .args(["verify", out.to_str().unwrap(), "--require-signature"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("signature   verified"));
+    threadstone()
+        .args(["report", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("working set"));
+
+    // Halving one latency is caught by the signature.
+    let mut sweep: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    let first = &mut sweep["latency"]["points"][0];
+    let halved = first["latency_ns"].as_f64().unwrap() / 2.0;
+    first["latency_ns"] = serde_json::json!(halved);
+    std::fs::write(&out, serde_json::to_string(&sweep).unwrap()).unwrap();
+    threadstone()
+        .args(["verify", out.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("signature   INVALID"));
+
+    // Re-signed after the fact, the edit still shows: a latency that is not
+    // the median of its samples.
+    threadstone()
+        .args([
+            "sign",
+            out.to_str().unwrap(),
+            "--key",
+            key.to_str().unwrap(),
+        ])
+        .assert()
+        .success();
+    threadstone()
+        .args(["verify", out.to_str().unwrap()])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("signature   verified"))
+        .stdout(predicate::str::contains("not the median of its samples"));
+}
+
+#[test]
+fn sweep_measures_bandwidth_per_kernel_and_thread_count() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("bandwidth.json");
+
+    threadstone()
+        .args([
+            "sweep",
+            "--bandwidth",
+            "--min-ms",
+            "2",
+            "--max-mib",
+            "1",
+            "-o",
+        ])
+        .arg(&out)
+        .assert()
+        .success();
+
+    let text = std::fs::read_to_string(&out).unwrap();
+    let sweep: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert!(sweep.get("latency").is_none());
+    let points = sweep["bandwidth"]["points"].as_array().unwrap();
+    // 4 KiB to 1 MiB is nine sizes, for each kernel at each thread count.
+    assert_eq!(points.len() % 18, 0, "{points:?}");
+    for access in ["read", "rmw"] {
+        let at_one = points
+            .iter()
+            .filter(|p| p["access"] == access && p["threads"] == 1)
+            .count();
+        assert_eq!(at_one, 9, "{access}");
+    }
+    assert_eq!(points[0]["bytes"], 4096);
+    assert!(points
+        .iter()
+        .all(|p| p["bandwidth_gib_s"].as_f64().unwrap() > 0.0));
+
+    threadstone()
+        .args(["sweep", "--max-mib", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--max-mib"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn a_pinned_run_records_its_placement() {
+    let output = threadstone()
+        .args(quick_run("sha256"))
+        .args(["--cpus", "0", "--format", "json"])
+        .assert()
+        .success();
+
+    let text = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(
+        report["config"]["affinity"]["explicit"],
+        serde_json::json!([0])
+    );
+
+    let pass = &report["workloads"][0]["multi_thread"];
+    assert_eq!(pass["pinned_cpus"], serde_json::json!([0, 0]));
+    assert_eq!(pass["cpus"], serde_json::json!([0, 0]));
+}
+
+#[cfg(not(target_os = "linux"))]
+#[test]
+fn pinning_is_refused_where_it_is_unsupported() {
+    threadstone()
+        .args(quick_run("sha256"))
+        .args(["--affinity", "compact"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("only supported on Linux"));
+}
+
+#[test]
+fn affinity_and_an_explicit_cpu_list_conflict() {
+    threadstone()
+        .args(["run", "--affinity", "compact", "--cpus", "0"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn a_scaling_run_reports_the_whole_curve() {
+    let output = threadstone()
+        .args(quick_run("sha256"))
+        .args(["--scaling", "1,3", "--format", "json"])
+        .assert()
+        .success();
+
+    let text = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(report["config"]["scaling"], serde_json::json!([1, 3]));
+
+    let points = report["workloads"][0]["scaling_curve"]["points"]
+        .as_array()
+        .expect("a curve must be recorded");
+    let threads: Vec<u64> = points
+        .iter()
+        .map(|p| p["threads"].as_u64().unwrap())
+        .collect();
+    assert_eq!(threads, vec![1, 3]);
+}
+
+#[test]
+fn a_malformed_scaling_list_is_rejected() {
+    threadstone()
+        .args(["run", "--scaling", "1,two"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a thread count"));
+}
+
+#[test]
+fn core_classes_are_refused_without_a_detected_split() {
+    let system = threadstone_core::SystemInfo::detect();
+    if !system.performance_cpus.is_empty() {
+        return; // a hybrid machine: the flag is meaningful here
+    }
+    threadstone()
+        .args(quick_run("sha256"))
+        .arg("--core-classes")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--core-classes"));
+}
+
+#[test]
+fn a_counters_run_succeeds_and_records_whether_counters_worked() {
+    let output = threadstone()
+        .args(quick_run("sha256"))
+        .args(["--counters", "--single-only", "--format", "json"])
+        .assert()
+        .success();
+
+    let text = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let status = &report["config"]["counters"];
+    let pass = &report["workloads"][0]["single_thread"];
+    match status["status"].as_str() {
+        Some("enabled") => assert!(pass["counters"]["ipc"].as_f64().unwrap() > 0.0),
+        Some("unavailable") => {
+            assert!(!status["reason"].as_str().unwrap().is_empty());
+            assert!(pass.get("counters").is_none());
+        }
+        other => panic!("counter status not recorded: {other:?}"),
+    }
+}
+
+#[test]
+fn an_adaptive_run_records_whether_it_met_its_target() {
+    let output = threadstone()
+        .args(quick_run("sha256"))
+        .args(["--single-only", "--format", "json"])
+        .args(["--target-ci", "0.0001%", "--max-samples", "4"])
+        .assert()
+        .success();
+
+    let text = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(report["config"]["precision"]["max_samples"], 4);
+    let pass = &report["workloads"][0]["single_thread"];
+    let precision = &pass["precision"];
+    assert_eq!(pass["samples"].as_array().unwrap().len(), 4);
+    assert_eq!(precision["met"], false);
+    assert_eq!(precision["stopped"], "max_samples");
+}
+
+#[test]
+fn a_budgeted_run_records_its_plan() {
+    let output = threadstone()
+        .args([
+            "run",
+            "--workload",
+            "sha256",
+            "--threads",
+            "2",
+            "--budget",
+            "3s",
+            "--format",
+            "json",
+        ])
+        .assert()
+        .success();
+
+    let text = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let plan = &report["config"]["budget"];
+    assert_eq!(plan["budget_ms"], 3000);
+    assert_eq!(plan["window_ms"], report["config"]["window_ms"]);
+    assert!(plan["window_ms"].as_u64().unwrap() >= 20);
+    assert_eq!(plan["workloads"][0]["id"], "sha256");
+}
+
+#[test]
+fn a_budget_replaces_the_sampling_flags() {
+    threadstone()
+        .args(["run", "--budget", "90s", "--samples", "5"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--budget"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn a_run_records_the_load_it_ran_under() {
+    let output = threadstone()
+        .args(quick_run("sha256"))
+        .args(["--single-only", "--format", "json"])
+        .assert()
+        .success();
+
+    // Other tests share the machine, so whether it counts as loaded varies;
+    // that it was sampled, and when, does not.
+    let text = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let report: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let load = report["load"].as_array().unwrap();
+    assert_eq!(load.len(), 2);
+    assert_eq!(load[0]["before"], "start");
+    assert_eq!(load[1]["before"], "sha256 at 1 thread");
+}
+
+#[test]
+fn an_idle_wait_needs_a_threshold() {
+    threadstone()
+        .args(["run", "--idle-wait", "30s"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--require-idle"));
+}
+
+#[test]
+fn sampling_budgets_need_a_target() {
+    threadstone()
+        .args(["run", "--max-samples", "20"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--target-ci"));
+}
+
+#[cfg(unix)]
+#[test]
+fn an_interrupted_run_still_writes_a_signed_partial_report() {
+    let dir = TempDir::new().unwrap();
+    let key = dir.path().join("threadstone.key");
+    let out = dir.path().join("partial.json");
+    threadstone()
+        .args(["keygen", "--dir", dir.path().to_str().unwrap()])
+        .assert()
+        .success();
+
+    // A thousand rounds of at least 2 ms each, so the multi-threaded pass
+    // lasts two seconds however fast the machine, and the signal is sent once
+    // the single-threaded pass has printed its line: always mid-run, and never
+    // before the handler is installed.
+    let mut args: Vec<String> = quick_run("sha256")
+        .into_iter()
+        .filter(|a| a != "--quiet")
+        .collect();
+    let samples = args.iter().position(|a| a == "--samples").unwrap();
+    args[samples + 1] = "1000".to_string();
+    let window = args.iter().position(|a| a == "--window-ms").unwrap();
+    args[window + 1] = "2".to_string();
+    let mut child = threadstone()
+        .args(args)
+        .args(["--out", out.to_str().unwrap()])
+        .args(["--sign-key", key.to_str().unwrap()])
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stderr = child.stderr.take().unwrap();
+    let mut byte = [0u8];
+    while std::io::Read::read(&mut stderr, &mut byte).unwrap() == 1 && byte[0] != b'\n' {}
+    let killed = Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .unwrap();
+    assert!(killed.success());
+    // Drained, so that nothing the run says on its way out can fill the pipe.
+    std::io::copy(&mut stderr, &mut std::io::sink()).unwrap();
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(1), "an interrupted run must not exit 0");
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    let error = report["workloads"][0]["error"].as_str().unwrap();
+    assert!(error.starts_with("interrupted"), "got: {error}");
+
+    threadstone()
+        .args(["verify", out.to_str().unwrap(), "--require-signature"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("signature   verified"))
+        .stdout(predicate::str::contains("partial run"));
+}
+
+#[test]
+fn an_isolated_run_verifies_like_any_other() {
+    let dir = TempDir::new().unwrap();
+    for per in ["workload", "pass"] {
+        let out = dir.path().join(format!("{per}.json"));
+        threadstone()
+            .args(quick_run("sha256"))
+            .args(["--isolate", per, "--out", out.to_str().unwrap()])
+            .assert()
+            .success();
+
+        let report: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+        assert_eq!(report["config"]["isolate"], per);
+        assert!(report["workloads"][0]["single_thread"]["value"].is_number());
+        assert!(report["workloads"][0]["multi_thread"]["value"].is_number());
+        threadstone()
+            .args(["verify", out.to_str().unwrap()])
+            .assert()
+            .success();
+    }
+}
+
+#[test]
+fn a_tuned_workload_is_recorded_and_left_out_of_the_score() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("tuned.json");
+    threadstone()
+        .args(quick_run("sgemm:n=64"))
+        .args(["--workload", "sha256", "--isolate", "workload"])
+        .args(["--out", out.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    let sgemm = &report["workloads"][0];
+    assert_eq!(
+        sgemm["params"],
+        serde_json::json!([{ "name": "n", "value": 64, "default": 256 }])
+    );
+    assert!(sgemm["single_thread"]["value"].is_number());
+    let sha256 = &report["workloads"][1]["params"][0];
+    assert_eq!(sha256["name"], "kib");
+    assert_eq!(sha256["value"], sha256["default"]);
+    let scored: Vec<&str> = report["score"]["single_core_components"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(scored, ["sha256"]);
+    threadstone()
+        .args(["verify", out.to_str().unwrap()])
+        .assert()
+        .success();
+    threadstone()
+        .args(["report", out.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "left out of the scores: sgemm n=64",
+        ));
+
+    threadstone()
+        .args(quick_run("sgemm:n=66"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a multiple of 4"));
+}
+
+#[test]
+fn a_repeated_run_records_every_launch_and_verifies() {
+    let dir = TempDir::new().unwrap();
+    let out = dir.path().join("repeat.json");
+    threadstone()
+        .args(quick_run("sha256"))
+        .args(["--repeat", "2", "--out", out.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(report["config"]["repeat"], 2);
+    assert_eq!(report["config"]["isolate"], "workload");
+    let pass = &report["workloads"][0]["single_thread"];
+    assert_eq!(pass["repeats"]["medians"].as_array().unwrap().len(), 2);
+    assert_eq!(pass["samples"].as_array().unwrap().len(), 2);
+    threadstone()
+        .args(["verify", out.to_str().unwrap()])
+        .assert()
+        .success();
+
+    threadstone()
+        .args(quick_run("sha256"))
+        .args(["--repeat", "0"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn ab_compares_two_binaries_round_by_round() {
+    let bin = assert_cmd::cargo::cargo_bin("threadstone");
+    let bin = bin.to_str().unwrap();
+    let ab = |extra: &[&str]| {
+        let mut cmd = threadstone();
+        cmd.args(["ab", "--baseline", bin, "--candidate", bin, "-w", "sha256"])
+            .args([
+                "--rounds",
+                "6",
+                "--window-ms",
+                "15",
+                "--threads",
+                "2",
+                "--quiet",
+            ])
+            .args(extra);
+        cmd
+    };
+
+    let output = ab(&["--order", "random", "--seed", "7"]).output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("6 rounds in random order, seed 7"), "{text}");
+    assert!(text.contains("single-thread"), "{text}");
+    assert!(text.contains("multi-thread"), "{text}");
+    assert!(text.contains("sha256"), "{text}");
+
+    threadstone()
+        .args(["ab", "--baseline", bin, "--candidate", bin, "--rounds", "5"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("at least 6"));
+    threadstone()
+        .args([
+            "ab",
+            "--baseline",
+            "/nonexistent/threadstone",
+            "--candidate",
+            bin,
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "baseline /nonexistent/threadstone",
+        ));
+}
+
+#[test]
+fn a_page_policy_is_recorded_with_the_huge_pages_it_got() {
+    let output = threadstone()
+        .args(quick_run("latency"))
+        .args(["--pages", "thp", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["config"]["pages"], "thp");
+    if cfg!(target_os = "linux") {
+        let pass = &report["workloads"][0]["single_thread"];
+        assert!(pass["huge_pages"]["anon_kib"].is_u64(), "{pass}");
+    }
+}
+
+#[test]
+fn the_child_answers_a_pass_request_in_json_lines() {
+    let request = r#"{"workload":"sha256","threads":1,"samples":1,"warmup":0,"window":{"secs":0,"nanos":15000000}}"#;
+    let output = assert_cmd::Command::cargo_bin("threadstone")
+        .unwrap()
+        .arg("child")
+        .write_stdin(format!("{request}\n"))
+        .assert()
+        .success();
+
+    let text = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let lines: Vec<serde_json::Value> = text
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines[0]["hello"]["protocol"], 1);
+    assert!(lines.iter().any(|l| l.get("sample").is_some()));
+    assert_eq!(lines.last().unwrap()["pass"]["threads"], 1);
+}
+
+#[test]
+fn numa_measures_every_node_against_every_node() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("numa.json");
+    let output = threadstone()
+        .args([
+            "numa",
+            "--samples",
+            "1",
+            "--window-ms",
+            "15",
+            "--quiet",
+            "-o",
+        ])
+        .arg(&path)
+        .output()
+        .unwrap();
+    if !std::path::Path::new("/sys/devices/system/node/online").exists() {
+        assert!(!output.status.success(), "{output:?}");
+        return;
+    }
+    assert!(output.status.success(), "{output:?}");
+    let matrix: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let nodes = matrix["nodes"].as_array().unwrap();
+    assert!(!nodes.is_empty());
+    let cells = matrix["cells"].as_array().unwrap();
+    let local = cells
+        .iter()
+        .find(|c| c["cpu_node"] == c["memory_node"])
+        .expect("a node with CPUs and memory has a local cell");
+    assert!(local["latency_ns"].as_f64().unwrap() > 0.0, "{local}");
+    assert!(local["bandwidth_gib_s"].as_f64().unwrap() > 0.0, "{local}");
+}
+
+#[test]
+fn c2c_measures_a_pair_and_refuses_a_single_cpu() {
+    let output = threadstone()
+        .args(["c2c", "--cpus", "0", "--quiet"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success(), "{output:?}");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("at least two CPUs"));
+
+    let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+    if cpus < 2 || !cfg!(target_os = "linux") {
+        return;
+    }
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("c2c.json");
+    threadstone()
+        .args(["c2c", "--cpus", "0,1", "--min-ms", "1", "--quiet", "-o"])
+        .arg(&path)
+        .assert()
+        .success();
+    let matrix: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(matrix["cpus"], serde_json::json!([0, 1]));
+    assert!(matrix["latency_ns"][0][0].is_null());
+    assert!(
+        matrix["latency_ns"][0][1].as_f64().unwrap() > 0.0,
+        "{matrix}"
+    );
+    assert_eq!(matrix["latency_ns"][0][1], matrix["latency_ns"][1][0]);
+}
+
+#[test]
+fn loaded_latency_steps_from_idle_to_full_injection() {
+    let dir = TempDir::new().unwrap();
+    let key = dir.path().join("threadstone.key");
+    let path = dir.path().join("loaded.json");
+    threadstone()
+        .args(["keygen", "--dir", dir.path().to_str().unwrap()])
+        .assert()
+        .success();
+    threadstone()
+        .args(["loaded-latency", "--injectors", "1", "--min-ms", "10", "-o"])
+        .arg(&path)
+        .arg("--sign-key")
+        .arg(&key)
+        .assert()
+        .success();
+    let sweep: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(sweep["loaded"]["injectors"], 1);
+    let points = sweep["loaded"]["points"].as_array().unwrap();
+    assert!(points[0]["delay"].is_null(), "the first point is idle");
+    assert_eq!(points.last().unwrap()["delay"], 0);
+    assert!(points
+        .iter()
+        .all(|p| p["latency_ns"].as_f64().unwrap() > 0.0));
+
+    threadstone()
+        .arg("verify")
+        .arg(&path)
+        .arg("--require-signature")
+        .assert()
+        .success();
+    threadstone()
+        .arg("report")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("always measured idle"));
+
+    threadstone()
+        .args(["loaded-latency", "--injectors", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("at least 1"));
+}
+
+#[test]
+fn mlp_measures_every_chain_count() {
+    let dir = TempDir::new().unwrap();
+    let key = dir.path().join("threadstone.key");
+    let path = dir.path().join("mlp.json");
+    threadstone()
+        .args(["keygen", "--dir", dir.path().to_str().unwrap()])
+        .assert()
+        .success();
+    threadstone()
+        .args(["mlp", "--min-ms", "5", "--samples", "2", "-o"])
+        .arg(&path)
+        .arg("--sign-key")
+        .arg(&key)
+        .assert()
+        .success();
+    let sweep: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(sweep["mlp"]["pages"], "default");
+    let points = sweep["mlp"]["points"].as_array().unwrap();
+    let chains: Vec<u64> = points
+        .iter()
+        .map(|p| p["chains"].as_u64().unwrap())
+        .collect();
+    assert_eq!(chains, [1, 2, 4, 8, 16, 32]);
+    for p in points {
+        assert_eq!(p["samples"].as_array().unwrap().len(), 2);
+        assert_eq!(p["ns_per_access"], p["stats"]["median"]);
+    }
+
+    threadstone()
+        .arg("verify")
+        .arg(&path)
+        .arg("--require-signature")
+        .assert()
+        .success();
+    threadstone()
+        .arg("report")
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("always one chain"));
+
+    threadstone()
+        .args(["mlp", "--samples", "0"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("at least 1"));
+}
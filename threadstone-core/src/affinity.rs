@@ -0,0 +1,475 @@
+//! Binding worker threads to specific logical CPUs.
+//!
+//! Left to itself, the scheduler places benchmark threads wherever it likes and
+//! moves them whenever it likes. On a single-socket homogeneous desktop that
+//! costs a little variance. On a hybrid CPU it decides whether a thread ran on a
+//! performance or an efficiency core, and on a multi-socket server it decides
+//! which memory controller a thread's pages live behind — both of which are
+//! differences in *what was measured*, not noise around it.
+//!
+//! An [`Affinity`] plan names a placement policy. [`Affinity::resolve`] turns it
+//! into a concrete CPU per worker using the topology under `/sys`, and the
+//! runner binds each worker before it allocates its state, so first-touch page
+//! placement follows the thread. Both the plan and the CPU each thread actually
+//! ran on are recorded in the result, so two results pinned differently are
+//! never silently compared.
+//!
+//! Pinning is implemented for Linux only. macOS offers no binding API that
+//! Apple silicon honours, and a plan that cannot be applied is refused rather
+//! than recorded as though it had been.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// CPUs an affinity mask can name: `cpu_set_t` is a 1024-bit mask in glibc and
+/// musl alike.
+pub const MAX_CPUS: usize = 1024;
+
+/// How worker threads are placed on logical CPUs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Affinity {
+    /// Fill one physical core's SMT siblings before moving to the next core,
+    /// and one socket before the next. Keeps threads close together, sharing
+    /// caches.
+    Compact,
+    /// Spread threads across sockets first, then across physical cores, and
+    /// only use SMT siblings once every core has one thread.
+    Scatter,
+    /// One thread per physical core in CPU order, then SMT siblings. The usual
+    /// choice for throughput runs that should not share a core's pipelines
+    /// until they have to.
+    PhysicalFirst,
+    /// Exactly these logical CPUs, in this order. Worker `i` is bound to entry
+    /// `i % len`.
+    Explicit(Vec<usize>),
+}
+
+impl Affinity {
+    /// Short label for tables and mismatch warnings.
+    pub fn label(&self) -> String {
+        match self {
+            Affinity::Compact => "compact".to_string(),
+            Affinity::Scatter => "scatter".to_string(),
+            Affinity::PhysicalFirst => "physical-first".to_string(),
+            Affinity::Explicit(cpus) => format!("cpus {}", format_cpu_list(cpus)),
+        }
+    }
+
+    /// The CPU each of `threads` workers should be bound to, in worker order.
+    ///
+    /// When a plan names fewer CPUs than there are threads, the list wraps, so
+    /// an over-subscribed run is still deterministic about which threads share.
+    pub fn resolve(&self, threads: usize) -> Result<Vec<usize>, String> {
+        if !supported() {
+            return Err("thread pinning is only supported on Linux".to_string());
+        }
+        let topology = Topology::detect()
+            .ok_or_else(|| "cannot read the CPU topology from /sys".to_string())?;
+        let order = self.order(&topology)?;
+        if order.is_empty() {
+            return Err("the affinity plan selects no usable CPUs".to_string());
+        }
+        Ok((0..threads).map(|i| order[i % order.len()]).collect())
+    }
+
+    /// The plan's CPU order over `topology`, before wrapping to a thread count.
+    fn order(&self, topology: &Topology) -> Result<Vec<usize>, String> {
+        match self {
+            Affinity::Compact => {
+                let mut cpus = topology.cpus.clone();
+                cpus.sort_by_key(|c| (c.package, c.core, c.cpu));
+                Ok(cpus.iter().map(|c| c.cpu).collect())
+            }
+            Affinity::PhysicalFirst => Ok(physical_first(&topology.cpus, false)),
+            Affinity::Scatter => Ok(physical_first(&topology.cpus, true)),
+            Affinity::Explicit(cpus) => {
+                for (i, cpu) in cpus.iter().enumerate() {
+                    if cpus[..i].contains(cpu) {
+                        return Err(format!("CPU {cpu} is listed twice"));
+                    }
+                    if !topology.cpus.iter().any(|c| c.cpu == *cpu) {
+                        return Err(format!(
+                            "CPU {cpu} is not online or not available to this process"
+                        ));
+                    }
+                }
+                Ok(cpus.clone())
+            }
+        }
+    }
+}
+
+/// One logical CPU's place in the machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuLocation {
+    /// Logical CPU number, as the kernel numbers it.
+    pub cpu: usize,
+    /// Socket.
+    pub package: usize,
+    /// Physical core within the socket.
+    pub core: usize,
+}
+
+/// The logical CPUs this process may run on, and where each one sits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Topology {
+    /// Usable CPUs, ascending by CPU number.
+    pub cpus: Vec<CpuLocation>,
+}
+
+impl Topology {
+    /// Read the topology from `/sys`, restricted to the CPUs this process is
+    /// allowed to use. `None` where that is not possible.
+    pub fn detect() -> Option<Topology> {
+        imp::topology()
+    }
+}
+
+/// Order CPUs one per physical core first, then the remaining SMT siblings.
+///
+/// With `interleave_packages`, each of those two phases alternates between
+/// sockets, so consecutive threads land on different packages.
+fn physical_first(cpus: &[CpuLocation], interleave_packages: bool) -> Vec<usize> {
+    let mut sorted = cpus.to_vec();
+    sorted.sort_by_key(|c| (c.package, c.core, c.cpu));
+
+    let mut primaries = Vec::new();
+    let mut siblings = Vec::new();
+    for (i, c) in sorted.iter().enumerate() {
+        let first_on_core =
+            i == 0 || (sorted[i - 1].package, sorted[i - 1].core) != (c.package, c.core);
+        if first_on_core {
+            primaries.push(*c);
+        } else {
+            siblings.push(*c);
+        }
+    }
+
+    let phase = |group: Vec<CpuLocation>| -> Vec<usize> {
+        if !interleave_packages {
+            return group.iter().map(|c| c.cpu).collect();
+        }
+        // Round-robin across packages: the k-th CPU of every package, then the
+        // (k+1)-th, and so on.
+        let mut packages: Vec<usize> = group.iter().map(|c| c.package).collect();
+        packages.dedup();
+        let per_package: Vec<Vec<usize>> = packages
+            .iter()
+            .map(|p| {
+                group
+                    .iter()
+                    .filter(|c| c.package == *p)
+                    .map(|c| c.cpu)
+                    .collect()
+            })
+            .collect();
+        let longest = per_package.iter().map(Vec::len).max().unwrap_or(0);
+        (0..longest)
+            .flat_map(|k| {
+                per_package
+                    .iter()
+                    .filter_map(move |list| list.get(k).copied())
+            })
+            .collect()
+    };
+
+    let mut order = phase(primaries);
+    order.extend(phase(siblings));
+    order
+}
+
+/// Parse a Linux-style CPU list such as `"0-7,16-23"` or `"3"`.
+///
+/// The same syntax `/sys` uses for `cpulist` files and `taskset -c` accepts.
+/// CPUs at or past [`MAX_CPUS`] are refused, since no mask can bind them.
+pub fn parse_cpu_list(text: &str) -> Result<Vec<usize>, String> {
+    let mut cpus = Vec::new();
+    for part in text.trim().split(',').map(str::trim) {
+        if part.is_empty() {
+            continue;
+        }
+        let parse = |s: &str| {
+            let cpu = s
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("'{s}' is not a CPU number"))?;
+            if cpu >= MAX_CPUS {
+                return Err(format!(
+                    "CPU {cpu} is past the last one a mask can name ({})",
+                    MAX_CPUS - 1
+                ));
+            }
+            Ok(cpu)
+        };
+        match part.split_once('-') {
+            Some((lo, hi)) => {
+                let (lo, hi) = (parse(lo)?, parse(hi)?);
+                if lo > hi {
+                    return Err(format!("range '{part}' runs backwards"));
+                }
+                cpus.extend(lo..=hi);
+            }
+            None => cpus.push(parse(part)?),
+        }
+    }
+    if cpus.is_empty() {
+        return Err("empty CPU list".to_string());
+    }
+    Ok(cpus)
+}
+
+/// Render CPUs compactly, collapsing consecutive runs into ranges.
+pub fn format_cpu_list(cpus: &[usize]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < cpus.len() {
+        let start = cpus[i];
+        let mut end = start;
+        while i + 1 < cpus.len() && cpus[i + 1] == end + 1 {
+            end += 1;
+            i += 1;
+        }
+        parts.push(if end > start {
+            format!("{start}-{end}")
+        } else {
+            start.to_string()
+        });
+        i += 1;
+    }
+    parts.join(",")
+}
+
+/// Whether this platform can bind threads to CPUs at all.
+pub fn supported() -> bool {
+    imp::SUPPORTED
+}
+
+/// Bind the calling thread to `cpu`.
+pub fn bind_current_thread(cpu: usize) -> Result<(), String> {
+    imp::bind(cpu)
+}
+
+/// The logical CPU the calling thread is running on right now, where the
+/// platform can say.
+pub fn current_cpu() -> Option<usize> {
+    imp::current_cpu()
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::{parse_cpu_list, CpuLocation, Topology, MAX_CPUS};
+    use std::fs;
+
+    pub const SUPPORTED: bool = true;
+
+    /// Words in a `cpu_set_t`.
+    const MASK_WORDS: usize = MAX_CPUS / 64;
+
+    // Declared directly rather than through the `libc` crate: std already links
+    // the C library, and three prototypes are not worth a dependency.
+    extern "C" {
+        fn sched_setaffinity(pid: i32, cpusetsize: usize, mask: *const u64) -> i32;
+        fn sched_getaffinity(pid: i32, cpusetsize: usize, mask: *mut u64) -> i32;
+        fn sched_getcpu() -> i32;
+    }
+
+    pub fn bind(cpu: usize) -> Result<(), String> {
+        if cpu >= MASK_WORDS * 64 {
+            return Err(format!("CPU {cpu} is beyond the supported mask size"));
+        }
+        let mut mask = [0u64; MASK_WORDS];
+        mask[cpu / 64] |= 1 << (cpu % 64);
+        // SAFETY: `mask` is a live buffer of exactly the size passed, and pid 0
+        // names the calling thread.
+        let rc = unsafe { sched_setaffinity(0, std::mem::size_of_val(&mask), mask.as_ptr()) };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(format!(
+                "cannot bind to CPU {cpu}: {}",
+                std::io::Error::last_os_error()
+            ))
+        }
+    }
+
+    pub fn current_cpu() -> Option<usize> {
+        // SAFETY: no arguments and no memory access; returns -1 on failure.
+        let cpu = unsafe { sched_getcpu() };
+        usize::try_from(cpu).ok()
+    }
+
+    /// CPUs this process may be scheduled on, which in a container or under
+    /// `taskset` is narrower than the online set.
+    fn allowed() -> Option<Vec<usize>> {
+        let mut mask = [0u64; MASK_WORDS];
+        // SAFETY: as in `bind`, with a writable buffer.
+        let rc = unsafe { sched_getaffinity(0, std::mem::size_of_val(&mask), mask.as_mut_ptr()) };
+        if rc != 0 {
+            return None;
+        }
+        Some(
+            (0..MASK_WORDS * 64)
+                .filter(|cpu| mask[cpu / 64] & (1 << (cpu % 64)) != 0)
+                .collect(),
+        )
+    }
+
+    fn read_usize(path: &str) -> Option<usize> {
+        fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    pub fn topology() -> Option<Topology> {
+        let online = fs::read_to_string("/sys/devices/system/cpu/online").ok()?;
+        let online = parse_cpu_list(&online).ok()?;
+        let allowed = allowed().unwrap_or_else(|| online.clone());
+
+        let cpus = online
+            .into_iter()
+            .filter(|cpu| allowed.contains(cpu))
+            .map(|cpu| {
+                let base = format!("/sys/devices/system/cpu/cpu{cpu}/topology");
+                // A kernel without topology files still yields a usable, if
+                // flat, ordering: every CPU its own core on socket 0.
+                CpuLocation {
+                    cpu,
+                    package: read_usize(&format!("{base}/physical_package_id")).unwrap_or(0),
+                    core: read_usize(&format!("{base}/core_id")).unwrap_or(cpu),
+                }
+            })
+            .collect::<Vec<_>>();
+        if cpus.is_empty() {
+            None
+        } else {
+            Some(Topology { cpus })
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::Topology;
+
+    pub const SUPPORTED: bool = false;
+
+    pub fn bind(_cpu: usize) -> Result<(), String> {
+        Err("thread pinning is only supported on Linux".to_string())
+    }
+
+    pub fn current_cpu() -> Option<usize> {
+        None
+    }
+
+    pub fn topology() -> Option<Topology> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two sockets, two cores each, two SMT threads per core, numbered the way
+    /// Linux numbers a typical Xeon: all primaries first, then all siblings.
+    fn two_socket() -> Topology {
+        let loc = |cpu, package, core| CpuLocation { cpu, package, core };
+        Topology {
+            cpus: vec![
+                loc(0, 0, 0),
+                loc(1, 0, 1),
+                loc(2, 1, 0),
+                loc(3, 1, 1),
+                loc(4, 0, 0),
+                loc(5, 0, 1),
+                loc(6, 1, 0),
+                loc(7, 1, 1),
+            ],
+        }
+    }
+
+    #[test]
+    fn cpu_lists_parse_ranges_and_singles() {
+        assert_eq!(parse_cpu_list("0-3,8").unwrap(), vec![0, 1, 2, 3, 8]);
+        assert_eq!(parse_cpu_list(" 5 ").unwrap(), vec![5]);
+        assert_eq!(
+            parse_cpu_list("0-7,16-23").unwrap().len(),
+            16,
+            "the example from the request"
+        );
+        assert!(parse_cpu_list("").is_err());
+        assert!(parse_cpu_list("3-1").is_err());
+        assert!(parse_cpu_list("a").is_err());
+    }
+
+    #[test]
+    fn cpu_lists_stop_at_what_a_mask_can_name() {
+        assert_eq!(parse_cpu_list("1023").unwrap(), vec![1023]);
+        assert!(parse_cpu_list("1024").is_err());
+        let err = parse_cpu_list("0-18446744073709551615").unwrap_err();
+        assert!(err.contains("past the last"), "{err}");
+    }
+
+    #[test]
+    fn cpu_lists_format_back_to_ranges() {
+        assert_eq!(format_cpu_list(&[0, 1, 2, 3, 8]), "0-3,8");
+        assert_eq!(format_cpu_list(&[4]), "4");
+        assert_eq!(format_cpu_list(&[]), "");
+    }
+
+    #[test]
+    fn compact_fills_a_core_before_the_next() {
+        let order = Affinity::Compact.order(&two_socket()).unwrap();
+        assert_eq!(order, vec![0, 4, 1, 5, 2, 6, 3, 7]);
+    }
+
+    #[test]
+    fn physical_first_uses_every_core_before_any_sibling() {
+        let order = Affinity::PhysicalFirst.order(&two_socket()).unwrap();
+        assert_eq!(order, vec![0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn scatter_alternates_sockets() {
+        let order = Affinity::Scatter.order(&two_socket()).unwrap();
+        assert_eq!(order, vec![0, 2, 1, 3, 4, 6, 5, 7]);
+    }
+
+    #[test]
+    fn explicit_lists_are_validated_against_the_machine() {
+        let topology = two_socket();
+        assert_eq!(
+            Affinity::Explicit(vec![6, 1]).order(&topology).unwrap(),
+            vec![6, 1]
+        );
+        assert!(Affinity::Explicit(vec![1, 1]).order(&topology).is_err());
+        assert!(Affinity::Explicit(vec![99]).order(&topology).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn binding_to_an_allowed_cpu_is_observed() {
+        let topology = Topology::detect().expect("Linux exposes a topology");
+        let cpu = topology.cpus[0].cpu;
+        std::thread::spawn(move || {
+            bind_current_thread(cpu).unwrap();
+            assert_eq!(current_cpu(), Some(cpu));
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn resolution_wraps_when_threads_outnumber_cpus() {
+        if !supported() {
+            assert!(Affinity::Compact.resolve(2).is_err());
+            return;
+        }
+        let distinct = Topology::detect().unwrap().cpus.len();
+        let threads = distinct * 2 + 1;
+        let cpus = Affinity::Compact.resolve(threads).unwrap();
+        assert_eq!(cpus.len(), threads);
+        for (i, cpu) in cpus.iter().enumerate() {
+            assert_eq!(*cpu, cpus[i % distinct], "thread {i} did not wrap");
+        }
+    }
+}
@@ -0,0 +1,247 @@
+//! Fitting a suite into a fixed time slot.
+//!
+//! A CI runner gives a benchmark a slot, not a configuration, and guessing at
+//! windows and sample counts until a run happens to fit wastes the slot on
+//! retries. With a budget the suite prices each workload first — a short probe
+//! of every workload at the minimum window, which covers its setup and
+//! calibration — and then chooses one window, warmup and sample count for the
+//! whole run from what is left.
+//!
+//! # What gives way first
+//!
+//! The window shrinks first, since even [`defaults::MIN_WINDOW`] is far above
+//! the clock's resolution, and it never goes below that. Then the round
+//! counts, down to a single warmup round and three samples: fewer than that
+//! starves the outlier filter. Only when the leanest plan still does not fit
+//! are whole workloads left out, costliest first, and each one is recorded.
+//! A slot with room to spare buys extra samples at the requested window
+//! instead, up to [`defaults::MAX_SAMPLES`].
+
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::runner::defaults;
+
+/// Calibration's cost, in windows. Its rounds grow geometrically towards the
+/// target, so together they take a small multiple of the last one.
+pub const CALIBRATION_WINDOWS: f64 = 3.0;
+
+/// Fraction of the budget held back for what the estimates do not cover:
+/// describing the machine, writing the report, and estimates that ran short.
+pub const SLACK: f64 = 0.1;
+
+/// Leaner round counts, `(warmup, samples)`, tried in order once the window
+/// has reached its floor.
+const LEAN: [(u32, u32); 2] = [(1, 5), (1, 3)];
+
+/// What one workload costs, as the probe measured it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WorkloadCost {
+    /// Workload identifier.
+    pub id: String,
+    /// Passes the suite makes of it.
+    pub passes: u32,
+    /// Time outside measurement rounds across all of its passes — the load
+    /// sample before each, thread start, allocation and first touch — in
+    /// milliseconds.
+    pub setup_ms: f64,
+}
+
+impl WorkloadCost {
+    /// Predicted seconds for every pass at `window` seconds per round.
+    fn secs(&self, window: f64, warmup: u32, samples: u32) -> f64 {
+        let rounds = CALIBRATION_WINDOWS + f64::from(warmup + samples);
+        self.setup_ms / 1e3 + f64::from(self.passes) * rounds * window
+    }
+}
+
+/// How a budgeted run was fitted into its slot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BudgetPlan {
+    /// The slot, in milliseconds.
+    pub budget_ms: u64,
+    /// Time spent before planning, probes included, in milliseconds.
+    pub probe_ms: u64,
+    /// Predicted duration of the whole run under this plan, in milliseconds.
+    pub estimated_ms: u64,
+    /// Window chosen for every round, in milliseconds.
+    pub window_ms: u64,
+    /// Warmup rounds chosen for every pass.
+    pub warmup: u32,
+    /// Measured rounds chosen for every pass.
+    pub samples: u32,
+    /// The probe's price for every workload, dropped ones included.
+    pub workloads: Vec<WorkloadCost>,
+    /// Workloads left out because even the leanest plan could not fit them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped: Vec<String>,
+}
+
+impl BudgetPlan {
+    /// The window as a duration.
+    pub fn window(&self) -> Duration {
+        Duration::from_millis(self.window_ms)
+    }
+}
+
+/// Choose a window, warmup and sample count that fit `costs` into `budget`,
+/// `spent` of which has already gone. `window`, `warmup` and `samples` are
+/// what the run would use given unlimited time.
+///
+/// At least one workload is always kept, at the leanest plan if need be; a
+/// run that measures nothing is worse than one that overruns, and the plan's
+/// estimate then says by how much.
+pub fn plan(
+    budget: Duration,
+    spent: Duration,
+    costs: Vec<WorkloadCost>,
+    window: Duration,
+    warmup: u32,
+    samples: u32,
+) -> BudgetPlan {
+    let available = budget.as_secs_f64() * (1.0 - SLACK) - spent.as_secs_f64();
+    let window = window.max(defaults::MIN_WINDOW);
+    let mut kept: Vec<&WorkloadCost> = costs.iter().collect();
+    let mut dropped = Vec::new();
+    let leanest = (warmup.min(LEAN[1].0), samples.min(LEAN[1].1));
+
+    let (window, warmup, samples) = loop {
+        let setup: f64 = kept.iter().map(|c| c.setup_ms / 1e3).sum();
+        let passes: u32 = kept.iter().map(|c| c.passes).sum();
+        let per_pass = (available - setup) / f64::from(passes.max(1));
+        if let Some(fit) = fit(per_pass, window, warmup, samples) {
+            break fit;
+        }
+        if kept.len() <= 1 {
+            break (defaults::MIN_WINDOW, leanest.0, leanest.1);
+        }
+        let min = defaults::MIN_WINDOW.as_secs_f64();
+        let costliest = (0..kept.len())
+            .max_by(|&a, &b| {
+                let cost = |c: &WorkloadCost| c.secs(min, leanest.0, leanest.1);
+                cost(kept[a]).total_cmp(&cost(kept[b]))
+            })
+            .unwrap_or(0);
+        dropped.push(kept.remove(costliest).id.clone());
+    };
+
+    let secs = window.as_secs_f64();
+    let estimated: f64 = spent.as_secs_f64()
+        + kept
+            .iter()
+            .map(|c| c.secs(secs, warmup, samples))
+            .sum::<f64>();
+    BudgetPlan {
+        budget_ms: budget.as_millis() as u64,
+        probe_ms: spent.as_millis() as u64,
+        estimated_ms: (estimated * 1e3).round() as u64,
+        window_ms: window.as_millis() as u64,
+        warmup,
+        samples,
+        workloads: costs,
+        dropped,
+    }
+}
+
+/// The most generous plan that gives each pass no more than `per_pass`
+/// seconds, or `None` if even the leanest needs a window below the floor.
+fn fit(per_pass: f64, window: Duration, warmup: u32, samples: u32) -> Option<(Duration, u32, u32)> {
+    let floor = defaults::MIN_WINDOW.as_secs_f64();
+    let ladder = std::iter::once((warmup, samples)).chain(
+        LEAN.into_iter()
+            .map(|(w, s)| (w.min(warmup), s.min(samples))),
+    );
+    for (warmup, samples) in ladder {
+        let fits = per_pass / (CALIBRATION_WINDOWS + f64::from(warmup + samples));
+        if fits < floor {
+            continue;
+        }
+        if fits >= window.as_secs_f64() {
+            // Room to spare: spend it on rounds rather than longer windows.
+            let rounds = (per_pass / window.as_secs_f64() - CALIBRATION_WINDOWS).floor();
+            let extra = (rounds as u32).saturating_sub(warmup);
+            let samples = extra.clamp(samples, defaults::MAX_SAMPLES.max(samples));
+            return Some((window, warmup, samples));
+        }
+        // Whole milliseconds, as the report records them, rounded down so the
+        // plan stays inside its slot.
+        let ms = ((fits * 1e3).floor() as u64).max(defaults::MIN_WINDOW.as_millis() as u64);
+        return Some((Duration::from_millis(ms), warmup, samples));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cost(id: &str, passes: u32, setup_ms: f64) -> WorkloadCost {
+        WorkloadCost {
+            id: id.to_string(),
+            passes,
+            setup_ms,
+        }
+    }
+
+    fn defaults_plan(budget_ms: u64, costs: Vec<WorkloadCost>) -> BudgetPlan {
+        plan(
+            Duration::from_millis(budget_ms),
+            Duration::ZERO,
+            costs,
+            defaults::WINDOW,
+            defaults::WARMUP,
+            defaults::SAMPLES,
+        )
+    }
+
+    #[test]
+    fn a_generous_budget_buys_samples_not_longer_windows() {
+        let p = defaults_plan(3_600_000, vec![cost("a", 2, 100.0)]);
+        assert_eq!(p.window(), defaults::WINDOW);
+        assert_eq!(p.warmup, defaults::WARMUP);
+        assert_eq!(p.samples, defaults::MAX_SAMPLES);
+        assert!(p.dropped.is_empty());
+        assert!(p.estimated_ms <= p.budget_ms);
+    }
+
+    #[test]
+    fn a_tight_budget_shrinks_the_window_first() {
+        // Two passes of twelve rounds at 250 ms is six seconds; four is not.
+        let p = defaults_plan(4_000, vec![cost("a", 2, 0.0)]);
+        assert!(p.window() < defaults::WINDOW);
+        assert!(p.window() >= defaults::MIN_WINDOW);
+        assert_eq!((p.warmup, p.samples), (defaults::WARMUP, defaults::SAMPLES));
+        assert!(p.estimated_ms <= p.budget_ms);
+    }
+
+    #[test]
+    fn rounds_give_way_before_workloads_do() {
+        // 0.9 s over four passes is 225 ms each: short of twelve rounds at
+        // the floor, but enough for nine at 25 ms.
+        let p = defaults_plan(1_000, vec![cost("a", 2, 0.0), cost("b", 2, 0.0)]);
+        assert_eq!(p.window(), Duration::from_millis(25));
+        assert_eq!((p.warmup, p.samples), (1, 5));
+        assert!(p.dropped.is_empty());
+    }
+
+    #[test]
+    fn workloads_are_dropped_costliest_first_when_nothing_else_fits() {
+        let costs = vec![cost("cheap", 2, 10.0), cost("dear", 2, 5_000.0)];
+        let p = defaults_plan(2_000, costs);
+        assert_eq!(p.dropped, vec!["dear".to_string()]);
+        assert_eq!(p.workloads.len(), 2, "the dropped workload's price is kept");
+        assert!(p.window() >= defaults::MIN_WINDOW);
+        assert!(p.estimated_ms <= p.budget_ms);
+    }
+
+    #[test]
+    fn the_last_workload_is_kept_at_the_floor_even_over_budget() {
+        let p = defaults_plan(1_000, vec![cost("only", 2, 5_000.0)]);
+        assert!(p.dropped.is_empty());
+        assert_eq!(p.window(), defaults::MIN_WINDOW);
+        assert_eq!((p.warmup, p.samples), (1, 3));
+        assert!(p.estimated_ms > p.budget_ms, "the overrun must be visible");
+    }
+}
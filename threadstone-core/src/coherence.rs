@@ -0,0 +1,180 @@
+//! Core-to-core cache-line transfer latency.
+//!
+//! When one core writes a line another core holds, the line has to move, and
+//! how far it moves depends on where the two cores sit: through a shared L3
+//! inside one cluster (an AMD CCX, an Intel mesh tile, an Apple cluster), or
+//! across the die-to-die or socket link between clusters. That cost decides
+//! where latency-sensitive threads that share data should be placed, and no
+//! throughput figure shows it.
+//!
+//! A [`CoreToCore`] holds the one-way latency for every pair of measured CPUs,
+//! as `threadstone c2c` or `run --core-to-core` measured it, with each CPU's
+//! cluster and the matrix summarised into an intra-cluster and an
+//! inter-cluster figure. A cluster is the set of CPUs sharing the last-level
+//! cache, read from `/sys` on Linux, or a whole package where the caches do
+//! not say.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::stats;
+
+/// A core-to-core latency matrix and its summary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CoreToCore {
+    /// The CPUs measured, in matrix order; one per physical core unless
+    /// chosen explicitly.
+    pub cpus: Vec<usize>,
+    /// Cluster number of each CPU in `cpus`: CPUs with the same number share
+    /// a last-level cache.
+    pub clusters: Vec<usize>,
+    /// One-way transfer latency between `cpus[i]` and `cpus[j]`, in
+    /// nanoseconds. `null` on the diagonal and for pairs not measured.
+    pub latency_ns: Vec<Vec<Option<f64>>>,
+    /// Median latency over pairs within one cluster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intra_cluster_ns: Option<f64>,
+    /// Median latency over pairs in different clusters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inter_cluster_ns: Option<f64>,
+}
+
+impl CoreToCore {
+    /// A matrix with its summary computed.
+    pub fn new(
+        cpus: Vec<usize>,
+        clusters: Vec<usize>,
+        latency_ns: Vec<Vec<Option<f64>>>,
+    ) -> CoreToCore {
+        let mut matrix = CoreToCore {
+            cpus,
+            clusters,
+            latency_ns,
+            intra_cluster_ns: None,
+            inter_cluster_ns: None,
+        };
+        let (intra, inter) = matrix.summary();
+        matrix.intra_cluster_ns = intra;
+        matrix.inter_cluster_ns = inter;
+        matrix
+    }
+
+    /// Median intra-cluster and inter-cluster latency, from the matrix.
+    pub fn summary(&self) -> (Option<f64>, Option<f64>) {
+        let mut intra = Vec::new();
+        let mut inter = Vec::new();
+        for (i, row) in self.latency_ns.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                let (Some(value), Some(a), Some(b)) =
+                    (value, self.clusters.get(i), self.clusters.get(j))
+                else {
+                    continue;
+                };
+                if i == j {
+                    continue;
+                }
+                let pairs = if a == b { &mut intra } else { &mut inter };
+                pairs.push(*value);
+            }
+        }
+        (stats::median(&intra), stats::median(&inter))
+    }
+
+    /// Number of distinct clusters among the measured CPUs.
+    pub fn cluster_count(&self) -> usize {
+        let mut clusters = self.clusters.clone();
+        clusters.sort_unstable();
+        clusters.dedup();
+        clusters.len()
+    }
+}
+
+/// Cluster number for each of `cpus`, numbered from 0 in order of first
+/// appearance. CPUs whose cluster cannot be read share one.
+pub fn clusters(cpus: &[usize]) -> Vec<usize> {
+    let keys: Vec<Option<String>> = cpus.iter().map(|&cpu| imp::cluster_key(cpu)).collect();
+    let mut seen: Vec<&Option<String>> = Vec::new();
+    keys.iter()
+        .map(|key| match seen.iter().position(|k| *k == key) {
+            Some(n) => n,
+            None => {
+                seen.push(key);
+                seen.len() - 1
+            }
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::fs;
+
+    /// The CPUs sharing `cpu`'s highest-level cache, or failing that its
+    /// package, as a string that is equal for CPUs of one cluster.
+    pub fn cluster_key(cpu: usize) -> Option<String> {
+        let base = format!("/sys/devices/system/cpu/cpu{cpu}");
+        let read = |path: String| fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+        let mut best: Option<(u32, String)> = None;
+        for index in 0..8 {
+            let dir = format!("{base}/cache/index{index}");
+            let (Some(level), Some(shared)) = (
+                read(format!("{dir}/level")).and_then(|l| l.parse::<u32>().ok()),
+                read(format!("{dir}/shared_cpu_list")),
+            ) else {
+                continue;
+            };
+            if best.as_ref().map_or(true, |(l, _)| level > *l) {
+                best = Some((level, shared));
+            }
+        }
+        // A private L2 as the last level says nothing about clusters, and
+        // the package is the better guess.
+        match best {
+            Some((level, shared)) if level >= 3 => Some(format!("cache {shared}")),
+            _ => {
+                read(format!("{base}/topology/physical_package_id")).map(|p| format!("package {p}"))
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    pub fn cluster_key(_cpu: usize) -> Option<String> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_summary_splits_pairs_by_cluster() {
+        // Two clusters of two: 20 ns inside, 80 ns across, one pair missing.
+        let (a, b) = (Some(20.0), Some(80.0));
+        let matrix = CoreToCore::new(
+            vec![0, 2, 4, 6],
+            vec![0, 0, 1, 1],
+            vec![
+                vec![None, a, b, b],
+                vec![a, None, b, None],
+                vec![b, b, None, Some(24.0)],
+                vec![b, None, Some(24.0), None],
+            ],
+        );
+        assert_eq!(matrix.intra_cluster_ns, Some(22.0));
+        assert_eq!(matrix.inter_cluster_ns, Some(80.0));
+        assert_eq!(matrix.cluster_count(), 2);
+    }
+
+    #[test]
+    fn every_cpu_gets_a_cluster() {
+        let n = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let cpus: Vec<usize> = (0..n).collect();
+        let clusters = clusters(&cpus);
+        assert_eq!(clusters.len(), n);
+        assert_eq!(clusters[0], 0, "numbered in order of first appearance");
+        assert!(clusters.iter().all(|&c| c < n));
+    }
+}
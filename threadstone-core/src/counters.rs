@@ -0,0 +1,519 @@
+//! Hardware performance counters around each measurement window.
+//!
+//! A throughput figure says *that* a build got slower, not *why*. Counting
+//! retired instructions, cycles, branch mispredicts and cache misses across the
+//! same windows the runner times turns "sort lost 8%" into "sort retires the
+//! same instructions at a lower IPC because branch misses doubled" — which is a
+//! finding a reader can act on.
+//!
+//! Each worker opens its own counter [`Group`] on itself after setup. Counting
+//! is enabled just before a measured round's kernel call and disabled just
+//! after, so calibration, warmup and the barriers stay out of the totals. The
+//! group is read once, when the worker exits.
+//!
+//! Counters are implemented on Linux through `perf_event_open`, and are the
+//! least portable thing this crate does: `kernel.perf_event_paranoid`, container
+//! seccomp profiles and virtual machines without a virtual PMU each deny them.
+//! None of those is a reason to fail a run. [`probe`] says up front whether the
+//! counters work and, if not, why; the suite records that reason in the result
+//! and measures without them.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A hardware event this module counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+    /// Core clock cycles while the thread ran. The group leader.
+    Cycles,
+    /// Retired instructions.
+    Instructions,
+    /// Retired branch instructions.
+    Branches,
+    /// Mispredicted branch instructions.
+    BranchMisses,
+    /// Level-1 data cache read misses.
+    L1dMisses,
+    /// Last-level cache misses.
+    LlcMisses,
+}
+
+impl Event {
+    /// Every event, leader first. `Cycles` and `Instructions` are required;
+    /// the rest are opened where the PMU offers them.
+    pub const ALL: [Event; 6] = [
+        Event::Cycles,
+        Event::Instructions,
+        Event::Branches,
+        Event::BranchMisses,
+        Event::L1dMisses,
+        Event::LlcMisses,
+    ];
+
+    /// Short label for messages.
+    pub fn label(self) -> &'static str {
+        match self {
+            Event::Cycles => "cycles",
+            Event::Instructions => "instructions",
+            Event::Branches => "branches",
+            Event::BranchMisses => "branch-misses",
+            Event::L1dMisses => "L1D misses",
+            Event::LlcMisses => "LLC misses",
+        }
+    }
+
+    /// Whether a group without this event is useless.
+    fn required(self) -> bool {
+        matches!(self, Event::Cycles | Event::Instructions)
+    }
+}
+
+/// Whether counters were collected for a run, as recorded in the result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CounterStatus {
+    /// Counters were opened. `events` lists those the PMU offered; a pass's
+    /// figures that need a missing event are absent.
+    Enabled {
+        /// Events counted.
+        events: Vec<Event>,
+    },
+    /// Counters were requested but could not be opened.
+    Unavailable {
+        /// Why, in terms a reader can act on.
+        reason: String,
+    },
+}
+
+/// Check that a counter group can be opened on this thread, and which events
+/// it carries.
+pub fn probe() -> CounterStatus {
+    match Group::open() {
+        Ok(group) => CounterStatus::Enabled {
+            events: group.events(),
+        },
+        Err(reason) => CounterStatus::Unavailable { reason },
+    }
+}
+
+/// One thread's counter group.
+pub struct Group {
+    inner: imp::Group,
+}
+
+impl Group {
+    /// Open a group counting the calling thread, in user space only, initially
+    /// disabled.
+    pub fn open() -> Result<Group, String> {
+        imp::Group::open().map(|inner| Group { inner })
+    }
+
+    /// Events in the group, in read order.
+    pub fn events(&self) -> Vec<Event> {
+        self.inner.events()
+    }
+
+    /// Start counting.
+    pub fn enable(&self) {
+        self.inner.enable();
+    }
+
+    /// Stop counting. Totals accumulate across enable/disable pairs.
+    pub fn disable(&self) {
+        self.inner.disable();
+    }
+
+    /// The totals so far, or `None` if they cannot be read.
+    pub fn read(&self) -> Option<Counts> {
+        self.inner.read()
+    }
+}
+
+/// Raw totals read from one thread's group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Counts {
+    /// Each event's count, in group order.
+    pub values: Vec<(Event, u64)>,
+    /// Nanoseconds the group was enabled.
+    pub enabled_ns: u64,
+    /// Nanoseconds the group was actually on the PMU. Below `enabled_ns` when
+    /// the kernel multiplexed it with other groups.
+    pub running_ns: u64,
+}
+
+impl Counts {
+    /// `event`'s count, extrapolated over any time the group was multiplexed
+    /// off the PMU.
+    fn scaled(&self, event: Event) -> Option<f64> {
+        let (_, raw) = self.values.iter().find(|(e, _)| *e == event)?;
+        Some(*raw as f64 * self.enabled_ns as f64 / self.running_ns as f64)
+    }
+}
+
+/// What the counters saw across every thread of one pass.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CounterReport {
+    /// Retired instructions, summed over threads.
+    pub instructions: u64,
+    /// Core cycles, summed over threads.
+    pub cycles: u64,
+    /// Instructions per cycle.
+    pub ipc: f64,
+    /// Branch mispredicts as a fraction of branches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch_miss_rate: Option<f64>,
+    /// L1D read misses per thousand instructions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1d_mpki: Option<f64>,
+    /// Last-level cache misses per thousand instructions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llc_mpki: Option<f64>,
+    /// Fraction of the enabled time the counters were live. Below 1 means
+    /// the totals were extrapolated from a multiplexed sample.
+    pub coverage: f64,
+}
+
+impl CounterReport {
+    /// Combine every thread's totals. `None` if any thread's group never ran,
+    /// since its share of the work would then be missing from the sums.
+    pub fn from_threads(threads: &[Counts]) -> Option<CounterReport> {
+        if threads.is_empty() || threads.iter().any(|c| c.running_ns == 0) {
+            return None;
+        }
+        let total = |event| -> Option<f64> { threads.iter().map(|c| c.scaled(event)).sum() };
+        let instructions = total(Event::Instructions)?;
+        let cycles = total(Event::Cycles)?;
+        if instructions <= 0.0 || cycles <= 0.0 {
+            return None;
+        }
+        let mpki = |event| total(event).map(|misses| misses * 1e3 / instructions);
+        let branch_miss_rate = match (total(Event::BranchMisses), total(Event::Branches)) {
+            (Some(misses), Some(branches)) if branches > 0.0 => Some(misses / branches),
+            _ => None,
+        };
+        let enabled: u64 = threads.iter().map(|c| c.enabled_ns).sum();
+        let running: u64 = threads.iter().map(|c| c.running_ns).sum();
+        Some(CounterReport {
+            instructions: instructions.round() as u64,
+            cycles: cycles.round() as u64,
+            ipc: instructions / cycles,
+            branch_miss_rate,
+            l1d_mpki: mpki(Event::L1dMisses),
+            llc_mpki: mpki(Event::LlcMisses),
+            coverage: running as f64 / enabled as f64,
+        })
+    }
+}
+
+#[cfg(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+))]
+mod imp {
+    use super::{Counts, Event};
+    use std::io;
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_PERF_EVENT_OPEN: i64 = 298;
+    #[cfg(target_arch = "aarch64")]
+    const SYS_PERF_EVENT_OPEN: i64 = 241;
+
+    const PERF_TYPE_HARDWARE: u32 = 0;
+    const PERF_TYPE_HW_CACHE: u32 = 3;
+    const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+    const PERF_COUNT_HW_INSTRUCTIONS: u64 = 1;
+    const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+    const PERF_COUNT_HW_BRANCH_INSTRUCTIONS: u64 = 4;
+    const PERF_COUNT_HW_BRANCH_MISSES: u64 = 5;
+    /// `L1D | OP_READ << 8 | RESULT_MISS << 16`.
+    const L1D_READ_MISS: u64 = 1 << 16;
+
+    const FORMAT_TOTAL_TIME_ENABLED: u64 = 1 << 0;
+    const FORMAT_TOTAL_TIME_RUNNING: u64 = 1 << 1;
+    const FORMAT_GROUP: u64 = 1 << 3;
+
+    const FLAG_DISABLED: u64 = 1 << 0;
+    const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+    const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+    const IOC_ENABLE: u64 = 0x2400;
+    const IOC_DISABLE: u64 = 0x2401;
+    const IOC_FLAG_GROUP: u64 = 1;
+
+    const EPERM: i32 = 1;
+    const ENOENT: i32 = 2;
+    const EACCES: i32 = 13;
+    const ENODEV: i32 = 19;
+    const ENOSYS: i32 = 38;
+    const EOPNOTSUPP: i32 = 95;
+
+    /// `struct perf_event_attr` up to `PERF_ATTR_SIZE_VER7`. Only the leading
+    /// fields are set; the kernel treats the zeroed rest as defaults.
+    #[repr(C)]
+    #[derive(Default)]
+    struct Attr {
+        kind: u32,
+        size: u32,
+        config: u64,
+        sample_period: u64,
+        sample_type: u64,
+        read_format: u64,
+        flags: u64,
+        reserved: [u64; 10],
+    }
+
+    // Declared directly, as in `affinity`: std already links the C library.
+    extern "C" {
+        fn syscall(number: i64, ...) -> i64;
+        fn ioctl(fd: i32, request: u64, ...) -> i32;
+        fn read(fd: i32, buf: *mut u8, count: usize) -> isize;
+        fn close(fd: i32) -> i32;
+    }
+
+    pub struct Group {
+        /// Open descriptors, leader first, with the event each counts.
+        fds: Vec<(Event, i32)>,
+    }
+
+    impl Group {
+        pub fn open() -> Result<Group, String> {
+            let mut group = Group { fds: Vec::new() };
+            for event in Event::ALL {
+                let leader = group.fds.first().map_or(-1, |&(_, fd)| fd);
+                match open_event(event, leader) {
+                    Ok(fd) => group.fds.push((event, fd)),
+                    Err(_) if !event.required() => {}
+                    Err(e) => return Err(explain(event, &e)),
+                }
+            }
+            Ok(group)
+        }
+
+        pub fn events(&self) -> Vec<Event> {
+            self.fds.iter().map(|&(event, _)| event).collect()
+        }
+
+        pub fn enable(&self) {
+            self.control(IOC_ENABLE);
+        }
+
+        pub fn disable(&self) {
+            self.control(IOC_DISABLE);
+        }
+
+        fn control(&self, request: u64) {
+            // SAFETY: the leader descriptor is open for the life of `self`,
+            // and these requests take an integer argument.
+            unsafe { ioctl(self.fds[0].1, request, IOC_FLAG_GROUP) };
+        }
+
+        pub fn read(&self) -> Option<Counts> {
+            // nr, time_enabled, time_running, then one value per event.
+            let mut buf = vec![0u64; 3 + self.fds.len()];
+            let bytes = std::mem::size_of_val(buf.as_slice());
+            // SAFETY: `buf` is a live, writable buffer of `bytes` bytes.
+            let n = unsafe { read(self.fds[0].1, buf.as_mut_ptr().cast(), bytes) };
+            if n != bytes as isize || buf[0] as usize != self.fds.len() {
+                return None;
+            }
+            Some(Counts {
+                values: self
+                    .fds
+                    .iter()
+                    .zip(&buf[3..])
+                    .map(|(&(event, _), &value)| (event, value))
+                    .collect(),
+                enabled_ns: buf[1],
+                running_ns: buf[2],
+            })
+        }
+    }
+
+    impl Drop for Group {
+        fn drop(&mut self) {
+            for &(_, fd) in self.fds.iter().rev() {
+                // SAFETY: each descriptor was returned by perf_event_open and
+                // is closed exactly once.
+                unsafe { close(fd) };
+            }
+        }
+    }
+
+    fn open_event(event: Event, leader: i32) -> io::Result<i32> {
+        let (kind, config) = match event {
+            Event::Cycles => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_CPU_CYCLES),
+            Event::Instructions => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_INSTRUCTIONS),
+            Event::Branches => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_BRANCH_INSTRUCTIONS),
+            Event::BranchMisses => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_BRANCH_MISSES),
+            Event::L1dMisses => (PERF_TYPE_HW_CACHE, L1D_READ_MISS),
+            Event::LlcMisses => (PERF_TYPE_HARDWARE, PERF_COUNT_HW_CACHE_MISSES),
+        };
+        let mut flags = FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV;
+        // Members follow the leader's enable state.
+        if leader == -1 {
+            flags |= FLAG_DISABLED;
+        }
+        let attr = Attr {
+            kind,
+            size: std::mem::size_of::<Attr>() as u32,
+            config,
+            read_format: FORMAT_GROUP | FORMAT_TOTAL_TIME_ENABLED | FORMAT_TOTAL_TIME_RUNNING,
+            flags,
+            ..Attr::default()
+        };
+        // SAFETY: `attr` outlives the call and its `size` field matches its
+        // layout. pid 0 with cpu -1 means "this thread, on any CPU".
+        // Every argument is passed as a full register width, since the
+        // variadic `syscall` reads each as a `long`.
+        let fd = unsafe {
+            syscall(
+                SYS_PERF_EVENT_OPEN,
+                &attr as *const Attr,
+                0i64,
+                -1i64,
+                i64::from(leader),
+                0u64,
+            )
+        };
+        if fd < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(fd as i32)
+        }
+    }
+
+    /// Turn an `errno` into the reason recorded in the result.
+    fn explain(event: Event, error: &io::Error) -> String {
+        match error.raw_os_error() {
+            Some(EACCES | EPERM) => match paranoid() {
+                Some(level) => format!(
+                    "perf_event_open was denied: kernel.perf_event_paranoid is \
+                     {level}, and counting this process needs 2 or lower (or \
+                     CAP_PERFMON)"
+                ),
+                None => "perf_event_open was denied, typically by a container's \
+                         seccomp profile"
+                    .to_string(),
+            },
+            Some(ENOENT | ENODEV | EOPNOTSUPP) => format!(
+                "the CPU exposes no {} counter to this kernel, as is usual in a \
+                 virtual machine without a virtual PMU",
+                event.label()
+            ),
+            Some(ENOSYS) => "this kernel was built without perf_event_open".to_string(),
+            _ => format!("perf_event_open failed for {}: {error}", event.label()),
+        }
+    }
+
+    fn paranoid() -> Option<i32> {
+        std::fs::read_to_string("/proc/sys/kernel/perf_event_paranoid")
+            .ok()?
+            .trim()
+            .parse()
+            .ok()
+    }
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "x86_64", target_arch = "aarch64")
+)))]
+mod imp {
+    use super::{Counts, Event};
+
+    pub struct Group;
+
+    impl Group {
+        pub fn open() -> Result<Group, String> {
+            Err("hardware counters are only supported on Linux".to_string())
+        }
+
+        pub fn events(&self) -> Vec<Event> {
+            Vec::new()
+        }
+
+        pub fn enable(&self) {}
+
+        pub fn disable(&self) {}
+
+        pub fn read(&self) -> Option<Counts> {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(values: &[(Event, u64)], enabled_ns: u64, running_ns: u64) -> Counts {
+        Counts {
+            values: values.to_vec(),
+            enabled_ns,
+            running_ns,
+        }
+    }
+
+    #[test]
+    fn figures_combine_every_thread() {
+        let thread = counts(
+            &[
+                (Event::Cycles, 1_000),
+                (Event::Instructions, 2_000),
+                (Event::Branches, 400),
+                (Event::BranchMisses, 20),
+                (Event::LlcMisses, 6),
+            ],
+            10,
+            10,
+        );
+        let r = CounterReport::from_threads(&[thread.clone(), thread]).unwrap();
+        assert_eq!((r.instructions, r.cycles), (4_000, 2_000));
+        assert_eq!(r.ipc, 2.0);
+        assert_eq!(r.branch_miss_rate, Some(0.05));
+        assert_eq!(r.llc_mpki, Some(3.0));
+        assert_eq!(r.l1d_mpki, None, "an event the PMU lacks stays absent");
+        assert_eq!(r.coverage, 1.0);
+    }
+
+    #[test]
+    fn multiplexed_counts_are_extrapolated() {
+        let thread = counts(&[(Event::Cycles, 500), (Event::Instructions, 750)], 10, 5);
+        let r = CounterReport::from_threads(&[thread]).unwrap();
+        assert_eq!((r.instructions, r.cycles), (1_500, 1_000));
+        assert_eq!(r.coverage, 0.5);
+    }
+
+    #[test]
+    fn a_thread_whose_group_never_ran_voids_the_pass() {
+        let ran = counts(&[(Event::Cycles, 1), (Event::Instructions, 1)], 10, 10);
+        let idle = counts(&[(Event::Cycles, 0), (Event::Instructions, 0)], 10, 0);
+        assert!(CounterReport::from_threads(&[ran, idle]).is_none());
+        assert!(CounterReport::from_threads(&[]).is_none());
+    }
+
+    #[test]
+    fn probing_either_opens_the_required_events_or_says_why() {
+        match probe() {
+            CounterStatus::Enabled { events } => {
+                assert_eq!(&events[..2], &[Event::Cycles, Event::Instructions]);
+            }
+            CounterStatus::Unavailable { reason } => assert!(!reason.is_empty()),
+        }
+    }
+
+    #[test]
+    fn an_open_group_counts_work_between_enable_and_disable() {
+        let Ok(group) = Group::open() else { return };
+        group.enable();
+        let mut acc = 1u64;
+        for i in 0..1_000_000u64 {
+            acc = std::hint::black_box(acc.wrapping_mul(31).wrapping_add(i));
+        }
+        group.disable();
+        let counts = group.read().expect("an open group can be read");
+        let report = CounterReport::from_threads(&[counts]).expect("the group ran");
+        assert!(report.instructions > 1_000_000, "{report:?}");
+    }
+}
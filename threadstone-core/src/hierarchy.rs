@@ -0,0 +1,494 @@
+//! Cache levels read off a latency sweep.
+//!
+//! A latency sweep — `threadstone sweep`, the chase in
+//! `threadstone_workloads::latency` walked across working-set sizes — draws a
+//! curve that sits flat inside each cache level and climbs at every boundary.
+//! This module does the reading a person would: it finds the plateaus, says how
+//! far each one runs and what an access on it costs, and then checks those
+//! capacities against the cache sizes the operating system reports.
+//!
+//! # Finding the plateaus
+//!
+//! Three passes over the curve, in log latency so that a step from 1 ns to
+//! 4 ns weighs the same as one from 20 ns to 80 ns:
+//!
+//! 1. **Segment.** The curve is cut into the runs of constant latency that
+//!    best fit it, charging each extra run a fixed penalty. The cut is exact —
+//!    a dynamic programme over every split, which at a few dozen points is
+//!    instant — and a boundary survives only where it explains more than one
+//!    point sitting about 14% off its run would.
+//! 2. **Merge.** Neighbouring runs within 25% of each other are joined. A
+//!    random chase approaches each level gradually, since a working set just
+//!    past a cache still hits it in proportion, so the segmentation also cuts
+//!    the shallow tail of every climb into runs of its own.
+//! 3. **Keep the flat ones.** What remains alternates between plateaus and
+//!    the climbs between them. A run is a plateau if it spans at least an
+//!    octave of sizes and its latency rises by less than about 16% per
+//!    doubling across it; anything steeper is a transition and is not a level.
+//!
+//! A level's capacity is the largest working set still on its plateau. With a
+//! chase, latency starts to rise at the first size past a cache, so that is
+//! the cache's size to within one step of the sweep — a reason to sweep in
+//! finer steps. The last plateau has no capacity when it runs to the end of
+//! the sweep: it is memory, or a cache larger than anything measured.
+//!
+//! # Checking against the reported sizes
+//!
+//! Each reported cache is matched with the nearest bounded plateau not already
+//! taken, smallest cache first, and is flagged when that plateau ends more than
+//! a factor of two away. A plateau no reported cache claims is flagged too. A
+//! disagreement is a finding, not an error: the reported L2 of a cluster that
+//! shares it is not what one core sees, translation misses add steps of their
+//! own once a working set outgrows the TLB, and `/sys` can simply be wrong.
+//!
+//! # TLB reach
+//!
+//! A chase with one node per page steps wherever a TLB runs out, but also
+//! wherever its nodes, one line per page, outgrow a data cache. Each point of
+//! a TLB probe therefore carries a baseline: the same number of nodes packed
+//! one per line, which hits the same caches through a handful of pages. The
+//! plateaus are read, as above, off the baseline's fastest latency plus what
+//! translation adds on top of it, which sits flat while the first-level TLB
+//! holds every page, steps up to the second-level TLB, and steps again to a
+//! page walk. The first two plateaus end at the two TLBs' reach, and the step
+//! from the second to the third is the walk.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::stats;
+use crate::sweep::{SweepPoint, TlbPoint, TlbReach};
+use crate::sysinfo::SystemInfo;
+
+/// Charge for each extra run in the segmentation, in squared log latency:
+/// one point about 14% (`e^0.14`) off its run's level.
+const SPLIT_PENALTY: f64 = 0.02;
+
+/// Neighbouring runs closer than this ratio are one level.
+const MERGE_RATIO: f64 = 1.25;
+
+/// Steepest rise, in log latency per doubling of the working set, that still
+/// counts as flat: about 16%.
+const FLAT_SLOPE: f64 = 0.15;
+
+/// How far a plateau may end from a reported cache size and still agree.
+const AGREE_FACTOR: f64 = 2.0;
+
+/// One cache level as the sweep shows it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Level {
+    /// Smallest working set on the plateau.
+    pub from_bytes: usize,
+    /// Largest working set on the plateau: the level's inferred capacity.
+    /// Absent when the plateau runs to the end of the sweep.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capacity_bytes: Option<usize>,
+    /// Median latency across the plateau, in nanoseconds.
+    pub latency_ns: f64,
+}
+
+/// A way in which the sweep and the reported cache sizes disagree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Disagreement {
+    /// The plateau nearest a reported cache ends more than a factor of two
+    /// away from it.
+    Misplaced {
+        /// `L1d`, `L2` or `L3`.
+        cache: String,
+        /// Size the system reports, in bytes.
+        reported_bytes: u64,
+        /// Capacity of the nearest plateau, in bytes.
+        inferred_bytes: usize,
+    },
+    /// A cache is reported at a size the sweep covers, but no bounded plateau
+    /// is left to match it.
+    Missing {
+        /// `L1d`, `L2` or `L3`.
+        cache: String,
+        /// Size the system reports, in bytes.
+        reported_bytes: u64,
+    },
+    /// A plateau ends where no reported cache does.
+    Unreported {
+        /// Capacity of the plateau, in bytes.
+        capacity_bytes: usize,
+        /// Latency on the plateau, in nanoseconds.
+        latency_ns: f64,
+    },
+}
+
+/// The cache levels in a sweep, smallest first. `points` must be in ascending
+/// order of size, as a sweep measures them.
+pub fn levels(points: &[SweepPoint]) -> Vec<Level> {
+    let runs = merge(points, segment(points));
+    let last = runs.last().map_or(0, |r| r.end);
+    runs.into_iter()
+        .filter(|run| is_flat(&points[run.clone()]))
+        .map(|run| Level {
+            from_bytes: points[run.start].bytes,
+            capacity_bytes: (run.end < last).then(|| points[run.end - 1].bytes),
+            latency_ns: median_latency(&points[run]),
+        })
+        .collect()
+}
+
+/// Check `levels` against the cache sizes in `system`. A reported cache larger
+/// than `largest_bytes`, the biggest working set measured, cannot be checked
+/// and is passed over.
+pub fn cross_check(
+    levels: &[Level],
+    system: &SystemInfo,
+    largest_bytes: usize,
+) -> Vec<Disagreement> {
+    let reported = [
+        ("L1d", system.l1d_bytes),
+        ("L2", system.l2_bytes),
+        ("L3", system.l3_bytes),
+    ];
+    let mut taken = vec![false; levels.len()];
+    let mut found = Vec::new();
+
+    for (cache, bytes) in reported {
+        let Some(reported_bytes) = bytes.filter(|&b| b <= largest_bytes as u64) else {
+            continue;
+        };
+        let distance = |capacity: usize| (capacity as f64 / reported_bytes as f64).log2().abs();
+        let nearest = levels
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !taken[*i])
+            .filter_map(|(i, level)| Some((i, level.capacity_bytes?)))
+            .min_by(|a, b| distance(a.1).total_cmp(&distance(b.1)));
+        let Some((i, inferred_bytes)) = nearest else {
+            found.push(Disagreement::Missing {
+                cache: cache.to_string(),
+                reported_bytes,
+            });
+            continue;
+        };
+        taken[i] = true;
+        if distance(inferred_bytes) > AGREE_FACTOR.log2() {
+            found.push(Disagreement::Misplaced {
+                cache: cache.to_string(),
+                reported_bytes,
+                inferred_bytes,
+            });
+        }
+    }
+
+    for (level, _) in levels.iter().zip(&taken).filter(|(_, &t)| !t) {
+        if let Some(capacity_bytes) = level.capacity_bytes {
+            found.push(Disagreement::Unreported {
+                capacity_bytes,
+                latency_ns: level.latency_ns,
+            });
+        }
+    }
+    found
+}
+
+/// The TLB levels in a probe at `page_bytes` per page. `points` must be in
+/// ascending order of page count.
+pub fn tlb_reach(points: &[TlbPoint], page_bytes: usize) -> TlbReach {
+    let floor = points
+        .iter()
+        .map(|p| p.baseline_ns)
+        .min_by(f64::total_cmp)
+        .unwrap_or(0.0);
+    // Translation never makes an access faster; a point that reads so is
+    // noise, and is held at the floor rather than taken below it.
+    let curve: Vec<SweepPoint> = points
+        .iter()
+        .map(|p| SweepPoint {
+            bytes: p.pages * page_bytes,
+            latency_ns: (floor + p.translation_ns()).max(floor),
+            samples: Vec::new(),
+            stats: p.stats.clone(),
+        })
+        .collect();
+    let levels = levels(&curve);
+    let reach = |i: usize| Some(levels.get(i)?.capacity_bytes? / page_bytes);
+    let l1_pages = reach(0);
+    let l2_pages = l1_pages.and(reach(1));
+    let walk_ns = l2_pages
+        .and(levels.get(2))
+        .map(|walk| walk.latency_ns - levels[1].latency_ns);
+    TlbReach {
+        levels,
+        l1_pages,
+        l2_pages,
+        walk_ns,
+    }
+}
+
+/// Cut the curve into the runs of constant log latency that minimise squared
+/// error plus [`SPLIT_PENALTY`] per run.
+fn segment(points: &[SweepPoint]) -> Vec<std::ops::Range<usize>> {
+    let n = points.len();
+    // Prefix sums, so the error of any run is constant time.
+    let (mut sum, mut squares) = (vec![0.0; n + 1], vec![0.0; n + 1]);
+    for (i, p) in points.iter().enumerate() {
+        let y = p.latency_ns.ln();
+        sum[i + 1] = sum[i] + y;
+        squares[i + 1] = squares[i] + y * y;
+    }
+    let error = |i: usize, j: usize| {
+        let s = sum[j] - sum[i];
+        squares[j] - squares[i] - s * s / (j - i) as f64
+    };
+
+    // best[j]: the cheapest segmentation of the first j points; start[j]:
+    // where its last run begins.
+    let mut best = vec![0.0; n + 1];
+    let mut start = vec![0; n + 1];
+    for j in 1..=n {
+        let (cost, i) = (0..j)
+            .map(|i| (best[i] + error(i, j) + SPLIT_PENALTY, i))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .expect("j is at least 1");
+        best[j] = cost;
+        start[j] = i;
+    }
+
+    let mut runs = Vec::new();
+    let mut j = n;
+    while j > 0 {
+        runs.push(start[j]..j);
+        j = start[j];
+    }
+    runs.reverse();
+    runs
+}
+
+/// Join neighbouring runs whose median latencies are within [`MERGE_RATIO`],
+/// closest pair first.
+fn merge(
+    points: &[SweepPoint],
+    mut runs: Vec<std::ops::Range<usize>>,
+) -> Vec<std::ops::Range<usize>> {
+    loop {
+        let closest = runs
+            .windows(2)
+            .enumerate()
+            .map(|(k, pair)| {
+                let a = median_latency(&points[pair[0].clone()]);
+                let b = median_latency(&points[pair[1].clone()]);
+                (a.max(b) / a.min(b), k)
+            })
+            .filter(|&(ratio, _)| ratio < MERGE_RATIO)
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+        let Some((_, k)) = closest else {
+            return runs;
+        };
+        let next = runs.remove(k + 1);
+        runs[k].end = next.end;
+    }
+}
+
+/// Whether a run spans an octave and climbs less than [`FLAT_SLOPE`] across
+/// it, by a least-squares fit of log latency on log size.
+fn is_flat(run: &[SweepPoint]) -> bool {
+    let (Some(first), Some(last)) = (run.first(), run.last()) else {
+        return false;
+    };
+    if last.bytes < 2 * first.bytes {
+        return false;
+    }
+    let xy: Vec<(f64, f64)> = run
+        .iter()
+        .map(|p| ((p.bytes as f64).log2(), p.latency_ns.ln()))
+        .collect();
+    let n = xy.len() as f64;
+    let mx = xy.iter().map(|p| p.0).sum::<f64>() / n;
+    let my = xy.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxy: f64 = xy.iter().map(|p| (p.0 - mx) * (p.1 - my)).sum();
+    let sxx: f64 = xy.iter().map(|p| (p.0 - mx).powi(2)).sum();
+    sxy / sxx < FLAT_SLOPE
+}
+
+fn median_latency(run: &[SweepPoint]) -> f64 {
+    let latencies: Vec<f64> = run.iter().map(|p| p.latency_ns).collect();
+    stats::median(&latencies).unwrap_or(f64::NAN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::Summary;
+
+    fn curve(points: &[(usize, f64)]) -> Vec<SweepPoint> {
+        points
+            .iter()
+            .map(|&(bytes, latency_ns)| SweepPoint {
+                bytes,
+                latency_ns,
+                samples: vec![latency_ns],
+                stats: Summary::new(&[latency_ns]).unwrap(),
+            })
+            .collect()
+    }
+
+    /// A chase over three caches of 48 KiB, 1 MiB and 16 MiB, at 1.2, 4 and
+    /// 14 ns, and 90 ns memory: at each size the share of hops that hit each
+    /// level is the share of the working set that level holds. A fixed ±2%
+    /// wobble stands in for noise. Sizes run from 4 KiB to 256 MiB with each
+    /// octave cut into `parts` steps, as `threadstone sweep --step` cuts them.
+    fn three_levels(parts: usize) -> Vec<SweepPoint> {
+        let caches = [(48usize << 10, 1.2), (1 << 20, 4.0), (16 << 20, 14.0)];
+        let sizes = (12..28)
+            .flat_map(|shift| (0..parts).map(move |i| (1usize << shift) * (parts + i) / parts))
+            .chain([1 << 28]);
+        let points: Vec<(usize, f64)> = sizes
+            .enumerate()
+            .map(|(i, bytes)| {
+                let (mut held, mut ns) = (0.0, 0.0);
+                for (capacity, cost) in caches {
+                    let share = (capacity as f64 / bytes as f64).min(1.0);
+                    ns += (share - held).max(0.0) * cost;
+                    held = held.max(share);
+                }
+                ns += (1.0 - held) * 90.0;
+                (bytes, ns * [1.0, 1.02, 0.98][i % 3])
+            })
+            .collect();
+        curve(&points)
+    }
+
+    #[test]
+    fn plateaus_are_found_through_the_climbs_between_them() {
+        // Powers of two alone cannot place a 48 KiB L1 closer than 32 KiB.
+        for (parts, l1) in [(4, 48 << 10), (2, 48 << 10), (1, 32 << 10)] {
+            let levels = levels(&three_levels(parts));
+            let capacities: Vec<_> = levels.iter().map(|l| l.capacity_bytes).collect();
+            assert_eq!(
+                capacities,
+                [Some(l1), Some(1 << 20), Some(16 << 20), None],
+                "at {parts} steps an octave: {levels:?}"
+            );
+            let latencies: Vec<f64> = levels.iter().map(|l| l.latency_ns).collect();
+            assert!((latencies[0] - 1.2).abs() < 0.05, "{latencies:?}");
+            assert!(latencies.windows(2).all(|w| w[1] > w[0] * MERGE_RATIO));
+        }
+    }
+
+    #[test]
+    fn a_measured_curve_reads_as_its_plateaus() {
+        // An Apple M4 Pro, from results/apple-m4-pro-sweep.json.
+        let measured = [
+            0.92, 1.0, 1.01, 0.97, 0.9, 0.9, 6.0, 6.44, 6.2, 6.18, 7.81, 8.12, 15.57, 51.11, 98.37,
+            111.06, 119.21,
+        ];
+        let points: Vec<(usize, f64)> = measured
+            .iter()
+            .enumerate()
+            .map(|(i, &ns)| (4096 << i, ns))
+            .collect();
+        let levels = levels(&curve(&points));
+        let capacities: Vec<_> = levels.iter().map(|l| l.capacity_bytes).collect();
+        assert_eq!(
+            capacities,
+            [Some(128 << 10), Some(2 << 20), Some(8 << 20), None]
+        );
+        assert_eq!(levels[3].from_bytes, 64 << 20);
+        assert_eq!(levels[3].latency_ns, 111.06);
+    }
+
+    #[test]
+    fn tlb_reach_is_read_off_translation_with_the_data_caches_taken_out() {
+        // A 64-entry first-level TLB, a 2048-entry second level at 1.5 ns more
+        // and a 20 ns walk, hit in proportion as `three_levels` hits caches;
+        // the nodes' own lines fall out of a 48 KiB L1d at 768 pages.
+        let points: Vec<TlbPoint> = (2..=16)
+            .map(|shift| {
+                let pages = 1usize << shift;
+                let share = |entries: usize| (entries as f64 / pages as f64).min(1.0);
+                let translation = (1.0 - share(64)) * 1.5 + (1.0 - share(2048)) * 18.5;
+                let baseline_ns = if pages <= 768 { 1.0 } else { 4.0 };
+                let latency_ns = baseline_ns + translation;
+                TlbPoint {
+                    pages,
+                    latency_ns,
+                    samples: vec![latency_ns],
+                    stats: Summary::new(&[latency_ns]).unwrap(),
+                    baseline_ns,
+                }
+            })
+            .collect();
+        let reach = tlb_reach(&points, 4096);
+        assert_eq!(reach.l1_pages, Some(64), "{reach:?}");
+        assert_eq!(reach.l2_pages, Some(2048), "{reach:?}");
+        let walk = reach.walk_ns.unwrap();
+        assert!((15.0..20.0).contains(&walk), "{walk}");
+        assert_eq!(reach.levels[0].capacity_bytes, Some(64 * 4096));
+
+        // Without the baseline the data-cache step reads as a TLB.
+        let flat: Vec<TlbPoint> = points
+            .iter()
+            .map(|p| TlbPoint {
+                baseline_ns: 1.0,
+                ..p.clone()
+            })
+            .collect();
+        assert_ne!(tlb_reach(&flat, 4096).l2_pages, Some(2048));
+    }
+
+    #[test]
+    fn reported_caches_are_checked_against_the_nearest_plateau() {
+        let levels = levels(&three_levels(4));
+        let largest = 256 << 20;
+        let mut system = SystemInfo {
+            l1d_bytes: Some(32 << 10),
+            l2_bytes: Some(1 << 20),
+            l3_bytes: Some(16 << 20),
+            ..SystemInfo::default()
+        };
+        // 48 KiB against 32 KiB is within a factor of two.
+        assert_eq!(cross_check(&levels, &system, largest), []);
+
+        system.l2_bytes = Some(256 << 10);
+        system.l3_bytes = Some(1 << 30);
+        assert_eq!(
+            cross_check(&levels, &system, largest),
+            [
+                Disagreement::Misplaced {
+                    cache: "L2".into(),
+                    reported_bytes: 256 << 10,
+                    inferred_bytes: 1 << 20,
+                },
+                // A 1 GiB L3 is past the sweep, so the 16 MiB plateau is
+                // unclaimed rather than set against it.
+                Disagreement::Unreported {
+                    capacity_bytes: 16 << 20,
+                    latency_ns: levels[2].latency_ns,
+                },
+            ]
+        );
+
+        assert_eq!(
+            cross_check(
+                &levels[..1],
+                &SystemInfo {
+                    l2_bytes: Some(4 << 20),
+                    ..system
+                },
+                largest
+            ),
+            [Disagreement::Missing {
+                cache: "L2".into(),
+                reported_bytes: 4 << 20,
+            }]
+        );
+        assert_eq!(
+            cross_check(&levels, &SystemInfo::default(), largest).len(),
+            3
+        );
+    }
+
+    #[test]
+    fn a_curve_without_plateaus_has_no_levels() {
+        assert_eq!(levels(&[]), []);
+        let climb: Vec<(usize, f64)> = (0..10).map(|i| (4096 << i, 2f64.powi(i))).collect();
+        assert_eq!(levels(&curve(&climb)), []);
+    }
+}
@@ -10,10 +10,23 @@
 //! `Box<dyn Kernel>` without generics leaking through the whole program. The
 //! cost is one virtual call per measurement window — the runner calibrates
 //! windows to hundreds of milliseconds, so that call is unmeasurable.
+//!
+//! # Parameters
+//!
+//! A workload's problem size is part of what its score means: an SGEMM over
+//! 1024³ matrices measures DRAM as much as FMA units, and its GFLOP/s cannot
+//! stand next to the reference's 256³ figure. So sizes are fixed by default,
+//! and a workload that can usefully be run at others declares them as
+//! [`Param`]s, each with its canonical default and the range it accepts.
+//! `threadstone run -w sgemm:n=1024` sets one through [`Kernel::configure`],
+//! which checks it first. The values a run used are recorded with its results,
+//! and only a workload at every default is scored.
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::pages::PagePolicy;
+
 /// What a workload's reported number means.
 ///
 /// Carrying the direction alongside the unit is what lets the scorer combine
@@ -53,6 +66,41 @@ impl Unit {
     pub fn higher_is_better(self) -> bool {
         !matches!(self, Unit::Nanoseconds)
     }
+
+    /// Label for [`Unit::per_clock`]: the conventional clock-normalised
+    /// figure for this unit.
+    pub fn per_clock_label(self) -> &'static str {
+        match self {
+            Unit::DhrystonesPerSec => "DMIPS/MHz",
+            Unit::Gflops => "FLOP/cycle",
+            Unit::GibPerSec => "B/cycle",
+            Unit::MibPerSec => "cycles/B",
+            Unit::MelemPerSec => "cycles/elem",
+            Unit::Nanoseconds => "cycles",
+        }
+    }
+
+    /// Normalise `value` by a core clock of `hz`, given the thread count that
+    /// produced it.
+    ///
+    /// Rates are first divided across threads, since every thread ran on its
+    /// own core. Wide kernels read as work per cycle; narrow ones, where that
+    /// would be a small fraction, as cycles per unit of work — the form each
+    /// is conventionally quoted in. Latencies become cycles per operation.
+    pub fn per_clock(self, value: f64, threads: usize, hz: f64) -> f64 {
+        /// Dhrystones per second on a VAX 11/780, the DMIPS divisor.
+        const VAX_DHRYSTONES_PER_SEC: f64 = 1757.0;
+
+        let per_thread = value / threads.max(1) as f64;
+        match self {
+            Unit::DhrystonesPerSec => per_thread / VAX_DHRYSTONES_PER_SEC / (hz / 1e6),
+            Unit::Gflops => per_thread * 1e9 / hz,
+            Unit::GibPerSec => per_thread * (1u64 << 30) as f64 / hz,
+            Unit::MibPerSec => hz / (per_thread * (1u64 << 20) as f64),
+            Unit::MelemPerSec => hz / (per_thread * 1e6),
+            Unit::Nanoseconds => value * hz / 1e9,
+        }
+    }
 }
 
 /// How a workload's working set relates to the thread count.
@@ -107,6 +155,98 @@ pub struct KernelInfo {
     pub reference: f64,
 }
 
+/// A tunable size or option a workload declares, at the value one instance
+/// runs at.
+///
+/// Values are whole numbers in the unit the name states, so that the command
+/// line and the recorded results need no parsing beyond an integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    /// Name as given on the command line, e.g. `"n"`.
+    pub name: &'static str,
+    /// One line on what it sets.
+    pub summary: &'static str,
+    /// The canonical value: the only one a scored run uses.
+    pub default: u64,
+    /// Smallest value accepted.
+    pub min: u64,
+    /// Largest value accepted.
+    pub max: u64,
+    /// Every accepted value is a multiple of this; 1 for any.
+    pub multiple_of: u64,
+    /// The value this instance runs at.
+    pub value: u64,
+}
+
+impl Param {
+    /// Whether this is at its canonical default.
+    pub fn is_default(&self) -> bool {
+        self.value == self.default
+    }
+}
+
+/// Why a parameter could not be set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    /// The workload declares no parameter of that name.
+    Unknown {
+        /// Identifier of the workload.
+        id: &'static str,
+        /// The name asked for.
+        name: String,
+        /// The names it does declare.
+        known: Vec<&'static str>,
+    },
+    /// The value lies outside the declared range.
+    OutOfRange {
+        /// Identifier of the workload.
+        id: &'static str,
+        /// The declaration refused against.
+        param: Param,
+        /// The value asked for.
+        value: u64,
+    },
+    /// The value is not a multiple of what the declaration requires.
+    NotMultiple {
+        /// Identifier of the workload.
+        id: &'static str,
+        /// The declaration refused against.
+        param: Param,
+        /// The value asked for.
+        value: u64,
+    },
+}
+
+impl std::fmt::Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamError::Unknown { id, name, known } if known.is_empty() => {
+                write!(
+                    f,
+                    "workload '{id}' has no parameters, so cannot set '{name}'"
+                )
+            }
+            ParamError::Unknown { id, name, known } => write!(
+                f,
+                "workload '{id}' has no parameter '{name}'; it has {}",
+                known.join(", ")
+            ),
+            ParamError::OutOfRange { id, param, value } => write!(
+                f,
+                "{id}:{}={value} is out of range: {} accepts {} to {}",
+                param.name, param.name, param.min, param.max
+            ),
+            ParamError::NotMultiple { id, param, value } => write!(
+                f,
+                "{id}:{}={value} is not a multiple of {}",
+                param.name, param.multiple_of
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
 /// Everything a kernel needs to size itself for one thread.
 #[derive(Debug, Clone, Copy)]
 pub struct SetupCtx {
@@ -114,6 +254,9 @@ pub struct SetupCtx {
     pub threads: usize,
     /// Index of the thread being set up, in `0..threads`.
     pub thread_index: usize,
+    /// How to back large buffers. Kernels whose working set is small enough
+    /// that the page size cannot matter may ignore it.
+    pub pages: PagePolicy,
 }
 
 impl SetupCtx {
@@ -165,6 +308,62 @@ pub trait Kernel: Send + Sync {
     /// wants `secs / iters_per_thread`, since concurrent accesses do not make
     /// any individual access faster.
     fn rate(&self, iters_per_thread: u64, threads: usize, secs: f64) -> f64;
+
+    /// Companion kernels measured alongside this one at its single- and
+    /// multi-thread counts, and reported with it as sub-results. They are
+    /// never scored. STREAM's Copy, Scale and Add beside the scored Triad are
+    /// the case this exists for; most workloads have none.
+    fn sub_results(&self) -> Vec<Box<dyn Kernel>> {
+        Vec::new()
+    }
+
+    /// The parameters this workload declares, each at the value this
+    /// instance runs at. Most workloads have none.
+    ///
+    /// Sub-results declare the same parameters as their workload and are
+    /// built at its values, so that they measure the same problem.
+    fn params(&self) -> Vec<Param> {
+        Vec::new()
+    }
+
+    /// Run at `value` for parameter `name`. Reached through
+    /// [`Kernel::configure`] with a name from [`Kernel::params`] and a value
+    /// already checked against it; a workload that declares parameters must
+    /// implement it, and one that does not refuses every name.
+    fn set_param(&mut self, name: &str, _value: u64) -> Result<(), ParamError> {
+        Err(ParamError::Unknown {
+            id: self.info().id,
+            name: name.to_string(),
+            known: self.params().iter().map(|p| p.name).collect(),
+        })
+    }
+
+    /// Set parameter `name` to `value`, if it is one this workload declares
+    /// and the value lies within its range.
+    fn configure(&mut self, name: &str, value: u64) -> Result<(), ParamError> {
+        let id = self.info().id;
+        let params = self.params();
+        let Some(&param) = params.iter().find(|p| p.name == name) else {
+            return Err(ParamError::Unknown {
+                id,
+                name: name.to_string(),
+                known: params.iter().map(|p| p.name).collect(),
+            });
+        };
+        if !(param.min..=param.max).contains(&value) {
+            return Err(ParamError::OutOfRange { id, param, value });
+        }
+        if value % param.multiple_of.max(1) != 0 {
+            return Err(ParamError::NotMultiple { id, param, value });
+        }
+        self.set_param(name, value)
+    }
+
+    /// Whether every parameter is at its default: the only configuration
+    /// whose results may be scored.
+    fn is_canonical(&self) -> bool {
+        self.params().iter().all(Param::is_default)
+    }
 }
 
 #[cfg(test)]
@@ -175,6 +374,7 @@ mod tests {
         SetupCtx {
             threads,
             thread_index,
+            pages: PagePolicy::Default,
         }
     }
 
@@ -190,6 +390,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn clock_normalised_figures_are_per_thread() {
+        // 64 GFLOP/s over 4 threads at 2 GHz is 8 FLOP per cycle per thread.
+        assert_eq!(Unit::Gflops.per_clock(64.0, 4, 2e9), 8.0);
+        // 100 ns at 3 GHz is 300 cycles, whatever the thread count.
+        assert_eq!(Unit::Nanoseconds.per_clock(100.0, 8, 3e9), 300.0);
+        // 1 GiB/s on each of two threads at 2^33 Hz is 8 cycles per byte.
+        let hz = (1u64 << 33) as f64;
+        assert_eq!(Unit::MibPerSec.per_clock(2048.0, 2, hz), 8.0);
+        // One VAX per megahertz is 1 DMIPS/MHz.
+        assert!((Unit::DhrystonesPerSec.per_clock(1757.0 * 3e3, 1, 3e9) - 1.0).abs() < 1e-12);
+    }
+
     #[test]
     fn remainder_goes_to_low_indices() {
         // 10 units across 4 threads: 3, 3, 2, 2.
@@ -204,6 +417,109 @@ mod tests {
         assert_eq!(ctx(0, 8).share(0), 1);
     }
 
+    /// State for the kernels below, which are configured but never run.
+    struct Idle;
+
+    impl KernelState for Idle {
+        fn run(&mut self, _: u64) -> u64 {
+            0
+        }
+    }
+
+    /// A kernel with one parameter, `n`, a multiple of 4 from 4 to 64.
+    struct Tunable(u64);
+
+    impl Kernel for Tunable {
+        fn info(&self) -> KernelInfo {
+            KernelInfo {
+                id: "tunable",
+                name: "Tunable",
+                summary: "test",
+                unit: Unit::Gflops,
+                footprint: Footprint::PerThread,
+                scaling: Scaling::Scales,
+                reference: 1.0,
+            }
+        }
+        fn setup(&self, _: &SetupCtx) -> Box<dyn KernelState> {
+            Box::new(Idle)
+        }
+        fn rate(&self, _: u64, _: usize, _: f64) -> f64 {
+            1.0
+        }
+        fn params(&self) -> Vec<Param> {
+            vec![Param {
+                name: "n",
+                summary: "test",
+                default: 16,
+                min: 4,
+                max: 64,
+                multiple_of: 4,
+                value: self.0,
+            }]
+        }
+        fn set_param(&mut self, _: &str, value: u64) -> Result<(), ParamError> {
+            self.0 = value;
+            Ok(())
+        }
+    }
+
+    /// A kernel that declares no parameters.
+    struct Fixed;
+
+    impl Kernel for Fixed {
+        fn info(&self) -> KernelInfo {
+            KernelInfo {
+                id: "fixed",
+                ..Tunable(16).info()
+            }
+        }
+        fn setup(&self, _: &SetupCtx) -> Box<dyn KernelState> {
+            Box::new(Idle)
+        }
+        fn rate(&self, _: u64, _: usize, _: f64) -> f64 {
+            1.0
+        }
+    }
+
+    #[test]
+    fn a_workload_without_parameters_refuses_every_name() {
+        let mut k = Fixed;
+        let err = k.configure("n", 16).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "workload 'fixed' has no parameters, so cannot set 'n'"
+        );
+        assert_eq!(k.set_param("n", 16), Err(err));
+    }
+
+    #[test]
+    fn parameters_are_checked_before_they_are_set() {
+        let mut k = Tunable(16);
+        assert!(k.is_canonical());
+        k.configure("n", 64).unwrap();
+        assert_eq!(k.params()[0].value, 64);
+        assert!(!k.is_canonical());
+
+        let err = k.configure("m", 8).unwrap_err();
+        assert!(matches!(&err, ParamError::Unknown { known, .. } if known == &["n"]));
+        assert_eq!(
+            err.to_string(),
+            "workload 'tunable' has no parameter 'm'; it has n"
+        );
+        let err = k.configure("n", 128).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "tunable:n=128 is out of range: n accepts 4 to 64"
+        );
+        assert!(matches!(
+            k.configure("n", 6),
+            Err(ParamError::NotMultiple { value: 6, .. })
+        ));
+        // Refused values leave the last accepted one in place.
+        assert_eq!(k.params()[0].value, 64);
+    }
+
     #[test]
     fn latency_is_the_only_lower_is_better_unit() {
         assert!(!Unit::Nanoseconds.higher_is_better());
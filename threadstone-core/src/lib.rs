@@ -33,21 +33,35 @@
 
 #![warn(missing_docs)]
 
+pub mod affinity;
+pub mod budget;
+pub mod coherence;
+pub mod counters;
+pub mod hierarchy;
 pub mod kernel;
+pub mod load;
+pub mod numa;
+pub mod pages;
+pub mod repeat;
 pub mod report;
 pub mod runner;
 pub mod score;
 pub mod stats;
 pub mod suite;
+pub mod sweep;
 pub mod sysinfo;
 pub mod time;
 
-pub use kernel::{Footprint, Kernel, KernelInfo, KernelState, Scaling, SetupCtx, Unit};
+pub use affinity::Affinity;
+pub use kernel::{
+    Footprint, Kernel, KernelInfo, KernelState, Param, ParamError, Scaling, SetupCtx, Unit,
+};
 pub use report::{Report, WorkloadReport};
 pub use runner::{Measurement, RunConfig, RunError};
 pub use score::ScoreCard;
 pub use stats::{Stability, Summary};
 pub use suite::{SuiteConfig, SuiteObserver};
+pub use sweep::SweepReport;
 pub use sysinfo::SystemInfo;
 
 /// Version of this crate, for stamping into result documents.
@@ -0,0 +1,208 @@
+//! Whether anything else was using the machine.
+//!
+//! A compile in another terminal takes cores, memory bandwidth and boost
+//! headroom from the benchmark, and nothing in the result would otherwise say
+//! so. The suite therefore samples the machine before the run and before
+//! every pass — with its own workers stopped, so that whatever it sees is
+//! somebody else's — and records what it saw.
+//!
+//! Each sample reads `/proc/stat` twice, [`INTERVAL`] apart, and reports what
+//! fraction of all CPU time in between was busy, waiting on I/O, or stolen by
+//! a hypervisor, along with the run queue and the one-minute load average. The
+//! load average is context only: it decays over a minute, so between passes it
+//! mostly describes the benchmark's own previous pass.
+//!
+//! Linux only. Elsewhere no samples are taken, and `--require-idle` is refused
+//! rather than silently passing.
+
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// How long each sample watches the machine.
+pub const INTERVAL: Duration = Duration::from_millis(100);
+
+/// Busy time above this fraction of the machine marks a sample loaded.
+pub const LOADED_BUSY: f64 = 0.1;
+
+/// Stolen time above this fraction marks a sample loaded: the hypervisor was
+/// giving this machine's cores to another guest.
+pub const LOADED_STEAL: f64 = 0.02;
+
+/// What the machine was doing just before a pass.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LoadSample {
+    /// What was about to run: `start`, or `<workload> at <n> threads`.
+    pub before: String,
+    /// One-minute load average.
+    pub loadavg: f64,
+    /// Tasks running or runnable, other than the one taking the sample.
+    pub run_queue: u32,
+    /// Fraction of all CPU time spent in user, system and interrupt work.
+    pub busy: f64,
+    /// Fraction of all CPU time idle with I/O outstanding.
+    pub iowait: f64,
+    /// Fraction of all CPU time taken by the hypervisor for other guests.
+    pub steal: f64,
+    /// Time spent waiting for the machine to settle before this sample, in
+    /// milliseconds, under `--require-idle`.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub waited_ms: u64,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+impl LoadSample {
+    /// CPU time lost to other work: busy plus stolen.
+    pub fn contention(&self) -> f64 {
+        self.busy + self.steal
+    }
+
+    /// Whether enough else was running to disturb a measurement.
+    pub fn is_loaded(&self) -> bool {
+        self.busy > LOADED_BUSY || self.steal > LOADED_STEAL
+    }
+}
+
+/// How long `--require-idle` waits for the machine to settle by default:
+/// long enough for a finished build's stragglers, short enough that a CI job
+/// against a machine that never settles fails promptly.
+pub const IDLE_WAIT: Duration = Duration::from_secs(60);
+
+/// A `--require-idle` threshold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RequireIdle {
+    /// Most [`LoadSample::contention`] a pass may start under.
+    pub max_busy: f64,
+    /// How long to wait for the machine to settle before giving up.
+    pub wait: Duration,
+}
+
+/// Whether this platform can be sampled at all.
+pub fn supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// Watch the machine for [`INTERVAL`] and describe it. `None` where the
+/// platform cannot be sampled.
+pub fn sample(before: &str) -> Option<LoadSample> {
+    #[cfg(target_os = "linux")]
+    {
+        let first = std::fs::read_to_string("/proc/stat").ok()?;
+        std::thread::sleep(INTERVAL);
+        let second = std::fs::read_to_string("/proc/stat").ok()?;
+        let loadavg = std::fs::read_to_string("/proc/loadavg").ok()?;
+        from_proc(before, &first, &second, &loadavg)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = before;
+        None
+    }
+}
+
+/// Jiffies from the aggregate `cpu` line of `/proc/stat`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Jiffies {
+    busy: u64,
+    iowait: u64,
+    steal: u64,
+    total: u64,
+}
+
+/// Build a sample from two `/proc/stat` snapshots and `/proc/loadavg`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn from_proc(before: &str, first: &str, second: &str, loadavg: &str) -> Option<LoadSample> {
+    let (a, b) = (jiffies(first)?, jiffies(second)?);
+    let total = b.total.saturating_sub(a.total).max(1) as f64;
+    let share = |x: u64, y: u64| y.saturating_sub(x) as f64 / total;
+    Some(LoadSample {
+        before: before.to_string(),
+        loadavg: loadavg.split_whitespace().next()?.parse().ok()?,
+        run_queue: procs_running(second)?.saturating_sub(1),
+        busy: share(a.busy, b.busy),
+        iowait: share(a.iowait, b.iowait),
+        steal: share(a.steal, b.steal),
+        waited_ms: 0,
+    })
+}
+
+/// `cpu  user nice system idle iowait irq softirq steal guest guest_nice`.
+/// Guest time is already counted in user time, so it is not added again.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn jiffies(stat: &str) -> Option<Jiffies> {
+    let line = stat.lines().find(|l| l.starts_with("cpu "))?;
+    let f: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .map(|v| v.parse().ok())
+        .collect::<Option<_>>()?;
+    let at = |i: usize| f.get(i).copied().unwrap_or(0);
+    let (user, nice, system, idle, iowait, irq, softirq, steal) =
+        (at(0), at(1), at(2), at(3), at(4), at(5), at(6), at(7));
+    let busy = user + nice + system + irq + softirq;
+    Some(Jiffies {
+        busy,
+        iowait,
+        steal,
+        total: busy + idle + iowait + steal,
+    })
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn procs_running(stat: &str) -> Option<u32> {
+    stat.lines()
+        .find_map(|l| l.strip_prefix("procs_running"))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEFORE: &str = "cpu  1000 0 500 8000 100 0 0 0 0 0\n\
+                          cpu0 500 0 250 4000 50 0 0 0 0 0\n\
+                          procs_running 1\nprocs_blocked 0\n";
+    // 400 jiffies later: 100 user, 20 system, 260 idle, 10 iowait, 10 steal.
+    const AFTER: &str = "cpu  1100 0 520 8260 110 0 0 10 0 0\n\
+                         cpu0 550 0 260 4130 55 0 0 5 0 0\n\
+                         procs_running 4\nprocs_blocked 0\n";
+    const LOADAVG: &str = "2.50 1.75 1.20 4/612 98765\n";
+
+    #[test]
+    fn a_sample_splits_the_interval_into_busy_iowait_and_steal() {
+        let s = from_proc("start", BEFORE, AFTER, LOADAVG).unwrap();
+        assert!((s.busy - 120.0 / 400.0).abs() < 1e-12);
+        assert!((s.iowait - 10.0 / 400.0).abs() < 1e-12);
+        assert!((s.steal - 10.0 / 400.0).abs() < 1e-12);
+        assert!((s.loadavg - 2.5).abs() < 1e-12);
+        assert_eq!(s.run_queue, 3, "the sampling task is not counted");
+        assert!(s.is_loaded());
+    }
+
+    #[test]
+    fn a_quiet_machine_is_not_loaded() {
+        let quiet = "cpu  1002 0 501 8397 100 0 0 0 0 0\nprocs_running 1\n";
+        let s = from_proc("start", BEFORE, quiet, LOADAVG).unwrap();
+        assert!(s.busy < 0.01);
+        assert_eq!(s.run_queue, 0);
+        assert!(!s.is_loaded());
+    }
+
+    #[test]
+    fn malformed_proc_files_yield_no_sample() {
+        assert!(from_proc("start", "", AFTER, LOADAVG).is_none());
+        assert!(from_proc("start", BEFORE, AFTER, "").is_none());
+        assert!(from_proc("start", BEFORE, "cpu  1 2 x\n", LOADAVG).is_none());
+    }
+
+    #[test]
+    fn this_platform_samples_if_it_claims_to() {
+        assert_eq!(sample("start").is_some(), supported());
+    }
+}
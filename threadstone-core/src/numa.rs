@@ -0,0 +1,240 @@
+//! NUMA topology, and binding a thread's memory to one node.
+//!
+//! On a multi-socket server, or a chiplet part configured as several nodes,
+//! memory is not one pool. Each node's DRAM sits behind its own controllers,
+//! and a CPU reaching another node's memory crosses the socket interconnect:
+//! more latency, and bandwidth shared with everything else crossing it. Which
+//! side of that line a benchmark's pages landed on is a difference in what was
+//! measured, so the layout travels with every result as
+//! [`SystemInfo::numa_nodes`](crate::SystemInfo::numa_nodes).
+//!
+//! Placement normally follows first touch: a page lands on the node of the CPU
+//! that first writes it. [`bind_memory`] overrides that for the calling thread
+//! with `set_mempolicy(MPOL_BIND)`, so everything it touches afterwards comes
+//! from one node regardless of where the thread runs. The runner applies it to
+//! each worker before setup when [`RunConfig::memory_node`] is set, which is
+//! how `threadstone numa` measures remote access on purpose.
+//!
+//! [`RunConfig::memory_node`]: crate::RunConfig::memory_node
+//!
+//! Linux only, read from `/sys/devices/system/node`. Elsewhere no nodes are
+//! reported and binding is refused.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// One NUMA node: the CPUs local to it and the memory behind it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct NumaNode {
+    /// Node number, as the kernel names it.
+    pub id: usize,
+    /// Logical CPUs on this node. Empty for a memory-only node.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cpus: Vec<usize>,
+    /// Memory on this node, in bytes. Zero for a CPU-only node.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_bytes: Option<u64>,
+    /// Firmware-reported relative distance to each node, in node order; 10
+    /// is local by convention.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub distances: Vec<u32>,
+}
+
+impl NumaNode {
+    /// Whether threads can be placed on this node.
+    pub fn has_cpus(&self) -> bool {
+        !self.cpus.is_empty()
+    }
+
+    /// Whether memory can be bound to this node.
+    pub fn has_memory(&self) -> bool {
+        self.memory_bytes.map_or(true, |b| b > 0)
+    }
+}
+
+/// Whether this platform can bind memory to a node at all.
+pub fn supported() -> bool {
+    imp::SUPPORTED
+}
+
+/// The machine's NUMA nodes, in node order. Empty where the platform does not
+/// say; a single entry on an ordinary one-node machine.
+pub fn detect() -> Vec<NumaNode> {
+    imp::detect()
+}
+
+/// Allocate everything the calling thread touches from now on from `node`
+/// only. Lasts until the thread exits.
+pub fn bind_memory(node: usize) -> Result<(), String> {
+    imp::bind(node)
+}
+
+/// Parse a node's `meminfo` file into its total memory, in bytes.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn node_memory(text: &str) -> Option<u64> {
+    text.lines()
+        .find_map(|l| l.split_once("MemTotal:"))
+        .and_then(|(_, v)| v.split_whitespace().next()?.parse::<u64>().ok())
+        .map(|kib| kib * 1024)
+}
+
+/// Parse a node's `distance` file.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn distances(text: &str) -> Option<Vec<u32>> {
+    text.split_whitespace().map(|d| d.parse().ok()).collect()
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::{distances, node_memory, NumaNode};
+    use crate::affinity::parse_cpu_list;
+    use std::fs;
+
+    pub const SUPPORTED: bool = true;
+
+    /// Nodes the mask can name; the kernel's own default `MAX_NUMNODES` limit
+    /// is 1024 on the largest configurations.
+    const MASK_WORDS: usize = 16;
+
+    const MPOL_BIND: i64 = 2;
+
+    #[cfg(target_arch = "x86_64")]
+    const SYS_SET_MEMPOLICY: Option<i64> = Some(238);
+    #[cfg(any(target_arch = "aarch64", target_arch = "riscv64"))]
+    const SYS_SET_MEMPOLICY: Option<i64> = Some(237);
+    #[cfg(not(any(
+        target_arch = "x86_64",
+        target_arch = "aarch64",
+        target_arch = "riscv64"
+    )))]
+    const SYS_SET_MEMPOLICY: Option<i64> = None;
+
+    // glibc has no `set_mempolicy` wrapper; that lives in libnuma, which is not
+    // worth a dependency for one call.
+    extern "C" {
+        fn syscall(number: i64, ...) -> i64;
+    }
+
+    const ROOT: &str = "/sys/devices/system/node";
+
+    pub fn detect() -> Vec<NumaNode> {
+        let Some(online) = fs::read_to_string(format!("{ROOT}/online"))
+            .ok()
+            .and_then(|text| parse_cpu_list(&text).ok())
+        else {
+            return Vec::new();
+        };
+        online
+            .into_iter()
+            .map(|id| {
+                let read = |file: &str| fs::read_to_string(format!("{ROOT}/node{id}/{file}")).ok();
+                NumaNode {
+                    id,
+                    cpus: read("cpulist")
+                        .and_then(|text| parse_cpu_list(&text).ok())
+                        .unwrap_or_default(),
+                    memory_bytes: read("meminfo").as_deref().and_then(node_memory),
+                    distances: read("distance")
+                        .as_deref()
+                        .and_then(distances)
+                        .unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+
+    pub fn bind(node: usize) -> Result<(), String> {
+        let Some(number) = SYS_SET_MEMPOLICY else {
+            return Err("binding memory is not supported on this architecture".to_string());
+        };
+        if node >= MASK_WORDS * 64 {
+            return Err(format!("node {node} is beyond the supported mask size"));
+        }
+        let mut mask = [0u64; MASK_WORDS];
+        mask[node / 64] |= 1 << (node % 64);
+        // The kernel reads one bit fewer than `maxnode` says, so libnuma and
+        // everyone else pass the mask size plus one.
+        let maxnode = (MASK_WORDS * 64 + 1) as u64;
+        // SAFETY: `mask` is a live buffer holding `maxnode - 1` bits, which is
+        // all the kernel reads; the policy applies to the calling thread only.
+        let rc = unsafe { syscall(number, MPOL_BIND, mask.as_ptr(), maxnode) };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(format!(
+                "cannot bind memory to node {node}: {}",
+                std::io::Error::last_os_error()
+            ))
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::NumaNode;
+
+    pub const SUPPORTED: bool = false;
+
+    pub fn detect() -> Vec<NumaNode> {
+        Vec::new()
+    }
+
+    pub fn bind(_node: usize) -> Result<(), String> {
+        Err("binding memory to a node is only supported on Linux".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_files_parse_into_memory_and_distances() {
+        let meminfo = "\
+Node 1 MemTotal:       65843712 kB
+Node 1 MemFree:        61234567 kB
+";
+        assert_eq!(node_memory(meminfo), Some(65_843_712 * 1024));
+        assert_eq!(node_memory("Node 1 MemFree: 3 kB\n"), None);
+        assert_eq!(distances("10 21\n"), Some(vec![10, 21]));
+        assert_eq!(distances("10 x"), None);
+    }
+
+    #[test]
+    fn detected_nodes_cover_distinct_cpus() {
+        let nodes = detect();
+        if !supported() {
+            assert!(nodes.is_empty());
+        }
+        let mut cpus: Vec<usize> = nodes.iter().flat_map(|n| n.cpus.clone()).collect();
+        let total = cpus.len();
+        cpus.sort_unstable();
+        cpus.dedup();
+        assert_eq!(cpus.len(), total, "a CPU belongs to one node");
+        for node in &nodes {
+            if !node.distances.is_empty() {
+                assert_eq!(node.distances.len(), nodes.len());
+            }
+        }
+    }
+
+    #[test]
+    fn memory_binds_to_a_node_that_has_some() {
+        let Some(node) = detect().into_iter().find(NumaNode::has_memory) else {
+            if !supported() {
+                assert!(bind_memory(0).is_err());
+            }
+            return;
+        };
+        // In a thread of its own, so the policy goes when the thread does.
+        let bound = std::thread::spawn(move || {
+            bind_memory(node.id)?;
+            let buf = vec![1u8; 1 << 20];
+            Ok::<_, String>(buf.iter().map(|&b| b as usize).sum::<usize>())
+        })
+        .join()
+        .unwrap();
+        assert_eq!(bound, Ok(1 << 20));
+        assert!(bind_memory(usize::MAX / 2).is_err());
+    }
+}
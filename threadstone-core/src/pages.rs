@@ -0,0 +1,337 @@
+//! Page sizes for large working sets.
+//!
+//! A 256 MiB pointer chase touches 65 536 distinct 4 KiB pages and only 128
+//! 2 MiB ones. With small pages nearly every hop also misses the TLB and pays a
+//! page walk; with huge pages almost none do. Which one a plain `Vec` gets is
+//! up to the kernel's transparent-huge-page mode — `always`, `madvise` or
+//! `never`, and whether it found free 2 MiB frames that day — so the same
+//! binary on the same machine can report latency tens of percent apart.
+//!
+//! `--pages` takes that choice away from the kernel's mood for the kernels
+//! whose working sets are large enough for it to matter:
+//!
+//! * [`PagePolicy::Default`] allocates as before and lets the THP mode decide.
+//! * [`PagePolicy::Thp`] maps the buffer 2 MiB-aligned and asks for huge pages
+//!   with `madvise(MADV_HUGEPAGE)`, which works under the `madvise` mode too.
+//! * [`PagePolicy::Hugetlb`] maps it from the reserved hugetlbfs pool with
+//!   `MAP_HUGETLB`. An empty pool falls back to [`PagePolicy::Thp`], and the
+//!   pass's [`HugePages`] shows that nothing came from the pool.
+//! * [`PagePolicy::Small`] refuses huge pages with `madvise(MADV_NOHUGEPAGE)`.
+//!
+//! What a policy asks for and what the kernel grants are different things, so
+//! every pass also records [`HugePages`]: the process's huge-page footprint
+//! once its workers are set up, read from `/proc/self/smaps_rollup`.
+//!
+//! Linux only. Elsewhere every policy allocates like `Default`, no footprint is
+//! recorded, and the CLI refuses anything but `default`.
+
+use std::ops::{Deref, DerefMut};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Size of the huge pages requested: 2 MiB, the only size x86-64 and arm64
+/// both offer by default.
+pub const HUGE_PAGE: usize = 2 << 20;
+
+/// How large buffers are backed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PagePolicy {
+    /// Whatever the kernel's transparent-huge-page mode gives.
+    #[default]
+    Default,
+    /// Transparent huge pages, requested with `madvise`.
+    Thp,
+    /// Pages from the reserved hugetlbfs pool.
+    Hugetlb,
+    /// 4 KiB pages only.
+    Small,
+}
+
+impl PagePolicy {
+    /// Name as `--pages` accepts it.
+    pub fn label(self) -> &'static str {
+        match self {
+            PagePolicy::Default => "default",
+            PagePolicy::Thp => "thp",
+            PagePolicy::Hugetlb => "hugetlb",
+            PagePolicy::Small => "small",
+        }
+    }
+}
+
+/// The process's huge-page footprint, in KiB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct HugePages {
+    /// Anonymous memory backed by transparent huge pages (`AnonHugePages`).
+    pub anon_kib: u64,
+    /// Memory mapped from the hugetlbfs pool (`Private_Hugetlb` plus
+    /// `Shared_Hugetlb`).
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub hugetlb_kib: u64,
+}
+
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+impl HugePages {
+    /// Everything huge-page backed.
+    pub fn total_kib(&self) -> u64 {
+        self.anon_kib + self.hugetlb_kib
+    }
+}
+
+/// Whether this platform honours a page policy at all.
+pub fn supported() -> bool {
+    cfg!(target_os = "linux")
+}
+
+/// The current huge-page footprint. `None` where it cannot be read.
+pub fn huge_pages() -> Option<HugePages> {
+    #[cfg(target_os = "linux")]
+    {
+        from_smaps_rollup(&std::fs::read_to_string("/proc/self/smaps_rollup").ok()?)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Parse the `kB` fields of `/proc/<pid>/smaps_rollup`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn from_smaps_rollup(text: &str) -> Option<HugePages> {
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|l| l.strip_prefix(name)?.strip_prefix(':'))
+            .and_then(|v| v.split_whitespace().next()?.parse::<u64>().ok())
+    };
+    Some(HugePages {
+        anon_kib: field("AnonHugePages")?,
+        hugetlb_kib: field("Private_Hugetlb").unwrap_or(0) + field("Shared_Hugetlb").unwrap_or(0),
+    })
+}
+
+/// A buffer of `T` backed as a [`PagePolicy`] asks. Dereferences to a slice.
+pub struct PageBuf<T: Copy> {
+    inner: Backing<T>,
+}
+
+enum Backing<T> {
+    Heap(Vec<T>),
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    Mapped(imp::Mapping, usize),
+}
+
+impl<T: Copy> PageBuf<T> {
+    /// `len` copies of `value`. Every element is written, so every page is
+    /// first-touched by the calling thread.
+    pub fn filled(len: usize, value: T, policy: PagePolicy) -> PageBuf<T> {
+        let bytes = len.max(1) * std::mem::size_of::<T>();
+        let mapped = match policy {
+            PagePolicy::Default => None,
+            _ => imp::Mapping::new(bytes, std::mem::align_of::<T>(), policy),
+        };
+        let inner = match mapped {
+            Some(map) => {
+                let ptr = map.ptr() as *mut T;
+                for i in 0..len {
+                    // SAFETY: the mapping holds at least `len` elements and is
+                    // suitably aligned; writing initialises each one.
+                    unsafe { ptr.add(i).write(value) };
+                }
+                Backing::Mapped(map, len)
+            }
+            None => Backing::Heap(vec![value; len]),
+        };
+        PageBuf { inner }
+    }
+}
+
+impl<T: Copy> Deref for PageBuf<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match &self.inner {
+            Backing::Heap(v) => v,
+            // SAFETY: every element was initialised in `filled`, and the
+            // mapping lives as long as `self`.
+            Backing::Mapped(map, len) => unsafe {
+                std::slice::from_raw_parts(map.ptr() as *const T, *len)
+            },
+        }
+    }
+}
+
+impl<T: Copy> DerefMut for PageBuf<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match &mut self.inner {
+            Backing::Heap(v) => v,
+            // SAFETY: as in `deref`, and `&mut self` makes the borrow unique.
+            Backing::Mapped(map, len) => unsafe {
+                std::slice::from_raw_parts_mut(map.ptr() as *mut T, *len)
+            },
+        }
+    }
+}
+
+// SAFETY: a `PageBuf` owns its memory outright, like the `Vec` it stands in
+// for.
+unsafe impl<T: Copy + Send> Send for PageBuf<T> {}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::{PagePolicy, HUGE_PAGE};
+
+    // Declared directly, as elsewhere in this crate, rather than through the
+    // `libc` crate.
+    extern "C" {
+        fn mmap(addr: *mut u8, len: usize, prot: i32, flags: i32, fd: i32, off: i64) -> *mut u8;
+        fn munmap(addr: *mut u8, len: usize) -> i32;
+        fn madvise(addr: *mut u8, len: usize, advice: i32) -> i32;
+    }
+
+    const PROT_READ: i32 = 0x1;
+    const PROT_WRITE: i32 = 0x2;
+    const MAP_PRIVATE: i32 = 0x02;
+    const MAP_ANONYMOUS: i32 = 0x20;
+    const MAP_HUGETLB: i32 = 0x40000;
+    const MAP_FAILED: *mut u8 = !0 as *mut u8;
+    const MADV_HUGEPAGE: i32 = 14;
+    const MADV_NOHUGEPAGE: i32 = 15;
+
+    /// An anonymous mapping, unmapped on drop.
+    pub struct Mapping {
+        base: *mut u8,
+        len: usize,
+        /// Offset of the usable, aligned start within the mapping.
+        offset: usize,
+    }
+
+    impl Mapping {
+        /// Map at least `bytes`, backed as `policy` asks. `None` if the kernel
+        /// refuses even a plain mapping, so the caller can use the heap.
+        pub fn new(bytes: usize, align: usize, policy: PagePolicy) -> Option<Mapping> {
+            let rounded = bytes.div_ceil(HUGE_PAGE) * HUGE_PAGE;
+            if policy == PagePolicy::Hugetlb {
+                if let Some(map) = Mapping::map(rounded, MAP_HUGETLB) {
+                    return Some(map);
+                }
+            }
+            // Over-map by one huge page so the usable range can start on a
+            // 2 MiB boundary, without which no huge page fits its first part.
+            let len = rounded + HUGE_PAGE.max(align);
+            let mut map = Mapping::map(len, 0)?;
+            map.offset = (HUGE_PAGE - map.base as usize % HUGE_PAGE) % HUGE_PAGE;
+            let advice = match policy {
+                PagePolicy::Small => MADV_NOHUGEPAGE,
+                _ => MADV_HUGEPAGE,
+            };
+            // Advice that is refused leaves ordinary pages, which the pass's
+            // recorded footprint will show.
+            // SAFETY: the range lies within the mapping just created.
+            unsafe { madvise(map.ptr(), rounded, advice) };
+            Some(map)
+        }
+
+        fn map(len: usize, flags: i32) -> Option<Mapping> {
+            // SAFETY: an anonymous private mapping at an address of the
+            // kernel's choosing; no existing memory is affected.
+            let base = unsafe {
+                mmap(
+                    std::ptr::null_mut(),
+                    len,
+                    PROT_READ | PROT_WRITE,
+                    MAP_PRIVATE | MAP_ANONYMOUS | flags,
+                    -1,
+                    0,
+                )
+            };
+            (base != MAP_FAILED).then_some(Mapping {
+                base,
+                len,
+                offset: 0,
+            })
+        }
+
+        pub fn ptr(&self) -> *mut u8 {
+            // SAFETY: `offset` is less than one huge page into the mapping.
+            unsafe { self.base.add(self.offset) }
+        }
+    }
+
+    impl Drop for Mapping {
+        fn drop(&mut self) {
+            // SAFETY: `base` and `len` are exactly what `mmap` returned and
+            // was given, and nothing refers to the memory once this runs.
+            unsafe { munmap(self.base, self.len) };
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::PagePolicy;
+
+    /// Never constructed: every policy uses the heap here.
+    pub struct Mapping(std::convert::Infallible);
+
+    impl Mapping {
+        pub fn new(_bytes: usize, _align: usize, _policy: PagePolicy) -> Option<Mapping> {
+            None
+        }
+
+        pub fn ptr(&self) -> *mut u8 {
+            match self.0 {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROLLUP: &str = "\
+00400000-7ffd5a1e3000 ---p 00000000 00:00 0                  [rollup]
+Rss:              270336 kB
+Anonymous:        268000 kB
+AnonHugePages:    262144 kB
+Shared_Hugetlb:        0 kB
+Private_Hugetlb:    4096 kB
+Swap:                  0 kB
+";
+
+    #[test]
+    fn the_rollup_yields_transparent_and_pooled_huge_pages() {
+        let h = from_smaps_rollup(ROLLUP).unwrap();
+        assert_eq!(h.anon_kib, 262_144);
+        assert_eq!(h.hugetlb_kib, 4096);
+        assert_eq!(h.total_kib(), 266_240);
+        assert!(from_smaps_rollup("Rss: 1 kB\n").is_none());
+    }
+
+    #[test]
+    fn every_policy_gives_a_filled_writable_buffer() {
+        for policy in [
+            PagePolicy::Default,
+            PagePolicy::Thp,
+            PagePolicy::Hugetlb,
+            PagePolicy::Small,
+        ] {
+            let mut buf = PageBuf::filled(3 * HUGE_PAGE / 8 + 5, 7u64, policy);
+            assert_eq!(buf.len(), 3 * HUGE_PAGE / 8 + 5);
+            assert!(buf.iter().all(|v| *v == 7), "{policy:?}");
+            buf[0] = 1;
+            let last = buf.len() - 1;
+            buf[last] = 2;
+            assert_eq!((buf[0], buf[last]), (1, 2));
+        }
+    }
+
+    #[test]
+    fn this_platform_reports_a_footprint_if_it_claims_to() {
+        assert_eq!(huge_pages().is_some(), supported());
+    }
+}
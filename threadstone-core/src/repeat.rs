@@ -0,0 +1,353 @@
+//! Combining repeated invocations of the suite into one result.
+//!
+//! The rounds of one pass share a process, so their spread is round-to-round
+//! noise only. What changes between launches — address-space layout, which
+//! physical pages the allocator lands on, the boost state the package happens
+//! to be in — is invisible to it, and is often the larger effect. A single
+//! run, however many samples it takes, cannot report it, and a comparison
+//! against such a run calls differences significant that the next launch
+//! would erase.
+//!
+//! `threadstone run --repeat N` therefore runs the whole suite N times, each
+//! in fresh child processes, and [`combine`] folds the reports into one. Each
+//! combined pass pools every invocation's rounds, so its statistics describe
+//! all of them, and records in [`Repeats`] each invocation's median, the
+//! spread within and between invocations, and a confidence interval that
+//! counts both. That interval is what `compare` judges a change against.
+//!
+//! # The combined uncertainty
+//!
+//! With `k` invocations whose medians have standard deviation `s`, the
+//! between-run standard error of their mean is `s / √k`. The within-run part
+//! is each invocation's own standard error, averaged in quadrature and shrunk
+//! by the same `√k`. The two are added in quadrature and scaled to 95% by
+//! Student's t with `k − 1` degrees of freedom rather than the normal 1.96,
+//! since `s` is estimated from only a handful of medians: the multiplier is
+//! 12.7 for two invocations, 4.3 for three and 2.8 for five. A two-run
+//! interval is accordingly wide, and a comparison that would need more runs to
+//! be significant says so.
+//!
+//! Details that do not pool — the placement observed, thread balance, clock
+//! and counters — come from the invocation whose median is the median of all
+//! of them. Adaptive-sampling outcomes are dropped: each describes when one
+//! invocation stopped, not the pooled rounds.
+//!
+//! Drift does not pool either. It is a trend within one process, and a trend
+//! test over rounds laid end to end across launches would report every step
+//! between them as throttling. Each invocation's own test stands instead: a
+//! combined pass carries the strongest significant drift any of them found,
+//! or the typical invocation's verdict, and loses a stability grade only if
+//! one of them drifted.
+
+use crate::report::{
+    CoreClassPass, Pass, Repeats, Report, ScalingCurve, ScalingReport, SubResult, WorkloadReport,
+};
+use crate::score::ScoreCard;
+use crate::stats::{self, Stability, Summary};
+
+/// z for a two-sided 95% interval, as [`Summary::ci95`] uses, to recover each
+/// invocation's standard error from it.
+const Z95: f64 = 1.96;
+
+/// Fold the reports of repeated invocations, in the order they ran, into
+/// one. The first report supplies the machine, the settings and the order
+/// of workloads. `None` when there are no reports.
+pub fn combine(runs: Vec<Report>) -> Option<Report> {
+    let first = runs.first()?;
+    let mut combined = first.clone();
+    combined.workloads = first
+        .workloads
+        .iter()
+        .map(|w| {
+            let same: Vec<&WorkloadReport> = runs
+                .iter()
+                .filter_map(|r| r.workloads.iter().find(|o| o.id == w.id))
+                .collect();
+            combine_workload(w, &same)
+        })
+        .collect();
+    combined.score = ScoreCard::from_workloads(&combined.workloads);
+    combined.load = runs
+        .iter()
+        .enumerate()
+        .flat_map(|(i, r)| {
+            r.load.iter().cloned().map(move |mut s| {
+                s.before = format!("run {}: {}", i + 1, s.before);
+                s
+            })
+        })
+        .collect();
+    combined.duration_secs = runs.iter().map(|r| r.duration_secs).sum();
+    combined.generated_at = runs.last()?.generated_at.clone();
+    combined.config.repeat = Some(runs.len() as u32);
+    Some(combined)
+}
+
+/// One workload across invocations; `same` holds every invocation's entry.
+fn combine_workload(first: &WorkloadReport, same: &[&WorkloadReport]) -> WorkloadReport {
+    let passes = |select: fn(&WorkloadReport) -> Option<&Pass>| {
+        combine_passes(&same.iter().filter_map(|w| select(w)).collect::<Vec<_>>())
+    };
+    let single = passes(|w| w.single_thread.as_ref());
+    let multi = passes(|w| w.multi_thread.as_ref());
+
+    let scaling_curve = first.scaling_curve.as_ref().and_then(|curve| {
+        let points = curve
+            .points
+            .iter()
+            .filter_map(|point| {
+                let at: Vec<&Pass> = same
+                    .iter()
+                    .filter_map(|w| w.scaling_curve.as_ref())
+                    .filter_map(|c| c.points.iter().find(|p| p.threads == point.threads))
+                    .map(|p| &p.pass)
+                    .collect();
+                combine_passes(&at)
+            })
+            .collect();
+        ScalingCurve::compute(points, first.unit)
+    });
+    let core_classes = first
+        .core_classes
+        .iter()
+        .filter_map(|c| {
+            let at: Vec<&Pass> = same
+                .iter()
+                .filter_map(|w| w.core_classes.iter().find(|o| o.class == c.class))
+                .map(|o| &o.pass)
+                .collect();
+            Some(CoreClassPass {
+                class: c.class,
+                pass: combine_passes(&at)?,
+            })
+        })
+        .collect();
+
+    let sub_results = first
+        .sub_results
+        .iter()
+        .map(|sub| {
+            let at = |select: fn(&SubResult) -> Option<&Pass>| {
+                let passes: Vec<&Pass> = same
+                    .iter()
+                    .filter_map(|w| w.sub_results.iter().find(|o| o.id == sub.id))
+                    .filter_map(select)
+                    .collect();
+                combine_passes(&passes)
+            };
+            SubResult {
+                single_thread: at(|s| s.single_thread.as_ref()),
+                multi_thread: at(|s| s.multi_thread.as_ref()),
+                ..sub.clone()
+            }
+        })
+        .collect();
+
+    // The same error from every invocation is stated once; otherwise each is
+    // attributed to the invocation that hit it.
+    let errors: Vec<(usize, &str)> = same
+        .iter()
+        .enumerate()
+        .filter_map(|(i, w)| Some((i + 1, w.error.as_deref()?)))
+        .collect();
+    let error = match errors.as_slice() {
+        [] => None,
+        [(_, e), rest @ ..] if rest.len() + 1 == same.len() && rest.iter().all(|r| r.1 == *e) => {
+            Some(e.to_string())
+        }
+        _ => Some(
+            errors
+                .iter()
+                .map(|(i, e)| format!("run {i}: {e}"))
+                .collect::<Vec<_>>()
+                .join("; "),
+        ),
+    };
+
+    WorkloadReport {
+        scaling: match (&single, &multi) {
+            (Some(s), Some(m)) => ScalingReport::compute(s, m, first.unit),
+            _ => None,
+        },
+        single_thread: single,
+        multi_thread: multi,
+        scaling_curve,
+        core_classes,
+        sub_results,
+        error,
+        ..first.clone()
+    }
+}
+
+/// Pool one pass's invocations. A pass that only one invocation produced is
+/// returned as it was.
+fn combine_passes(passes: &[&Pass]) -> Option<Pass> {
+    let (first, rest) = passes.split_first()?;
+    if rest.is_empty() {
+        return Some((*first).clone());
+    }
+    let mut order: Vec<&Pass> = passes.to_vec();
+    order.sort_by(|a, b| a.value.total_cmp(&b.value));
+    let typical = order[(order.len() - 1) / 2];
+
+    let samples: Vec<f64> = passes.iter().flat_map(|p| p.samples.clone()).collect();
+    let mut stats = Summary::new(&samples)?;
+    stats.drift = passes
+        .iter()
+        .filter_map(|p| p.stats.drift.as_ref().filter(|d| d.significant))
+        .max_by(|a, b| a.change.abs().total_cmp(&b.change.abs()))
+        .or(typical.stats.drift.as_ref())
+        .cloned();
+    stats.stability = Stability::from_cv(stats.cv);
+    if stats.drift.as_ref().is_some_and(|d| d.significant) {
+        stats.stability = stats.stability.downgraded();
+    }
+
+    Some(Pass {
+        value: stats.median,
+        samples,
+        stats,
+        window_too_short: passes.iter().any(|p| p.window_too_short),
+        precision: None,
+        repeats: Some(repeats(passes)),
+        ..typical.clone()
+    })
+}
+
+/// Within- and between-invocation spread of one pass.
+fn repeats(passes: &[&Pass]) -> Repeats {
+    let k = passes.len() as f64;
+    let medians: Vec<f64> = passes.iter().map(|p| p.value).collect();
+    let mean = medians.iter().sum::<f64>() / k;
+    let sd = (medians.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / (k - 1.0)).sqrt();
+    let rms = |f: fn(&Pass) -> f64| (passes.iter().map(|p| f(p).powi(2)).sum::<f64>() / k).sqrt();
+
+    let between = sd / k.sqrt();
+    let within = rms(|p| p.stats.ci95 / Z95) / k.sqrt();
+    Repeats {
+        medians,
+        within_cv: rms(|p| p.stats.cv),
+        between_cv: if mean > 0.0 { sd / mean } else { 0.0 },
+        ci95: stats::t95(passes.len() - 1) * (between.powi(2) + within.powi(2)).sqrt(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pass(samples: &[f64]) -> Pass {
+        let stats = Summary::new(samples).unwrap();
+        Pass {
+            threads: 1,
+            iters_per_thread: 1000,
+            value: stats.median,
+            samples: samples.to_vec(),
+            stats,
+            window_ms: 250.0,
+            window_too_short: false,
+            pinned_cpus: vec![],
+            cpus: vec![],
+            balance: None,
+            clock: None,
+            counters: None,
+            counters_error: None,
+            precision: None,
+            huge_pages: None,
+            repeats: None,
+        }
+    }
+
+    #[test]
+    fn invocations_pool_their_rounds_and_keep_their_medians() {
+        let runs = [
+            pass(&[99.0, 100.0, 101.0]),
+            pass(&[104.0, 105.0, 106.0]),
+            pass(&[94.0, 95.0, 96.0]),
+        ];
+        let combined = combine_passes(&runs.iter().collect::<Vec<_>>()).unwrap();
+        assert_eq!(combined.samples.len(), 9);
+        assert_eq!(combined.value, combined.stats.median);
+        let r = combined.repeats.unwrap();
+        assert_eq!(r.medians, vec![100.0, 105.0, 95.0]);
+        assert!((r.between_cv - 0.05).abs() < 1e-12);
+        assert!(r.within_cv < r.between_cv);
+    }
+
+    #[test]
+    fn the_combined_interval_is_wider_than_either_source_alone() {
+        let runs = [
+            pass(&[99.0, 100.0, 101.0]),
+            pass(&[104.0, 105.0, 106.0]),
+            pass(&[94.0, 95.0, 96.0]),
+        ];
+        let refs: Vec<&Pass> = runs.iter().collect();
+        let r = repeats(&refs);
+        let between = 4.3027 * 5.0 / 3f64.sqrt();
+        assert!(r.ci95 > between, "{} vs {between}", r.ci95);
+        assert!(
+            r.ci95 > runs[0].stats.ci95 / 3f64.sqrt(),
+            "within-run noise still counts"
+        );
+        // Identical invocations leave only within-run noise, shrunk by √k and
+        // widened by t for two degrees of freedom in place of z.
+        let same = [&runs[0], &runs[0], &runs[0]];
+        let r = repeats(&same);
+        assert_eq!(r.between_cv, 0.0);
+        let within = runs[0].stats.ci95 / Z95 / 3f64.sqrt();
+        assert!((r.ci95 - 4.3027 * within).abs() < 1e-9);
+    }
+
+    #[test]
+    fn two_invocations_give_a_wide_interval() {
+        // Medians 100 and 104 from two launches: s = 2√2, so the standard
+        // error of their mean is 2, and t for one degree of freedom is 12.71.
+        let runs = [pass(&[100.0; 3]), pass(&[104.0; 3])];
+        let r = repeats(&runs.iter().collect::<Vec<_>>());
+        assert!((r.ci95 - 12.7062 * 2.0).abs() < 1e-9, "{}", r.ci95);
+    }
+
+    #[test]
+    fn a_step_between_invocations_is_not_drift() {
+        // Each launch flat, the second a little faster: laid end to end the
+        // rounds climb, but nothing moved within a process.
+        let flat = [
+            100.0, 100.2, 99.9, 100.1, 100.0, 99.8, 100.1, 99.9, 100.2, 99.8,
+        ];
+        let runs = [pass(&flat), pass(&flat.map(|v| v * 1.05))];
+        let pooled = Summary::new(&[flat, flat.map(|v| v * 1.05)].concat()).unwrap();
+        assert!(
+            pooled.drift.as_ref().unwrap().significant,
+            "premise: pooling shows a trend"
+        );
+
+        let combined = combine_passes(&runs.iter().collect::<Vec<_>>()).unwrap();
+        assert!(!combined.stats.drift.unwrap().significant);
+        assert_eq!(
+            combined.stats.stability,
+            Stability::from_cv(combined.stats.cv)
+        );
+    }
+
+    #[test]
+    fn a_drift_within_one_invocation_still_costs_a_grade() {
+        let slide: Vec<f64> = (0..8).map(|i| 100.0 - 0.4 * f64::from(i)).collect();
+        let runs = [pass(&[100.0, 100.1, 99.9, 100.0]), pass(&slide)];
+        let combined = combine_passes(&runs.iter().collect::<Vec<_>>()).unwrap();
+        let drift = combined.stats.drift.unwrap();
+        assert!(drift.significant && drift.change < 0.0, "{drift:?}");
+        assert_eq!(
+            combined.stats.stability,
+            Stability::from_cv(combined.stats.cv).downgraded()
+        );
+    }
+
+    #[test]
+    fn a_pass_from_one_invocation_is_left_as_it_was() {
+        let only = pass(&[1.0, 2.0, 3.0]);
+        let combined = combine_passes(&[&only]).unwrap();
+        assert!(combined.repeats.is_none());
+        assert_eq!(combined.samples, only.samples);
+        assert!(combine_passes(&[]).is_none());
+    }
+}
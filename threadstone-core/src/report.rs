@@ -3,11 +3,18 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::kernel::{KernelInfo, Scaling, Unit};
-use crate::runner::Measurement;
+use crate::affinity::Affinity;
+use crate::budget::BudgetPlan;
+use crate::coherence::CoreToCore;
+use crate::counters::{CounterReport, CounterStatus};
+use crate::kernel::{KernelInfo, Param, Scaling, Unit};
+use crate::load::{LoadSample, RequireIdle};
+use crate::pages::{HugePages, PagePolicy};
+use crate::runner::{Measurement, Precision, PrecisionReport};
 use crate::score::ScoreCard;
-use crate::stats::Summary;
-use crate::sysinfo::SystemInfo;
+use crate::stats::{Summary, ThreadBalance};
+use crate::suite::Isolation;
+use crate::sysinfo::{CoreClass, SystemInfo};
 
 /// Schema version of the result document.
 ///
@@ -28,12 +35,19 @@ pub struct Report {
     pub duration_secs: f64,
     /// The machine and toolchain this was measured on.
     pub system: SystemInfo,
+    /// What else the machine was doing, sampled before the run and before
+    /// every pass. Empty where the platform cannot be sampled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub load: Vec<LoadSample>,
     /// How the run was configured.
     pub config: RunSettings,
     /// One entry per workload, in execution order.
     pub workloads: Vec<WorkloadReport>,
     /// Composite scores.
     pub score: ScoreCard,
+    /// Core-to-core cache-line latency, with `--core-to-core`. Not scored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub core_to_core: Option<CoreToCore>,
     /// Detached signature over the canonical form of this document.
     ///
     /// Excluded from the bytes it signs; see [`canonical_json`].
@@ -52,6 +66,79 @@ pub struct RunSettings {
     pub warmup: u32,
     /// Target measurement window, in milliseconds.
     pub window_ms: u64,
+    /// How worker threads were bound to CPUs. Absent when placement was left
+    /// to the scheduler.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub affinity: Option<Affinity>,
+    /// Thread counts measured for each workload's scaling curve. Empty when no
+    /// curve was requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scaling: Vec<usize>,
+    /// Whether hardware counters were collected, and if not, why. Absent when
+    /// they were not requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counters: Option<CounterStatus>,
+    /// The adaptive-sampling target, when `samples` was a minimum rather than
+    /// a fixed count.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<PrecisionSettings>,
+    /// How a budgeted run chose its window, warmup and samples — the values
+    /// recorded above — and which workloads it left out to fit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetPlan>,
+    /// The idle threshold every pass had to start under, when one was set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_idle: Option<IdleSettings>,
+    /// How passes were kept apart from the process assembling the report.
+    /// Absent when they ran in it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub isolate: Option<Isolation>,
+    /// Invocations of the suite combined into this report, under `--repeat`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<u32>,
+    /// How the large working sets were asked to be backed, under `--pages`.
+    /// Absent when the kernel's own huge-page mode decided.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<PagePolicy>,
+}
+
+/// An adaptive-sampling target, as recorded in the result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PrecisionSettings {
+    /// Target relative ci95, as a fraction of the median.
+    pub target_ci: f64,
+    /// Most measured rounds any pass was allowed.
+    pub max_samples: u32,
+    /// Longest any pass was allowed to run, in milliseconds.
+    pub max_time_ms: u64,
+}
+
+impl From<Precision> for PrecisionSettings {
+    fn from(p: Precision) -> Self {
+        PrecisionSettings {
+            target_ci: p.target_ci,
+            max_samples: p.max_samples,
+            max_time_ms: p.max_time.as_millis() as u64,
+        }
+    }
+}
+
+/// A `--require-idle` threshold, as recorded in the result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct IdleSettings {
+    /// Most busy-plus-stolen CPU time a pass could start under, as a fraction.
+    pub max_busy: f64,
+    /// Longest the run would wait for the machine to settle, in milliseconds.
+    pub wait_ms: u64,
+}
+
+impl From<RequireIdle> for IdleSettings {
+    fn from(r: RequireIdle) -> Self {
+        IdleSettings {
+            max_busy: r.max_busy,
+            wait_ms: r.wait.as_millis() as u64,
+        }
+    }
 }
 
 /// One workload's results across both passes.
@@ -67,6 +154,10 @@ pub struct WorkloadReport {
     pub unit: Unit,
     /// Reference value used for scoring.
     pub reference: f64,
+    /// Every parameter the workload declares, at the value it ran with. Only
+    /// a workload at every default is scored; see [`WorkloadReport::is_canonical`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<ParamSetting>,
     /// Single-thread pass.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub single_thread: Option<Pass>,
@@ -76,15 +167,88 @@ pub struct WorkloadReport {
     /// Speedup and efficiency, when both passes ran.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub scaling: Option<ScalingReport>,
+    /// Speedup and efficiency at every thread count of a `--scaling` run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scaling_curve: Option<ScalingCurve>,
+    /// Single-thread passes pinned to each class of core on a heterogeneous
+    /// CPU, fastest class first. Empty unless requested.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub core_classes: Vec<CoreClassPass>,
+    /// Unscored companion kernels measured at the same thread counts, such as
+    /// STREAM's Copy, Scale and Add beside the scored Triad. See
+    /// [`Kernel::sub_results`](crate::Kernel::sub_results).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sub_results: Vec<SubResult>,
     /// Set when this workload is excluded from the multi-core score, with the
     /// reason. See [`Scaling::SingleThreadOnly`].
     #[serde(skip_serializing_if = "Option::is_none")]
     pub excluded_from_multi_core: Option<String>,
-    /// Why this workload produced no result, if it failed.
+    /// Why this workload produced no result, if it failed. Begins with
+    /// [`INTERRUPTED`] when the run was stopped by a signal during it.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 }
 
+/// Prefix of [`WorkloadReport::error`] on the workload a signal interrupted.
+///
+/// An interrupted run still writes the workloads that completed, and marks the
+/// one in flight with this so that a reader can tell a deliberately partial
+/// document from one that lost workloads to failures.
+pub const INTERRUPTED: &str = "interrupted";
+
+impl WorkloadReport {
+    /// Whether the run was interrupted while this workload was in flight.
+    pub fn interrupted(&self) -> bool {
+        self.error
+            .as_deref()
+            .is_some_and(|e| e.starts_with(INTERRUPTED))
+    }
+
+    /// Whether every parameter ran at its default, so that the results
+    /// describe the problem the reference does and may be scored. Reports
+    /// written before workloads had parameters have none, and are.
+    pub fn is_canonical(&self) -> bool {
+        self.params.iter().all(ParamSetting::is_default)
+    }
+
+    /// The parameters set away from their defaults, as `name=value`.
+    pub fn tuned(&self) -> Vec<String> {
+        self.params
+            .iter()
+            .filter(|p| !p.is_default())
+            .map(|p| format!("{}={}", p.name, p.value))
+            .collect()
+    }
+}
+
+/// A workload parameter as a run used it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ParamSetting {
+    /// Name, as `--workload` takes it.
+    pub name: String,
+    /// The value the workload ran with.
+    pub value: u64,
+    /// The canonical value it is scored at.
+    pub default: u64,
+}
+
+impl ParamSetting {
+    /// Whether this ran at its default.
+    pub fn is_default(&self) -> bool {
+        self.value == self.default
+    }
+}
+
+impl From<Param> for ParamSetting {
+    fn from(p: Param) -> Self {
+        ParamSetting {
+            name: p.name.to_string(),
+            value: p.value,
+            default: p.default,
+        }
+    }
+}
+
 /// One measurement pass at a fixed thread count.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Pass {
@@ -107,6 +271,42 @@ pub struct Pass {
     /// every report the tool wrote would fail to parse.
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub window_too_short: bool,
+    /// The CPU each thread was bound to, in thread order. Empty when the run
+    /// was not pinned.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_cpus: Vec<usize>,
+    /// The CPU each thread was observed on at the end of its last round, in
+    /// thread order. Empty where the platform cannot say.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cpus: Vec<usize>,
+    /// How evenly the threads finished each round. Absent for one thread.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<ThreadBalance>,
+    /// The core clock the pass ran at, and the headline figure normalised by
+    /// it. Absent where the clock cannot be estimated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock: Option<ClockReport>,
+    /// Hardware counter figures over the measured rounds. Absent unless
+    /// counters were requested and available.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counters: Option<CounterReport>,
+    /// Why the pass has no counter figures although counters were enabled for
+    /// the run: a worker's group would not open, or could not be read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counters_error: Option<String>,
+    /// Whether the pass reached its adaptive-sampling target, and what ended
+    /// it. Absent for a fixed round count.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub precision: Option<PrecisionReport>,
+    /// The process's huge-page footprint once the pass's workers had set up,
+    /// which for a large working set says which pages it actually ran on.
+    /// Absent where the platform cannot say.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub huge_pages: Option<HugePages>,
+    /// How the pass varied across the invocations of a `--repeat` run, whose
+    /// rounds `samples` pools. Absent for a single invocation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeats: Option<Repeats>,
 }
 
 impl Pass {
@@ -120,8 +320,107 @@ impl Pass {
             stats: m.summary.clone(),
             window_ms: m.window_ms,
             window_too_short: m.window_too_short,
+            pinned_cpus: m.pinned.clone(),
+            cpus: m.cpus.clone(),
+            balance: m.balance.clone(),
+            clock: ClockReport::new(m),
+            counters: m.counters.clone(),
+            counters_error: m.counters_error.clone(),
+            precision: m.precision.clone(),
+            huge_pages: m.huge_pages,
+            repeats: None,
         }
     }
+
+    /// Half-width of the 95% confidence interval on `value`: across
+    /// invocations for a repeated pass, otherwise within its one.
+    pub fn ci95(&self) -> f64 {
+        self.repeats.as_ref().map_or(self.stats.ci95, |r| r.ci95)
+    }
+}
+
+/// One pass across repeated invocations of the suite. See [`crate::repeat`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Repeats {
+    /// Each invocation's median, in invocation order.
+    pub medians: Vec<f64>,
+    /// Typical spread inside one invocation: the root mean square of their
+    /// coefficients of variation.
+    pub within_cv: f64,
+    /// Spread between invocations: the standard deviation of `medians` over
+    /// their mean.
+    pub between_cv: f64,
+    /// Half-width of the 95% confidence interval on the pass's value, with
+    /// both spreads counted, in its unit.
+    pub ci95: f64,
+}
+
+/// What the core clock was doing during a pass.
+///
+/// A faster result on a chip that boosted higher is not the same finding as a
+/// faster result at the same clock. Dividing by the clock separates the two.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClockReport {
+    /// Median core clock across measured rounds, in GHz.
+    pub ghz: f64,
+    /// Lowest per-round estimate, in GHz.
+    pub min_ghz: f64,
+    /// Highest per-round estimate, in GHz.
+    pub max_ghz: f64,
+    /// Core cycles each thread spent per work unit.
+    pub cycles_per_iter: f64,
+    /// The headline value normalised by the clock, per thread, in
+    /// `per_clock_unit`.
+    pub per_clock: f64,
+    /// Unit of `per_clock`, such as `FLOP/cycle`, `cycles/B`, or, for
+    /// latencies, `cycles`.
+    pub per_clock_unit: String,
+}
+
+impl ClockReport {
+    /// Summarise the clock probes of a measurement. `None` when it has none.
+    pub fn new(m: &Measurement) -> Option<ClockReport> {
+        let hz = Summary::new(&m.core_hz)?.median;
+        let ghz = |hz: f64| hz / 1e9;
+        let (min, max) = m
+            .core_hz
+            .iter()
+            .fold((f64::INFINITY, 0.0f64), |(lo, hi), &x| {
+                (lo.min(x), hi.max(x))
+            });
+        Some(ClockReport {
+            ghz: ghz(hz),
+            min_ghz: ghz(min),
+            max_ghz: ghz(max),
+            cycles_per_iter: hz * m.window_ms / 1e3 / m.iters_per_thread.max(1) as f64,
+            per_clock: m.unit.per_clock(m.value(), m.threads, hz),
+            per_clock_unit: m.unit.per_clock_label().to_string(),
+        })
+    }
+}
+
+/// A single-thread pass confined to one class of core.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CoreClassPass {
+    /// Which class of core the thread was pinned to.
+    pub class: CoreClass,
+    /// The measurement. Its `pinned_cpus` names the core used.
+    pub pass: Pass,
+}
+
+/// An unscored companion measurement, in its workload's unit.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SubResult {
+    /// Stable identifier, e.g. `"stream-copy"`.
+    pub id: String,
+    /// Display name.
+    pub name: String,
+    /// Single-thread pass, when the workload's ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub single_thread: Option<Pass>,
+    /// Multi-thread pass, when the workload's ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multi_thread: Option<Pass>,
 }
 
 /// How well a workload used additional threads.
@@ -161,6 +460,104 @@ impl ScalingReport {
     }
 }
 
+/// Efficiency below which a scaling curve is said to have reached its knee.
+///
+/// 70% is where adding threads has stopped paying for itself on most
+/// workloads: each extra thread now buys well under a core's worth of work,
+/// usually because a shared resource — memory bandwidth, a shared cache, SMT
+/// siblings splitting one core — has saturated.
+pub const KNEE_EFFICIENCY: f64 = 0.7;
+
+/// Throughput at several thread counts, and what the shape says.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScalingCurve {
+    /// One point per measured thread count, ascending. The first is always
+    /// the one-thread baseline.
+    pub points: Vec<ScalingPoint>,
+    /// Serial fraction fitted to Amdahl's law, `S(n) = 1 / (s + (1 - s) / n)`,
+    /// by least squares, clamped to `[0, 1]`.
+    ///
+    /// A model, not a measurement. It describes how the speedup *bends*, and
+    /// that bend is as often a saturated shared resource as genuinely serial
+    /// code. Absent when fewer than two thread counts were measured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_fraction: Option<f64>,
+    /// The smallest thread count whose efficiency fell below
+    /// `knee_efficiency`. Absent when every point stayed above it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub knee_threads: Option<usize>,
+    /// The efficiency threshold `knee_threads` was judged against.
+    pub knee_efficiency: f64,
+}
+
+/// One thread count on a [`ScalingCurve`].
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ScalingPoint {
+    /// Threads used.
+    pub threads: usize,
+    /// The full measurement at this thread count.
+    pub pass: Pass,
+    /// Value relative to the one-thread point, direction-corrected.
+    pub speedup: f64,
+    /// `speedup / threads`.
+    pub efficiency: f64,
+}
+
+impl ScalingCurve {
+    /// Build a curve from passes at distinct thread counts.
+    ///
+    /// Returns `None` without a one-thread pass, since every speedup is
+    /// relative to it.
+    pub fn compute(mut passes: Vec<Pass>, unit: Unit) -> Option<ScalingCurve> {
+        passes.sort_by_key(|p| p.threads);
+        let base = passes.iter().find(|p| p.threads == 1)?.clone();
+
+        let points: Vec<ScalingPoint> = passes
+            .into_iter()
+            .filter_map(|pass| {
+                let s = ScalingReport::compute(&base, &pass, unit)?;
+                Some(ScalingPoint {
+                    threads: pass.threads,
+                    pass,
+                    speedup: s.speedup,
+                    efficiency: s.efficiency,
+                })
+            })
+            .collect();
+
+        let knee_threads = points
+            .iter()
+            .find(|p| p.efficiency < KNEE_EFFICIENCY)
+            .map(|p| p.threads);
+        Some(ScalingCurve {
+            serial_fraction: amdahl_serial_fraction(&points),
+            knee_threads,
+            knee_efficiency: KNEE_EFFICIENCY,
+            points,
+        })
+    }
+}
+
+/// Least-squares serial fraction for Amdahl's law.
+///
+/// Rearranged, Amdahl says `1/S - 1/n = s · (1 - 1/n)`: a line through the
+/// origin with slope `s`, so the fit is closed-form. The one-thread point sits
+/// at the origin and carries no information.
+fn amdahl_serial_fraction(points: &[ScalingPoint]) -> Option<f64> {
+    let (mut sxy, mut sxx) = (0.0, 0.0);
+    for p in points.iter().filter(|p| p.threads > 1 && p.speedup > 0.0) {
+        let n = p.threads as f64;
+        let x = 1.0 - 1.0 / n;
+        let y = 1.0 / p.speedup - 1.0 / n;
+        sxy += x * y;
+        sxx += x * x;
+    }
+    if sxx == 0.0 {
+        return None;
+    }
+    Some((sxy / sxx).clamp(0.0, 1.0))
+}
+
 /// A detached Ed25519 signature over a report's canonical form.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Signature {
@@ -178,13 +575,19 @@ pub struct Signature {
 }
 
 impl Report {
+    /// Whether the run was interrupted, so that the document covers only the
+    /// workloads that completed before the signal.
+    pub fn is_partial(&self) -> bool {
+        self.workloads.iter().any(WorkloadReport::interrupted)
+    }
+
     /// Serialise this report to canonical bytes for signing or verification.
     ///
     /// # Why this goes through JSON text rather than straight to a `Value`
     ///
-    /// `serde_json`'s float parser is not correctly rounded. It writes the
-    /// shortest round-tripping representation, but reading that text back can
-    /// land on the adjacent double:
+    /// `serde_json`'s default float parser is not correctly rounded. It writes
+    /// the shortest round-tripping representation, but reading that text back
+    /// can land on the adjacent double:
     ///
     /// ```text
     /// 99.0 * 0.8 + 99.5 * 0.2  ->  bits 4058c66666666667
@@ -198,20 +601,28 @@ impl Report {
     /// disk, and every signature fails. Statistics like `p05` land on values of
     /// exactly this kind routinely.
     ///
-    /// Round-tripping through text first puts both sides on the same footing:
-    /// the signer canonicalises the values a reader will actually see. One trip
-    /// is enough — parsing the shortest representation of an already-parsed
-    /// value is a fixed point.
+    /// Nor is that a fixed point: a value read back one ULP off can be read
+    /// back another ULP off the next time, so no number of trips settles it.
+    /// The workspace therefore builds `serde_json` with `float_roundtrip`,
+    /// whose parser is exact. Round-tripping through text first still puts
+    /// both sides on the same footing — the signer canonicalises the values a
+    /// reader will actually see — whatever else changes how a number is read.
     pub fn signing_bytes(&self) -> Result<Vec<u8>, serde_json::Error> {
-        let text = serde_json::to_string(self)?;
-        let mut value: serde_json::Value = serde_json::from_str(&text)?;
-        if let Some(map) = value.as_object_mut() {
-            map.remove("signature");
-        }
-        Ok(canonical_json(&value).into_bytes())
+        signing_bytes(self)
     }
 }
 
+/// The canonical bytes of any signed document: its JSON after one round trip
+/// through text, without its `signature` field. See [`Report::signing_bytes`].
+pub(crate) fn signing_bytes(document: &impl Serialize) -> Result<Vec<u8>, serde_json::Error> {
+    let text = serde_json::to_string(document)?;
+    let mut value: serde_json::Value = serde_json::from_str(&text)?;
+    if let Some(map) = value.as_object_mut() {
+        map.remove("signature");
+    }
+    Ok(canonical_json(&value).into_bytes())
+}
+
 /// Render a JSON value in canonical form: sorted keys, no insignificant
 /// whitespace.
 ///
@@ -292,9 +703,13 @@ pub fn workload_report(
         summary: info.summary.to_string(),
         unit: info.unit,
         reference: info.reference,
+        params: Vec::new(),
         single_thread: single,
         multi_thread: multi,
         scaling,
+        scaling_curve: None,
+        core_classes: Vec::new(),
+        sub_results: Vec::new(),
         excluded_from_multi_core: excluded,
         error,
     }
@@ -408,9 +823,52 @@ mod tests {
             stats: Summary::new(&[value]).unwrap(),
             window_ms: 250.0,
             window_too_short: false,
+            pinned_cpus: vec![],
+            cpus: vec![],
+            balance: None,
+            clock: None,
+            counters: None,
+            counters_error: None,
+            precision: None,
+            huge_pages: None,
+            repeats: None,
         }
     }
 
+    #[test]
+    fn a_tuned_workload_is_recorded_but_never_scored() {
+        let info = KernelInfo {
+            id: "sgemm",
+            name: "SGEMM",
+            summary: "test",
+            unit: Unit::Gflops,
+            footprint: crate::kernel::Footprint::PerThread,
+            scaling: Scaling::Scales,
+            reference: 12.0,
+        };
+        let at = |value: u64| {
+            let mut w = workload_report(&info, Some(pass(24.0, 1)), Some(pass(96.0, 4)), None);
+            w.params = vec![ParamSetting {
+                name: "n".into(),
+                value,
+                default: 256,
+            }];
+            w
+        };
+        let canonical = at(256);
+        assert!(canonical.is_canonical() && canonical.tuned().is_empty());
+        let card = ScoreCard::from_workloads(&[canonical]);
+        assert_eq!(card.single_core_components.len(), 1);
+        assert!((card.single_core.unwrap() - 2000.0).abs() < 1e-9);
+
+        let tuned = at(1024);
+        assert!(!tuned.is_canonical());
+        assert_eq!(tuned.tuned(), ["n=1024"]);
+        let card = ScoreCard::from_workloads(&[tuned]);
+        assert!(card.single_core_components.is_empty() && card.multi_core_components.is_empty());
+        assert!(card.single_core.is_none() && card.multi_core.is_none());
+    }
+
     #[test]
     fn throughput_scaling_uses_the_ratio_directly() {
         let s = ScalingReport::compute(&pass(10.0, 1), &pass(80.0, 8), Unit::Gflops).unwrap();
@@ -425,6 +883,68 @@ mod tests {
         assert!((s.speedup - 0.5).abs() < 1e-12);
     }
 
+    #[test]
+    fn the_clock_report_normalises_by_the_median_probe() {
+        let mut m = Measurement {
+            id: "sgemm",
+            unit: Unit::Gflops,
+            threads: 2,
+            iters_per_thread: 1_000,
+            samples: vec![64.0],
+            summary: Summary::new(&[64.0]).unwrap(),
+            window_ms: 100.0,
+            window_too_short: false,
+            pinned: vec![],
+            cpus: vec![],
+            balance: None,
+            core_hz: vec![],
+            counters: None,
+            counters_error: None,
+            precision: None,
+            huge_pages: None,
+        };
+        assert!(ClockReport::new(&m).is_none(), "no probes, no clock");
+
+        m.core_hz = vec![3.9e9, 4.0e9, 4.1e9];
+        let clock = ClockReport::new(&m).unwrap();
+        assert!((clock.ghz - 4.0).abs() < 1e-12);
+        assert_eq!((clock.min_ghz, clock.max_ghz), (3.9, 4.1));
+        // 4e9 cycles/s × 0.1 s / 1000 units.
+        assert!((clock.cycles_per_iter - 400_000.0).abs() < 1e-6);
+        // 64 GFLOP/s across 2 threads at 4 GHz.
+        assert!((clock.per_clock - 8.0).abs() < 1e-12);
+        assert_eq!(clock.per_clock_unit, "FLOP/cycle");
+    }
+
+    #[test]
+    fn a_perfectly_parallel_curve_has_no_serial_fraction_and_no_knee() {
+        let passes = [1, 2, 4, 8].map(|n| pass(10.0 * n as f64, n)).to_vec();
+        let curve = ScalingCurve::compute(passes, Unit::Gflops).unwrap();
+        assert_eq!(curve.points.len(), 4);
+        assert!(curve.serial_fraction.unwrap() < 1e-12);
+        assert_eq!(curve.knee_threads, None);
+    }
+
+    #[test]
+    fn amdahl_recovers_a_known_serial_fraction() {
+        // 10% serial: S(n) = 1 / (0.1 + 0.9 / n).
+        let passes = [4, 1, 16, 2, 8]
+            .map(|n| pass(10.0 / (0.1 + 0.9 / n as f64), n))
+            .to_vec();
+        let curve = ScalingCurve::compute(passes, Unit::Gflops).unwrap();
+        let threads: Vec<usize> = curve.points.iter().map(|p| p.threads).collect();
+        assert_eq!(threads, vec![1, 2, 4, 8, 16], "points must be sorted");
+        assert!((curve.serial_fraction.unwrap() - 0.1).abs() < 1e-9);
+        // Efficiency at 4 threads is 3.08 / 4 = 0.77; at 8 it is 0.59.
+        assert_eq!(curve.knee_threads, Some(8));
+    }
+
+    #[test]
+    fn a_curve_needs_a_one_thread_baseline() {
+        let passes = vec![pass(20.0, 2), pass(40.0, 4)];
+        assert!(ScalingCurve::compute(passes, Unit::Gflops).is_none());
+    }
+
     #[test]
     fn scaling_is_undefined_for_a_zero_baseline() {
         assert!(ScalingReport::compute(&pass(0.0, 1), &pass(8.0, 8), Unit::Gflops).is_none());
@@ -432,9 +952,9 @@ mod tests {
 
     #[test]
     fn canonical_bytes_survive_a_json_round_trip() {
-        // The regression this guards is subtle and total: `serde_json` writes
-        // 99.10000000000001 and reads it back as the adjacent double, so
-        // canonicalising an in-memory struct and canonicalising the same
+        // The regression this guards is subtle and total: `serde_json`'s
+        // default parser reads 99.10000000000001 back as the adjacent double,
+        // so canonicalising an in-memory struct and canonicalising the same
         // document after a save/load produce different bytes — and every
         // signature ever written fails to verify.
         //
@@ -455,13 +975,17 @@ mod tests {
         let original = Holder { p05: awkward };
         let text = serde_json::to_string(&original).unwrap();
         let reloaded: Holder = serde_json::from_str(&text).unwrap();
-        assert_ne!(
+        assert_eq!(
             reloaded.p05.to_bits(),
             original.p05.to_bits(),
-            "premise: serde_json must actually lose this value"
+            "serde_json must be built with float_roundtrip, or it loses this value"
         );
 
-        // Canonicalising via a text round trip must nonetheless agree.
+        // Canonicalising via a text round trip must agree, and go on agreeing
+        // after any number of further saves and loads.
+        let resaved: Holder =
+            serde_json::from_str(&serde_json::to_string(&reloaded).unwrap()).unwrap();
+        assert_eq!(resaved.p05.to_bits(), original.p05.to_bits());
         let canonical = |h: &Holder| {
             let text = serde_json::to_string(h).unwrap();
             let value: serde_json::Value = serde_json::from_str(&text).unwrap();
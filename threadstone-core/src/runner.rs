@@ -28,13 +28,30 @@
 //! is discovered at run time, and — critically — discovered *with all threads
 //! running*, because a count calibrated on an idle machine will overshoot
 //! wildly once memory bandwidth is contended.
+//!
+//! # How many rounds
+//!
+//! The same argument applies to the round count. A fixed seven rounds wastes
+//! time on a quiet machine and stops short on a noisy one, leaving a confidence
+//! interval too wide for `compare` to call anything. With a [`Precision`]
+//! target the runner treats `samples` as a minimum and keeps measuring until
+//! the relative ci95 is narrow enough or a round or time budget runs out, and
+//! records which of those ended the pass.
 
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::Barrier;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Barrier, Mutex};
 use std::time::{Duration, Instant};
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::affinity::{self, Affinity};
+use crate::counters::{self, CounterReport};
 use crate::kernel::{Kernel, SetupCtx, Unit};
-use crate::stats::Summary;
+use crate::numa;
+use crate::pages::{self, HugePages, PagePolicy};
+use crate::stats::{Summary, ThreadBalance};
+use crate::time;
 
 /// Runner defaults, chosen to be trustworthy rather than fast.
 pub mod defaults {
@@ -62,19 +79,81 @@ pub mod defaults {
     /// Seven is the smallest odd count that gives the MAD outlier filter enough
     /// points to work with while keeping total suite time reasonable.
     pub const SAMPLES: u32 = 7;
+
+    /// Round budget for an adaptively sampled pass.
+    ///
+    /// At the default window this is about twelve seconds of measurement, and
+    /// if fifty rounds have not pinned the median down the machine is too
+    /// noisy for more rounds to help.
+    pub const MAX_SAMPLES: u32 = 50;
+
+    /// Time budget for an adaptively sampled pass, calibration included.
+    pub const MAX_TIME: Duration = Duration::from_secs(30);
 }
 
 /// How a run should be executed.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct RunConfig {
     /// Number of OS threads. Must be at least 1.
     pub threads: usize,
-    /// Measured rounds to collect.
+    /// Measured rounds to collect; the minimum when `precision` is set, though
+    /// never below two.
     pub samples: u32,
     /// Rounds to discard before measuring.
     pub warmup: u32,
     /// Duration each round should aim for.
     pub window: Duration,
+    /// Where to bind worker threads. `None` leaves placement to the scheduler.
+    pub affinity: Option<Affinity>,
+    /// Whether to count hardware events across the measured rounds.
+    pub counters: bool,
+    /// Keep measuring past `samples` until this precision is reached. `None`
+    /// collects exactly `samples` rounds.
+    pub precision: Option<Precision>,
+    /// How kernels back their large buffers.
+    pub pages: PagePolicy,
+    /// Bind every worker's memory to this NUMA node. `None` leaves placement
+    /// to first touch.
+    pub memory_node: Option<usize>,
+}
+
+/// An adaptive-sampling target: measure until the result is this precise, or
+/// a budget runs out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Precision {
+    /// Target half-width of the 95% confidence interval, as a fraction of the
+    /// median (0.005 = ±0.5%).
+    pub target_ci: f64,
+    /// Stop after this many measured rounds even if the target is unmet.
+    pub max_samples: u32,
+    /// Stop once the pass has run this long, calibration and warmup included,
+    /// even if the target is unmet.
+    pub max_time: Duration,
+}
+
+/// What ended an adaptively sampled pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Stop {
+    /// The confidence interval reached the target.
+    Target,
+    /// The round budget ran out first.
+    MaxSamples,
+    /// The time budget ran out first.
+    MaxTime,
+}
+
+/// How an adaptively sampled pass ended.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PrecisionReport {
+    /// The requested relative ci95, as a fraction of the median.
+    pub target_ci: f64,
+    /// The relative ci95 the pass ended with.
+    pub achieved_ci: f64,
+    /// Whether `achieved_ci` is within `target_ci`.
+    pub met: bool,
+    /// What ended the pass.
+    pub stopped: Stop,
 }
 
 impl Default for RunConfig {
@@ -84,6 +163,11 @@ impl Default for RunConfig {
             samples: defaults::SAMPLES,
             warmup: defaults::WARMUP,
             window: defaults::WINDOW,
+            affinity: None,
+            counters: false,
+            precision: None,
+            pages: PagePolicy::Default,
+            memory_node: None,
         }
     }
 }
@@ -123,6 +207,28 @@ pub struct Measurement {
     /// Set when the calibrated window stayed under [`defaults::MIN_WINDOW`],
     /// meaning clock granularity is a material part of the reading.
     pub window_too_short: bool,
+    /// The CPU each worker was bound to, in worker order. Empty when the run
+    /// was not pinned.
+    pub pinned: Vec<usize>,
+    /// The CPU each worker was observed on at the end of its last round, in
+    /// worker order. Empty where the platform cannot say.
+    pub cpus: Vec<usize>,
+    /// How evenly the workers finished each measured round. `None` for a
+    /// single thread.
+    pub balance: Option<ThreadBalance>,
+    /// Core clock estimate taken by the first worker after each measured
+    /// round, in hertz. Empty where [`time::core_clock_hz`] is unsupported.
+    pub core_hz: Vec<f64>,
+    /// Hardware counter totals over the measured rounds. `None` when counters
+    /// were not requested, or any worker could not open or read its group.
+    pub counters: Option<CounterReport>,
+    /// Why `counters` is `None` although counters were requested.
+    pub counters_error: Option<String>,
+    /// How adaptive sampling ended. `None` for a fixed round count.
+    pub precision: Option<PrecisionReport>,
+    /// The process's huge-page footprint once every worker was set up.
+    /// `None` where the platform cannot say.
+    pub huge_pages: Option<HugePages>,
 }
 
 impl Measurement {
@@ -151,6 +257,29 @@ pub enum RunError {
         /// Identifier of the kernel that failed.
         id: &'static str,
     },
+    /// The affinity plan could not be resolved or applied. A pinned run that
+    /// silently ran unpinned would record a placement that never happened.
+    AffinityFailed {
+        /// Identifier of the kernel that failed.
+        id: &'static str,
+        /// What went wrong.
+        reason: String,
+    },
+    /// The observer asked the run to stop before it had its samples. See
+    /// [`Observer::interrupted`].
+    Interrupted {
+        /// Identifier of the kernel that was interrupted.
+        id: &'static str,
+    },
+    /// The process measuring the pass on the suite's behalf failed: it
+    /// crashed, was killed, or reported an error. See
+    /// [`suite::Executor`](crate::suite::Executor).
+    Isolated {
+        /// Identifier of the kernel whose pass was lost.
+        id: &'static str,
+        /// What happened to the process.
+        reason: String,
+    },
 }
 
 impl std::fmt::Display for RunError {
@@ -166,6 +295,13 @@ impl std::fmt::Display for RunError {
             RunError::NoValidSamples { id } => {
                 write!(f, "workload '{id}': every sample was non-finite")
             }
+            RunError::AffinityFailed { id, reason } => {
+                write!(f, "workload '{id}': cannot pin threads: {reason}")
+            }
+            RunError::Interrupted { id } => {
+                write!(f, "workload '{id}': interrupted before the pass completed")
+            }
+            RunError::Isolated { id, reason } => write!(f, "workload '{id}': {reason}"),
         }
     }
 }
@@ -183,7 +319,8 @@ pub trait Observer: Sync {
     fn calibrated(&self, id: &str, iters: u64, window_ms: f64) {
         let _ = (id, iters, window_ms);
     }
-    /// Round `index` of `total` completed, yielding `rate`.
+    /// Round `index` of `total` completed, yielding `rate`. Under adaptive
+    /// sampling `total` is the minimum until `index` passes it.
     fn sample(&self, id: &str, index: u32, total: u32, rate: f64) {
         let _ = (id, index, total, rate);
     }
@@ -191,6 +328,11 @@ pub trait Observer: Sync {
     fn finished(&self, id: &str, measurement: &Measurement) {
         let _ = (id, measurement);
     }
+    /// Whether the run should stop. Polled between rounds, so a pass ends at
+    /// its next barrier with [`RunError::Interrupted`] rather than mid-window.
+    fn interrupted(&self) -> bool {
+        false
+    }
 }
 
 /// An [`Observer`] that reports nothing.
@@ -203,6 +345,9 @@ struct Control {
     iters: AtomicU64,
     /// Set once to tell workers to exit instead of running another round.
     stop: AtomicBool,
+    /// Set while the next round is a measured one, so the first worker knows
+    /// to follow it with a clock probe.
+    measuring: AtomicBool,
     /// Accumulates every worker's checksum so the optimiser cannot prove the
     /// results unused across the whole program.
     checksum: AtomicU64,
@@ -228,34 +373,109 @@ pub fn run<O: Observer + ?Sized>(
         return Err(RunError::ZeroSamples);
     }
 
+    let started = Instant::now();
     let info = kernel.info();
     let threads = cfg.threads;
-    let total_rounds = cfg.warmup + cfg.samples;
+    // One round has a zero-width interval, which would meet any target, so
+    // adaptive sampling always takes at least two.
+    let min_samples = if cfg.precision.is_some() {
+        cfg.samples.max(2)
+    } else {
+        cfg.samples
+    };
+    let max_samples = cfg
+        .precision
+        .map_or(min_samples, |p| p.max_samples.max(min_samples));
+
+    // Resolved up front so that a bad plan fails before any thread exists.
+    let pinned = match &cfg.affinity {
+        Some(plan) => plan
+            .resolve(threads)
+            .map_err(|reason| RunError::AffinityFailed {
+                id: info.id,
+                reason,
+            })?,
+        None => Vec::new(),
+    };
+    let bind_failure: Mutex<Option<String>> = Mutex::new(None);
+    let observed: Vec<AtomicUsize> = (0..threads).map(|_| AtomicUsize::new(usize::MAX)).collect();
+    // Each worker's own duration for the latest round, in nanoseconds.
+    let worker_nanos: Vec<AtomicU64> = (0..threads).map(|_| AtomicU64::new(0)).collect();
+    let core_hz: Mutex<Vec<f64>> = Mutex::new(Vec::with_capacity(cfg.samples as usize));
+    // One entry per worker whose counter group opened and was read.
+    let counts: Mutex<Vec<counters::Counts>> = Mutex::new(Vec::with_capacity(threads));
+    // The first reason a worker's group could not be opened or read.
+    let counter_failure: Mutex<Option<String>> = Mutex::new(None);
 
     let control = Control {
         iters: AtomicU64::new(1),
         stop: AtomicBool::new(false),
+        measuring: AtomicBool::new(false),
         checksum: AtomicU64::new(0),
     };
     // `threads` workers plus the coordinating main thread.
     let gate = Barrier::new(threads + 1);
 
     let mut calibrated_iters = 1u64;
-    let mut window_samples: Vec<f64> = Vec::with_capacity(total_rounds as usize);
+    let mut window_samples: Vec<f64> = Vec::with_capacity((cfg.warmup + cfg.samples) as usize);
     let mut rates: Vec<f64> = Vec::with_capacity(cfg.samples as usize);
+    let mut thread_rounds: Vec<Vec<f64>> = Vec::with_capacity(cfg.samples as usize);
     let mut calibration_failed = false;
+    let mut interrupted = false;
+    let mut bind_failed = false;
+    let mut stopped = Stop::MaxSamples;
+    let mut huge_pages = None;
 
     std::thread::scope(|scope| {
         for thread_index in 0..threads {
             let control = &control;
             let gate = &gate;
+            let pinned = &pinned;
+            let bind_failure = &bind_failure;
+            let observed = &observed[thread_index];
+            let elapsed = &worker_nanos[thread_index];
+            let core_hz = &core_hz;
+            let counts = &counts;
+            let counter_failure = &counter_failure;
+            let want_counters = cfg.counters;
+            let pages = cfg.pages;
+            let memory_node = cfg.memory_node;
             scope.spawn(move || {
+                // Bind before setup, so the working set is first-touched from
+                // the CPU that will use it and lands on that CPU's memory node,
+                // or on the node named for it.
+                if let Some(&cpu) = pinned.get(thread_index) {
+                    if let Err(e) = affinity::bind_current_thread(cpu) {
+                        bind_failure.lock().unwrap().get_or_insert(e);
+                    }
+                }
+                if let Some(node) = memory_node {
+                    if let Err(e) = numa::bind_memory(node) {
+                        bind_failure.lock().unwrap().get_or_insert(e);
+                    }
+                }
+
                 // Allocation and first-touch happen here, outside every window.
                 let ctx = SetupCtx {
                     threads,
                     thread_index,
+                    pages,
                 };
                 let mut state = kernel.setup(&ctx);
+                // Opened per worker, since a group counts only its own thread.
+                // A failure leaves this worker's entry missing from `counts`,
+                // and its reason in `counter_failure`.
+                let group = match want_counters.then(counters::Group::open) {
+                    Some(Ok(group)) => Some(group),
+                    Some(Err(e)) => {
+                        counter_failure.lock().unwrap().get_or_insert(e);
+                        None
+                    }
+                    None => None,
+                };
+
+                // Ready: every worker is bound and set up.
+                gate.wait();
 
                 loop {
                     // Round start: release together with every other worker.
@@ -264,18 +484,62 @@ pub fn run<O: Observer + ?Sized>(
                         break;
                     }
                     let iters = control.iters.load(Ordering::Acquire);
+                    let measuring = control.measuring.load(Ordering::Acquire);
+                    let started = time::now_nanos();
+                    let group = group.as_ref().filter(|_| measuring);
+                    if let Some(g) = group {
+                        g.enable();
+                    }
                     let sum = state.run(iters);
+                    if let Some(g) = group {
+                        g.disable();
+                    }
+                    // This worker's own finish, so a round's straggler can be
+                    // told apart from a uniformly slow round.
+                    elapsed.store(time::now_nanos() - started, Ordering::Relaxed);
                     // Publishing the checksum creates a program-visible data
                     // dependency on the kernel's output, which is what keeps
                     // the work from being eliminated.
                     control.checksum.fetch_xor(sum, Ordering::Relaxed);
+                    if let Some(cpu) = affinity::current_cpu() {
+                        observed.store(cpu, Ordering::Relaxed);
+                    }
                     // Round end: the main thread's timer stops when the last
                     // worker reaches here.
                     gate.wait();
+                    // Probe the clock on a core that just ran the kernel, in
+                    // the gap before the next round start so that it never
+                    // lands inside a window.
+                    if measuring && thread_index == 0 {
+                        if let Some(hz) = time::core_clock_hz() {
+                            core_hz.lock().unwrap().push(hz);
+                        }
+                    }
+                }
+
+                if let Some(group) = &group {
+                    match group.read() {
+                        Some(c) => counts.lock().unwrap().push(c),
+                        None => {
+                            counter_failure.lock().unwrap().get_or_insert(format!(
+                                "worker {thread_index} could not read its counters"
+                            ));
+                        }
+                    }
                 }
             });
         }
 
+        gate.wait();
+        // Every worker's buffers exist now, and no earlier pass's do.
+        huge_pages = pages::huge_pages();
+        if bind_failure.lock().unwrap().is_some() {
+            bind_failed = true;
+            control.stop.store(true, Ordering::Release);
+            gate.wait();
+            return;
+        }
+
         // ---- Calibration -------------------------------------------------
         obs.calibrating(info.id, threads);
         match calibrate(&control, &gate, cfg.window) {
@@ -291,7 +555,16 @@ pub fn run<O: Observer + ?Sized>(
         // ---- Warmup and measurement --------------------------------------
         control.iters.store(calibrated_iters, Ordering::Release);
 
-        for round in 0..total_rounds {
+        for round in 0.. {
+            // Checked at the top of every round, so that an interruption
+            // during calibration or the previous round ends the pass here.
+            if obs.interrupted() {
+                interrupted = true;
+                break;
+            }
+            control
+                .measuring
+                .store(round >= cfg.warmup, Ordering::Release);
             let secs = timed_round(&gate);
             if round == 0 {
                 obs.calibrated(info.id, calibrated_iters, secs * 1e3);
@@ -300,8 +573,31 @@ pub fn run<O: Observer + ?Sized>(
             if round >= cfg.warmup {
                 let rate = kernel.rate(calibrated_iters, threads, secs);
                 let index = round - cfg.warmup + 1;
-                obs.sample(info.id, index, cfg.samples, rate);
+                obs.sample(info.id, index, min_samples.max(index), rate);
                 rates.push(rate);
+                // The round-end barrier orders every worker's store before
+                // this load.
+                thread_rounds.push(
+                    worker_nanos
+                        .iter()
+                        .map(|n| n.load(Ordering::Relaxed) as f64 / 1e6)
+                        .collect(),
+                );
+
+                if index >= min_samples {
+                    let Some(p) = cfg.precision else { break };
+                    if relative_ci(&rates).is_some_and(|ci| ci <= p.target_ci) {
+                        stopped = Stop::Target;
+                        break;
+                    }
+                    if index >= max_samples {
+                        break;
+                    }
+                    if started.elapsed() >= p.max_time {
+                        stopped = Stop::MaxTime;
+                        break;
+                    }
+                }
             }
         }
 
@@ -310,12 +606,41 @@ pub fn run<O: Observer + ?Sized>(
         gate.wait();
     });
 
+    if bind_failed {
+        let reason = bind_failure.into_inner().unwrap().unwrap_or_default();
+        return Err(RunError::AffinityFailed {
+            id: info.id,
+            reason,
+        });
+    }
     if calibration_failed {
         return Err(RunError::CalibrationFailed { id: info.id });
     }
+    if interrupted {
+        return Err(RunError::Interrupted { id: info.id });
+    }
 
     let summary = Summary::new(&rates).ok_or(RunError::NoValidSamples { id: info.id })?;
+    let precision = cfg.precision.map(|p| {
+        let achieved_ci = relative_ci(&rates).unwrap_or(f64::MAX);
+        PrecisionReport {
+            target_ci: p.target_ci,
+            achieved_ci,
+            met: achieved_ci <= p.target_ci,
+            stopped,
+        }
+    });
     let window_ms = Summary::new(&window_samples).map_or(0.0, |s| s.median);
+    let counts = counts.into_inner().unwrap();
+    let counters = (counts.len() == threads)
+        .then(|| CounterReport::from_threads(&counts))
+        .flatten();
+    let counters_error = (cfg.counters && counters.is_none()).then(|| {
+        counter_failure
+            .into_inner()
+            .unwrap()
+            .unwrap_or_else(|| "a worker's counters never ran".to_string())
+    });
 
     let measurement = Measurement {
         id: info.id,
@@ -326,11 +651,31 @@ pub fn run<O: Observer + ?Sized>(
         summary,
         window_ms,
         window_too_short: window_ms < defaults::MIN_WINDOW.as_secs_f64() * 1e3,
+        pinned,
+        cpus: observed
+            .iter()
+            .map(|cpu| cpu.load(Ordering::Relaxed))
+            .map(|cpu| (cpu != usize::MAX).then_some(cpu))
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default(),
+        balance: ThreadBalance::new(&thread_rounds),
+        core_hz: core_hz.into_inner().unwrap(),
+        counters,
+        counters_error,
+        precision,
+        huge_pages,
     };
     obs.finished(info.id, &measurement);
     Ok(measurement)
 }
 
+/// Half-width of the 95% confidence interval as a fraction of the median,
+/// the figure `compare` weighs a change against.
+fn relative_ci(rates: &[f64]) -> Option<f64> {
+    let s = Summary::new(rates)?;
+    (s.n > 1 && s.median != 0.0).then(|| (s.ci95 / s.median).abs())
+}
+
 /// Execute one lockstep round and return its wall-clock duration in seconds.
 ///
 /// The timer starts the instant every worker has been released and stops when
@@ -468,6 +813,11 @@ mod tests {
             samples: 3,
             warmup: 1,
             window: Duration::from_millis(20),
+            affinity: None,
+            counters: false,
+            precision: None,
+            pages: PagePolicy::Default,
+            memory_node: None,
         }
     }
 
@@ -546,6 +896,128 @@ mod tests {
         );
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn a_pinned_run_stays_on_its_cpus() {
+        let cpu = crate::affinity::Topology::detect().unwrap().cpus[0].cpu;
+        let cfg = RunConfig {
+            affinity: Some(Affinity::Explicit(vec![cpu])),
+            ..quick(2)
+        };
+        let m = run(&Spin, cfg, &SilentObserver).unwrap();
+        assert_eq!(m.pinned, vec![cpu, cpu]);
+        assert_eq!(m.cpus, vec![cpu, cpu]);
+    }
+
+    #[test]
+    fn an_unusable_affinity_plan_fails_before_running() {
+        let cfg = RunConfig {
+            affinity: Some(Affinity::Explicit(vec![100_000])),
+            ..quick(1)
+        };
+        let err = run(&Spin, cfg, &SilentObserver).unwrap_err();
+        assert!(
+            matches!(err, RunError::AffinityFailed { id: "spin", .. }),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn multi_thread_runs_report_thread_balance() {
+        assert!(run(&Spin, quick(1), &SilentObserver)
+            .unwrap()
+            .balance
+            .is_none());
+        let m = run(&Spin, quick(3), &SilentObserver).unwrap();
+        let balance = m.balance.expect("three threads have a balance");
+        assert_eq!(balance.thread_ms.len(), 3);
+        assert!(balance.slowest_thread < 3);
+        assert!(balance.imbalance >= 1.0);
+        assert!(balance.thread_ms.iter().all(|&ms| ms > 0.0));
+    }
+
+    #[test]
+    fn measured_rounds_carry_a_clock_probe() {
+        let m = run(&Spin, quick(2), &SilentObserver).unwrap();
+        if time::core_clock_hz().is_some() {
+            assert_eq!(m.core_hz.len(), 3, "one probe per measured round");
+            assert!(m.core_hz.iter().all(|&hz| hz > 0.0));
+        } else {
+            assert!(m.core_hz.is_empty());
+        }
+    }
+
+    #[test]
+    fn counters_are_collected_only_where_they_can_be() {
+        let m = run(&Spin, quick(2), &SilentObserver).unwrap();
+        assert!(m.counters.is_none() && m.counters_error.is_none());
+        let cfg = RunConfig {
+            counters: true,
+            ..quick(2)
+        };
+        let m = run(&Spin, cfg, &SilentObserver).unwrap();
+        match counters::probe() {
+            counters::CounterStatus::Enabled { .. } => {
+                let c = m.counters.expect("counters open on this machine");
+                assert!(c.instructions > 0 && c.ipc > 0.0, "{c:?}");
+                assert!(m.counters_error.is_none());
+            }
+            counters::CounterStatus::Unavailable { reason } => {
+                // The pass says why, in the probe's own words.
+                assert!(m.counters.is_none());
+                assert_eq!(m.counters_error, Some(reason));
+            }
+        }
+    }
+
+    #[test]
+    fn adaptive_sampling_stops_once_the_target_is_met() {
+        // A target no real measurement misses: the minimum count suffices.
+        let cfg = RunConfig {
+            precision: Some(Precision {
+                target_ci: 1.0,
+                max_samples: 50,
+                max_time: Duration::from_secs(60),
+            }),
+            ..quick(1)
+        };
+        let m = run(&Spin, cfg, &SilentObserver).unwrap();
+        assert_eq!(m.samples.len(), 3, "the minimum still applies");
+        let p = m.precision.unwrap();
+        assert_eq!(p.stopped, Stop::Target);
+        assert!(p.met && p.achieved_ci <= 1.0);
+    }
+
+    #[test]
+    fn adaptive_sampling_stops_at_its_budgets_when_the_target_is_out_of_reach() {
+        let unreachable = |max_samples, max_time| RunConfig {
+            precision: Some(Precision {
+                target_ci: 0.0,
+                max_samples,
+                max_time,
+            }),
+            ..quick(1)
+        };
+        let m = run(
+            &Spin,
+            unreachable(5, Duration::from_secs(60)),
+            &SilentObserver,
+        )
+        .unwrap();
+        assert_eq!(m.samples.len(), 5);
+        let p = m.precision.unwrap();
+        assert_eq!(p.stopped, Stop::MaxSamples);
+        assert!(!p.met);
+
+        let m = run(&Spin, unreachable(1_000, Duration::ZERO), &SilentObserver).unwrap();
+        assert_eq!(
+            m.samples.len(),
+            3,
+            "an expired clock still gets the minimum"
+        );
+        assert_eq!(m.precision.unwrap().stopped, Stop::MaxTime);
+    }
+
     #[test]
     fn observer_sees_one_event_per_measured_sample() {
         struct Counting {
@@ -570,6 +1042,27 @@ mod tests {
         assert_eq!(obs.finished.load(Ordering::Relaxed), 1);
     }
 
+    #[test]
+    fn an_interrupted_pass_stops_at_the_next_round() {
+        struct StopAfter(AtomicUsize);
+        impl Observer for StopAfter {
+            fn sample(&self, _id: &str, _i: u32, _n: u32, _r: f64) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+            }
+            fn interrupted(&self) -> bool {
+                self.0.load(Ordering::Relaxed) >= 1
+            }
+        }
+        let obs = StopAfter(AtomicUsize::new(0));
+        let err = run(&Spin, quick(1), &obs).unwrap_err();
+        assert!(matches!(err, RunError::Interrupted { id: "spin" }), "{err}");
+        assert_eq!(
+            obs.0.load(Ordering::Relaxed),
+            1,
+            "no round may start after the request"
+        );
+    }
+
     #[test]
     fn empty_kernel_fails_calibration_instead_of_hanging() {
         struct Empty;
@@ -37,6 +37,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::kernel::Unit;
+use crate::report::{Pass, WorkloadReport};
 use crate::stats::geometric_mean;
 
 /// Name of the fixed reference, recorded in every result.
@@ -118,6 +119,33 @@ impl ScoreCard {
             multi_core_components: multi,
         }
     }
+
+    /// Score every pass of `workloads` that produced a usable value.
+    ///
+    /// A workload run with any parameter away from its default is left out:
+    /// its reference describes the default problem, so a ratio against it
+    /// would compare two different benchmarks.
+    pub fn from_workloads(workloads: &[WorkloadReport]) -> ScoreCard {
+        let components = |select: fn(&WorkloadReport) -> &Option<Pass>| {
+            workloads
+                .iter()
+                .filter(|w| w.is_canonical())
+                .filter_map(|w| {
+                    let measured = select(w).as_ref()?.value;
+                    Some(ScoreComponent {
+                        id: w.id.clone(),
+                        measured,
+                        reference: w.reference,
+                        ratio: ratio(measured, w.reference, w.unit)?,
+                    })
+                })
+                .collect()
+        };
+        ScoreCard::new(
+            components(|w| &w.single_thread),
+            components(|w| &w.multi_thread),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -9,6 +9,12 @@
 //! interrupts, migrations, and thermal events only ever make a sample slower —
 //! so the distribution has a hard floor and a long right tail. The mean chases
 //! that tail; the median does not.
+//!
+//! None of that notices *order*. A fanless laptop that throttles steadily
+//! through a pass produces samples that slide downhill with a small spread, so
+//! the CV looks fine while the median depends on when the pass stopped. A
+//! Mann–Kendall trend test over the samples in collection order catches that,
+//! and a significant drift costs the result a stability grade.
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -21,6 +27,19 @@ const MAD_TO_SIGMA: f64 = 1.482_602_218_505_602;
 /// Three is conventional and, for a hard-floored distribution, conservative.
 const OUTLIER_SIGMAS: f64 = 3.0;
 
+/// Two-sided p-value below which a trend is treated as real.
+const DRIFT_ALPHA: f64 = 0.05;
+
+/// ...and the change across the pass, as a fraction of the median, that makes
+/// it worth a stability grade. Matches the `Stable` CV threshold: a drift
+/// smaller than that is within what a stable result already admits.
+const DRIFT_MIN_CHANGE: f64 = 0.01;
+
+/// Fewer samples than this cannot show a significant trend at `DRIFT_ALPHA`:
+/// the most four can give, all in order, is S = 6 against a variance of 8.67,
+/// a p-value near 0.09.
+const DRIFT_MIN_SAMPLES: usize = 5;
+
 /// How much run-to-run variation a result exhibited.
 ///
 /// Thresholds are expressed on the coefficient of variation of the retained
@@ -54,6 +73,15 @@ impl Stability {
         }
     }
 
+    /// One grade worse, for a result whose spread understates its problems.
+    pub fn downgraded(self) -> Self {
+        match self {
+            Stability::Stable => Stability::Acceptable,
+            Stability::Acceptable => Stability::Noisy,
+            Stability::Noisy | Stability::Unreliable => Stability::Unreliable,
+        }
+    }
+
     /// Whether results at this stability level support drawing conclusions.
     pub fn is_trustworthy(self) -> bool {
         matches!(self, Stability::Stable | Stability::Acceptable)
@@ -101,8 +129,98 @@ pub struct Summary {
     /// Uses a normal approximation (1.96·σ/√n), which is adequate at the sample
     /// counts this suite collects and errs slightly narrow below n≈10.
     pub ci95: f64,
-    /// Verdict on whether this result is trustworthy.
+    /// Verdict on whether this result is trustworthy. One grade below what
+    /// `cv` alone gives when `drift` is significant.
     pub stability: Stability,
+    /// Trend across the samples in collection order. Absent for fewer than
+    /// five samples.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drift: Option<Drift>,
+}
+
+/// A monotonic trend across samples in collection order.
+///
+/// Tested with Mann–Kendall, which asks only whether later samples tend to be
+/// larger (or smaller) than earlier ones, so a single outlier cannot create a
+/// trend the way it can tilt a least-squares fit. The size of the trend is the
+/// Theil–Sen slope: the median of every pairwise slope.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Drift {
+    /// Mann–Kendall statistic: concordant minus discordant sample pairs.
+    pub s: i64,
+    /// Two-sided p-value of `s` under "no trend", by the normal approximation
+    /// with tie and continuity corrections.
+    pub p_value: f64,
+    /// Theil–Sen slope, in sample units per sample.
+    pub slope: f64,
+    /// Change across the whole pass implied by `slope`, as a signed fraction
+    /// of the median.
+    pub change: f64,
+    /// Set when `p_value` is below 0.05 and `change` exceeds 1% either way.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub significant: bool,
+}
+
+impl Drift {
+    /// Test `samples`, in collection order, for a monotonic trend. Returns
+    /// `None` for fewer than five samples or a zero median.
+    pub fn new(samples: &[f64]) -> Option<Drift> {
+        let n = samples.len();
+        if n < DRIFT_MIN_SAMPLES {
+            return None;
+        }
+        let median = median_of(&sorted(samples));
+        if median == 0.0 {
+            return None;
+        }
+
+        let mut s = 0i64;
+        let mut slopes = Vec::with_capacity(n * (n - 1) / 2);
+        for i in 0..n {
+            for j in i + 1..n {
+                let d = samples[j] - samples[i];
+                s += if d > 0.0 {
+                    1
+                } else if d < 0.0 {
+                    -1
+                } else {
+                    0
+                };
+                slopes.push(d / (j - i) as f64);
+            }
+        }
+
+        // Variance of S under the null, less the contribution of tied groups.
+        // `slice::chunk_by` would say this directly, but needs Rust 1.77.
+        let sorted_samples = sorted(samples);
+        let mut ties = 0.0;
+        let mut start = 0;
+        for end in 1..=n {
+            if end == n || sorted_samples[end] != sorted_samples[start] {
+                let t = (end - start) as f64;
+                ties += t * (t - 1.0) * (2.0 * t + 5.0);
+                start = end;
+            }
+        }
+        let nf = n as f64;
+        let var = (nf * (nf - 1.0) * (2.0 * nf + 5.0) - ties) / 18.0;
+        let z = if s == 0 || var <= 0.0 {
+            0.0
+        } else {
+            (s - s.signum()) as f64 / var.sqrt()
+        };
+        let p_value = erfc(z.abs() / std::f64::consts::SQRT_2).min(1.0);
+
+        let slope = median_of(&sorted(&slopes));
+        let change = slope * (nf - 1.0) / median.abs();
+        Some(Drift {
+            s,
+            p_value,
+            slope,
+            change,
+            significant: p_value < DRIFT_ALPHA && change.abs() > DRIFT_MIN_CHANGE,
+        })
+    }
 }
 
 impl Summary {
@@ -120,6 +238,7 @@ impl Summary {
             return None;
         }
 
+        let clean_in_order = clean.clone();
         let (kept, outliers) = if clean.len() < 4 {
             (clean, 0)
         } else {
@@ -163,6 +282,15 @@ impl Summary {
             0.0
         };
 
+        // Tested on every finite sample rather than the retained ones: a slide
+        // steep enough to push its ends past the outlier limit is exactly the
+        // case that must not be trimmed away before the test sees it.
+        let drift = Drift::new(&clean_in_order);
+        let mut stability = Stability::from_cv(cv);
+        if drift.as_ref().is_some_and(|d| d.significant) {
+            stability = stability.downgraded();
+        }
+
         Some(Summary {
             n,
             outliers,
@@ -179,7 +307,186 @@ impl Summary {
             } else {
                 0.0
             },
-            stability: Stability::from_cv(cv),
+            stability,
+            drift,
+        })
+    }
+}
+
+/// A thread that finished last in at least this share of rounds is a
+/// consistent straggler rather than the loser of a coin toss.
+const STRAGGLER_SHARE: f64 = 0.75;
+
+/// ...and only matters if it held the round up by at least this much, as a
+/// ratio of the slowest thread's time to the mean thread's.
+const STRAGGLER_IMBALANCE: f64 = 1.05;
+
+/// Fewer rounds than this cannot establish that one thread is consistently
+/// slow.
+const STRAGGLER_MIN_ROUNDS: usize = 3;
+
+/// How evenly the threads of a multi-thread pass finished their work.
+///
+/// A lockstep round ends when the *last* thread arrives, so one slow thread —
+/// on an efficiency core, or sharing a core with an interrupt handler — sets
+/// the whole pass's rate. These figures make that visible.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ThreadBalance {
+    /// Each thread's median completion time across rounds, in milliseconds,
+    /// in thread order.
+    pub thread_ms: Vec<f64>,
+    /// Median across rounds of the gap between the first and last thread to
+    /// finish, in milliseconds.
+    pub spread_ms: f64,
+    /// Median across rounds of the slowest thread's time over the mean
+    /// thread's. 1.0 is perfect balance.
+    pub imbalance: f64,
+    /// The thread that finished last in the most rounds.
+    pub slowest_thread: usize,
+    /// Fraction of rounds in which `slowest_thread` finished last.
+    pub slowest_share: f64,
+    /// Set when one thread was consistently last by a material margin: the
+    /// pass's rate describes that thread's core more than the machine.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub straggler: bool,
+}
+
+impl ThreadBalance {
+    /// Summarise per-round, per-thread completion times.
+    ///
+    /// `rounds[r][t]` is thread `t`'s time in round `r`, in any unit; results
+    /// are reported in that unit. Returns `None` for fewer than two threads,
+    /// where balance is meaningless, or for no rounds at all.
+    pub fn new(rounds: &[Vec<f64>]) -> Option<ThreadBalance> {
+        let threads = rounds.first()?.len();
+        if threads < 2
+            || rounds
+                .iter()
+                .any(|r| r.len() != threads || r.iter().any(|v| !v.is_finite()))
+        {
+            return None;
+        }
+
+        let thread_ms = (0..threads)
+            .map(|t| median_of(&sorted(&rounds.iter().map(|r| r[t]).collect::<Vec<_>>())))
+            .collect();
+
+        let mut last_count = vec![0usize; threads];
+        let mut spreads = Vec::with_capacity(rounds.len());
+        let mut ratios = Vec::with_capacity(rounds.len());
+        for round in rounds {
+            let (slowest, max) =
+                round
+                    .iter()
+                    .copied()
+                    .enumerate()
+                    .fold((0, f64::NEG_INFINITY), |best, (t, v)| {
+                        if v > best.1 {
+                            (t, v)
+                        } else {
+                            best
+                        }
+                    });
+            let min = round.iter().copied().fold(f64::INFINITY, f64::min);
+            let mean = round.iter().sum::<f64>() / threads as f64;
+            last_count[slowest] += 1;
+            spreads.push(max - min);
+            ratios.push(if mean > 0.0 { max / mean } else { 1.0 });
+        }
+
+        // Ties go to the lowest index, so the choice is deterministic.
+        let (slowest_thread, count) =
+            last_count
+                .iter()
+                .copied()
+                .enumerate()
+                .fold(
+                    (0, 0),
+                    |best, (t, c)| if c > best.1 { (t, c) } else { best },
+                );
+        let slowest_share = count as f64 / rounds.len() as f64;
+        let imbalance = median_of(&sorted(&ratios));
+
+        Some(ThreadBalance {
+            thread_ms,
+            spread_ms: median_of(&sorted(&spreads)),
+            imbalance,
+            slowest_thread,
+            slowest_share,
+            straggler: rounds.len() >= STRAGGLER_MIN_ROUNDS
+                && slowest_share >= STRAGGLER_SHARE
+                && imbalance >= STRAGGLER_IMBALANCE,
+        })
+    }
+}
+
+/// Two measurements taken in interleaved pairs, round for round.
+///
+/// Pairing is what cancels drift: a machine that warms up, throttles or picks
+/// up a background job slows both members of a pair alike, so the ratio
+/// within each pair keeps the difference between the two and loses most of
+/// what happened to the machine. The test is Wilcoxon's signed-rank test on
+/// the log ratios, which asks only whether one side tends to win its pairs
+/// and by how much relative to the others, so a single disturbed pair cannot
+/// carry it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Paired {
+    /// Pairs with both values finite and positive.
+    pub n: usize,
+    /// Median of the per-pair ratios, candidate over baseline, minus one: the
+    /// signed fractional change.
+    pub change: f64,
+    /// Two-sided p-value of "neither side tends to win", by the normal
+    /// approximation with tie and continuity corrections.
+    pub p_value: f64,
+}
+
+impl Paired {
+    /// Test `candidate[i]` against `baseline[i]`. Returns `None` without at
+    /// least one usable pair.
+    pub fn new(baseline: &[f64], candidate: &[f64]) -> Option<Paired> {
+        let logs: Vec<f64> = baseline
+            .iter()
+            .zip(candidate)
+            .filter(|(a, b)| a.is_finite() && b.is_finite() && **a > 0.0 && **b > 0.0)
+            .map(|(a, b)| (b / a).ln())
+            .collect();
+        if logs.is_empty() {
+            return None;
+        }
+        let change = median_of(&sorted(&logs)).exp() - 1.0;
+
+        // Exact ties carry no information about direction and are dropped.
+        let mut nonzero: Vec<f64> = logs.iter().copied().filter(|d| *d != 0.0).collect();
+        nonzero.sort_by(|a, b| a.abs().total_cmp(&b.abs()));
+        let m = nonzero.len();
+        let mut w_plus = 0.0;
+        let mut ties = 0.0;
+        let mut start = 0;
+        for end in 1..=m {
+            if end == m || nonzero[end].abs() != nonzero[start].abs() {
+                // Tied magnitudes share the mean of the ranks they span.
+                let rank = (start + end + 1) as f64 / 2.0;
+                w_plus += rank * nonzero[start..end].iter().filter(|d| **d > 0.0).count() as f64;
+                let t = (end - start) as f64;
+                ties += t * t * t - t;
+                start = end;
+            }
+        }
+        let mf = m as f64;
+        let mean = mf * (mf + 1.0) / 4.0;
+        let var = mf * (mf + 1.0) * (2.0 * mf + 1.0) / 24.0 - ties / 48.0;
+        let p_value = if var <= 0.0 {
+            1.0
+        } else {
+            let diff = (w_plus - mean).abs();
+            let z = (diff - 0.5).max(0.0) / var.sqrt();
+            erfc(z / std::f64::consts::SQRT_2).min(1.0)
+        };
+        Some(Paired {
+            n: logs.len(),
+            change,
+            p_value,
         })
     }
 }
@@ -198,6 +505,41 @@ pub fn geometric_mean(values: &[f64]) -> Option<f64> {
     Some((log_sum / values.len() as f64).exp())
 }
 
+/// Two-sided 95% critical values of Student's t for 1 to 30 degrees of
+/// freedom.
+const T95: [f64; 30] = [
+    12.7062, 4.3027, 3.1824, 2.7764, 2.5706, 2.4469, 2.3646, 2.3060, 2.2622, 2.2281, 2.2010,
+    2.1788, 2.1604, 2.1448, 2.1314, 2.1199, 2.1098, 2.1009, 2.0930, 2.0860, 2.0796, 2.0739, 2.0687,
+    2.0639, 2.0595, 2.0555, 2.0518, 2.0484, 2.0452, 2.0423,
+];
+
+/// The multiplier for a two-sided 95% interval on a mean estimated with `df`
+/// degrees of freedom: Student's t, which a handful of values needs far more
+/// of than the normal 1.96. Infinite for no degrees of freedom.
+///
+/// Tabulated to 30, and past that the Cornish–Fisher expansion about the
+/// normal quantile, which agrees with the table there to 1e-4.
+pub fn t95(df: usize) -> f64 {
+    match df {
+        0 => f64::INFINITY,
+        1..=30 => T95[df - 1],
+        _ => {
+            let z: f64 = 1.959_964;
+            let v = df as f64;
+            z + (z.powi(3) + z) / (4.0 * v)
+                + (5.0 * z.powi(5) + 16.0 * z.powi(3) + 3.0 * z) / (96.0 * v * v)
+                + (3.0 * z.powi(7) + 19.0 * z.powi(5) + 17.0 * z.powi(3) - 15.0 * z)
+                    / (384.0 * v.powi(3))
+        }
+    }
+}
+
+/// Median of the finite values in `values`. `None` if there are none.
+pub fn median(values: &[f64]) -> Option<f64> {
+    let finite: Vec<f64> = values.iter().copied().filter(|v| v.is_finite()).collect();
+    (!finite.is_empty()).then(|| median_of(&sorted(&finite)))
+}
+
 fn sorted(values: &[f64]) -> Vec<f64> {
     let mut v = values.to_vec();
     // `clean` has already excluded non-finite values, so a total order exists.
@@ -232,6 +574,22 @@ fn percentile_of(sorted: &[f64], q: f64) -> f64 {
     }
 }
 
+/// Complementary error function, by Abramowitz & Stegun 7.1.26. Absolute
+/// error below 1.5e-7, far finer than a significance threshold needs.
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erfc_abs = poly * (-x * x).exp();
+    if x >= 0.0 {
+        erfc_abs
+    } else {
+        2.0 - erfc_abs
+    }
+}
+
 fn median_absolute_deviation(values: &[f64], median: f64) -> f64 {
     let devs: Vec<f64> = values.iter().map(|v| (v - median).abs()).collect();
     median_of(&sorted(&devs))
@@ -313,6 +671,82 @@ mod tests {
         assert_eq!(s.outliers, 0);
     }
 
+    #[test]
+    fn a_steady_slide_is_a_significant_drift_that_costs_a_grade() {
+        // Throttling: every round 0.3% slower than the last, with a spread
+        // small enough to pass for acceptable on CV alone.
+        let samples: Vec<f64> = (0..9).map(|i| 100.0 * (1.0 - 0.003 * i as f64)).collect();
+        let s = Summary::new(&samples).unwrap();
+        assert_eq!(Stability::from_cv(s.cv), Stability::Stable);
+        let drift = s.drift.as_ref().unwrap();
+        assert_eq!(drift.s, -36, "every pair discordant");
+        assert!(drift.p_value < 0.001, "p = {}", drift.p_value);
+        approx(drift.slope, -0.3);
+        assert!((drift.change + 0.024).abs() < 1e-3, "{}", drift.change);
+        assert!(drift.significant);
+        assert_eq!(s.stability, Stability::Acceptable);
+    }
+
+    #[test]
+    fn shuffled_samples_show_no_drift() {
+        let s = Summary::new(&[100.0, 99.2, 100.6, 99.5, 100.3, 99.9, 100.1, 99.7]).unwrap();
+        let drift = s.drift.unwrap();
+        assert!(!drift.significant, "{drift:?}");
+        assert!(drift.p_value > 0.2);
+    }
+
+    #[test]
+    fn a_significant_but_tiny_trend_is_not_drift() {
+        // Perfectly monotonic, but only 0.07% end to end.
+        let samples: Vec<f64> = (0..8).map(|i| 1000.0 + 0.1 * i as f64).collect();
+        let drift = Drift::new(&samples).unwrap();
+        assert!(drift.p_value < 0.05);
+        assert!(!drift.significant);
+    }
+
+    #[test]
+    fn drift_needs_five_samples_and_tolerates_ties() {
+        assert!(Summary::new(&[3.0, 2.0, 1.0]).unwrap().drift.is_none());
+        // Four samples in perfect order are still not a significant trend, so
+        // they are not tested at all; five in order are.
+        let four = Summary::new(&[100.0, 101.0, 102.0, 103.0]).unwrap();
+        assert!(four.drift.is_none());
+        assert_eq!(four.stability, Stability::from_cv(four.cv));
+        let five = Drift::new(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        assert!(five.p_value < 0.05 && five.significant, "{five:?}");
+        let drift = Drift::new(&[5.0; 6]).unwrap();
+        assert_eq!(drift.s, 0);
+        approx(drift.p_value, 1.0);
+        assert!(!drift.significant);
+    }
+
+    #[test]
+    fn t95_widens_for_few_values_and_meets_the_normal_for_many() {
+        assert!(t95(0).is_infinite());
+        approx(t95(1), 12.7062);
+        approx(t95(4), 2.7764);
+        // The expansion picks up where the table stops.
+        for (df, t) in [(31, 2.0395), (60, 2.0003), (120, 1.9799)] {
+            assert!((t95(df) - t).abs() < 1e-4, "t95({df}) = {}", t95(df));
+        }
+        assert!((t95(100_000) - 1.96).abs() < 1e-3);
+    }
+
+    #[test]
+    fn erfc_matches_known_values() {
+        assert!((erfc(0.0) - 1.0).abs() < 1e-6);
+        // 1.96 sigma, two-sided: p = 0.05.
+        assert!((erfc(1.96 / std::f64::consts::SQRT_2) - 0.05).abs() < 1e-4);
+        assert!((erfc(-1.0) - 1.842_700_79).abs() < 1e-6);
+    }
+
+    #[test]
+    fn stability_downgrades_one_grade_and_bottoms_out() {
+        assert_eq!(Stability::Stable.downgraded(), Stability::Acceptable);
+        assert_eq!(Stability::Noisy.downgraded(), Stability::Unreliable);
+        assert_eq!(Stability::Unreliable.downgraded(), Stability::Unreliable);
+    }
+
     #[test]
     fn percentiles_interpolate() {
         let v: Vec<f64> = (1..=101).map(f64::from).collect();
@@ -332,6 +766,74 @@ mod tests {
         assert!(!Stability::Noisy.is_trustworthy());
     }
 
+    #[test]
+    fn balance_needs_at_least_two_threads() {
+        assert!(ThreadBalance::new(&[]).is_none());
+        assert!(ThreadBalance::new(&[vec![10.0], vec![11.0]]).is_none());
+    }
+
+    #[test]
+    fn an_even_pass_has_no_straggler() {
+        // The last thread to finish rotates, and never by much.
+        let rounds: Vec<Vec<f64>> = (0..6)
+            .map(|r| {
+                (0..4)
+                    .map(|t| if t == r % 4 { 10.1 } else { 10.0 })
+                    .collect()
+            })
+            .collect();
+        let b = ThreadBalance::new(&rounds).unwrap();
+        assert!(!b.straggler);
+        assert!(b.imbalance < 1.01);
+        approx(b.spread_ms, 0.1);
+    }
+
+    #[test]
+    fn a_consistently_slow_thread_is_a_straggler() {
+        let rounds: Vec<Vec<f64>> = (0..7).map(|_| vec![10.0, 10.0, 13.0, 10.0]).collect();
+        let b = ThreadBalance::new(&rounds).unwrap();
+        assert!(b.straggler);
+        assert_eq!(b.slowest_thread, 2);
+        approx(b.slowest_share, 1.0);
+        approx(b.imbalance, 13.0 / 10.75);
+        assert_eq!(b.thread_ms, vec![10.0, 10.0, 13.0, 10.0]);
+    }
+
+    #[test]
+    fn a_slow_thread_over_too_few_rounds_is_not_flagged() {
+        let rounds = vec![vec![10.0, 13.0], vec![10.0, 13.0]];
+        assert!(!ThreadBalance::new(&rounds).unwrap().straggler);
+    }
+
+    #[test]
+    fn a_candidate_that_wins_every_pair_is_significant_despite_drift() {
+        // Both sides slide 2% per round; the candidate is 1% ahead throughout.
+        let baseline: Vec<f64> = (0..10).map(|i| 100.0 * (1.0 - 0.02 * i as f64)).collect();
+        let candidate: Vec<f64> = baseline.iter().map(|v| v * 1.01).collect();
+        let p = Paired::new(&baseline, &candidate).unwrap();
+        assert_eq!(p.n, 10);
+        approx(p.change, 0.01);
+        assert!(p.p_value < 0.01, "p = {}", p.p_value);
+    }
+
+    #[test]
+    fn pairs_that_split_evenly_are_not_significant() {
+        let baseline = [100.0; 8];
+        let candidate = [101.0, 99.0, 102.0, 98.0, 101.5, 98.5, 100.5, 99.5];
+        let p = Paired::new(&baseline, &candidate).unwrap();
+        assert!(p.p_value > 0.5, "p = {}", p.p_value);
+        assert!(p.change.abs() < 0.01);
+    }
+
+    #[test]
+    fn identical_pairs_and_unusable_input() {
+        let p = Paired::new(&[5.0, 5.0, 5.0], &[5.0, 5.0, 5.0]).unwrap();
+        assert_eq!(p.change, 0.0);
+        assert_eq!(p.p_value, 1.0);
+        assert!(Paired::new(&[0.0, f64::NAN], &[1.0, 1.0]).is_none());
+        assert!(Paired::new(&[], &[]).is_none());
+    }
+
     #[test]
     fn geometric_mean_of_powers_is_exact() {
         approx(geometric_mean(&[1.0, 4.0]).unwrap(), 2.0);
@@ -8,14 +8,25 @@
 
 use std::time::{Duration, Instant};
 
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::affinity::{Affinity, Topology};
+use crate::budget::{self, BudgetPlan, WorkloadCost};
+use crate::counters::{self, CounterStatus};
 use crate::kernel::{Kernel, Scaling};
-use crate::report::{now_rfc3339, workload_report, Pass, Report, RunSettings, SCHEMA_VERSION};
-use crate::runner::{self, Observer, RunConfig};
-use crate::score::{ratio, ScoreCard, ScoreComponent};
-use crate::sysinfo::SystemInfo;
+use crate::load::{self, LoadSample, RequireIdle};
+use crate::pages::PagePolicy;
+use crate::report::{
+    now_rfc3339, workload_report, CoreClassPass, IdleSettings, ParamSetting, Pass,
+    PrecisionSettings, Report, RunSettings, ScalingCurve, SubResult, INTERRUPTED, SCHEMA_VERSION,
+};
+use crate::runner::{self, Observer, Precision, RunConfig, RunError};
+use crate::score::ScoreCard;
+use crate::sysinfo::{CoreClass, SystemInfo};
 
 /// How to execute a suite.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct SuiteConfig {
     /// Threads for the multi-core pass. Zero means "every logical core".
     pub threads: usize,
@@ -29,6 +40,93 @@ pub struct SuiteConfig {
     pub single_thread: bool,
     /// Whether to run the multi-thread pass.
     pub multi_thread: bool,
+    /// Where to bind worker threads in every pass. `None` leaves placement to
+    /// the scheduler.
+    pub affinity: Option<Affinity>,
+    /// Thread counts for a scaling curve, in addition to the two scored
+    /// passes. `None` measures only the two.
+    pub scaling: Option<ThreadCounts>,
+    /// Whether to add a single-thread pass pinned to each class of core, on a
+    /// heterogeneous CPU whose classes the platform identifies.
+    pub core_classes: bool,
+    /// Whether to count hardware events in every pass. Where the platform
+    /// refuses, the run goes ahead without them and records why.
+    pub counters: bool,
+    /// Adaptive sampling for every pass, with `samples` as the minimum. `None`
+    /// collects exactly `samples` rounds.
+    pub precision: Option<Precision>,
+    /// Fit the whole run into this long. The suite prices each workload with
+    /// a short probe and replaces `window`, `warmup` and `samples` — which
+    /// then say what to use given time to spare — with a plan that fits. See
+    /// [`budget`].
+    pub budget: Option<Duration>,
+    /// Start no pass while the machine is busier than this, waiting for it to
+    /// settle and stopping the run if it does not. `None` records the load
+    /// without acting on it.
+    pub require_idle: Option<RequireIdle>,
+    /// How kernels with large working sets back them.
+    pub pages: PagePolicy,
+}
+
+/// Which thread counts a scaling curve visits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThreadCounts {
+    /// Powers of two up to every logical core, plus the core count itself.
+    Auto,
+    /// These counts. Zero stands for "every logical core", as it does for
+    /// [`SuiteConfig::threads`].
+    List(Vec<usize>),
+}
+
+impl ThreadCounts {
+    /// Parse `auto`, or a comma-separated list such as `1,2,4,8,16,all`.
+    pub fn parse(text: &str) -> Result<ThreadCounts, String> {
+        let text = text.trim();
+        if text == "auto" {
+            return Ok(ThreadCounts::Auto);
+        }
+        let counts = text
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| match part {
+                "all" => Ok(0),
+                _ => match part.parse::<usize>() {
+                    Ok(0) => Err("thread counts must be at least 1".to_string()),
+                    Ok(n) => Ok(n),
+                    Err(_) => Err(format!("'{part}' is not a thread count")),
+                },
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if counts.is_empty() {
+            return Err("no thread counts given".to_string());
+        }
+        Ok(ThreadCounts::List(counts))
+    }
+
+    /// The counts to measure on a machine with `all` logical cores: ascending,
+    /// without duplicates, and always starting at 1 — every speedup on the
+    /// curve is relative to the one-thread point.
+    pub fn resolve(&self, all: usize) -> Vec<usize> {
+        let all = all.max(1);
+        let mut counts = match self {
+            ThreadCounts::Auto => {
+                let mut counts: Vec<usize> = std::iter::successors(Some(1usize), |n| {
+                    n.checked_mul(2).filter(|&next| next <= all)
+                })
+                .collect();
+                counts.push(all);
+                counts
+            }
+            ThreadCounts::List(list) => {
+                list.iter().map(|&n| if n == 0 { all } else { n }).collect()
+            }
+        };
+        counts.push(1);
+        counts.sort_unstable();
+        counts.dedup();
+        counts
+    }
 }
 
 impl Default for SuiteConfig {
@@ -40,6 +138,31 @@ impl Default for SuiteConfig {
             window: runner::defaults::WINDOW,
             single_thread: true,
             multi_thread: true,
+            affinity: None,
+            scaling: None,
+            core_classes: false,
+            counters: false,
+            precision: None,
+            budget: None,
+            require_idle: None,
+            pages: PagePolicy::Default,
+        }
+    }
+}
+
+impl SuiteConfig {
+    /// The runner configuration for one pass at `threads` threads.
+    pub fn run_config(&self, threads: usize) -> RunConfig {
+        RunConfig {
+            threads,
+            samples: self.samples,
+            warmup: self.warmup,
+            window: self.window,
+            affinity: self.affinity.clone(),
+            counters: self.counters,
+            precision: self.precision,
+            pages: self.pages,
+            memory_node: None,
         }
     }
 }
@@ -54,46 +177,228 @@ pub trait SuiteObserver: Observer {
     fn workload_failed(&self, id: &str, error: &str) {
         let _ = (id, error);
     }
+    /// A budgeted run is probing a workload's cost at `threads` threads.
+    fn probing(&self, id: &str, name: &str, threads: usize) {
+        let _ = (id, name, threads);
+    }
+    /// A budgeted run settled on `plan`, and is about to measure.
+    fn planned(&self, plan: &BudgetPlan) {
+        let _ = plan;
+    }
+    /// The machine is busier than `--require-idle` allows; the run is waiting
+    /// for it to settle.
+    fn waiting_for_idle(&self, sample: &LoadSample) {
+        let _ = sample;
+    }
+}
+
+/// Where a suite's passes run.
+///
+/// [`InProcess`] runs them on this process's own threads. A workload that
+/// crashes, or allocates until the kernel's out-of-memory killer steps in,
+/// then takes the whole run with it; an executor that runs passes in a child
+/// process turns that into one workload's error instead. The suite keeps
+/// everything else — load sampling, the budget, scoring, the report — so an
+/// isolated run is the same run with a different failure mode.
+pub trait Executor {
+    /// Measure one pass of `kernel` under `run_cfg`, reporting its rounds to
+    /// `obs` and polling it for interruption as [`runner::run`] does.
+    ///
+    /// `workload` is the workload the pass belongs to: the kernel's own id,
+    /// or its parent's for a sub-result.
+    fn pass(
+        &self,
+        workload: &str,
+        kernel: &dyn Kernel,
+        run_cfg: RunConfig,
+        obs: &dyn Observer,
+    ) -> Result<Pass, RunError>;
+
+    /// How passes are kept apart from the suite, for the report. `None` when
+    /// they share its process.
+    fn isolation(&self) -> Option<Isolation> {
+        None
+    }
+}
+
+/// Runs every pass in the calling process.
+pub struct InProcess;
+
+impl Executor for InProcess {
+    fn pass(
+        &self,
+        _workload: &str,
+        kernel: &dyn Kernel,
+        run_cfg: RunConfig,
+        obs: &dyn Observer,
+    ) -> Result<Pass, RunError> {
+        runner::run(kernel, run_cfg, obs).map(|m| Pass::from_measurement(&m))
+    }
+}
+
+/// How much of a run each child process measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Isolation {
+    /// One process per workload, for all of its passes and its sub-results'.
+    Workload,
+    /// A fresh process for every pass.
+    Pass,
+}
+
+impl Isolation {
+    /// Short label for display.
+    pub fn label(self) -> &'static str {
+        match self {
+            Isolation::Workload => "per workload",
+            Isolation::Pass => "per pass",
+        }
+    }
 }
 
-/// Run `kernels` under `cfg` and assemble a [`Report`].
+/// Forwards only interruption, so that a probe's rounds stay out of the
+/// progress display while a signal can still stop it.
+struct Probing<'a>(&'a dyn SuiteObserver);
+
+impl Observer for Probing<'_> {
+    fn interrupted(&self) -> bool {
+        self.0.interrupted()
+    }
+}
+
+/// Run `kernels` under `cfg` in this process and assemble a [`Report`].
+pub fn run(
+    kernels: &[Box<dyn Kernel>],
+    cfg: SuiteConfig,
+    tool_version: &str,
+    obs: &dyn SuiteObserver,
+) -> Report {
+    run_with(kernels, cfg, tool_version, obs, &InProcess)
+}
+
+/// Run `kernels` under `cfg`, measuring every pass through `executor`, and
+/// assemble a [`Report`].
 ///
 /// A workload that fails is recorded with its error and excluded from scoring;
 /// the rest of the suite still runs. Losing one workload should cost that
 /// workload's data, not the whole run's.
-pub fn run(
+///
+/// When the observer reports an interruption, the pass in flight stops at its
+/// next round and the suite stops with it: the workload it belonged to is
+/// marked [`INTERRUPTED`], later workloads are left out, and the report holds
+/// everything that completed. A machine that stays busier than
+/// [`SuiteConfig::require_idle`] allows stops the run the same way.
+pub fn run_with(
     kernels: &[Box<dyn Kernel>],
     cfg: SuiteConfig,
     tool_version: &str,
     obs: &dyn SuiteObserver,
+    executor: &dyn Executor,
 ) -> Report {
     let started = Instant::now();
     let system = SystemInfo::detect();
+    let mut watch = Watch::default();
+    watch.halt = settle("start", &cfg, obs, &mut watch.load).err();
+
+    // Probed once, so that an unavailable PMU costs one recorded reason rather
+    // than a failed open in every worker of every pass.
+    let counters = cfg.counters.then(counters::probe);
+    let mut cfg = SuiteConfig {
+        counters: matches!(counters, Some(CounterStatus::Enabled { .. })),
+        ..cfg
+    };
     let mt_threads = if cfg.threads == 0 {
         system.default_threads()
     } else {
         cfg.threads
     };
 
+    let curve_threads = cfg
+        .scaling
+        .as_ref()
+        .map_or_else(Vec::new, |c| c.resolve(system.default_threads()));
+    let class_cpus = class_cpus(&system);
+    let classes = class_cpus.len();
+
+    // ---- Budget ----------------------------------------------------------
+    // Probed before anything is measured, so that the plan applies to every
+    // pass alike and the run's settings stay one set of numbers.
+    let budget = cfg.budget.filter(|_| watch.halt.is_none()).map(|slot| {
+        let costs = kernels
+            .iter()
+            .map(|kernel| {
+                let threads =
+                    pass_threads(kernel.as_ref(), &cfg, mt_threads, &curve_threads, classes);
+                probe(kernel.as_ref(), &cfg, &threads, obs, executor)
+            })
+            .collect();
+        let plan = budget::plan(
+            slot,
+            started.elapsed(),
+            costs,
+            cfg.window,
+            cfg.warmup,
+            cfg.samples,
+        );
+        obs.planned(&plan);
+        plan
+    });
+    if let Some(plan) = &budget {
+        cfg.window = plan.window();
+        cfg.warmup = plan.warmup;
+        cfg.samples = plan.samples;
+    }
+
     let mut workloads = Vec::with_capacity(kernels.len());
-    let mut single_components = Vec::new();
-    let mut multi_components = Vec::new();
 
     for kernel in kernels {
         let info = kernel.info();
+        if budget
+            .as_ref()
+            .is_some_and(|p| p.dropped.iter().any(|id| id == info.id))
+        {
+            continue;
+        }
+        // Interrupted between workloads: the marker goes on the next one, so
+        // that the document still says it stopped early.
+        if obs.interrupted() || watch.halt.is_some() {
+            let why = watch
+                .halt
+                .as_deref()
+                .map_or_else(String::new, |h| format!(": {h}"));
+            let mut report = workload_report(
+                &info,
+                None,
+                None,
+                Some(format!("{INTERRUPTED} before this workload started{why}")),
+            );
+            report.params = kernel
+                .params()
+                .into_iter()
+                .map(ParamSetting::from)
+                .collect();
+            workloads.push(report);
+            break;
+        }
         let mut errors: Vec<String> = Vec::new();
+        let mut interrupted = false;
 
         // ---- Single-thread pass ------------------------------------------
         let single = if cfg.single_thread {
-            obs.workload_start(info.id, info.name, 1);
-            let run_cfg = RunConfig {
-                threads: 1,
-                samples: cfg.samples,
-                warmup: cfg.warmup,
-                window: cfg.window,
-            };
-            match runner::run(kernel.as_ref(), run_cfg, obs) {
-                Ok(m) => Some(Pass::from_measurement(&m)),
+            match measure(
+                info.id,
+                kernel.as_ref(),
+                cfg.run_config(1),
+                &cfg,
+                obs,
+                executor,
+                &mut watch,
+            ) {
+                Ok(p) => Some(p),
+                Err(RunError::Interrupted { .. }) => {
+                    interrupted = true;
+                    None
+                }
                 Err(e) => {
                     let msg = e.to_string();
                     obs.workload_failed(info.id, &msg);
@@ -110,16 +415,22 @@ pub fn run(
         // `Scaling::SingleThreadOnly` for why a multi-threaded latency figure
         // would be actively misleading rather than merely uninteresting.
         let runs_multi = cfg.multi_thread && info.scaling == Scaling::Scales && mt_threads > 1;
-        let multi = if runs_multi {
-            obs.workload_start(info.id, info.name, mt_threads);
-            let run_cfg = RunConfig {
-                threads: mt_threads,
-                samples: cfg.samples,
-                warmup: cfg.warmup,
-                window: cfg.window,
-            };
-            match runner::run(kernel.as_ref(), run_cfg, obs) {
-                Ok(m) => Some(Pass::from_measurement(&m)),
+        let multi = if runs_multi && !interrupted {
+            let run_cfg = cfg.run_config(mt_threads);
+            match measure(
+                info.id,
+                kernel.as_ref(),
+                run_cfg,
+                &cfg,
+                obs,
+                executor,
+                &mut watch,
+            ) {
+                Ok(p) => Some(p),
+                Err(RunError::Interrupted { .. }) => {
+                    interrupted = true;
+                    None
+                }
                 Err(e) => {
                     let msg = e.to_string();
                     obs.workload_failed(info.id, &msg);
@@ -131,32 +442,11 @@ pub fn run(
             None
         };
 
-        if let Some(p) = &single {
-            if let Some(r) = ratio(p.value, info.reference, info.unit) {
-                single_components.push(ScoreComponent {
-                    id: info.id.to_string(),
-                    measured: p.value,
-                    reference: info.reference,
-                    ratio: r,
-                });
-            }
-        }
-        if let Some(p) = &multi {
-            if let Some(r) = ratio(p.value, info.reference, info.unit) {
-                multi_components.push(ScoreComponent {
-                    id: info.id.to_string(),
-                    measured: p.value,
-                    reference: info.reference,
-                    ratio: r,
-                });
-            }
-        }
-
         // A workload with neither pass must say why, or a reader — and
         // `threadstone verify` — cannot tell a skipped workload from a silently
         // broken one. The case that reaches here is `--multi-only` against a
         // single-thread-only workload: nothing failed, but nothing ran either.
-        if single.is_none() && multi.is_none() && errors.is_empty() {
+        if single.is_none() && multi.is_none() && errors.is_empty() && !interrupted {
             errors.push(match info.scaling {
                 Scaling::SingleThreadOnly => format!(
                     "not run: '{}' is measured single-threaded only, and the \
@@ -169,12 +459,155 @@ pub fn run(
             });
         }
 
+        // ---- Sub-results -------------------------------------------------
+        // Unscored companions, measured at each thread count the workload
+        // itself was measured at, so they sit beside its figures.
+        let mut sub_results = Vec::new();
+        for sub in kernel.sub_results() {
+            let sub_info = sub.info();
+            let mut result = SubResult {
+                id: sub_info.id.to_string(),
+                name: sub_info.name.to_string(),
+                single_thread: None,
+                multi_thread: None,
+            };
+            let slots = [
+                (&single, &mut result.single_thread),
+                (&multi, &mut result.multi_thread),
+            ];
+            for (scored, slot) in slots {
+                let Some(threads) = scored.as_ref().map(|p| p.threads) else {
+                    continue;
+                };
+                if interrupted {
+                    break;
+                }
+                match measure(
+                    info.id,
+                    sub.as_ref(),
+                    cfg.run_config(threads),
+                    &cfg,
+                    obs,
+                    executor,
+                    &mut watch,
+                ) {
+                    Ok(p) => *slot = Some(p),
+                    Err(RunError::Interrupted { .. }) => interrupted = true,
+                    Err(e) => {
+                        let msg = e.to_string();
+                        obs.workload_failed(sub_info.id, &msg);
+                        errors.push(format!("{}: {msg}", sub_info.name));
+                    }
+                }
+            }
+            if result.single_thread.is_some() || result.multi_thread.is_some() {
+                sub_results.push(result);
+            }
+        }
+
+        // ---- Scaling curve -----------------------------------------------
+        // The scored passes are reused where a curve point coincides with
+        // them; only the remaining thread counts are measured here.
+        let mut curve_passes = Vec::new();
+        if info.scaling == Scaling::Scales {
+            for &threads in &curve_threads {
+                if interrupted {
+                    break;
+                }
+                let reused = [&single, &multi]
+                    .into_iter()
+                    .flatten()
+                    .find(|p| p.threads == threads);
+                if let Some(p) = reused {
+                    curve_passes.push(p.clone());
+                    continue;
+                }
+                let run_cfg = cfg.run_config(threads);
+                match measure(
+                    info.id,
+                    kernel.as_ref(),
+                    run_cfg,
+                    &cfg,
+                    obs,
+                    executor,
+                    &mut watch,
+                ) {
+                    Ok(p) => curve_passes.push(p),
+                    Err(RunError::Interrupted { .. }) => interrupted = true,
+                    Err(e) => {
+                        let msg = e.to_string();
+                        obs.workload_failed(info.id, &msg);
+                        errors.push(format!("scaling curve at {threads} threads: {msg}"));
+                    }
+                }
+            }
+        }
+
+        // ---- Core classes ------------------------------------------------
+        // One thread, bound to the last allowed CPU of each class. The last
+        // rather than the first because CPU 0 conventionally carries the most
+        // interrupt and housekeeping load.
+        let mut class_passes = Vec::new();
+        if cfg.core_classes {
+            for &(class, cpu) in &class_cpus {
+                if interrupted {
+                    break;
+                }
+                let run_cfg = RunConfig {
+                    affinity: Some(Affinity::Explicit(vec![cpu])),
+                    ..cfg.run_config(1)
+                };
+                match measure(
+                    info.id,
+                    kernel.as_ref(),
+                    run_cfg,
+                    &cfg,
+                    obs,
+                    executor,
+                    &mut watch,
+                ) {
+                    Ok(pass) => class_passes.push(CoreClassPass { class, pass }),
+                    Err(RunError::Interrupted { .. }) => interrupted = true,
+                    Err(e) => {
+                        let msg = e.to_string();
+                        obs.workload_failed(info.id, &msg);
+                        errors.push(format!("{} pass: {msg}", class.label()));
+                    }
+                }
+            }
+        }
+
+        if interrupted {
+            let why = watch
+                .halt
+                .as_deref()
+                .map_or_else(String::new, |h| format!(": {h}"));
+            errors.insert(
+                0,
+                format!(
+                    "{INTERRUPTED} before this workload completed{why}; the passes \
+                     recorded finished first"
+                ),
+            );
+        }
         let error = if errors.is_empty() {
             None
         } else {
             Some(errors.join("; "))
         };
-        workloads.push(workload_report(&info, single, multi, error));
+        let mut report = workload_report(&info, single, multi, error);
+        report.params = kernel
+            .params()
+            .into_iter()
+            .map(ParamSetting::from)
+            .collect();
+        report.scaling_curve = ScalingCurve::compute(curve_passes, info.unit);
+        report.core_classes = class_passes;
+        report.sub_results = sub_results;
+        workloads.push(report);
+        if interrupted {
+            break;
+        }
     }
 
     Report {
@@ -187,14 +620,217 @@ pub fn run(
             samples: cfg.samples,
             warmup: cfg.warmup,
             window_ms: cfg.window.as_millis() as u64,
+            affinity: cfg.affinity,
+            scaling: curve_threads,
+            counters,
+            precision: cfg.precision.map(PrecisionSettings::from),
+            budget,
+            require_idle: cfg.require_idle.map(IdleSettings::from),
+            isolate: executor.isolation(),
+            repeat: None,
+            pages: (cfg.pages != PagePolicy::Default).then_some(cfg.pages),
         },
         system,
+        load: watch.load,
+        score: ScoreCard::from_workloads(&workloads),
         workloads,
-        score: ScoreCard::new(single_components, multi_components),
+        core_to_core: None,
         signature: None,
     }
 }
 
+/// What settling before each pass has found.
+#[derive(Default)]
+struct Watch {
+    /// The load sample taken before each pass, in order.
+    load: Vec<LoadSample>,
+    /// Why the run stopped early, when it was the machine rather than a signal.
+    halt: Option<String>,
+}
+
+/// Settle, then run one pass of `workload`. A machine that never settles
+/// stops the run as a signal does, with `watch.halt` saying why.
+fn measure(
+    workload: &str,
+    kernel: &dyn Kernel,
+    run_cfg: RunConfig,
+    cfg: &SuiteConfig,
+    obs: &dyn SuiteObserver,
+    executor: &dyn Executor,
+    watch: &mut Watch,
+) -> Result<Pass, RunError> {
+    let info = kernel.info();
+    let threads = run_cfg.threads;
+    let label = format!(
+        "{} at {threads} thread{}",
+        info.id,
+        if threads == 1 { "" } else { "s" }
+    );
+    if let Err(reason) = settle(&label, cfg, obs, &mut watch.load) {
+        watch.halt = Some(reason);
+        return Err(RunError::Interrupted { id: info.id });
+    }
+    obs.workload_start(info.id, info.name, threads);
+    executor.pass(workload, kernel, run_cfg, obs)
+}
+
+/// How often a run waiting for the machine to settle looks again.
+const IDLE_POLL: Duration = Duration::from_secs(1);
+
+/// Record what the machine is doing before `label` and, under
+/// [`SuiteConfig::require_idle`], wait for it to settle. Returns why the run
+/// must stop if it never does.
+fn settle(
+    label: &str,
+    cfg: &SuiteConfig,
+    obs: &dyn SuiteObserver,
+    load: &mut Vec<LoadSample>,
+) -> Result<(), String> {
+    let started = Instant::now();
+    let Some(mut sample) = load::sample(label) else {
+        return Ok(());
+    };
+    if let Some(idle) = cfg.require_idle {
+        let mut waited = false;
+        while sample.contention() > idle.max_busy && !obs.interrupted() {
+            if started.elapsed() >= idle.wait {
+                let reason = format!(
+                    "the machine stayed {:.0}% busy for {:.0} s, above the {:.0}% allowed",
+                    sample.contention() * 100.0,
+                    started.elapsed().as_secs_f64(),
+                    idle.max_busy * 100.0
+                );
+                load.push(sample);
+                return Err(reason);
+            }
+            obs.waiting_for_idle(&sample);
+            std::thread::sleep(IDLE_POLL);
+            waited = true;
+            match load::sample(label) {
+                Some(next) => sample = next,
+                None => break,
+            }
+        }
+        if waited {
+            sample.waited_ms = started.elapsed().saturating_sub(load::INTERVAL).as_millis() as u64;
+        }
+    }
+    load.push(sample);
+    Ok(())
+}
+
+/// The CPU each core class's pass is bound to: the last of the class that
+/// this process may run on. A class with none left to it under `taskset` or a
+/// cgroup is skipped rather than pinned somewhere it cannot go.
+fn class_cpus(system: &SystemInfo) -> Vec<(CoreClass, usize)> {
+    let allowed = Topology::detect().map(|t| t.cpus.iter().map(|c| c.cpu).collect::<Vec<_>>());
+    CoreClass::ALL
+        .into_iter()
+        .filter_map(|class| {
+            let cpu = system
+                .cpus_of(class)
+                .iter()
+                .rev()
+                .find(|cpu| allowed.as_ref().map_or(true, |a| a.contains(cpu)))?;
+            Some((class, *cpu))
+        })
+        .collect()
+}
+
+/// Thread count of every pass [`run`] makes of a workload under `cfg`.
+fn pass_threads(
+    kernel: &dyn Kernel,
+    cfg: &SuiteConfig,
+    mt_threads: usize,
+    curve: &[usize],
+    classes: usize,
+) -> Vec<usize> {
+    let scales = kernel.info().scaling == Scaling::Scales;
+    let mut threads = Vec::new();
+    if cfg.single_thread {
+        threads.push(1);
+    }
+    if cfg.multi_thread && scales && mt_threads > 1 {
+        threads.push(mt_threads);
+    }
+    // Every sub-result repeats the scored passes.
+    let scored = threads.clone();
+    for _ in kernel.sub_results() {
+        threads.extend(&scored);
+    }
+    if scales {
+        // Curve points that coincide with a scored pass reuse it.
+        let extra: Vec<usize> = curve
+            .iter()
+            .copied()
+            .filter(|n| !threads.contains(n))
+            .collect();
+        threads.extend(extra);
+    }
+    if cfg.core_classes {
+        threads.extend(std::iter::repeat(1).take(classes));
+    }
+    threads
+}
+
+/// Price a workload for the budget planner from short runs at the minimum
+/// window: one at one thread and one at the most threads any of its passes
+/// uses, each standing in for the passes on its side of that divide.
+fn probe(
+    kernel: &dyn Kernel,
+    cfg: &SuiteConfig,
+    threads: &[usize],
+    obs: &dyn SuiteObserver,
+    executor: &dyn Executor,
+) -> WorkloadCost {
+    let info = kernel.info();
+    let floor = runner::defaults::MIN_WINDOW;
+    // Whatever a probe takes beyond its calibration and one round is setup.
+    let rounds = (budget::CALIBRATION_WINDOWS + 1.0) * floor.as_secs_f64();
+    let setup_ms = |n: usize| {
+        if obs.interrupted() {
+            return 0.0;
+        }
+        obs.probing(info.id, info.name, n);
+        let probe_cfg = RunConfig {
+            samples: 1,
+            warmup: 0,
+            window: floor,
+            counters: false,
+            precision: None,
+            ..cfg.run_config(n)
+        };
+        let started = Instant::now();
+        match executor.pass(info.id, kernel, probe_cfg, &Probing(obs)) {
+            Ok(_) => (started.elapsed().as_secs_f64() - rounds).max(0.0) * 1e3,
+            // The real pass will fail as quickly, so it costs nothing to plan.
+            Err(_) => 0.0,
+        }
+    };
+    let single = if threads.contains(&1) {
+        setup_ms(1)
+    } else {
+        0.0
+    };
+    let most = threads.iter().copied().max().unwrap_or(1);
+    let multi = if most > 1 { setup_ms(most) } else { single };
+    // Every pass first watches the machine for a load sample, which the probe
+    // does not, and which no choice of window or round count shortens.
+    let sample_ms = if load::supported() {
+        load::INTERVAL.as_secs_f64() * 1e3
+    } else {
+        0.0
+    };
+    WorkloadCost {
+        id: info.id.to_string(),
+        passes: threads.len() as u32,
+        setup_ms: threads
+            .iter()
+            .map(|&n| sample_ms + if n == 1 { single } else { multi })
+            .sum(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -254,6 +890,14 @@ mod tests {
             window: Duration::from_millis(15),
             single_thread: true,
             multi_thread: true,
+            affinity: None,
+            scaling: None,
+            core_classes: false,
+            counters: false,
+            precision: None,
+            budget: None,
+            require_idle: None,
+            pages: PagePolicy::Default,
         }
     }
 
@@ -277,6 +921,53 @@ mod tests {
         assert!(report.duration_secs > 0.0);
     }
 
+    #[test]
+    fn sub_results_follow_the_scored_passes_and_stay_unscored() {
+        struct WithCompanion;
+        impl Kernel for WithCompanion {
+            fn info(&self) -> KernelInfo {
+                Busy {
+                    id: "main",
+                    scaling: Scaling::Scales,
+                }
+                .info()
+            }
+            fn setup(&self, ctx: &SetupCtx) -> Box<dyn KernelState> {
+                Busy {
+                    id: "main",
+                    scaling: Scaling::Scales,
+                }
+                .setup(ctx)
+            }
+            fn rate(&self, iters: u64, threads: usize, secs: f64) -> f64 {
+                iters as f64 * threads as f64 / secs / 1e6
+            }
+            fn sub_results(&self) -> Vec<Box<dyn Kernel>> {
+                vec![Box::new(Busy {
+                    id: "companion",
+                    scaling: Scaling::Scales,
+                })]
+            }
+        }
+
+        let kernels: Vec<Box<dyn Kernel>> = vec![Box::new(WithCompanion)];
+        let report = run(&kernels, quick(), "test", &Silent);
+        let w = &report.workloads[0];
+        assert_eq!(w.sub_results.len(), 1);
+        let sub = &w.sub_results[0];
+        assert_eq!(sub.id, "companion");
+        assert_eq!(sub.single_thread.as_ref().unwrap().threads, 1);
+        assert_eq!(sub.multi_thread.as_ref().unwrap().threads, 2);
+        assert_eq!(report.score.single_core_components.len(), 1);
+
+        let single_only = SuiteConfig {
+            multi_thread: false,
+            ..quick()
+        };
+        let report = run(&kernels, single_only, "test", &Silent);
+        assert!(report.workloads[0].sub_results[0].multi_thread.is_none());
+    }
+
     #[test]
     fn single_thread_only_workloads_skip_the_multi_pass() {
         let kernels: Vec<Box<dyn Kernel>> = vec![Box::new(Busy {
@@ -350,6 +1041,186 @@ mod tests {
         );
     }
 
+    #[test]
+    fn a_lost_child_process_costs_only_its_own_workload() {
+        // Stands in for a child killed mid-pass: every pass of `doomed` is
+        // lost, everything else is measured here.
+        struct Doomed;
+        impl Executor for Doomed {
+            fn pass(
+                &self,
+                workload: &str,
+                kernel: &dyn Kernel,
+                run_cfg: RunConfig,
+                obs: &dyn Observer,
+            ) -> Result<Pass, RunError> {
+                match kernel.info().id {
+                    "doomed" => Err(RunError::Isolated {
+                        id: "doomed",
+                        reason: "child process killed by SIGKILL".into(),
+                    }),
+                    _ => InProcess.pass(workload, kernel, run_cfg, obs),
+                }
+            }
+            fn isolation(&self) -> Option<Isolation> {
+                Some(Isolation::Pass)
+            }
+        }
+
+        let kernels: Vec<Box<dyn Kernel>> = ["doomed", "busy"]
+            .into_iter()
+            .map(|id| {
+                Box::new(Busy {
+                    id,
+                    scaling: Scaling::Scales,
+                }) as Box<dyn Kernel>
+            })
+            .collect();
+        let report = run_with(&kernels, quick(), "test", &Silent, &Doomed);
+
+        assert_eq!(report.config.isolate, Some(Isolation::Pass));
+        let doomed = &report.workloads[0];
+        assert!(doomed.single_thread.is_none() && doomed.multi_thread.is_none());
+        assert!(
+            doomed.error.as_deref().unwrap().contains("SIGKILL"),
+            "the loss is recorded as the workload's error: {:?}",
+            doomed.error
+        );
+        assert!(report.workloads[1].multi_thread.is_some());
+        assert_eq!(report.score.single_core_components.len(), 1);
+    }
+
+    #[test]
+    fn an_interrupted_suite_keeps_what_completed() {
+        // Interrupts once three passes have finished: both of the first
+        // workload's, and the second workload's single-thread pass.
+        struct StopAfter(std::sync::atomic::AtomicUsize);
+        impl Observer for StopAfter {
+            fn finished(&self, _id: &str, _m: &runner::Measurement) {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            }
+            fn interrupted(&self) -> bool {
+                self.0.load(std::sync::atomic::Ordering::Relaxed) >= 3
+            }
+        }
+        impl SuiteObserver for StopAfter {}
+
+        let kernels: Vec<Box<dyn Kernel>> = ["first", "second", "third"]
+            .into_iter()
+            .map(|id| {
+                Box::new(Busy {
+                    id,
+                    scaling: Scaling::Scales,
+                }) as Box<dyn Kernel>
+            })
+            .collect();
+        let obs = StopAfter(std::sync::atomic::AtomicUsize::new(0));
+        let report = run(&kernels, quick(), "test", &obs);
+
+        assert!(report.is_partial());
+        assert_eq!(report.workloads.len(), 2, "nothing runs after the signal");
+        assert!(report.workloads[0].error.is_none());
+        assert!(!report.workloads[0].interrupted());
+        let w = &report.workloads[1];
+        assert!(w.interrupted(), "{:?}", w.error);
+        assert!(w.single_thread.is_some(), "a completed pass is kept");
+        assert!(w.multi_thread.is_none());
+        assert!(report.score.single_core.is_some());
+    }
+
+    #[test]
+    fn a_budgeted_run_records_the_plan_it_used() {
+        let kernels: Vec<Box<dyn Kernel>> = vec![Box::new(Busy {
+            id: "busy",
+            scaling: Scaling::Scales,
+        })];
+        let cfg = SuiteConfig {
+            budget: Some(Duration::from_secs(2)),
+            ..quick()
+        };
+        let report = run(&kernels, cfg, "test", &Silent);
+        let plan = report.config.budget.as_ref().unwrap();
+
+        assert_eq!(plan.workloads.len(), 1);
+        assert_eq!(plan.workloads[0].passes, 2);
+        assert!(plan.window() >= runner::defaults::MIN_WINDOW);
+        assert_eq!(report.config.window_ms, plan.window_ms);
+        assert_eq!(report.config.samples, plan.samples);
+        assert_eq!(report.config.warmup, plan.warmup);
+        let pass = report.workloads[0].single_thread.as_ref().unwrap();
+        assert_eq!(pass.samples.len(), plan.samples as usize);
+        // The load sample before each pass is priced, however quick the kernel.
+        if load::supported() {
+            let sampling = 2.0 * load::INTERVAL.as_secs_f64() * 1e3;
+            assert!(plan.workloads[0].setup_ms >= sampling, "{plan:?}");
+        }
+    }
+
+    #[test]
+    fn an_impossible_budget_keeps_one_workload_at_the_floor() {
+        let kernels: Vec<Box<dyn Kernel>> = ["first", "second"]
+            .into_iter()
+            .map(|id| {
+                Box::new(Busy {
+                    id,
+                    scaling: Scaling::Scales,
+                }) as Box<dyn Kernel>
+            })
+            .collect();
+        let cfg = SuiteConfig {
+            budget: Some(Duration::from_millis(1)),
+            ..quick()
+        };
+        let report = run(&kernels, cfg, "test", &Silent);
+        let plan = report.config.budget.as_ref().unwrap();
+
+        assert_eq!(plan.dropped.len(), 1);
+        assert_eq!(report.workloads.len(), 1);
+        assert_ne!(report.workloads[0].id, plan.dropped[0]);
+        assert_eq!(plan.window(), runner::defaults::MIN_WINDOW);
+    }
+
+    #[test]
+    fn the_machine_is_sampled_before_the_run_and_every_pass() {
+        if !load::supported() {
+            return;
+        }
+        let kernels: Vec<Box<dyn Kernel>> = vec![Box::new(Busy {
+            id: "busy",
+            scaling: Scaling::Scales,
+        })];
+        let report = run(&kernels, quick(), "test", &Silent);
+        let before: Vec<&str> = report.load.iter().map(|s| s.before.as_str()).collect();
+        assert_eq!(before, ["start", "busy at 1 thread", "busy at 2 threads"]);
+    }
+
+    #[test]
+    fn a_machine_that_never_settles_stops_the_run() {
+        if !load::supported() {
+            return;
+        }
+        let kernels: Vec<Box<dyn Kernel>> = vec![Box::new(Busy {
+            id: "busy",
+            scaling: Scaling::Scales,
+        })];
+        // No machine is ever busy by less than nothing.
+        let cfg = SuiteConfig {
+            require_idle: Some(RequireIdle {
+                max_busy: -1.0,
+                wait: Duration::ZERO,
+            }),
+            ..quick()
+        };
+        let report = run(&kernels, cfg, "test", &Silent);
+
+        assert!(report.is_partial());
+        let error = report.workloads[0].error.as_deref().unwrap();
+        assert!(error.contains("busy"), "got: {error}");
+        assert!(report.workloads[0].single_thread.is_none());
+        assert_eq!(report.load.len(), 1, "nothing is sampled after the stop");
+        assert!(report.config.require_idle.is_some());
+    }
+
     #[test]
     fn disabling_a_pass_omits_it() {
         let kernels: Vec<Box<dyn Kernel>> = vec![Box::new(Busy {
@@ -403,6 +1274,84 @@ mod tests {
         assert!(report.workloads[0].error.is_some());
     }
 
+    #[test]
+    fn thread_counts_parse_lists_and_keywords() {
+        assert_eq!(ThreadCounts::parse("auto").unwrap(), ThreadCounts::Auto);
+        assert_eq!(
+            ThreadCounts::parse("1,2,4,all").unwrap(),
+            ThreadCounts::List(vec![1, 2, 4, 0])
+        );
+        assert!(ThreadCounts::parse("0").is_err());
+        assert!(ThreadCounts::parse("two").is_err());
+        assert!(ThreadCounts::parse("").is_err());
+    }
+
+    #[test]
+    fn thread_counts_resolve_sorted_from_one() {
+        assert_eq!(ThreadCounts::Auto.resolve(12), vec![1, 2, 4, 8, 12]);
+        assert_eq!(ThreadCounts::Auto.resolve(8), vec![1, 2, 4, 8]);
+        assert_eq!(ThreadCounts::Auto.resolve(1), vec![1]);
+        assert_eq!(
+            ThreadCounts::List(vec![8, 0, 2, 2]).resolve(16),
+            vec![1, 2, 8, 16]
+        );
+    }
+
+    #[test]
+    fn a_scaling_run_records_a_curve_per_scaling_workload() {
+        let kernels: Vec<Box<dyn Kernel>> = vec![
+            Box::new(Busy {
+                id: "busy",
+                scaling: Scaling::Scales,
+            }),
+            Box::new(Busy {
+                id: "st-only",
+                scaling: Scaling::SingleThreadOnly,
+            }),
+        ];
+        let cfg = SuiteConfig {
+            scaling: Some(ThreadCounts::List(vec![2, 3])),
+            ..quick()
+        };
+        let report = run(&kernels, cfg, "test", &Silent);
+        assert_eq!(report.config.scaling, vec![1, 2, 3]);
+
+        let curve = report.workloads[0].scaling_curve.as_ref().unwrap();
+        let threads: Vec<usize> = curve.points.iter().map(|p| p.threads).collect();
+        assert_eq!(threads, vec![1, 2, 3]);
+        assert!((curve.points[0].speedup - 1.0).abs() < 1e-12);
+        assert!(
+            report.workloads[1].scaling_curve.is_none(),
+            "single-thread-only workloads have no curve"
+        );
+    }
+
+    #[test]
+    fn core_class_passes_use_only_detected_classes() {
+        let kernels: Vec<Box<dyn Kernel>> = vec![Box::new(Busy {
+            id: "busy",
+            scaling: Scaling::Scales,
+        })];
+        let cfg = SuiteConfig {
+            core_classes: true,
+            ..quick()
+        };
+        let report = run(&kernels, cfg, "test", &Silent);
+        let w = &report.workloads[0];
+        let expected = class_cpus(&report.system);
+        assert_eq!(w.core_classes.len(), expected.len());
+        for (c, &(class, cpu)) in w.core_classes.iter().zip(&expected) {
+            assert_eq!(c.class, class);
+            assert_eq!(c.pass.pinned_cpus, [cpu]);
+            assert_eq!(c.pass.threads, 1);
+            assert!(report
+                .system
+                .cpus_of(c.class)
+                .contains(&c.pass.pinned_cpus[0]));
+        }
+        assert!(w.error.is_none(), "{:?}", w.error);
+    }
+
     #[test]
     fn report_serialises_and_round_trips() {
         let kernels: Vec<Box<dyn Kernel>> = vec![Box::new(Busy {
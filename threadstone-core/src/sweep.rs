@@ -0,0 +1,316 @@
+//! Working-set sweeps, and the document that carries one.
+//!
+//! `threadstone sweep` walks the latency chase across working-set sizes, and
+//! `threadstone sweep --bandwidth` does the same with two streaming kernels;
+//! `threadstone mlp` walks it across chain counts instead, and `threadstone
+//! loaded-latency` across levels of injected traffic. None is scored, and neither fits a [`Report`](crate::Report), whose
+//! workloads, scores and run settings describe a suite run. A sweep is written
+//! as a [`SweepReport`] instead: a sibling document with the same provenance —
+//! schema and tool version, completion time, [`SystemInfo`] — and the same
+//! canonical signing bytes, so that `sign` and `verify` treat it exactly as they
+//! treat a result. A signed result and the signed sweep of the same machine
+//! carry the same `system`, and can be checked to belong together rather than
+//! paired by file name.
+//!
+//! A latency sweep also records the levels read off its curve and how they
+//! compare with the reported cache sizes (see [`hierarchy`](crate::hierarchy)),
+//! and a TLB probe the reach read off its own. All of these follow from the
+//! points and the system, so a verifier recomputes them rather than trusting
+//! them.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::hierarchy::{Disagreement, Level};
+use crate::pages::PagePolicy;
+use crate::report::{self, Signature};
+use crate::stats::Summary;
+use crate::sysinfo::SystemInfo;
+
+/// A latency, bandwidth, TLB, MLP or loaded-latency sweep with its
+/// provenance.
+///
+/// Schema versions are shared with [`Report`](crate::Report): a change that
+/// would break a consumer of either bumps [`SCHEMA_VERSION`](report::SCHEMA_VERSION).
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SweepReport {
+    /// Version of this document's schema.
+    pub schema_version: u32,
+    /// Version of the tool that produced it.
+    pub tool_version: String,
+    /// UTC completion time, RFC 3339.
+    pub generated_at: String,
+    /// Total wall-clock duration of the sweep, in seconds.
+    pub duration_secs: f64,
+    /// The machine and toolchain this was measured on.
+    pub system: SystemInfo,
+    /// The latency sweep, for `threadstone sweep`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencySweep>,
+    /// The bandwidth sweep, for `threadstone sweep --bandwidth`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bandwidth: Option<BandwidthSweep>,
+    /// The TLB reach probe, for `threadstone sweep --tlb`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tlb: Option<TlbSweep>,
+    /// The memory-level parallelism sweep, for `threadstone mlp`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mlp: Option<MlpSweep>,
+    /// The loaded-latency curve, for `threadstone loaded-latency`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loaded: Option<LoadedSweep>,
+    /// Detached signature over the canonical form of this document, as on a
+    /// [`Report`](crate::Report).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Signature>,
+}
+
+impl SweepReport {
+    /// Serialise this sweep to canonical bytes for signing or verification,
+    /// exactly as [`Report::signing_bytes`](crate::Report::signing_bytes) does.
+    pub fn signing_bytes(&self) -> Result<Vec<u8>, serde_json::Error> {
+        report::signing_bytes(self)
+    }
+}
+
+/// Pointer-chase latency across working-set sizes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LatencySweep {
+    /// Minimum measurement time per size, in milliseconds.
+    pub min_ms: u64,
+    /// Largest ratio between neighbouring sizes asked for.
+    pub step: f64,
+    /// One point per size, in ascending order of size.
+    pub points: Vec<SweepPoint>,
+    /// Cache levels read off the points, smallest first.
+    pub levels: Vec<Level>,
+    /// Where the levels and the sizes in `system` disagree.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disagreements: Vec<Disagreement>,
+}
+
+/// One point on a cache-hierarchy sweep.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SweepPoint {
+    /// Working set size in bytes.
+    pub bytes: usize,
+    /// Latency per access in nanoseconds: the median of `samples`.
+    pub latency_ns: f64,
+    /// Per-window latencies, in collection order.
+    pub samples: Vec<f64>,
+    /// Robust statistics over `samples`.
+    pub stats: Summary,
+}
+
+/// Pointer-chase latency with one node per page, across page counts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TlbSweep {
+    /// Minimum measurement time per page count, in milliseconds.
+    pub min_ms: u64,
+    /// Largest ratio between neighbouring page counts asked for.
+    pub step: f64,
+    /// One probe per page size measured: 4 KiB, and 2 MiB where huge pages
+    /// were granted.
+    pub probes: Vec<TlbProbe>,
+}
+
+/// The chase at one page size, and what it says about the TLBs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TlbProbe {
+    /// Page size the buffer was backed with, in bytes.
+    pub page_bytes: usize,
+    /// One point per page count, in ascending order.
+    pub points: Vec<TlbPoint>,
+    /// Levels and reach read off the points.
+    pub reach: TlbReach,
+}
+
+/// One point on a TLB probe.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TlbPoint {
+    /// Pages in the chase, one node on each.
+    pub pages: usize,
+    /// Latency per access in nanoseconds: the median of `samples`.
+    pub latency_ns: f64,
+    /// Per-window latencies, in collection order.
+    pub samples: Vec<f64>,
+    /// Robust statistics over `samples`.
+    pub stats: Summary,
+    /// Latency of a chase over as many nodes packed one per cache line, in
+    /// nanoseconds: the same data footprint on the fewest pages.
+    pub baseline_ns: f64,
+}
+
+impl TlbPoint {
+    /// What translation adds to each access, in nanoseconds: the page-stride
+    /// latency less the baseline's.
+    pub fn translation_ns(&self) -> f64 {
+        self.latency_ns - self.baseline_ns
+    }
+}
+
+/// TLB levels as a page-stride chase shows them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct TlbReach {
+    /// Plateaus of the translation curve, smallest first, with sizes in bytes
+    /// of address space spanned.
+    pub levels: Vec<Level>,
+    /// Pages the first-level data TLB covers: the end of the first plateau.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l1_pages: Option<usize>,
+    /// Pages the second-level TLB covers: the end of the second plateau.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub l2_pages: Option<usize>,
+    /// What a page walk adds to an access over a second-level TLB hit, in
+    /// nanoseconds: the step from the second plateau to the third.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub walk_ns: Option<f64>,
+}
+
+/// Effective pointer-chase latency with independent chains interleaved on one
+/// thread, across chain counts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MlpSweep {
+    /// Minimum measurement time per chain count, in milliseconds.
+    pub min_ms: u64,
+    /// How the chase's buffer was backed.
+    pub pages: PagePolicy,
+    /// One point per chain count, in ascending order.
+    pub points: Vec<MlpPoint>,
+}
+
+/// The chase at one chain count.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MlpPoint {
+    /// Independent chains interleaved.
+    pub chains: usize,
+    /// Effective time per access in nanoseconds: the median of `samples`.
+    pub ns_per_access: f64,
+    /// Effective time per access in each window, in collection order.
+    pub samples: Vec<f64>,
+    /// Robust statistics over `samples`.
+    pub stats: Summary,
+}
+
+/// Pointer-chase latency while other threads load memory, across levels of
+/// injected traffic.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LoadedSweep {
+    /// Minimum measurement time per step, in milliseconds.
+    pub min_ms: u64,
+    /// Threads injecting traffic.
+    pub injectors: usize,
+    /// How the chase's and the injectors' buffers were backed.
+    pub pages: PagePolicy,
+    /// The idle point, then one per delay, lightest pressure first.
+    pub points: Vec<LoadedPoint>,
+}
+
+/// One point on the loaded-latency curve.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LoadedPoint {
+    /// Spin-loop hints each injector waits between bursts; absent for the
+    /// idle point, with no injection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u32>,
+    /// Triad bandwidth the injectors achieved during the step, in GiB/s.
+    pub bandwidth_gib_s: f64,
+    /// Chase latency during the step, in nanoseconds per hop.
+    pub latency_ns: f64,
+}
+
+/// Read and read-modify-write bandwidth across working-set sizes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BandwidthSweep {
+    /// Minimum measurement time per point, in milliseconds.
+    pub min_ms: u64,
+    /// Thread counts measured.
+    pub threads: Vec<usize>,
+    /// One point per thread count, kernel and size.
+    pub points: Vec<BandwidthPoint>,
+}
+
+/// How a bandwidth kernel touches its working set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum Access {
+    /// Every element read once per pass.
+    #[serde(rename = "read")]
+    Read,
+    /// Every element read, incremented and written back once per pass.
+    #[serde(rename = "rmw")]
+    ReadModifyWrite,
+}
+
+impl Access {
+    /// Both kernels, in the order they are reported.
+    pub const ALL: [Access; 2] = [Access::Read, Access::ReadModifyWrite];
+
+    /// Stable identifier, as written to JSON.
+    pub fn id(self) -> &'static str {
+        match self {
+            Access::Read => "read",
+            Access::ReadModifyWrite => "rmw",
+        }
+    }
+
+    /// Bytes counted as moved per element.
+    pub fn bytes_per_element(self) -> u64 {
+        match self {
+            Access::Read => 8,
+            Access::ReadModifyWrite => 16,
+        }
+    }
+}
+
+/// One point on a bandwidth sweep.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BandwidthPoint {
+    /// Working set size in bytes, across all threads.
+    pub bytes: usize,
+    /// Which kernel was measured.
+    pub access: Access,
+    /// Threads that shared the working set.
+    pub threads: usize,
+    /// Bandwidth across all threads, in GiB/s.
+    pub bandwidth_gib_s: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_serialises_as_its_id() {
+        for access in Access::ALL {
+            let json = serde_json::to_string(&access).unwrap();
+            assert_eq!(json, format!("\"{}\"", access.id()));
+            assert_eq!(serde_json::from_str::<Access>(&json).unwrap(), access);
+        }
+    }
+
+    #[test]
+    fn the_signature_is_left_out_of_the_signed_bytes() {
+        let mut sweep = SweepReport {
+            schema_version: report::SCHEMA_VERSION,
+            tool_version: "2.0.0".into(),
+            generated_at: "2026-08-10T12:00:00Z".into(),
+            duration_secs: 1.0,
+            system: SystemInfo::default(),
+            latency: None,
+            bandwidth: None,
+            tlb: None,
+            mlp: None,
+            loaded: None,
+            signature: None,
+        };
+        let unsigned = sweep.signing_bytes().unwrap();
+        sweep.signature = Some(Signature {
+            algorithm: "ed25519".into(),
+            public_key: "key".into(),
+            value: "value".into(),
+        });
+        assert_eq!(sweep.signing_bytes().unwrap(), unsigned);
+        sweep.duration_secs = 2.0;
+        assert_ne!(sweep.signing_bytes().unwrap(), unsigned);
+    }
+}
@@ -20,6 +20,8 @@ use std::process::Command;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::numa::{self, NumaNode};
+
 /// Description of the machine and toolchain that produced a result.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SystemInfo {
@@ -40,6 +42,15 @@ pub struct SystemInfo {
     /// Efficiency cores on a heterogeneous CPU.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub efficiency_cores: Option<usize>,
+    /// Logical CPUs that are performance cores, where the platform names them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub performance_cpus: Vec<usize>,
+    /// Logical CPUs that are efficiency cores, where the platform names them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub efficiency_cpus: Vec<usize>,
+    /// How the core classes were told apart, e.g. `"hybrid_pmu"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub core_class_source: Option<String>,
     /// L1 data cache per core, in bytes.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub l1d_bytes: Option<u64>,
@@ -55,6 +66,10 @@ pub struct SystemInfo {
     /// Installed physical memory, in bytes.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memory_bytes: Option<u64>,
+    /// NUMA nodes: which CPUs and how much memory each holds, and how far
+    /// apart they are. Empty where the platform does not say.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub numa_nodes: Vec<NumaNode>,
     /// Operating system family, e.g. `"macos"`.
     pub os: String,
     /// OS release string where obtainable.
@@ -130,6 +145,7 @@ impl SystemInfo {
         };
 
         platform::fill(&mut info);
+        info.numa_nodes = numa::detect();
         info
     }
 
@@ -145,7 +161,7 @@ impl SystemInfo {
     /// One-line description for terminal headers.
     pub fn describe(&self) -> String {
         let cpu = self.cpu_model.as_deref().unwrap_or("unknown CPU");
-        let topology = match (self.performance_cores, self.efficiency_cores) {
+        let mut topology = match (self.performance_cores, self.efficiency_cores) {
             (Some(p), Some(e)) if e > 0 => format!("{p}P+{e}E"),
             _ => match self.physical_cores {
                 Some(p) if p != self.logical_cores => {
@@ -154,8 +170,68 @@ impl SystemInfo {
                 _ => format!("{}C", self.logical_cores),
             },
         };
+        if self.numa_nodes.len() > 1 {
+            topology.push_str(&format!(", {} nodes", self.numa_nodes.len()));
+        }
         format!("{cpu} ({topology}) · {} · {}", self.os, self.target)
     }
+
+    /// The logical CPUs of one core class. Empty where the platform does not
+    /// say which CPU is which.
+    pub fn cpus_of(&self, class: CoreClass) -> &[usize] {
+        match class {
+            CoreClass::Performance => &self.performance_cpus,
+            CoreClass::Efficiency => &self.efficiency_cpus,
+        }
+    }
+}
+
+/// A class of core on a heterogeneous CPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CoreClass {
+    /// The fastest cores: Intel P-cores, Arm "big" and "prime" cores.
+    Performance,
+    /// The slower, more frugal cores: Intel E-cores, Arm "LITTLE" cores.
+    Efficiency,
+}
+
+impl CoreClass {
+    /// Both classes, fastest first.
+    pub const ALL: [CoreClass; 2] = [CoreClass::Performance, CoreClass::Efficiency];
+
+    /// Short label for tables.
+    pub fn label(self) -> &'static str {
+        match self {
+            CoreClass::Performance => "P-core",
+            CoreClass::Efficiency => "E-core",
+        }
+    }
+}
+
+/// Split CPUs into performance and efficiency classes by a per-CPU rating
+/// such as capacity or maximum frequency.
+///
+/// The lowest-rated CPUs are the efficiency class and everything else is
+/// performance, so an Arm prime + big + LITTLE design puts prime and big
+/// together. Returns `None` unless the lowest rating is at most `max_ratio` of
+/// the highest: a few percent of spread is binning or favoured-core boost on a
+/// homogeneous part, not a second class of core.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn split_by_rating(ratings: &[(usize, u64)], max_ratio: f64) -> Option<(Vec<usize>, Vec<usize>)> {
+    let lowest = ratings.iter().map(|r| r.1).min()?;
+    let highest = ratings.iter().map(|r| r.1).max()?;
+    if highest == 0 || lowest as f64 > highest as f64 * max_ratio {
+        return None;
+    }
+    let class = |efficient: bool| {
+        ratings
+            .iter()
+            .filter(|r| (r.1 == lowest) == efficient)
+            .map(|r| r.0)
+            .collect()
+    };
+    Some((class(false), class(true)))
 }
 
 /// Values fixed when this crate was compiled.
@@ -292,10 +368,58 @@ mod platform {
 
 #[cfg(target_os = "linux")]
 mod platform {
-    use super::{capture, SystemInfo};
+    use super::{capture, split_by_rating, SystemInfo};
+    use crate::affinity::parse_cpu_list;
     use std::collections::BTreeSet;
     use std::fs;
 
+    /// Frequency spread below which CPUs are taken to be one class. Intel's
+    /// favoured cores boost a few hundred MHz above their siblings; E-cores
+    /// top out well over 20% below P-cores.
+    const FREQUENCY_RATIO: f64 = 0.85;
+
+    /// Read one integer per online CPU from `cpu{N}/{file}` under `/sys`.
+    fn per_cpu(online: &[usize], file: &str) -> Option<Vec<(usize, u64)>> {
+        online
+            .iter()
+            .map(|&cpu| {
+                let path = format!("/sys/devices/system/cpu/cpu{cpu}/{file}");
+                let value = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+                Some((cpu, value))
+            })
+            .collect()
+    }
+
+    /// Which logical CPUs are performance and which efficiency cores.
+    ///
+    /// Three sources, most authoritative first. Intel hybrid parts register a
+    /// separate PMU per core type, each listing its CPUs. Arm kernels publish
+    /// the scheduler's `cpu_capacity` per CPU. Failing both, a large spread in
+    /// maximum frequency still identifies the classes.
+    fn core_classes() -> Option<(Vec<usize>, Vec<usize>, &'static str)> {
+        let read_list = |path: &str| {
+            fs::read_to_string(path)
+                .ok()
+                .and_then(|text| parse_cpu_list(&text).ok())
+        };
+        if let (Some(p), Some(e)) = (
+            read_list("/sys/devices/cpu_core/cpus"),
+            read_list("/sys/devices/cpu_atom/cpus"),
+        ) {
+            return Some((p, e, "hybrid_pmu"));
+        }
+
+        let online = read_list("/sys/devices/system/cpu/online")?;
+        if let Some(capacity) = per_cpu(&online, "cpu_capacity") {
+            // Capacities are normalised to 1024 for the biggest core, and any
+            // difference at all is the kernel declaring asymmetry.
+            return split_by_rating(&capacity, 1.0 - f64::EPSILON)
+                .map(|(p, e)| (p, e, "cpu_capacity"));
+        }
+        let frequency = per_cpu(&online, "cpufreq/cpuinfo_max_freq")?;
+        split_by_rating(&frequency, FREQUENCY_RATIO).map(|(p, e)| (p, e, "cpuinfo_max_freq"))
+    }
+
     /// First value for `key` in `/proc/cpuinfo`.
     fn cpuinfo_field(text: &str, key: &str) -> Option<String> {
         text.lines()
@@ -400,6 +524,20 @@ mod platform {
                     .map(|v| v.trim_matches('"').to_string())
             })
             .or_else(|| capture("uname", &["-r"]));
+
+        if let Some((performance, efficiency, source)) = core_classes() {
+            // Counts describe the machine as this process sees it. Under a
+            // restricted CPU set the lists still name every core of each
+            // class, but the counts would no longer add up, so they are left
+            // unset rather than contradict `logical_cores`.
+            if performance.len() + efficiency.len() == info.logical_cores {
+                info.performance_cores = Some(performance.len());
+                info.efficiency_cores = Some(efficiency.len());
+            }
+            info.performance_cpus = performance;
+            info.efficiency_cpus = efficiency;
+            info.core_class_source = Some(source.to_string());
+        }
     }
 }
 
@@ -488,6 +626,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn a_capacity_gap_splits_big_from_little() {
+        // Four LITTLE cores at 446, three big at 871, one prime at 1024.
+        let ratings: Vec<(usize, u64)> = (0..8)
+            .map(|cpu| {
+                let capacity = match cpu {
+                    0..=3 => 446,
+                    4..=6 => 871,
+                    _ => 1024,
+                };
+                (cpu, capacity)
+            })
+            .collect();
+        let (p, e) = split_by_rating(&ratings, 0.999).unwrap();
+        assert_eq!(p, vec![4, 5, 6, 7]);
+        assert_eq!(e, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn favoured_core_boost_is_not_a_second_class() {
+        // Turbo Boost Max 3.0: two cores boost 200 MHz above the rest.
+        let ratings = [
+            (0, 5_200_000),
+            (1, 5_200_000),
+            (2, 5_000_000),
+            (3, 5_000_000),
+        ];
+        assert!(split_by_rating(&ratings, 0.85).is_none());
+        let uniform = [(0, 1024), (1, 1024)];
+        assert!(split_by_rating(&uniform, 0.999).is_none());
+    }
+
+    #[test]
+    fn core_class_lists_match_their_counts() {
+        let info = SystemInfo::detect();
+        assert_eq!(
+            info.performance_cpus.is_empty(),
+            info.efficiency_cpus.is_empty(),
+            "classes are detected together or not at all"
+        );
+        if let Some(p) = info.performance_cores {
+            if !info.performance_cpus.is_empty() {
+                assert_eq!(p, info.performance_cpus.len());
+            }
+        }
+    }
+
     #[cfg(any(target_os = "macos", target_os = "linux"))]
     #[test]
     fn capture_returns_none_for_missing_binary() {
@@ -19,6 +19,13 @@
 //! read of the same underlying hardware. So the measurement clock is `Instant`,
 //! and the raw counter is opt-in.
 //!
+//! ## The core clock is estimated, not read
+//!
+//! Neither counter above ticks with the core: both run at a fixed frequency
+//! whatever the CPU is boosting to. [`core_clock_hz`] recovers the actual
+//! clock by timing a chain of dependent integer adds — one add per cycle on
+//! every out-of-order core this suite targets — against the fixed counter.
+//!
 //! ## Resolution is measured, not assumed
 //!
 //! [`resolution_nanos`] empirically determines the smallest non-zero interval
@@ -71,6 +78,39 @@ pub fn cycles_per_second() -> f64 {
     *FREQ.get_or_init(imp::calibrate_frequency)
 }
 
+/// Estimate the calling thread's current core clock, in Hz.
+///
+/// Runs a chain of dependent register adds, whose latency is one core cycle,
+/// and times it against [`cycles`]. Takes about a quarter of a millisecond at
+/// 3 GHz: short enough to run between measurement rounds without the clock
+/// settling to a different state, long enough that the 24 MHz counter on
+/// Apple silicon resolves it to about a tenth of a percent.
+///
+/// The best of three attempts is kept, since an interrupt can only make the
+/// chain look slower. Returns `None` on architectures without an
+/// implementation.
+pub fn core_clock_hz() -> Option<f64> {
+    /// Each block is 16 dependent adds.
+    const BLOCKS: u64 = 1 << 14;
+    const ATTEMPTS: usize = 3;
+
+    let ticks_per_sec = cycles_per_second();
+    let mut best: Option<f64> = None;
+    for _ in 0..ATTEMPTS {
+        let start = cycles();
+        if !imp::add_chain(BLOCKS) {
+            return None;
+        }
+        let ticks = cycles().wrapping_sub(start);
+        if ticks == 0 {
+            continue;
+        }
+        let hz = (BLOCKS * 16) as f64 * ticks_per_sec / ticks as f64;
+        best = Some(best.map_or(hz, |b| b.max(hz)));
+    }
+    best
+}
+
 /// Smallest non-zero interval [`now_nanos`] can distinguish, in nanoseconds.
 ///
 /// Measured by spinning until the clock advances, repeated to take the minimum
@@ -138,6 +178,42 @@ mod imp {
         };
         freq as f64
     }
+
+    /// `blocks` × 16 dependent adds.
+    pub fn add_chain(blocks: u64) -> bool {
+        let mut acc: u64 = 0;
+        let mut n = blocks.max(1);
+        // SAFETY: register-only arithmetic and a local branch; touches no
+        // memory and no stack.
+        unsafe {
+            core::arch::asm!(
+                "2:",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "add {acc}, {acc}, #1",
+                "subs {n}, {n}, #1",
+                "b.ne 2b",
+                acc = inout(reg) acc,
+                n = inout(reg) n,
+                options(nomem, nostack),
+            );
+        }
+        std::hint::black_box((acc, n));
+        true
+    }
 }
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -172,6 +248,47 @@ mod imp {
         let delta = cycles().wrapping_sub(c0) as f64;
         delta / elapsed
     }
+
+    /// `blocks` × 16 dependent adds. The loop counter runs on a separate
+    /// dependency chain, so it overlaps the adds and costs no cycles of its
+    /// own. The addend is a register rather than an immediate: recent Intel
+    /// cores fold chains of `add reg, imm` at rename, which would retire
+    /// several "dependent" adds per cycle and overstate the clock.
+    pub fn add_chain(blocks: u64) -> bool {
+        let mut acc: usize = 0;
+        let mut n = blocks.max(1) as usize;
+        // SAFETY: register-only arithmetic and a local branch; touches no
+        // memory and no stack.
+        unsafe {
+            core::arch::asm!(
+                "2:",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "add {acc}, {one}",
+                "dec {n}",
+                "jnz 2b",
+                acc = inout(reg) acc,
+                n = inout(reg) n,
+                one = in(reg) 1usize,
+                options(nomem, nostack),
+            );
+        }
+        std::hint::black_box((acc, n));
+        true
+    }
 }
 
 #[cfg(not(any(target_arch = "aarch64", target_arch = "x86", target_arch = "x86_64")))]
@@ -186,6 +303,11 @@ mod imp {
     pub fn calibrate_frequency() -> f64 {
         1e9
     }
+
+    /// No dependent-add chain is implemented here, so no core clock estimate.
+    pub fn add_chain(_blocks: u64) -> bool {
+        false
+    }
 }
 
 #[cfg(test)]
@@ -224,6 +346,14 @@ mod tests {
         );
     }
 
+    #[cfg(any(target_arch = "aarch64", target_arch = "x86", target_arch = "x86_64"))]
+    #[test]
+    fn core_clock_is_plausible() {
+        let hz = core_clock_hz().expect("implemented on this architecture");
+        // 200 MHz to 10 GHz: a throttled VM to a heavily overclocked desktop.
+        assert!((2e8..1e10).contains(&hz), "core clock implausible: {hz} Hz");
+    }
+
     #[test]
     fn resolution_is_bounded() {
         let r = resolution_nanos();
@@ -0,0 +1,215 @@
+//! Bandwidth against working-set size — the cache hierarchy from the
+//! throughput side.
+//!
+//! [`latency::sweep`](crate::latency::sweep) maps the hierarchy by how long one
+//! miss takes. This maps it by how fast each level streams: a working set that
+//! fits in L1 is read at the core's load-port rate, and every level beyond
+//! delivers less, down to DRAM. The plateaus line up with the latency curve's,
+//! and the two together describe a memory system better than either alone.
+//!
+//! Two kernels, over `u64` arrays so that the loops vectorise without any
+//! floating-point reassociation:
+//!
+//! * **Read** sums the array: 8 bytes per element, reads only.
+//! * **Read-modify-write** increments every element in place: 16 bytes per
+//!   element, counted as one read and one write in STREAM's manner.
+//!
+//! The working set is the total across threads, split into one slice per
+//! thread as [`stream`](crate::stream) splits its arrays, so the shared levels
+//! — L3 and DRAM — sit at the same sizes at any thread count. A private level
+//! holds `threads` times its capacity at all threads, since each core brings
+//! its own, and its plateau runs correspondingly further right.
+//!
+//! Threads are pinned physical-first where the platform allows. None of this is
+//! scored.
+
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+
+use threadstone_core::affinity::{self, Affinity};
+pub use threadstone_core::sweep::{Access, BandwidthPoint};
+
+/// Measure both kernels over every size in `sizes` at each of `threads`, for
+/// at least `min_millis` per point, calling `progress` before each one.
+pub fn sweep(
+    sizes: &[usize],
+    threads: &[usize],
+    min_millis: u64,
+    progress: &mut dyn FnMut(usize, Access, usize),
+) -> Vec<BandwidthPoint> {
+    let mut points = Vec::new();
+    for &n in threads {
+        for access in Access::ALL {
+            for &bytes in sizes {
+                progress(bytes, access, n);
+                points.push(BandwidthPoint {
+                    bytes,
+                    access,
+                    threads: n,
+                    bandwidth_gib_s: measure(access, bytes, n, min_millis),
+                });
+            }
+        }
+    }
+    points
+}
+
+/// Sizes for a bandwidth sweep: powers of two from 4 KiB to 256 MiB, or on to
+/// four times `llc_bytes` where that is further, so the last points are DRAM
+/// on any machine.
+pub fn default_sizes(llc_bytes: Option<u64>) -> Vec<usize> {
+    let beyond = llc_bytes.map_or(0, |l| (l as usize).saturating_mul(4));
+    let mut sizes = crate::latency::default_sweep_sizes();
+    while sizes.last().is_some_and(|&s| s < beyond) {
+        sizes.push(sizes[sizes.len() - 1] * 2);
+    }
+    sizes
+}
+
+/// Bandwidth in GiB/s for one kernel, size and thread count.
+///
+/// Every thread allocates and first-touches its own slice, then all of them
+/// make the same number of passes between two barriers. The pass count grows
+/// until one such round lasts `min_millis`, so small working sets are not
+/// timed over a window too short for the clock.
+fn measure(access: Access, bytes: usize, threads: usize, min_millis: u64) -> f64 {
+    let elements = (bytes / 8).max(threads);
+    let target = Duration::from_millis(min_millis);
+    let cpus = Affinity::PhysicalFirst.resolve(threads).ok();
+    // Zero passes is the signal to stop.
+    let passes = AtomicU64::new(0);
+    let sync = Barrier::new(threads + 1);
+
+    std::thread::scope(|scope| {
+        for index in 0..threads {
+            let (passes, sync, cpus) = (&passes, &sync, &cpus);
+            let len = elements / threads + usize::from(index < elements % threads);
+            scope.spawn(move || {
+                if let Some(cpus) = cpus {
+                    // Unpinned is still a valid measurement, only a noisier one.
+                    let _ = affinity::bind_current_thread(cpus[index]);
+                }
+                let mut data: Vec<u64> = (0..len as u64).collect();
+                let mut sink = 0;
+                loop {
+                    sync.wait();
+                    let count = passes.load(Ordering::Acquire);
+                    if count == 0 {
+                        break;
+                    }
+                    for _ in 0..count {
+                        // Through `black_box`, or a read pass over data
+                        // nothing changes would be computed once and reused.
+                        sink ^= pass(access, black_box(&mut data));
+                    }
+                    sync.wait();
+                }
+                black_box(sink);
+            });
+        }
+
+        // One untimed pass first, so the slices are resident in whatever
+        // level holds them before the clock starts.
+        round(&passes, &sync, 1);
+        let mut count = 1;
+        let elapsed = loop {
+            count *= 4;
+            let elapsed = round(&passes, &sync, count);
+            if elapsed >= target || count >= u64::MAX / 4 {
+                break elapsed;
+            }
+        };
+        passes.store(0, Ordering::Release);
+        sync.wait();
+
+        let moved = count as f64 * elements as f64 * access.bytes_per_element() as f64;
+        moved / elapsed.as_secs_f64() / (1u64 << 30) as f64
+    })
+}
+
+/// Run `count` passes on every thread and time them.
+fn round(passes: &AtomicU64, sync: &Barrier, count: u64) -> Duration {
+    passes.store(count, Ordering::Release);
+    sync.wait();
+    let started = Instant::now();
+    sync.wait();
+    started.elapsed()
+}
+
+/// One pass of `access` over `data`, returning a value that depends on it.
+#[inline]
+fn pass(access: Access, data: &mut [u64]) -> u64 {
+    match access {
+        Access::Read => {
+            // Eight independent sums, so the adds are not one serial chain
+            // and the loop vectorises.
+            let mut lanes = [0u64; 8];
+            let chunks = data.chunks_exact(8);
+            let tail = chunks
+                .remainder()
+                .iter()
+                .fold(0u64, |s, &v| s.wrapping_add(v));
+            for chunk in chunks {
+                for (lane, &v) in lanes.iter_mut().zip(chunk) {
+                    *lane = lane.wrapping_add(v);
+                }
+            }
+            lanes.iter().fold(tail, |s, &v| s.wrapping_add(v))
+        }
+        Access::ReadModifyWrite => {
+            for v in data.iter_mut() {
+                *v = v.wrapping_add(1);
+            }
+            data[data.len() / 2]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_kernel_is_measured_at_every_size_and_thread_count() {
+        let mut seen = 0;
+        let points = sweep(&[4096, 1 << 20], &[1, 2], 2, &mut |_, _, _| seen += 1);
+        assert_eq!(points.len(), 8);
+        assert_eq!(seen, 8);
+        for access in Access::ALL {
+            for threads in [1, 2] {
+                let n = points
+                    .iter()
+                    .filter(|p| p.access == access && p.threads == threads)
+                    .count();
+                assert_eq!(n, 2, "{access:?} at {threads} threads");
+            }
+        }
+        for p in &points {
+            assert!(
+                p.bandwidth_gib_s > 0.0 && p.bandwidth_gib_s.is_finite(),
+                "{p:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn default_sizes_reach_well_beyond_the_last_level_cache() {
+        let plain = default_sizes(None);
+        assert_eq!(plain.first(), Some(&4096));
+        assert_eq!(plain.last(), Some(&(256 << 20)));
+        // A 384 MiB L3 needs at least 1.5 GiB to be sure of DRAM.
+        let big = default_sizes(Some(384 << 20));
+        assert!(*big.last().unwrap() >= 1536 << 20);
+        assert!(big.windows(2).all(|w| w[1] == 2 * w[0]));
+    }
+
+    #[test]
+    fn passes_depend_on_the_data() {
+        let mut data = vec![1u64, 2, 3, 4];
+        assert_eq!(pass(Access::Read, &mut data), 10);
+        assert_eq!(pass(Access::ReadModifyWrite, &mut data), 4);
+        assert_eq!(data, [2, 3, 4, 5]);
+    }
+}
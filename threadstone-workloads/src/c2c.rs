@@ -0,0 +1,227 @@
+//! Core-to-core latency — one cache line bounced between two pinned threads.
+//!
+//! Two threads, each bound to one CPU of the pair, share a single cache line
+//! holding a counter. The first stores an odd value and waits; the second sees
+//! it, stores the next even value, and waits in turn. Every store has to take
+//! the line away from the other core before it can complete, and every load
+//! that sees it has to fetch the line back, so each round trip is two
+//! cache-line transfers and nothing else. Half a round trip is the one-way
+//! latency.
+//!
+//! Round trips are timed in batches, and a pair's figure is the median batch,
+//! so a preempted batch does not move it. Every pair of the chosen CPUs is
+//! measured once and the matrix is filled symmetrically: the transfer cost
+//! between two cores does not depend on which one started.
+//!
+//! Not a [`Kernel`](threadstone_core::Kernel): the runner pins workers to CPUs
+//! of its own choosing, and this needs every pair in turn. Linux only, since
+//! it is meaningless without pinning.
+
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Barrier, Mutex};
+use std::time::{Duration, Instant};
+
+use threadstone_core::affinity::{self, Affinity, Topology};
+use threadstone_core::coherence::{self, CoreToCore};
+use threadstone_core::stats;
+
+/// Round trips per timed batch. A few milliseconds on any machine, so the
+/// clock reads are noise.
+const BATCH: u64 = 2000;
+
+/// Round trips before timing starts, to settle both threads on their CPUs
+/// and the line in the pair's caches.
+const WARMUP: u64 = 500;
+
+/// Timed batches per pair at the least.
+const MIN_BATCHES: usize = 5;
+
+/// Value that tells the responding thread to stop.
+const STOP: u64 = u64::MAX;
+
+/// A counter alone on its line. 128 bytes covers the adjacent-line
+/// prefetcher on x86 and the 128-byte lines of Apple silicon.
+#[repr(align(128))]
+struct Line(AtomicU64);
+
+/// One CPU per physical core this process may use, in CPU order. SMT
+/// siblings share a core's L1 and would measure that, not a transfer.
+pub fn default_cpus() -> Option<Vec<usize>> {
+    let topology = Topology::detect()?;
+    let order = Affinity::PhysicalFirst.resolve(topology.cpus.len()).ok()?;
+    let mut cores: Vec<(usize, usize)> = Vec::new();
+    let mut cpus = Vec::new();
+    for cpu in order {
+        let location = topology.cpus.iter().find(|c| c.cpu == cpu)?;
+        if !cores.contains(&(location.package, location.core)) {
+            cores.push((location.package, location.core));
+            cpus.push(cpu);
+        }
+    }
+    cpus.sort_unstable();
+    Some(cpus)
+}
+
+/// Measure every pair of `cpus` for at least `min_millis` each, calling
+/// `progress` with the pair about to be measured and how many are done.
+///
+/// `interrupted` is asked before each pair, since a matrix over many cores
+/// takes minutes; once it says yes, the matrix is abandoned.
+pub fn measure(
+    cpus: &[usize],
+    min_millis: u64,
+    progress: &mut dyn FnMut((usize, usize), usize, usize),
+    interrupted: &dyn Fn() -> bool,
+) -> Result<CoreToCore, String> {
+    if cpus.len() < 2 {
+        return Err("at least two CPUs are needed to bounce a line between".to_string());
+    }
+    if !affinity::supported() {
+        return Err("thread pinning is only supported on Linux".to_string());
+    }
+    let target = Duration::from_millis(min_millis);
+    let n = cpus.len();
+    let total = n * (n - 1) / 2;
+    let mut latency = vec![vec![None; n]; n];
+    let mut done = 0;
+    for i in 0..n {
+        for j in i + 1..n {
+            if interrupted() {
+                return Err("interrupted".to_string());
+            }
+            progress((cpus[i], cpus[j]), done, total);
+            let ns = pair(cpus[i], cpus[j], target)?;
+            latency[i][j] = Some(ns);
+            latency[j][i] = Some(ns);
+            done += 1;
+        }
+    }
+    Ok(CoreToCore::new(
+        cpus.to_vec(),
+        coherence::clusters(cpus),
+        latency,
+    ))
+}
+
+/// One-way latency between `a` and `b`, in nanoseconds.
+fn pair(a: usize, b: usize, target: Duration) -> Result<f64, String> {
+    let line = Line(AtomicU64::new(0));
+    let line = &line.0;
+    // Both threads bind before either touches the line, and both give up if
+    // either could not, so neither waits for a partner that has gone.
+    let bound = Barrier::new(2);
+    let failed = Mutex::new(None);
+    let bind = |cpu| {
+        if let Err(e) = affinity::bind_current_thread(cpu) {
+            failed.lock().unwrap().get_or_insert(e);
+        }
+        bound.wait();
+        failed.lock().unwrap().is_none()
+    };
+
+    let latency = std::thread::scope(|scope| {
+        scope.spawn(|| {
+            if !bind(b) {
+                return;
+            }
+            let mut expect = 1;
+            loop {
+                let seen = line.load(Ordering::Acquire);
+                if seen == STOP {
+                    return;
+                }
+                if seen == expect {
+                    line.store(expect + 1, Ordering::Release);
+                    expect += 2;
+                } else {
+                    std::hint::spin_loop();
+                }
+            }
+        });
+
+        scope
+            .spawn(|| {
+                if !bind(a) {
+                    return None;
+                }
+                let mut next = 1;
+                let mut round_trips = |count: u64| {
+                    for _ in 0..count {
+                        line.store(next, Ordering::Release);
+                        while line.load(Ordering::Acquire) != next + 1 {
+                            std::hint::spin_loop();
+                        }
+                        next += 2;
+                    }
+                };
+
+                round_trips(WARMUP);
+                let mut batches = Vec::new();
+                let started = Instant::now();
+                while batches.len() < MIN_BATCHES || started.elapsed() < target {
+                    let batch = Instant::now();
+                    round_trips(BATCH);
+                    batches.push(batch.elapsed().as_secs_f64() / (2 * BATCH) as f64 * 1e9);
+                }
+                line.store(STOP, Ordering::Release);
+                black_box(next);
+                stats::median(&batches)
+            })
+            .join()
+            .expect("the initiator does not panic")
+    });
+
+    match failed.into_inner().unwrap() {
+        Some(e) => Err(e),
+        None => latency.ok_or_else(|| format!("no round trip completed between CPUs {a} and {b}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fewer_than_two_cpus_have_no_pair() {
+        assert!(measure(&[0], 1, &mut |_, _, _| {}, &|| false).is_err());
+    }
+
+    #[test]
+    fn an_interrupt_stops_the_matrix_before_the_next_pair() {
+        if !affinity::supported() {
+            return;
+        }
+        let mut calls = 0;
+        let result = measure(&[0, 1, 2], 1, &mut |_, _, _| calls += 1, &|| true);
+        assert_eq!(result.unwrap_err(), "interrupted");
+        assert_eq!(calls, 0, "no pair is started once interrupted");
+    }
+
+    #[test]
+    fn every_pair_is_measured_once_and_mirrored() {
+        let Some(cpus) = default_cpus().filter(|c| c.len() >= 2) else {
+            return;
+        };
+        let cpus = &cpus[..cpus.len().min(3)];
+        let mut calls = Vec::new();
+        let matrix = measure(
+            cpus,
+            1,
+            &mut |pair, done, total| calls.push((pair, done, total)),
+            &|| false,
+        )
+        .unwrap();
+        let n = cpus.len();
+        assert_eq!(calls.len(), n * (n - 1) / 2);
+        for i in 0..n {
+            assert!(matrix.latency_ns[i][i].is_none());
+            for j in 0..n {
+                if i != j {
+                    assert!(matrix.latency_ns[i][j].unwrap() > 0.0);
+                    assert_eq!(matrix.latency_ns[i][j], matrix.latency_ns[j][i]);
+                }
+            }
+        }
+    }
+}
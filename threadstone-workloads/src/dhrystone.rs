@@ -491,6 +491,7 @@ pub fn dmips(dhrystones_per_sec: f64) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use threadstone_core::pages::PagePolicy;
 
     fn as_str(buf: &Str30) -> &str {
         let end = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
@@ -632,6 +633,7 @@ mod tests {
         let mut state = k.setup(&SetupCtx {
             threads: 1,
             thread_index: 0,
+            pages: PagePolicy::Default,
         });
         let checksum = state.run(10_000);
         assert_ne!(checksum, 0, "checksum must depend on real work");
@@ -32,10 +32,28 @@
 //! Rather than report a flattering wrong number or silently change the metric,
 //! the suite measures one honest figure and excludes it from the multi-core
 //! score.
+//! The loaded metric is measured separately, and unscored, by
+//! [`loaded`](crate::loaded).
+//!
+//! # Page size
+//!
+//! A hop to a random line in 256 MiB almost always needs a translation the
+//! TLB does not hold. With 4 KiB pages that adds a page walk, itself a chain
+//! of dependent loads, to most hops; with 2 MiB pages the 128 translations
+//! mostly fit the second-level TLB. The figure therefore depends on the page
+//! size as much as on the DRAM, and the buffer is backed as `--pages` asks
+//! (see [`threadstone_core::pages`]) so that the choice is made, and recorded,
+//! rather than left to the kernel's huge-page mode.
+//!
+//! The buffer size is a parameter, `mib` (`-w latency:mib=64`), for reading
+//! one level of the hierarchy without a whole sweep. Only 256 is scored.
 
 use threadstone_core::kernel::{
-    Footprint, Kernel, KernelInfo, KernelState, Scaling, SetupCtx, Unit,
+    Footprint, Kernel, KernelInfo, KernelState, Param, ParamError, Scaling, SetupCtx, Unit,
 };
+use threadstone_core::pages::{PageBuf, PagePolicy};
+use threadstone_core::stats::Summary;
+pub use threadstone_core::sweep::SweepPoint;
 
 use crate::rng::Rng;
 
@@ -44,23 +62,31 @@ use crate::rng::Rng;
 /// 64 bytes on x86-64 and on Apple silicon's 128-byte-line cores this still
 /// guarantees at most two nodes per line, which does not materially help a
 /// randomised chase.
-const LINE_BYTES: usize = 64;
+pub(crate) const LINE_BYTES: usize = 64;
 
-/// `usize` values per node, so each node occupies one cache line.
-const WORDS_PER_NODE: usize = LINE_BYTES / std::mem::size_of::<usize>();
+/// Bytes in one chase word.
+const WORD_BYTES: usize = std::mem::size_of::<usize>();
 
-/// Buffer size for the headline measurement.
+/// Buffer size for the headline measurement, and the one it is scored at.
 ///
 /// 256 MiB is far past the largest last-level cache in a consumer machine, so
 /// every hop reaches DRAM.
 pub const DEFAULT_BYTES: usize = 256 << 20;
 
+/// Finest step a sweep may take between neighbouring sizes.
+///
+/// A step cuts each octave into `1 / (step - 1)` parts, so anything much finer
+/// would ask for thousands of points per octave; at 1.01 a full sweep is
+/// already well over a thousand sizes.
+pub const MIN_STEP: f64 = 1.01;
+
 /// Seed for the permutation, fixed so every machine chases the same cycle.
-const SEED: u64 = 0x1A7E_4C7A_5EED;
+pub(crate) const SEED: u64 = 0x1A7E_4C7A_5EED;
 
 /// A pointer-chase buffer: `chase[i]` holds the word index of the next node.
-struct Chase {
-    chase: Vec<usize>,
+pub(crate) struct Chase {
+    chase: PageBuf<usize>,
+    nodes: usize,
     /// Where the next `run` resumes, so consecutive calls continue the cycle
     /// rather than restarting from a node that may still be cached.
     cursor: usize,
@@ -69,7 +95,23 @@ struct Chase {
 impl Chase {
     /// Build a single closed cycle covering every node in a `bytes` buffer.
     fn new(bytes: usize, seed: u64) -> Chase {
-        let nodes = (bytes / LINE_BYTES).max(2);
+        Chase::with_pages(bytes, seed, PagePolicy::Default)
+    }
+
+    /// [`Chase::new`], with the buffer backed as `pages` asks.
+    pub(crate) fn with_pages(bytes: usize, seed: u64, pages: PagePolicy) -> Chase {
+        Chase::spaced((bytes / LINE_BYTES).max(2), LINE_BYTES, seed, pages)
+    }
+
+    /// A cycle over `nodes` nodes, one every `stride` bytes.
+    ///
+    /// Within its stride each node sits one line further along than the last,
+    /// wrapping, so that a page-sized stride does not put every node in the
+    /// same cache set. With a stride of one line every node is at its start.
+    pub(crate) fn spaced(nodes: usize, stride: usize, seed: u64, pages: PagePolicy) -> Chase {
+        let nodes = nodes.max(2);
+        let lines = (stride / LINE_BYTES).max(1);
+        let word = |node: usize| (node * stride + node % lines * LINE_BYTES) / WORD_BYTES;
         let mut order: Vec<usize> = (0..nodes).collect();
         Rng::new(seed).shuffle(&mut order);
 
@@ -77,18 +119,74 @@ impl Chase {
         // order[i+1], and the last points back at the first. Because `order` is
         // a permutation, following the links visits every node exactly once
         // before returning to the start.
-        let mut chase = vec![0usize; nodes * WORDS_PER_NODE];
+        let mut chase = PageBuf::filled(nodes * stride.max(LINE_BYTES) / WORD_BYTES, 0usize, pages);
         for i in 0..nodes {
             let from = order[i];
             let to = order[(i + 1) % nodes];
-            chase[from * WORDS_PER_NODE] = to * WORDS_PER_NODE;
+            chase[word(from)] = word(to);
         }
 
         Chase {
             chase,
-            cursor: order[0] * WORDS_PER_NODE,
+            nodes,
+            cursor: word(order[0]),
+        }
+    }
+
+    /// Nodes in the cycle: the hops in one full lap.
+    pub(crate) fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// `chains` positions spread evenly around the cycle, found by walking one
+    /// lap of it from the cursor, which also touches every node.
+    pub(crate) fn spread(&self, chains: usize) -> Vec<usize> {
+        let chains = chains.clamp(1, self.nodes);
+        let mut starts = Vec::with_capacity(chains);
+        let mut p = self.cursor;
+        for hop in 0..self.nodes {
+            if hop * chains % self.nodes < chains && starts.len() < chains {
+                starts.push(p);
+            }
+            p = self.chase[p];
+        }
+        starts
+    }
+
+    /// Advance every position in `cursors` by `hops`, one hop of each in turn.
+    /// The positions do not depend on each other, so their loads can all be in
+    /// flight at once.
+    pub(crate) fn run_interleaved(&self, cursors: &mut [usize], hops: u64) -> u64 {
+        match cursors.len() {
+            1 => self.interleave::<1>(cursors, hops),
+            2 => self.interleave::<2>(cursors, hops),
+            4 => self.interleave::<4>(cursors, hops),
+            8 => self.interleave::<8>(cursors, hops),
+            16 => self.interleave::<16>(cursors, hops),
+            32 => self.interleave::<32>(cursors, hops),
+            _ => {
+                for _ in 0..hops {
+                    for p in cursors.iter_mut() {
+                        *p = self.chase[*p];
+                    }
+                }
+                cursors.iter().fold(0, |acc, &p| acc ^ p) as u64
+            }
         }
     }
+
+    /// [`Chase::run_interleaved`] with the positions in an array of known
+    /// length, which the compiler can keep in registers.
+    fn interleave<const N: usize>(&self, cursors: &mut [usize], hops: u64) -> u64 {
+        let mut p: [usize; N] = cursors.try_into().expect("N cursors");
+        for _ in 0..hops {
+            for q in p.iter_mut() {
+                *q = self.chase[*q];
+            }
+        }
+        cursors.copy_from_slice(&p);
+        p.iter().fold(0, |acc, &q| acc ^ q) as u64
+    }
 }
 
 impl KernelState for Chase {
@@ -105,7 +203,18 @@ impl KernelState for Chase {
 }
 
 /// The memory latency workload.
-pub struct LatencyKernel;
+pub struct LatencyKernel {
+    /// Chase buffer size in bytes.
+    bytes: usize,
+}
+
+impl Default for LatencyKernel {
+    fn default() -> Self {
+        LatencyKernel {
+            bytes: DEFAULT_BYTES,
+        }
+    }
+}
 
 impl Kernel for LatencyKernel {
     fn info(&self) -> KernelInfo {
@@ -124,7 +233,11 @@ impl Kernel for LatencyKernel {
     fn setup(&self, ctx: &SetupCtx) -> Box<dyn KernelState> {
         // Distinct permutations per thread, so that if a caller does force a
         // multi-threaded run the threads do not share a chase pattern.
-        Box::new(Chase::new(DEFAULT_BYTES, SEED ^ ctx.thread_index as u64))
+        Box::new(Chase::with_pages(
+            self.bytes,
+            SEED ^ ctx.thread_index as u64,
+            ctx.pages,
+        ))
     }
 
     fn rate(&self, iters_per_thread: u64, _threads: usize, secs: f64) -> f64 {
@@ -133,15 +246,23 @@ impl Kernel for LatencyKernel {
         // multiplied.
         secs / iters_per_thread as f64 * 1e9
     }
-}
 
-/// One point on a cache-hierarchy sweep.
-#[derive(Debug, Clone, Copy)]
-pub struct SweepPoint {
-    /// Working set size in bytes.
-    pub bytes: usize,
-    /// Measured latency per access, in nanoseconds.
-    pub latency_ns: f64,
+    fn params(&self) -> Vec<Param> {
+        vec![Param {
+            name: "mib",
+            summary: "MiB chased",
+            default: (DEFAULT_BYTES >> 20) as u64,
+            min: 1,
+            max: 16 << 10,
+            multiple_of: 1,
+            value: (self.bytes >> 20) as u64,
+        }]
+    }
+
+    fn set_param(&mut self, _name: &str, value: u64) -> Result<(), ParamError> {
+        self.bytes = (value as usize) << 20;
+        Ok(())
+    }
 }
 
 /// Measure chase latency across a range of working-set sizes.
@@ -150,48 +271,78 @@ pub struct SweepPoint {
 /// flat inside each level and steps up at every boundary, so the plateaus name
 /// the cache sizes and the step heights name their access costs. This is the
 /// data behind the hierarchy chart, and it is a far more useful description of
-/// a memory system than any single number.
+/// a memory system than any single number. [`hierarchy::levels`] reads the
+/// plateaus off it.
 ///
-/// Each size is measured for at least `min_millis`, with the iteration count
-/// calibrated per size so small buffers are not measured over a window too
-/// short for the clock.
-pub fn sweep(sizes: &[usize], min_millis: u64) -> Vec<SweepPoint> {
+/// Each size is timed over `samples` windows that together last at least
+/// `min_millis`, with the hop count calibrated per size so small buffers are
+/// not measured over a window too short for the clock.
+///
+/// [`hierarchy::levels`]: threadstone_core::hierarchy::levels
+pub fn sweep(
+    sizes: &[usize],
+    min_millis: u64,
+    samples: usize,
+    progress: &mut dyn FnMut(usize),
+) -> Vec<SweepPoint> {
     sizes
         .iter()
-        .map(|&bytes| SweepPoint {
-            bytes,
-            latency_ns: measure(bytes, min_millis),
+        .map(|&bytes| {
+            progress(bytes);
+            let mut chase = Chase::new(bytes, SEED);
+            let samples = time(&mut chase, min_millis, samples.max(1));
+            let stats = Summary::new(&samples).expect("chase windows are finite");
+            SweepPoint {
+                bytes,
+                latency_ns: stats.median,
+                samples,
+                stats,
+            }
         })
         .collect()
 }
 
-/// Latency in nanoseconds for one working-set size.
-fn measure(bytes: usize, min_millis: u64) -> f64 {
-    use std::time::Instant;
-
-    let mut chase = Chase::new(bytes, SEED);
-    let target = std::time::Duration::from_millis(min_millis);
-
+/// Latencies in nanoseconds for one chase, one per window, over `samples`
+/// windows that together last at least `min_millis`.
+pub(crate) fn time(chase: &mut Chase, min_millis: u64, samples: usize) -> Vec<f64> {
     // Touch every node once so the timed pass measures steady-state behaviour
     // rather than first-touch page faults.
-    let nodes = chase.chase.len() / WORDS_PER_NODE;
-    chase.run(nodes as u64);
+    chase.run(chase.nodes() as u64);
+    windows(&mut |hops| chase.run(hops), min_millis, samples)
+}
 
-    // Grow the hop count until the window is long enough to trust.
-    let mut hops: u64 = 1024;
-    loop {
+/// Nanoseconds per hop of `run`, which takes `hops` steps and returns a value
+/// to keep, over `samples` windows that together last at least `min_millis`.
+pub(crate) fn windows(
+    run: &mut dyn FnMut(u64) -> u64,
+    min_millis: u64,
+    samples: usize,
+) -> Vec<f64> {
+    use std::time::{Duration, Instant};
+
+    let target = Duration::from_millis(min_millis) / samples as u32;
+    let mut timed = |hops: u64| {
         let start = Instant::now();
-        let sink = chase.run(hops);
+        let sink = run(hops);
         let elapsed = start.elapsed();
         std::hint::black_box(sink);
-        if elapsed >= target {
-            return elapsed.as_secs_f64() / hops as f64 * 1e9;
-        }
+        elapsed
+    };
+
+    // Grow the hop count until one window is long enough to trust. The
+    // calibration windows are not kept: the first of them still runs on
+    // whatever the previous size left in the caches.
+    let mut hops: u64 = 1024;
+    while timed(hops) < target {
         let Some(next) = hops.checked_mul(4) else {
-            return elapsed.as_secs_f64() / hops as f64 * 1e9;
+            break;
         };
         hops = next;
     }
+
+    (0..samples)
+        .map(|_| timed(hops).as_secs_f64() / hops as f64 * 1e9)
+        .collect()
 }
 
 /// Sizes for a hierarchy sweep: powers of two from 4 KiB to 256 MiB.
@@ -202,11 +353,36 @@ pub fn default_sweep_sizes() -> Vec<usize> {
     (12..=28).map(|shift| 1usize << shift).collect()
 }
 
+/// Sizes from 4 KiB to 256 MiB, no two neighbours more than `step` apart.
+///
+/// Each octave is cut into equal steps — quarters for a `step` of 1.25, halves
+/// for 1.5 — rather than spaced geometrically, so every power of two stays in
+/// the sweep and so do the 1.25× and 1.5× multiples that caches such as a
+/// 48 KiB L1 or a 1.25 MiB L2 are built in. A `step` of 2 or more gives
+/// [`default_sweep_sizes`]; one below [`MIN_STEP`] panics.
+pub fn sweep_sizes(step: f64) -> Vec<usize> {
+    assert!(step >= MIN_STEP, "a sweep step must be at least {MIN_STEP}");
+    // The first step of an octave cut into `k` parts is the widest, at 1 + 1/k.
+    let parts = (1.0 / (step - 1.0)).ceil().max(1.0) as usize;
+    let mut sizes: Vec<usize> = (12..28)
+        .flat_map(|shift| {
+            let base = 1usize << shift;
+            (0..parts).map(move |i| (base + base * i / parts) / LINE_BYTES * LINE_BYTES)
+        })
+        .chain([1 << 28])
+        .collect();
+    sizes.dedup();
+    sizes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::collections::HashSet;
 
+    /// `usize` values per node in a chase built by [`Chase::new`].
+    const WORDS_PER_NODE: usize = LINE_BYTES / WORD_BYTES;
+
     /// Follow the chase and collect every node it visits.
     fn visited(chase: &Chase, nodes: usize) -> Vec<usize> {
         let mut seen = Vec::with_capacity(nodes);
@@ -285,10 +461,77 @@ mod tests {
     fn same_seed_builds_the_same_cycle() {
         let a = Chase::new(32 * 1024, 7);
         let b = Chase::new(32 * 1024, 7);
-        assert_eq!(a.chase, b.chase);
+        assert_eq!(a.chase[..], b.chase[..]);
         assert_eq!(a.cursor, b.cursor);
     }
 
+    #[test]
+    fn the_page_policy_changes_the_backing_not_the_cycle() {
+        let a = Chase::new(4 << 20, 9);
+        for pages in [PagePolicy::Thp, PagePolicy::Hugetlb, PagePolicy::Small] {
+            let b = Chase::with_pages(4 << 20, 9, pages);
+            assert!(a.chase[..] == b.chase[..], "{pages:?}");
+            assert_eq!(a.cursor, b.cursor);
+        }
+    }
+
+    #[test]
+    fn a_page_stride_puts_one_node_on_each_page_along_a_different_line() {
+        let nodes = 200;
+        let chase = Chase::spaced(nodes, 4096, 5, PagePolicy::Default);
+        assert_eq!(chase.nodes(), nodes);
+        assert_eq!(chase.chase.len() * WORD_BYTES, nodes * 4096);
+        let seen = visited(&chase, nodes);
+        let pages: HashSet<usize> = seen.iter().map(|w| w * WORD_BYTES / 4096).collect();
+        assert_eq!(pages.len(), nodes, "every page must hold exactly one node");
+        for w in &seen {
+            let page = w * WORD_BYTES / 4096;
+            assert_eq!(w * WORD_BYTES % 4096, page % 64 * LINE_BYTES);
+        }
+        assert_eq!(
+            chase.chase[seen[nodes - 1]],
+            chase.cursor,
+            "the cycle must close"
+        );
+    }
+
+    #[test]
+    fn spread_chains_start_evenly_around_the_one_cycle() {
+        let nodes = 1024;
+        let chase = Chase::new(nodes * LINE_BYTES, 6);
+        let lap = visited(&chase, nodes);
+        for chains in [1, 2, 3, 4, 32] {
+            let starts = chase.spread(chains);
+            assert_eq!(starts.len(), chains);
+            let at: Vec<usize> = starts
+                .iter()
+                .map(|s| lap.iter().position(|p| p == s).unwrap())
+                .collect();
+            for (k, &i) in at.iter().enumerate() {
+                assert_eq!(i, (k * nodes).div_ceil(chains), "{chains} chains: {at:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn interleaved_chains_each_follow_the_cycle() {
+        let chase = Chase::new(64 * 1024, 8);
+        for chains in [1, 3, 8] {
+            let mut cursors = chase.spread(chains);
+            let expected: Vec<usize> = cursors
+                .iter()
+                .map(|&start| {
+                    let mut one = Chase::new(64 * 1024, 8);
+                    one.cursor = start;
+                    one.run(77);
+                    one.cursor
+                })
+                .collect();
+            chase.run_interleaved(&mut cursors, 77);
+            assert_eq!(cursors, expected);
+        }
+    }
+
     #[test]
     fn tiny_buffers_do_not_panic() {
         // Guards the `.max(2)` floor: a zero- or one-node cycle is degenerate.
@@ -300,7 +543,7 @@ mod tests {
 
     #[test]
     fn rate_is_nanoseconds_per_hop_and_ignores_threads() {
-        let k = LatencyKernel;
+        let k = LatencyKernel::default();
         // A million hops in 100 ms is 100 ns each.
         assert!((k.rate(1_000_000, 1, 0.1) - 100.0).abs() < 1e-9);
         assert!(
@@ -322,13 +565,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn finer_sweeps_cut_each_octave_evenly_and_keep_the_powers_of_two() {
+        assert_eq!(sweep_sizes(2.0), default_sweep_sizes());
+        let quarters = sweep_sizes(1.25);
+        assert_eq!(&quarters[..5], &[4096, 5120, 6144, 7168, 8192]);
+        assert_eq!(*quarters.last().unwrap(), 256 << 20);
+        assert!(quarters.contains(&(48 << 10)) && quarters.contains(&(1280 << 10)));
+        for pair in quarters.windows(2) {
+            assert!(pair[1] > pair[0] && pair[1] as f64 <= pair[0] as f64 * 1.25);
+        }
+        assert_eq!(sweep_sizes(1.5).len(), 2 * 16 + 1);
+        let finest = sweep_sizes(MIN_STEP);
+        assert!(finest.len() > 1000 && finest.len() <= 101 * 16 + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least")]
+    fn a_step_finer_than_the_floor_is_refused() {
+        sweep_sizes(1.000_001);
+    }
+
     #[test]
     fn sweep_latency_grows_with_the_working_set() {
         // L1-resident accesses must be measurably faster than ones that miss to
         // DRAM. Only two sizes and a short window, to keep the test quick.
-        let points = sweep(&[16 * 1024, 64 << 20], 20);
+        let mut seen = Vec::new();
+        let points = sweep(&[16 * 1024, 64 << 20], 20, 3, &mut |b| seen.push(b));
+        assert_eq!(seen, [16 * 1024, 64 << 20]);
         assert_eq!(points.len(), 2);
-        assert!(points.iter().all(|p| p.latency_ns > 0.0));
+        for p in &points {
+            assert_eq!(p.samples.len(), 3);
+            assert_eq!(p.latency_ns, p.stats.median);
+            assert!(p.latency_ns > 0.0);
+        }
         assert!(
             points[1].latency_ns > points[0].latency_ns * 2.0,
             "a 64 MiB chase ({:.1}ns) should be far slower than a 16 KiB one ({:.1}ns)",
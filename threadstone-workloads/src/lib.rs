@@ -9,11 +9,20 @@
 //! |---|---|
 //! | [`dhrystone`] | Integer ALU, branch prediction, call overhead |
 //! | [`sgemm`] | Floating-point and SIMD throughput out of L2 |
-//! | [`stream`] | Sustained DRAM bandwidth |
+//! | [`stream`] | Sustained DRAM bandwidth, with Copy, Scale and Add unscored beside it |
 //! | [`latency`] | Unhidden memory latency — the one number caches cannot fix |
 //! | [`sha256`] | Dependent-chain integer ALU with no memory traffic |
 //! | [`sort`] | Branch mispredicts and irregular access, as real code produces |
 //!
+//! [`loaded`], [`c2c`], [`bandwidth`], [`tlb`] and [`mlp`] are not workloads:
+//! the first combines the `latency` chase with `stream` traffic to measure
+//! latency under load, the second bounces a cache line between pairs of cores,
+//! the third maps cache bandwidth across working-set sizes, the fourth spaces
+//! the chase a page apart to find the TLBs' reach, and the fifth interleaves
+//! independent chases to count the misses a core keeps in flight. All stay
+//! outside the suite's scores.
+//! [`threadstone_core::hierarchy`] reads cache levels off a latency sweep.
+//!
 //! # Two rules every kernel here follows
 //!
 //! **Inputs are deterministic.** Every buffer is filled from a fixed seed (see
@@ -24,16 +33,28 @@
 //! **Results are observable.** Each `run` returns a checksum derived from real
 //! computed output, which the runner black-boxes. Without that data dependency
 //! LLVM deletes the loop, and the benchmark measures an empty `for`.
+//!
+//! # Sizes are parameters, scored only at their defaults
+//!
+//! Every size above is fixed for scoring, but all except Dhrystone's can be
+//! changed for exploration: each declares its size as a
+//! [`Param`](threadstone_core::kernel::Param), and `-w sgemm:n=1024` sets it.
+//! The registry below always hands out kernels at their defaults.
 
 #![warn(missing_docs)]
 
+pub mod bandwidth;
+pub mod c2c;
 pub mod dhrystone;
 pub mod latency;
+pub mod loaded;
+pub mod mlp;
 pub mod rng;
 pub mod sgemm;
 pub mod sha256;
 pub mod sort;
 pub mod stream;
+pub mod tlb;
 
 use threadstone_core::kernel::Kernel;
 
@@ -45,11 +66,11 @@ use threadstone_core::kernel::Kernel;
 pub fn all() -> Vec<Box<dyn Kernel>> {
     vec![
         Box::new(dhrystone::DhrystoneKernel),
-        Box::new(sgemm::SgemmKernel),
-        Box::new(sha256::Sha256Kernel),
-        Box::new(sort::SortKernel),
-        Box::new(stream::StreamKernel),
-        Box::new(latency::LatencyKernel),
+        Box::new(sgemm::SgemmKernel::default()),
+        Box::new(sha256::Sha256Kernel::default()),
+        Box::new(sort::SortKernel::default()),
+        Box::new(stream::StreamKernel::default()),
+        Box::new(latency::LatencyKernel::default()),
     ]
 }
 
@@ -58,6 +79,18 @@ pub fn by_id(id: &str) -> Option<Box<dyn Kernel>> {
     all().into_iter().find(|k| k.info().id == id)
 }
 
+/// Look up a workload or one of its sub-results by identifier, as a child
+/// process measuring a single pass must.
+pub fn find(id: &str) -> Option<Box<dyn Kernel>> {
+    all()
+        .into_iter()
+        .flat_map(|k| {
+            let subs = k.sub_results();
+            std::iter::once(k).chain(subs)
+        })
+        .find(|k| k.info().id == id)
+}
+
 /// Every workload identifier, for CLI validation and help text.
 pub fn ids() -> Vec<&'static str> {
     all().iter().map(|k| k.info().id).collect()
@@ -68,6 +101,7 @@ mod tests {
     use super::*;
     use std::collections::HashSet;
     use threadstone_core::kernel::{Footprint, Scaling, SetupCtx};
+    use threadstone_core::pages::PagePolicy;
 
     #[test]
     fn registry_has_six_workloads() {
@@ -81,6 +115,58 @@ mod tests {
         assert_eq!(unique.len(), ids.len(), "duplicate workload id in {ids:?}");
     }
 
+    #[test]
+    fn sub_results_are_found_but_are_not_workloads() {
+        for kernel in all() {
+            for sub in kernel.sub_results() {
+                let id = sub.info().id;
+                assert_eq!(find(id).unwrap().info().id, id);
+                assert!(by_id(id).is_none(), "{id} must not be selectable");
+                assert!(!ids().contains(&id), "{id} collides with a workload");
+            }
+        }
+        assert_eq!(find("stream").unwrap().info().id, "stream");
+    }
+
+    #[test]
+    fn the_registry_hands_out_every_workload_at_its_defaults() {
+        for kernel in all() {
+            let info = kernel.info();
+            assert!(kernel.is_canonical(), "{}", info.id);
+            for p in kernel.params() {
+                assert!(
+                    p.min <= p.default && p.default <= p.max,
+                    "{}:{}: default outside its own range",
+                    info.id,
+                    p.name
+                );
+                assert_eq!(p.default % p.multiple_of, 0, "{}:{}", info.id, p.name);
+            }
+        }
+        let sized: Vec<&str> = all()
+            .iter()
+            .filter(|k| !k.params().is_empty())
+            .map(|k| k.info().id)
+            .collect();
+        assert_eq!(sized, ["sgemm", "sha256", "sort", "stream", "latency"]);
+    }
+
+    #[test]
+    fn every_parameter_runs_at_its_smallest_value() {
+        for mut kernel in all() {
+            for p in kernel.params() {
+                kernel.configure(p.name, p.min).unwrap();
+            }
+            let mut state = kernel.setup(&SetupCtx {
+                threads: 2,
+                thread_index: 1,
+                pages: PagePolicy::Default,
+            });
+            assert_ne!(state.run(1), 0, "{}", kernel.info().id);
+            assert!(kernel.rate(1, 2, 1.0) > 0.0);
+        }
+    }
+
     #[test]
     fn identifiers_are_lookup_keys() {
         for id in ids() {
@@ -116,6 +202,7 @@ mod tests {
             let mut state = kernel.setup(&SetupCtx {
                 threads: 1,
                 thread_index: 0,
+                pages: PagePolicy::Default,
             });
             let checksum = state.run(1);
             assert_ne!(
@@ -197,6 +284,7 @@ mod tests {
                 let mut state = kernel.setup(&SetupCtx {
                     threads,
                     thread_index,
+                    pages: PagePolicy::Default,
                 });
                 state.run(1);
                 let _ = info.id;
@@ -0,0 +1,173 @@
+//! Loaded latency — memory latency under injected bandwidth pressure.
+//!
+//! The [`latency`](crate::latency) workload measures one dependent load on an
+//! otherwise idle memory system, and explains why it stays single-threaded.
+//! Real programs rarely get an idle memory system: while one thread chases
+//! pointers, others are streaming, and every queue between the core and the
+//! DRAM fills up. Latency under that load is a different metric, and this
+//! module measures it the way Intel's Memory Latency Checker does in its
+//! loaded-latency mode.
+//!
+//! One thread runs the 256 MiB chase. Every other thread is an *injector*
+//! running the STREAM triad over its own slice of 192 MiB, in bursts of
+//! [`BURST`] elements separated by a delay of spin-loop hints. Stepping the
+//! delay from long to zero steps the pressure from light to saturating, and at
+//! each step the chase's latency is read alongside the bandwidth the
+//! injectors actually achieved. The first point has no injection at all, so
+//! the curve starts from the idle figure.
+//!
+//! The delay is a count of [`std::hint::spin_loop`] calls, whose cost differs
+//! between CPUs, so the delays are only a knob. The achieved bandwidth is the
+//! axis to read the curve against.
+//!
+//! None of this is scored, and nothing here touches the `latency` kernel's
+//! figure.
+
+use std::hint::black_box;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Barrier;
+use std::time::{Duration, Instant};
+
+use threadstone_core::affinity::{self, Affinity};
+use threadstone_core::kernel::KernelState;
+use threadstone_core::pages::PagePolicy;
+use threadstone_core::sweep::LoadedPoint;
+
+use crate::latency::{Chase, DEFAULT_BYTES, SEED};
+use crate::stream::{Stream, BYTES_PER_ELEMENT, TOTAL_ELEMENTS};
+
+/// Triad elements per injection burst: 24 KiB moved.
+pub const BURST: usize = 1024;
+
+/// Hops the chase takes between clock reads, so reading the clock stays far
+/// below one percent of the time measured.
+const HOPS_PER_CHECK: u64 = 1 << 14;
+
+/// How long each step runs before it is measured, so the injectors have left
+/// the previous step's pace behind.
+const SETTLE: Duration = Duration::from_millis(20);
+
+/// Delays to step through, lightest pressure first.
+pub fn default_delays() -> Vec<u32> {
+    vec![5000, 2000, 1000, 500, 200, 100, 50, 20, 0]
+}
+
+/// Measure chase latency with `injectors` threads loading memory at each of
+/// `delays`, after an idle point with none. Each point is measured for at
+/// least `min_millis`.
+///
+/// Where threads can be pinned, the chase gets the first CPU of a
+/// physical-first order and the injectors the next ones, so none of them
+/// shares a core until the machine runs out.
+pub fn measure(
+    injectors: usize,
+    delays: &[u32],
+    min_millis: u64,
+    pages: PagePolicy,
+) -> Vec<LoadedPoint> {
+    let target = Duration::from_millis(min_millis);
+    let cpus = Affinity::PhysicalFirst.resolve(injectors + 1).ok();
+    let pin = |slot: usize| {
+        if let Some(cpus) = &cpus {
+            // Unpinned is still a valid measurement, only a noisier one.
+            let _ = affinity::bind_current_thread(cpus[slot]);
+        }
+    };
+
+    let delay = AtomicU32::new(0);
+    let loading = AtomicBool::new(false);
+    let quit = AtomicBool::new(false);
+    let moved: Vec<AtomicU64> = (0..injectors).map(|_| AtomicU64::new(0)).collect();
+    let ready = Barrier::new(injectors + 1);
+    let share = (TOTAL_ELEMENTS / injectors.max(1)).max(BURST);
+
+    std::thread::scope(|scope| {
+        for (slot, moved) in moved.iter().enumerate() {
+            let (delay, loading, quit, ready, pin) = (&delay, &loading, &quit, &ready, &pin);
+            scope.spawn(move || {
+                pin(slot + 1);
+                let mut stream = Stream::new(share, pages);
+                ready.wait();
+                let mut sink = 0;
+                while !quit.load(Ordering::Relaxed) {
+                    if !loading.load(Ordering::Relaxed) {
+                        std::thread::sleep(Duration::from_millis(1));
+                        continue;
+                    }
+                    for start in (0..stream.len()).step_by(BURST) {
+                        stream.triad_range(start, (start + BURST).min(stream.len()));
+                        let bytes = (BURST as f64 * BYTES_PER_ELEMENT) as u64;
+                        moved.fetch_add(bytes, Ordering::Relaxed);
+                        for _ in 0..delay.load(Ordering::Relaxed) {
+                            std::hint::spin_loop();
+                        }
+                        if !loading.load(Ordering::Relaxed) {
+                            break;
+                        }
+                    }
+                    sink ^= stream.checksum();
+                }
+                black_box(sink);
+            });
+        }
+
+        // The chase runs on a thread of its own too, so that pinning it does
+        // not outlive the measurement on the caller's thread.
+        let chaser = scope.spawn(|| {
+            pin(0);
+            let mut chase = Chase::with_pages(DEFAULT_BYTES, SEED, pages);
+            // One full lap first, so no point pays first-touch faults.
+            black_box(chase.run(chase.nodes() as u64));
+            ready.wait();
+
+            let total = || moved.iter().map(|m| m.load(Ordering::Relaxed)).sum::<u64>();
+            let steps = std::iter::once(None).chain(delays.iter().copied().map(Some));
+            let mut points = Vec::new();
+            for step in steps {
+                if let Some(d) = step {
+                    delay.store(d, Ordering::Relaxed);
+                }
+                loading.store(step.is_some(), Ordering::Relaxed);
+                std::thread::sleep(SETTLE);
+
+                let before = total();
+                let started = Instant::now();
+                let mut hops = 0u64;
+                let mut sink = 0;
+                while started.elapsed() < target {
+                    sink ^= chase.run(HOPS_PER_CHECK);
+                    hops += HOPS_PER_CHECK;
+                }
+                let secs = started.elapsed().as_secs_f64();
+                black_box(sink);
+                points.push(LoadedPoint {
+                    delay: step,
+                    bandwidth_gib_s: (total() - before) as f64 / secs / (1u64 << 30) as f64,
+                    latency_ns: secs / hops as f64 * 1e9,
+                });
+            }
+            quit.store(true, Ordering::Relaxed);
+            points
+        });
+        chaser.join().expect("the chase thread does not panic")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_curve_starts_idle_and_steps_through_every_delay() {
+        let points = measure(1, &[1000, 0], 10, PagePolicy::Default);
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].delay, None);
+        assert_eq!(points[0].bandwidth_gib_s, 0.0, "nothing injects when idle");
+        assert_eq!(points[1].delay, Some(1000));
+        assert_eq!(points[2].delay, Some(0));
+        for p in &points {
+            assert!(p.latency_ns > 0.0 && p.latency_ns.is_finite(), "{p:?}");
+        }
+        assert!(points[2].bandwidth_gib_s > 0.0, "{:?}", points[2]);
+    }
+}
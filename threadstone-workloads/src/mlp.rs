@@ -0,0 +1,142 @@
+//! Memory-level parallelism — independent chases interleaved on one thread.
+//!
+//! The [`latency`](crate::latency) workload runs one dependent chain on
+//! purpose: every load waits for the last, so the figure is the cost of one
+//! miss with nothing to overlap it. Real code is rarely that serial. A hash
+//! join probing several keys, or a B-tree search per query in a batch, has
+//! several independent misses available, and a core can keep a number of them
+//! in flight at once — bounded by its line fill buffers, the miss-status
+//! registers each outstanding L1 miss occupies. That number, not the latency
+//! alone, sets how fast such code runs.
+//!
+//! This module measures it. One thread walks [`CHAINS`] independent chains —
+//! 1, 2, 4 and so on up to 32 — through the same 256 MiB chase, taking one hop
+//! of each in turn. Each chain is still a dependent chase, but the chains do
+//! not depend on each other, so the core may issue all their loads before the
+//! first returns. The chains start evenly spread around the chase's single
+//! cycle, so they stay that far apart and never share a node within a lap.
+//!
+//! The figure at each chain count is the effective time per access: the
+//! window's length over the loads it completed. It falls roughly as one over
+//! the chain count while the core has buffers to spare, and stops falling
+//! once they are all in use. The chain count at which it stops is the
+//! saturation point, and the one-chain latency over the fastest effective
+//! latency is how many misses the core overlaps in practice.
+//!
+//! Not scored.
+
+use threadstone_core::pages::PagePolicy;
+use threadstone_core::stats::Summary;
+use threadstone_core::sweep::MlpPoint;
+
+use crate::latency::{self, Chase, DEFAULT_BYTES, SEED};
+
+/// Chain counts measured, one at a time.
+pub const CHAINS: [usize; 6] = [1, 2, 4, 8, 16, 32];
+
+/// How close to the fastest effective latency a chain count must come to
+/// count as saturated: within 10%.
+pub const SATURATION_SLACK: f64 = 1.1;
+
+/// Measure the chase at each of `chains`, over `samples` windows per count
+/// that together last at least `min_millis`, with the buffer backed as
+/// `pages` asks.
+pub fn measure(
+    chains: &[usize],
+    min_millis: u64,
+    samples: usize,
+    pages: PagePolicy,
+    progress: &mut dyn FnMut(usize),
+) -> Vec<MlpPoint> {
+    let chase = Chase::with_pages(DEFAULT_BYTES, SEED, pages);
+    chains
+        .iter()
+        .map(|&n| {
+            progress(n);
+            let mut cursors = chase.spread(n);
+            let rounds = latency::windows(
+                &mut |rounds| chase.run_interleaved(&mut cursors, rounds),
+                min_millis,
+                samples.max(1),
+            );
+            // A round is one hop of every chain.
+            let samples: Vec<f64> = rounds.iter().map(|ns| ns / n as f64).collect();
+            let stats = Summary::new(&samples).expect("chase windows are finite");
+            MlpPoint {
+                chains: n,
+                ns_per_access: stats.median,
+                samples,
+                stats,
+            }
+        })
+        .collect()
+}
+
+/// The fewest chains whose effective latency comes within
+/// [`SATURATION_SLACK`] of the fastest measured: past it, more independent
+/// misses no longer help.
+pub fn saturation(points: &[MlpPoint]) -> Option<&MlpPoint> {
+    let fastest = points
+        .iter()
+        .map(|p| p.ns_per_access)
+        .min_by(f64::total_cmp)?;
+    points
+        .iter()
+        .find(|p| p.ns_per_access <= fastest * SATURATION_SLACK)
+}
+
+/// Misses the core overlaps at best: the one-chain latency over the fastest
+/// effective latency. `None` without a one-chain point.
+pub fn in_flight(points: &[MlpPoint]) -> Option<f64> {
+    let single = points.iter().find(|p| p.chains == 1)?;
+    let fastest = points
+        .iter()
+        .map(|p| p.ns_per_access)
+        .min_by(f64::total_cmp)?;
+    Some(single.ns_per_access / fastest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(chains: usize, ns_per_access: f64) -> MlpPoint {
+        MlpPoint {
+            chains,
+            ns_per_access,
+            samples: vec![ns_per_access],
+            stats: Summary::new(&[ns_per_access]).unwrap(),
+        }
+    }
+
+    #[test]
+    fn saturation_is_the_first_count_near_the_fastest() {
+        // Ten fill buffers: halving to 8 chains, then flat with a little noise.
+        let points: Vec<MlpPoint> = [(1, 100.0), (2, 50.0), (4, 25.5), (8, 13.0)]
+            .into_iter()
+            .chain([(16, 10.4), (32, 10.0)])
+            .map(|(n, ns)| point(n, ns))
+            .collect();
+        assert_eq!(saturation(&points).unwrap().chains, 16);
+        assert!((in_flight(&points).unwrap() - 10.0).abs() < 1e-9);
+        assert!(saturation(&[]).is_none());
+        assert!(in_flight(&points[1..]).is_none());
+    }
+
+    #[test]
+    fn independent_chains_overlap_their_misses() {
+        let mut seen = Vec::new();
+        let points = measure(&[1, 8], 20, 3, PagePolicy::Default, &mut |n| seen.push(n));
+        assert_eq!(seen, [1, 8]);
+        for p in &points {
+            assert_eq!(p.samples.len(), 3);
+            assert_eq!(p.ns_per_access, p.stats.median);
+        }
+        assert!(
+            points[1].ns_per_access < points[0].ns_per_access / 2.0,
+            "8 chains ({:.1} ns an access) should overlap far more than 1 ({:.1} ns)",
+            points[1].ns_per_access,
+            points[0].ns_per_access
+        );
+    }
+}
@@ -24,14 +24,19 @@
 //! measure peak FLOPs with no memory system involvement at all, and one beyond
 //! L2 would measure bandwidth, which [`crate::stream`] already covers. `N` is a
 //! multiple of 4 so the row blocking divides evenly with no remainder path.
+//!
+//! `n` is a parameter (`-w sgemm:n=1024`) for mapping where the multiply
+//! falls out of each cache level, and must stay a multiple of 4. Only 256 is
+//! scored.
 
 use threadstone_core::kernel::{
-    Footprint, Kernel, KernelInfo, KernelState, Scaling, SetupCtx, Unit,
+    Footprint, Kernel, KernelInfo, KernelState, Param, ParamError, Scaling, SetupCtx, Unit,
 };
 
 use crate::rng::Rng;
 
-/// Matrix dimension. Must stay a multiple of [`ROW_BLOCK`].
+/// Matrix dimension the workload is scored at. Must stay a multiple of
+/// [`ROW_BLOCK`].
 const N: usize = 256;
 
 /// Rows of `C` updated per pass over a row of `B`.
@@ -41,57 +46,82 @@ const N: usize = 256;
 /// element.
 const ROW_BLOCK: usize = 4;
 
-/// Floating-point operations in one multiply-accumulate pass: one multiply and
-/// one add per inner-loop step, over `N³` steps.
-const FLOPS_PER_MULTIPLY: f64 = 2.0 * (N as f64) * (N as f64) * (N as f64);
+/// Floating-point operations in one multiply-accumulate pass over `n × n`
+/// matrices: one multiply and one add per inner-loop step, over `n³` steps.
+fn flops_per_multiply(n: usize) -> f64 {
+    2.0 * (n as f64).powi(3)
+}
 
 /// One thread's matrices.
 struct Sgemm {
+    /// Matrix dimension.
+    n: usize,
     a: Vec<f64>,
     b: Vec<f64>,
     c: Vec<f64>,
 }
 
 impl Sgemm {
-    fn new(seed: u64) -> Sgemm {
+    fn new(n: usize, seed: u64) -> Sgemm {
         let mut rng = Rng::new(seed);
         // Values in [-1, 1): centred on zero so repeated accumulation into `C`
         // performs a random walk instead of growing monotonically into the
         // range where doubles lose precision or reach infinity.
         let mut fill = |len: usize| (0..len).map(|_| rng.next_f64() * 2.0 - 1.0).collect();
         Sgemm {
-            a: fill(N * N),
-            b: fill(N * N),
-            c: vec![0.0; N * N],
+            n,
+            a: fill(n * n),
+            b: fill(n * n),
+            c: vec![0.0; n * n],
         }
     }
 
     /// One `C += A · B`.
     fn multiply(&mut self) {
-        for i0 in (0..N).step_by(ROW_BLOCK) {
-            // Four disjoint mutable rows of C. `split_at_mut` is what lets the
-            // borrow checker see them as non-overlapping.
-            let (rows, _) = self.c[i0 * N..].split_at_mut(ROW_BLOCK * N);
-            let (c0, rest) = rows.split_at_mut(N);
-            let (c1, rest) = rest.split_at_mut(N);
-            let (c2, c3) = rest.split_at_mut(N);
-
-            for k in 0..N {
-                let a0 = self.a[i0 * N + k];
-                let a1 = self.a[(i0 + 1) * N + k];
-                let a2 = self.a[(i0 + 2) * N + k];
-                let a3 = self.a[(i0 + 3) * N + k];
-                let b_row = &self.b[k * N..k * N + N];
-
-                // Slicing to a common length lets LLVM drop the bounds checks
-                // and vectorise the body.
-                for j in 0..N {
-                    let bv = b_row[j];
-                    c0[j] += a0 * bv;
-                    c1[j] += a1 * bv;
-                    c2[j] += a2 * bv;
-                    c3[j] += a3 * bv;
-                }
+        if self.n == N {
+            self.multiply_at::<N>();
+        } else {
+            multiply_rows(self.n, &self.a, &self.b, &mut self.c);
+        }
+    }
+
+    /// [`Sgemm::multiply`] with the dimension fixed at compile time, so the
+    /// scored size gets loops with constant trip counts, as it had before `n`
+    /// became a parameter.
+    fn multiply_at<const M: usize>(&mut self) {
+        multiply_rows(M, &self.a, &self.b, &mut self.c);
+    }
+}
+
+/// `c += a · b` over `n × n` matrices, `ROW_BLOCK` rows of `c` at a time.
+///
+/// Always inlined, so that [`Sgemm::multiply_at`] sees `n` as a constant.
+#[inline(always)]
+fn multiply_rows(n: usize, a: &[f64], b: &[f64], c: &mut [f64]) {
+    for i0 in (0..n).step_by(ROW_BLOCK) {
+        // Four disjoint mutable rows of C. `split_at_mut` is what lets the
+        // borrow checker see them as non-overlapping.
+        let (rows, _) = c[i0 * n..].split_at_mut(ROW_BLOCK * n);
+        let (c0, rest) = rows.split_at_mut(n);
+        let (c1, rest) = rest.split_at_mut(n);
+        let (c2, c3) = rest.split_at_mut(n);
+        // Slicing to a common length lets LLVM drop the bounds checks and
+        // vectorise the body.
+        let (c0, c1, c2, c3) = (&mut c0[..n], &mut c1[..n], &mut c2[..n], &mut c3[..n]);
+
+        for k in 0..n {
+            let a0 = a[i0 * n + k];
+            let a1 = a[(i0 + 1) * n + k];
+            let a2 = a[(i0 + 2) * n + k];
+            let a3 = a[(i0 + 3) * n + k];
+            let b_row = &b[k * n..k * n + n];
+
+            for j in 0..n {
+                let bv = b_row[j];
+                c0[j] += a0 * bv;
+                c1[j] += a1 * bv;
+                c2[j] += a2 * bv;
+                c3[j] += a3 * bv;
             }
         }
     }
@@ -107,14 +137,23 @@ impl KernelState for Sgemm {
         // observed elements, without adding an O(N²) reduction to the window.
         let mut sum = 0u64;
         for i in 0..8 {
-            sum = sum.rotate_left(7) ^ self.c[i * (N * N / 8)].to_bits();
+            sum = sum.rotate_left(7) ^ self.c[i * (self.n * self.n / 8)].to_bits();
         }
         sum
     }
 }
 
 /// The dense matrix multiply workload.
-pub struct SgemmKernel;
+pub struct SgemmKernel {
+    /// Matrix dimension.
+    n: usize,
+}
+
+impl Default for SgemmKernel {
+    fn default() -> Self {
+        SgemmKernel { n: N }
+    }
+}
 
 impl Kernel for SgemmKernel {
     fn info(&self) -> KernelInfo {
@@ -137,25 +176,43 @@ impl Kernel for SgemmKernel {
     fn setup(&self, ctx: &SetupCtx) -> Box<dyn KernelState> {
         // Distinct data per thread, so no two threads share cache lines and the
         // measurement reflects independent compute.
-        Box::new(Sgemm::new(0x56EE_0000 ^ ctx.thread_index as u64))
+        Box::new(Sgemm::new(self.n, 0x56EE_0000 ^ ctx.thread_index as u64))
     }
 
     fn rate(&self, iters_per_thread: u64, threads: usize, secs: f64) -> f64 {
-        iters_per_thread as f64 * threads as f64 * FLOPS_PER_MULTIPLY / secs / 1e9
+        iters_per_thread as f64 * threads as f64 * flops_per_multiply(self.n) / secs / 1e9
+    }
+
+    fn params(&self) -> Vec<Param> {
+        vec![Param {
+            name: "n",
+            summary: "matrix dimension",
+            default: N as u64,
+            min: 16,
+            max: 4096,
+            multiple_of: ROW_BLOCK as u64,
+            value: self.n as u64,
+        }]
+    }
+
+    fn set_param(&mut self, _name: &str, value: u64) -> Result<(), ParamError> {
+        self.n = value as usize;
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use threadstone_core::pages::PagePolicy;
 
     /// Textbook triple loop, used only to check the blocked version.
-    fn reference_multiply(a: &[f64], b: &[f64], c: &mut [f64]) {
-        for i in 0..N {
-            for k in 0..N {
-                let a_ik = a[i * N + k];
-                for j in 0..N {
-                    c[i * N + j] += a_ik * b[k * N + j];
+    fn reference_multiply(n: usize, a: &[f64], b: &[f64], c: &mut [f64]) {
+        for i in 0..n {
+            for k in 0..n {
+                let a_ik = a[i * n + k];
+                for j in 0..n {
+                    c[i * n + j] += a_ik * b[k * n + j];
                 }
             }
         }
@@ -163,9 +220,9 @@ mod tests {
 
     #[test]
     fn blocked_multiply_matches_the_naive_one() {
-        let mut s = Sgemm::new(1);
+        let mut s = Sgemm::new(N, 1);
         let mut expected = vec![0.0; N * N];
-        reference_multiply(&s.a, &s.b, &mut expected);
+        reference_multiply(N, &s.a, &s.b, &mut expected);
         s.multiply();
 
         for (i, (got, want)) in s.c.iter().zip(&expected).enumerate() {
@@ -181,7 +238,7 @@ mod tests {
 
     #[test]
     fn multiply_produces_nontrivial_output() {
-        let mut s = Sgemm::new(2);
+        let mut s = Sgemm::new(N, 2);
         s.multiply();
         assert!(s.c.iter().all(|v| v.is_finite()), "results must be finite");
         assert!(
@@ -194,7 +251,7 @@ mod tests {
     fn repeated_multiplies_stay_finite() {
         // 200 accumulations must not drift to infinity, or long runs would
         // start measuring NaN handling instead of arithmetic.
-        let mut s = Sgemm::new(3);
+        let mut s = Sgemm::new(N, 3);
         s.run(200);
         assert!(s.c.iter().all(|v| v.is_finite()));
     }
@@ -206,26 +263,42 @@ mod tests {
 
     #[test]
     fn rate_converts_to_gflops() {
-        let k = SgemmKernel;
+        let k = SgemmKernel::default();
         // One 256³ multiply is 2·256³ = 33,554,432 FLOPs. In one second that is
         // 0.0335 GFLOP/s.
         let r = k.rate(1, 1, 1.0);
-        assert!((r - FLOPS_PER_MULTIPLY / 1e9).abs() < 1e-12);
+        assert!((r - flops_per_multiply(N) / 1e9).abs() < 1e-12);
         assert!((r - 0.033_554_432).abs() < 1e-9);
         // Each thread runs its own independent matmul, so FLOPs add up.
         assert!((k.rate(1, 8, 1.0) - 8.0 * r).abs() < 1e-9);
     }
 
+    #[test]
+    fn a_smaller_multiply_is_still_correct_and_counted_at_its_size() {
+        let mut k = SgemmKernel::default();
+        k.configure("n", 64).unwrap();
+        assert!(k.configure("n", 66).is_err(), "n must divide the row block");
+        assert!((k.rate(1, 1, 1.0) - 2.0 * 64f64.powi(3) / 1e9).abs() < 1e-15);
+
+        let mut s = Sgemm::new(64, 4);
+        let mut expected = vec![0.0; 64 * 64];
+        reference_multiply(64, &s.a, &s.b, &mut expected);
+        s.multiply();
+        assert!(s.c.iter().zip(&expected).all(|(g, w)| (g - w).abs() < 1e-9));
+    }
+
     #[test]
     fn threads_get_different_data() {
-        let k = SgemmKernel;
+        let k = SgemmKernel::default();
         let mut a = k.setup(&SetupCtx {
             threads: 2,
             thread_index: 0,
+            pages: PagePolicy::Default,
         });
         let mut b = k.setup(&SetupCtx {
             threads: 2,
             thread_index: 1,
+            pages: PagePolicy::Default,
         });
         assert_ne!(a.run(1), b.run(1), "per-thread seeds should differ");
     }
@@ -25,14 +25,15 @@
 //!
 //! The buffer is 64 KiB, comfortably inside L1 or L2 on any modern core, so the
 //! measurement is compute-bound. [`crate::stream`] covers the memory system.
+//! `kib` is a parameter (`-w sha256:kib=1024`); only 64 is scored.
 
 use threadstone_core::kernel::{
-    Footprint, Kernel, KernelInfo, KernelState, Scaling, SetupCtx, Unit,
+    Footprint, Kernel, KernelInfo, KernelState, Param, ParamError, Scaling, SetupCtx, Unit,
 };
 
 use crate::rng::Rng;
 
-/// Bytes hashed per iteration.
+/// Bytes hashed per iteration at the size the workload is scored at.
 const BUFFER_BYTES: usize = 64 << 10;
 
 /// SHA-256 round constants: the first 32 bits of the fractional parts of the
@@ -179,7 +180,18 @@ impl KernelState for Hasher {
 }
 
 /// The SHA-256 throughput workload.
-pub struct Sha256Kernel;
+pub struct Sha256Kernel {
+    /// Bytes hashed per iteration: always whole kibibytes, so whole blocks.
+    bytes: usize,
+}
+
+impl Default for Sha256Kernel {
+    fn default() -> Self {
+        Sha256Kernel {
+            bytes: BUFFER_BYTES,
+        }
+    }
+}
 
 impl Kernel for Sha256Kernel {
     fn info(&self) -> KernelInfo {
@@ -199,20 +211,38 @@ impl Kernel for Sha256Kernel {
     fn setup(&self, ctx: &SetupCtx) -> Box<dyn KernelState> {
         let mut rng = Rng::new(0x5A45_1234 ^ ctx.thread_index as u64);
         Box::new(Hasher {
-            buffer: (0..BUFFER_BYTES).map(|_| rng.next_u64() as u8).collect(),
+            buffer: (0..self.bytes).map(|_| rng.next_u64() as u8).collect(),
             state: Sha256::default(),
         })
     }
 
     fn rate(&self, iters_per_thread: u64, threads: usize, secs: f64) -> f64 {
-        let bytes = iters_per_thread as f64 * threads as f64 * BUFFER_BYTES as f64;
+        let bytes = iters_per_thread as f64 * threads as f64 * self.bytes as f64;
         bytes / secs / (1u64 << 20) as f64
     }
+
+    fn params(&self) -> Vec<Param> {
+        vec![Param {
+            name: "kib",
+            summary: "KiB hashed per iteration",
+            default: (BUFFER_BYTES >> 10) as u64,
+            min: 1,
+            max: 1 << 20,
+            multiple_of: 1,
+            value: (self.bytes >> 10) as u64,
+        }]
+    }
+
+    fn set_param(&mut self, _name: &str, value: u64) -> Result<(), ParamError> {
+        self.bytes = (value as usize) << 10;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use threadstone_core::pages::PagePolicy;
 
     fn hex(bytes: &[u8]) -> String {
         use std::fmt::Write as _;
@@ -292,10 +322,11 @@ mod tests {
 
     #[test]
     fn hashing_changes_the_state() {
-        let k = Sha256Kernel;
+        let k = Sha256Kernel::default();
         let mut state = k.setup(&SetupCtx {
             threads: 1,
             thread_index: 0,
+            pages: PagePolicy::Default,
         });
         let first = state.run(1);
         let second = state.run(1);
@@ -308,12 +339,15 @@ mod tests {
 
     #[test]
     fn rate_converts_to_mib_per_second() {
-        let k = Sha256Kernel;
+        let mut k = Sha256Kernel::default();
         // 16 iterations of 64 KiB is exactly 1 MiB.
         assert!((k.rate(16, 1, 1.0) - 1.0).abs() < 1e-12);
         // Independent per-thread buffers, so throughput adds.
         assert!((k.rate(16, 8, 1.0) - 8.0).abs() < 1e-12);
         assert!((k.rate(16, 1, 0.5) - 2.0).abs() < 1e-12);
+        // At 1 MiB a buffer, one iteration is the whole MiB.
+        k.configure("kib", 1024).unwrap();
+        assert!((k.rate(1, 1, 1.0) - 1.0).abs() < 1e-12);
     }
 
     #[test]
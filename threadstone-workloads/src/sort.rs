@@ -28,14 +28,17 @@
 //! 1 Mi elements is 8 MiB, larger than L2 on most cores and comparable to the
 //! last-level cache, so the sort's later merge passes genuinely touch memory.
 //! Small enough that a full multi-threaded run stays under 128 MiB.
+//!
+//! `elements` is a parameter (`-w sort:elements=65536`), for sorts that fit a
+//! cache or spill far past one. Only 1 Mi is scored.
 
 use threadstone_core::kernel::{
-    Footprint, Kernel, KernelInfo, KernelState, Scaling, SetupCtx, Unit,
+    Footprint, Kernel, KernelInfo, KernelState, Param, ParamError, Scaling, SetupCtx, Unit,
 };
 
 use crate::rng::Rng;
 
-/// Elements sorted per iteration.
+/// Elements sorted per iteration at the size the workload is scored at.
 const ELEMENTS: usize = 1 << 20;
 
 /// One thread's pristine input and its scratch buffer.
@@ -47,9 +50,9 @@ struct Sort {
 }
 
 impl Sort {
-    fn new(seed: u64) -> Sort {
+    fn new(elements: usize, seed: u64) -> Sort {
         let mut rng = Rng::new(seed);
-        let pristine: Vec<u64> = (0..ELEMENTS).map(|_| rng.next_u64()).collect();
+        let pristine: Vec<u64> = (0..elements).map(|_| rng.next_u64()).collect();
         Sort {
             scratch: pristine.clone(),
             pristine,
@@ -67,14 +70,23 @@ impl KernelState for Sort {
             // observable, so it cannot be eliminated.
             checksum = checksum
                 .wrapping_add(self.scratch[0])
-                .wrapping_add(self.scratch[ELEMENTS - 1]);
+                .wrapping_add(self.scratch[self.scratch.len() - 1]);
         }
         checksum
     }
 }
 
 /// The sort workload.
-pub struct SortKernel;
+pub struct SortKernel {
+    /// Elements sorted per iteration.
+    elements: usize,
+}
+
+impl Default for SortKernel {
+    fn default() -> Self {
+        SortKernel { elements: ELEMENTS }
+    }
+}
 
 impl Kernel for SortKernel {
     fn info(&self) -> KernelInfo {
@@ -93,13 +105,33 @@ impl Kernel for SortKernel {
     }
 
     fn setup(&self, ctx: &SetupCtx) -> Box<dyn KernelState> {
-        Box::new(Sort::new(0x503D_A17A ^ ctx.thread_index as u64))
+        Box::new(Sort::new(
+            self.elements,
+            0x503D_A17A ^ ctx.thread_index as u64,
+        ))
     }
 
     fn rate(&self, iters_per_thread: u64, threads: usize, secs: f64) -> f64 {
-        let elements = iters_per_thread as f64 * threads as f64 * ELEMENTS as f64;
+        let elements = iters_per_thread as f64 * threads as f64 * self.elements as f64;
         elements / secs / 1e6
     }
+
+    fn params(&self) -> Vec<Param> {
+        vec![Param {
+            name: "elements",
+            summary: "values sorted per iteration",
+            default: ELEMENTS as u64,
+            min: 1024,
+            max: 1 << 28,
+            multiple_of: 1,
+            value: self.elements as u64,
+        }]
+    }
+
+    fn set_param(&mut self, _name: &str, value: u64) -> Result<(), ParamError> {
+        self.elements = value as usize;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -108,7 +140,7 @@ mod tests {
 
     #[test]
     fn output_is_sorted() {
-        let mut s = Sort::new(1);
+        let mut s = Sort::new(ELEMENTS, 1);
         s.run(1);
         assert!(
             s.scratch.windows(2).all(|w| w[0] <= w[1]),
@@ -118,7 +150,7 @@ mod tests {
 
     #[test]
     fn sorting_preserves_the_multiset() {
-        let mut s = Sort::new(2);
+        let mut s = Sort::new(ELEMENTS, 2);
         s.run(1);
         let mut expected = s.pristine.clone();
         expected.sort_unstable();
@@ -129,7 +161,7 @@ mod tests {
     fn input_is_not_already_sorted() {
         // If the generator produced ordered data, pdqsort's pattern detection
         // would make this workload measure almost nothing.
-        let s = Sort::new(3);
+        let s = Sort::new(ELEMENTS, 3);
         let ordered = s.pristine.windows(2).filter(|w| w[0] <= w[1]).count();
         assert!(
             ordered < s.pristine.len() * 6 / 10,
@@ -144,19 +176,19 @@ mod tests {
         // it, iteration two would sort an already-sorted array and finish in a
         // fraction of the time, so the measured rate would climb with the
         // iteration count instead of describing the machine.
-        let mut s = Sort::new(4);
+        let mut s = Sort::new(ELEMENTS, 4);
         let first = s.run(1);
         let second = s.run(1);
         assert_eq!(second, first, "each iteration must do identical work");
 
         // Two iterations in one call must therefore total exactly twice one.
-        let mut fresh = Sort::new(4);
+        let mut fresh = Sort::new(ELEMENTS, 4);
         assert_eq!(fresh.run(2), first.wrapping_mul(2));
     }
 
     #[test]
     fn pristine_data_is_never_modified() {
-        let mut s = Sort::new(5);
+        let mut s = Sort::new(ELEMENTS, 5);
         let before = s.pristine.clone();
         s.run(3);
         assert_eq!(s.pristine, before);
@@ -164,18 +196,29 @@ mod tests {
 
     #[test]
     fn threads_get_different_data() {
-        let a = Sort::new(0x1);
-        let b = Sort::new(0x2);
+        let a = Sort::new(ELEMENTS, 0x1);
+        let b = Sort::new(ELEMENTS, 0x2);
         assert_ne!(a.pristine, b.pristine);
     }
 
     #[test]
     fn rate_converts_to_millions_of_elements() {
-        let k = SortKernel;
+        let k = SortKernel::default();
         // One pass over 1 Mi elements in one second.
         assert!((k.rate(1, 1, 1.0) - ELEMENTS as f64 / 1e6).abs() < 1e-9);
         assert!((k.rate(1, 1, 1.0) - 1.048_576).abs() < 1e-6);
         // Independent per-thread arrays, so throughput adds.
         assert!((k.rate(1, 4, 1.0) - 4.0 * k.rate(1, 1, 1.0)).abs() < 1e-9);
     }
+
+    #[test]
+    fn a_tuned_sort_sorts_and_counts_its_own_size() {
+        let mut k = SortKernel::default();
+        k.configure("elements", 4096).unwrap();
+        assert!((k.rate(1, 1, 1.0) - 0.004_096).abs() < 1e-12);
+        let mut s = Sort::new(4096, 6);
+        s.run(1);
+        assert_eq!(s.scratch.len(), 4096);
+        assert!(s.scratch.windows(2).all(|w| w[0] <= w[1]));
+    }
 }
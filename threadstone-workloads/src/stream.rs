@@ -4,7 +4,21 @@
 //! cache, following John McCalpin's STREAM benchmark. Triad is the most
 //! demanding of the four STREAM kernels — two reads and one write per element,
 //! with just enough arithmetic that a compiler cannot turn it into a `memcpy` —
-//! so it is the one reported here.
+//! so it is the one scored.
+//!
+//! The other three are measured beside it as sub-results (see
+//! [`Kernel::sub_results`]), because every published STREAM table quotes all
+//! four:
+//!
+//! | Kernel | Expression | Bytes per element |
+//! |---|---|---|
+//! | Copy | `c[i] = a[i]` | 16 |
+//! | Scale | `b[i] = scalar · c[i]` | 16 |
+//! | Add | `c[i] = a[i] + b[i]` | 24 |
+//! | Triad | `a[i] = b[i] + scalar · c[i]` | 24 |
+//!
+//! They share the same partitioned arrays, sizing and page policy, so the four
+//! figures differ only in the loop.
 //!
 //! # Sizing
 //!
@@ -12,24 +26,40 @@
 //! cache, otherwise the benchmark measures cache bandwidth and reports a number
 //! several times too high. Each array here is 64 MiB (192 MiB across all
 //! three), which clears that bar on every consumer CPU and most servers.
+//! Where it does not, `-w stream:mib=2048` sets the size of each array; only
+//! 64 is scored, so such a run has no score to compare.
 //!
 //! The total is fixed regardless of thread count: the arrays are *partitioned*
 //! across threads, not replicated. Replicating would grow the footprint with
 //! the thread count and change what is being measured partway up the scaling
-//! curve. Partitioning also gives correct NUMA placement for free, since each
-//! thread allocates and first-touches its own slice, so the pages land in the
-//! memory attached to the socket that will read them.
+//! curve. Partitioning also gives correct NUMA placement for free once threads
+//! are pinned, since each thread allocates and first-touches its own slice, so
+//! the pages land in the memory attached to the socket that will read them. A
+//! run with `RunConfig::memory_node` set binds them to one node instead, which
+//! is how `threadstone numa` measures remote bandwidth.
+//!
+//! The arrays are backed as `--pages` asks (see [`threadstone_core::pages`]).
+//! A streaming sweep touches each page's translation once per 4 KiB or 2 MiB,
+//! so the page size matters far less here than to the latency chase, but a
+//! prefetcher that stops at page boundaries sees fewer of them with huge pages.
 //!
 //! # Byte accounting
 //!
-//! Each element moves 24 bytes: two 8-byte reads and one 8-byte write. This
-//! follows STREAM's convention of ignoring the read-for-ownership traffic that
-//! a write to a non-resident cache line actually generates on most
+//! Each Triad element moves 24 bytes: two 8-byte reads and one 8-byte write.
+//! This follows STREAM's convention of ignoring the read-for-ownership traffic
+//! that a write to a non-resident cache line actually generates on most
 //! architectures. Real DRAM traffic is therefore up to a third higher than the
 //! reported figure. The convention is kept because every published STREAM
 //! number uses it, and a differently-accounted number would not be comparable
 //! to any of them.
 //!
+//! The same rule counts Copy and Scale as 16 bytes, one read and one write,
+//! which makes Copy against Triad a probe of write allocation: where every
+//! store pays a read for ownership, Copy really moves half as much again as it
+//! is credited with and Triad only a third, so Copy reads about 11% below
+//! Triad. A core that streams stores without reading the line first shows no
+//! such gap.
+//!
 //! # What went wrong before
 //!
 //! The previous implementation called Rayon's `par_chunks_mut` *inside* a
@@ -39,58 +69,150 @@
 //! it GB/s.
 
 use threadstone_core::kernel::{
-    Footprint, Kernel, KernelInfo, KernelState, Scaling, SetupCtx, Unit,
+    Footprint, Kernel, KernelInfo, KernelState, Param, ParamError, Scaling, SetupCtx, Unit,
 };
+use threadstone_core::pages::{PageBuf, PagePolicy};
 
 /// The multiplier in the triad expression, from the original STREAM.
 const SCALAR: f64 = 3.0;
 
-/// Elements per array, across all threads: 8 Mi × 8 bytes = 64 MiB each.
-const TOTAL_ELEMENTS: usize = 8 << 20;
+/// Elements per array, across all threads, at the size the workload is
+/// scored at: 8 Mi × 8 bytes = 64 MiB each.
+pub(crate) const TOTAL_ELEMENTS: usize = 8 << 20;
+
+/// Elements in one MiB of `f64`.
+const ELEMENTS_PER_MIB: usize = (1 << 20) / std::mem::size_of::<f64>();
 
-/// Bytes counted as moved per element, per STREAM's convention.
-const BYTES_PER_ELEMENT: f64 = 24.0;
+/// Bytes counted as moved per Triad element, per STREAM's convention.
+pub(crate) const BYTES_PER_ELEMENT: f64 = 24.0;
+
+/// One of the four STREAM kernels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// `c[i] = a[i]`.
+    Copy,
+    /// `b[i] = scalar · c[i]`.
+    Scale,
+    /// `c[i] = a[i] + b[i]`.
+    Add,
+    /// `a[i] = b[i] + scalar · c[i]`, the scored one.
+    Triad,
+}
+
+impl Op {
+    /// Bytes counted as moved per element, per STREAM's convention.
+    pub fn bytes_per_element(self) -> f64 {
+        match self {
+            Op::Copy | Op::Scale => 16.0,
+            Op::Add | Op::Triad => BYTES_PER_ELEMENT,
+        }
+    }
+}
 
 /// One thread's slice of the three arrays.
-struct Stream {
-    a: Vec<f64>,
-    b: Vec<f64>,
-    c: Vec<f64>,
+pub(crate) struct Stream {
+    a: PageBuf<f64>,
+    b: PageBuf<f64>,
+    c: PageBuf<f64>,
     /// Elements in this thread's slice.
     len: usize,
+    /// The kernel [`KernelState::run`] performs.
+    op: Op,
 }
 
 impl Stream {
-    fn new(len: usize) -> Stream {
-        // `vec![v; n]` writes every element, which first-touches every page on
-        // this thread. Without that, the first measured round would pay the
-        // page faults and read low.
+    pub(crate) fn new(len: usize, pages: PagePolicy) -> Stream {
+        Stream::for_op(Op::Triad, len, pages)
+    }
+
+    fn for_op(op: Op, len: usize, pages: PagePolicy) -> Stream {
+        // `PageBuf::filled` writes every element, which first-touches every
+        // page on this thread. Without that, the first measured round would
+        // pay the page faults and read low.
         Stream {
-            a: vec![1.0; len],
-            b: vec![2.0; len],
-            c: vec![0.5; len],
+            a: PageBuf::filled(len, 1.0, pages),
+            b: PageBuf::filled(len, 2.0, pages),
+            c: PageBuf::filled(len, 0.5, pages),
             len,
+            op,
         }
     }
 
     #[inline]
     fn triad(&mut self) {
+        self.triad_range(0, self.len);
+    }
+
+    /// One pass of this slice's kernel. Each writes an array it does not
+    /// read, so repeated passes produce the same output.
+    #[inline]
+    fn pass(&mut self) {
+        match self.op {
+            // Indexed rather than `copy_from_slice`, which becomes a call to
+            // the C library's `memcpy`, and that switches to non-temporal
+            // stores for copies this large — hiding the very write-allocate
+            // traffic Copy against Triad is there to show.
+            Op::Copy => {
+                let (a, c) = (&self.a[..], &mut self.c[..]);
+                #[allow(clippy::manual_memcpy)]
+                for i in 0..c.len() {
+                    c[i] = a[i];
+                }
+            }
+            Op::Scale => {
+                let (b, c) = (&mut self.b[..], &self.c[..]);
+                for i in 0..b.len() {
+                    b[i] = SCALAR * c[i];
+                }
+            }
+            Op::Add => {
+                let (a, b, c) = (&self.a[..], &self.b[..], &mut self.c[..]);
+                for i in 0..c.len() {
+                    c[i] = a[i] + b[i];
+                }
+            }
+            Op::Triad => self.triad(),
+        }
+    }
+
+    /// The array this slice's kernel writes.
+    fn output(&self) -> &[f64] {
+        match self.op {
+            Op::Copy | Op::Add => &self.c,
+            Op::Scale => &self.b,
+            Op::Triad => &self.a,
+        }
+    }
+
+    /// The triad over elements `start..end` only.
+    #[inline]
+    pub(crate) fn triad_range(&mut self, start: usize, end: usize) {
         // Equal-length slices let LLVM elide bounds checks and vectorise.
-        let a = &mut self.a[..self.len];
-        let b = &self.b[..self.len];
-        let c = &self.c[..self.len];
-        for i in 0..self.len {
+        let a = &mut self.a[start..end];
+        let b = &self.b[start..end];
+        let c = &self.c[start..end];
+        for i in 0..a.len() {
             a[i] = b[i] + SCALAR * c[i];
         }
     }
+
+    /// Elements in this slice.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// A checksum over the output array.
+    pub(crate) fn checksum(&self) -> u64 {
+        checksum(&self.a)
+    }
 }
 
 impl KernelState for Stream {
     fn run(&mut self, iters: u64) -> u64 {
         for _ in 0..iters {
-            self.triad();
+            self.pass();
         }
-        checksum(&self.a)
+        checksum(self.output())
     }
 }
 
@@ -112,8 +234,32 @@ fn checksum(values: &[f64]) -> u64 {
     sum
 }
 
+/// The size of each array, as the `mib` parameter.
+fn mib_param(elements: usize) -> Param {
+    Param {
+        name: "mib",
+        summary: "MiB in each of the three arrays, across all threads",
+        default: (TOTAL_ELEMENTS / ELEMENTS_PER_MIB) as u64,
+        min: 1,
+        max: 64 << 10,
+        multiple_of: 1,
+        value: (elements / ELEMENTS_PER_MIB) as u64,
+    }
+}
+
 /// The STREAM Triad workload.
-pub struct StreamKernel;
+pub struct StreamKernel {
+    /// Elements per array, across all threads.
+    elements: usize,
+}
+
+impl Default for StreamKernel {
+    fn default() -> Self {
+        StreamKernel {
+            elements: TOTAL_ELEMENTS,
+        }
+    }
+}
 
 impl Kernel for StreamKernel {
     fn info(&self) -> KernelInfo {
@@ -132,25 +278,101 @@ impl Kernel for StreamKernel {
     }
 
     fn setup(&self, ctx: &SetupCtx) -> Box<dyn KernelState> {
-        Box::new(Stream::new(ctx.share(TOTAL_ELEMENTS)))
+        Box::new(Stream::new(ctx.share(self.elements), ctx.pages))
+    }
+
+    fn rate(&self, iters_per_thread: u64, _threads: usize, secs: f64) -> f64 {
+        gib_per_sec(Op::Triad, self.elements, iters_per_thread, secs)
+    }
+
+    fn sub_results(&self) -> Vec<Box<dyn Kernel>> {
+        [Op::Copy, Op::Scale, Op::Add]
+            .into_iter()
+            .map(|op| {
+                Box::new(StreamOpKernel {
+                    op,
+                    elements: self.elements,
+                }) as Box<dyn Kernel>
+            })
+            .collect()
+    }
+
+    fn params(&self) -> Vec<Param> {
+        vec![mib_param(self.elements)]
+    }
+
+    fn set_param(&mut self, _name: &str, value: u64) -> Result<(), ParamError> {
+        self.elements = value as usize * ELEMENTS_PER_MIB;
+        Ok(())
+    }
+}
+
+/// One of the unscored STREAM kernels, measured as a sub-result of `stream`.
+pub struct StreamOpKernel {
+    /// The kernel measured.
+    pub op: Op,
+    /// Elements per array, across all threads, as its workload's.
+    elements: usize,
+}
+
+impl Kernel for StreamOpKernel {
+    fn info(&self) -> KernelInfo {
+        let (id, name, summary) = match self.op {
+            Op::Copy => (
+                "stream-copy",
+                "STREAM Copy",
+                "Memory bandwidth of a plain copy, one read and one write per element",
+            ),
+            Op::Scale => (
+                "stream-scale",
+                "STREAM Scale",
+                "Memory bandwidth of a copy with one multiply per element",
+            ),
+            Op::Add => (
+                "stream-add",
+                "STREAM Add",
+                "Memory bandwidth of two reads and one write per element, no multiply",
+            ),
+            Op::Triad => return StreamKernel::default().info(),
+        };
+        KernelInfo {
+            id,
+            name,
+            summary,
+            // Never scored: Triad's reference stands in so the entry is whole.
+            ..StreamKernel::default().info()
+        }
+    }
+
+    fn setup(&self, ctx: &SetupCtx) -> Box<dyn KernelState> {
+        Box::new(Stream::for_op(self.op, ctx.share(self.elements), ctx.pages))
     }
 
     fn rate(&self, iters_per_thread: u64, _threads: usize, secs: f64) -> f64 {
-        gib_per_sec(iters_per_thread, secs)
+        gib_per_sec(self.op, self.elements, iters_per_thread, secs)
+    }
+
+    fn params(&self) -> Vec<Param> {
+        vec![mib_param(self.elements)]
+    }
+
+    fn set_param(&mut self, _name: &str, value: u64) -> Result<(), ParamError> {
+        self.elements = value as usize * ELEMENTS_PER_MIB;
+        Ok(())
     }
 }
 
-/// Bandwidth in GiB/s for `passes` sweeps over the whole array set.
+/// Bandwidth in GiB/s for `passes` sweeps of `op` over arrays of `elements`.
 ///
 /// The thread count does not appear, and that is the point of declaring this
 /// kernel [`Footprint::Partitioned`]. In one round each of `T` threads makes
-/// `iters_per_thread` passes over its own `TOTAL_ELEMENTS / T` slice, so the
-/// round touches `iters_per_thread × TOTAL_ELEMENTS` elements no matter what
-/// `T` is. Multiplying by the thread count — which is right for a per-thread
-/// kernel like SGEMM — would overstate bandwidth by exactly `T`×.
-fn gib_per_sec(passes: u64, secs: f64) -> f64 {
-    let elements = passes as f64 * TOTAL_ELEMENTS as f64;
-    elements * BYTES_PER_ELEMENT / secs / (1u64 << 30) as f64
+/// `iters_per_thread` passes over its own `elements / T` slice, so the round
+/// touches `iters_per_thread × elements` elements no matter what `T` is.
+/// Multiplying by the thread count — which is right for a per-thread kernel
+/// like SGEMM — would overstate bandwidth by exactly `T`×.
+fn gib_per_sec(op: Op, elements: usize, passes: u64, secs: f64) -> f64 {
+    let elements = passes as f64 * elements as f64;
+    elements * op.bytes_per_element() / secs / (1u64 << 30) as f64
 }
 
 #[cfg(test)]
@@ -159,7 +381,7 @@ mod tests {
 
     #[test]
     fn triad_computes_the_stream_expression() {
-        let mut s = Stream::new(1024);
+        let mut s = Stream::new(1024, PagePolicy::Default);
         s.triad();
         // b = 2.0, c = 0.5, scalar = 3.0, so a should be 2 + 1.5 = 3.5.
         assert!(s.a.iter().all(|v| (v - 3.5).abs() < 1e-12));
@@ -167,22 +389,38 @@ mod tests {
 
     #[test]
     fn triad_is_idempotent() {
-        let mut s = Stream::new(256);
+        let mut s = Stream::new(256, PagePolicy::Default);
         s.triad();
-        let first = s.a.clone();
+        let first = s.a.to_vec();
         s.triad();
-        assert_eq!(first, s.a, "repeated passes must produce the same output");
+        assert_eq!(
+            first,
+            &s.a[..],
+            "repeated passes must produce the same output"
+        );
+    }
+
+    #[test]
+    fn arrays_on_huge_pages_compute_what_default_pages_do() {
+        // Two huge pages per array, so a THP mapping has whole pages to give.
+        let len = 2 * threadstone_core::pages::HUGE_PAGE / 8;
+        let mut thp = Stream::new(len, PagePolicy::Thp);
+        let mut default = Stream::new(len, PagePolicy::Default);
+        thp.triad();
+        default.triad();
+        assert_eq!(&thp.a[..], &default.a[..]);
     }
 
     #[test]
     fn slices_partition_the_total_exactly() {
-        let k = StreamKernel;
+        let k = StreamKernel::default();
         for threads in [1usize, 2, 3, 7, 14, 64] {
             let total: usize = (0..threads)
                 .map(|thread_index| {
                     SetupCtx {
                         threads,
                         thread_index,
+                        pages: PagePolicy::Default,
                     }
                     .share(TOTAL_ELEMENTS)
                 })
@@ -200,20 +438,30 @@ mod tests {
     fn bandwidth_arithmetic_is_correct() {
         // One pass over the whole 8 Mi-element array in one second.
         let expected = TOTAL_ELEMENTS as f64 * 24.0 / (1u64 << 30) as f64;
-        assert!((gib_per_sec(1, 1.0) - expected).abs() < 1e-9);
+        assert!((gib_per_sec(Op::Triad, TOTAL_ELEMENTS, 1, 1.0) - expected).abs() < 1e-9);
         // Exactly 192 MiB of traffic, so 0.1875 GiB.
-        assert!((gib_per_sec(1, 1.0) - 0.1875).abs() < 1e-12);
+        assert!((gib_per_sec(Op::Triad, TOTAL_ELEMENTS, 1, 1.0) - 0.1875).abs() < 1e-12);
         // Twice the passes in the same time is twice the bandwidth.
-        assert!((gib_per_sec(2, 1.0) - 2.0 * gib_per_sec(1, 1.0)).abs() < 1e-12);
+        assert!(
+            (gib_per_sec(Op::Triad, TOTAL_ELEMENTS, 2, 1.0)
+                - 2.0 * gib_per_sec(Op::Triad, TOTAL_ELEMENTS, 1, 1.0))
+            .abs()
+                < 1e-12
+        );
         // Half the time is twice the bandwidth.
-        assert!((gib_per_sec(1, 0.5) - 2.0 * gib_per_sec(1, 1.0)).abs() < 1e-12);
+        assert!(
+            (gib_per_sec(Op::Triad, TOTAL_ELEMENTS, 1, 0.5)
+                - 2.0 * gib_per_sec(Op::Triad, TOTAL_ELEMENTS, 1, 1.0))
+            .abs()
+                < 1e-12
+        );
     }
 
     #[test]
     fn bandwidth_does_not_scale_with_the_thread_count() {
         // The regression this guards: multiplying per-thread passes by the
         // thread count for a partitioned kernel reports T× the real bandwidth.
-        let k = StreamKernel;
+        let k = StreamKernel::default();
         let one = k.rate(4, 1, 1.0);
         for threads in [2usize, 8, 14, 64] {
             assert!(
@@ -227,13 +475,13 @@ mod tests {
     fn checksum_is_nonzero_for_a_uniform_result() {
         // A triad output is uniform, so a first-xor-last checksum would be
         // zero and look exactly like a loop that was optimised away.
-        let mut s = Stream::new(64);
+        let mut s = Stream::new(64, PagePolicy::Default);
         assert_ne!(s.run(1), 0);
     }
 
     #[test]
     fn checksum_tracks_the_data() {
-        let mut s = Stream::new(64);
+        let mut s = Stream::new(64, PagePolicy::Default);
         let before = s.run(1);
         s.b[32] = 100.0;
         s.triad();
@@ -244,6 +492,51 @@ mod tests {
         );
     }
 
+    #[test]
+    fn every_op_computes_its_stream_expression() {
+        // a = 1.0, b = 2.0, c = 0.5, scalar = 3.0, each pass run twice.
+        for (op, expected) in [(Op::Copy, 1.0), (Op::Scale, 1.5), (Op::Add, 3.0)] {
+            let mut s = Stream::for_op(op, 256, PagePolicy::Default);
+            let first = s.run(1);
+            assert_eq!(s.run(1), first, "{op:?} must be idempotent");
+            assert!(s.output().iter().all(|v| *v == expected), "{op:?}");
+        }
+    }
+
+    #[test]
+    fn copy_and_scale_count_two_thirds_of_the_triad_bytes() {
+        let triad = StreamKernel::default().rate(3, 1, 1.0);
+        let subs = StreamKernel::default().sub_results();
+        let ids: Vec<&str> = subs.iter().map(|k| k.info().id).collect();
+        assert_eq!(ids, ["stream-copy", "stream-scale", "stream-add"]);
+        for k in &subs {
+            let info = k.info();
+            let expected = if info.id == "stream-add" {
+                1.0
+            } else {
+                2.0 / 3.0
+            };
+            assert!(
+                (k.rate(3, 8, 1.0) / triad - expected).abs() < 1e-12,
+                "{}",
+                info.id
+            );
+            assert_eq!(info.footprint, Footprint::Partitioned);
+            assert!(k.sub_results().is_empty());
+        }
+    }
+
+    #[test]
+    fn sub_results_measure_the_arrays_their_workload_was_given() {
+        let mut k = StreamKernel::default();
+        k.configure("mib", 2048).unwrap();
+        assert!((k.rate(1, 4, 1.0) - 2.0 * 3.0).abs() < 1e-12, "3 × 2 GiB");
+        for sub in k.sub_results() {
+            assert_eq!(sub.params(), k.params(), "{}", sub.info().id);
+            assert!(!sub.is_canonical());
+        }
+    }
+
     #[test]
     fn checksum_handles_tiny_slices() {
         assert_ne!(checksum(&[1.0]), 0);
@@ -252,11 +545,12 @@ mod tests {
 
     #[test]
     fn setup_never_produces_an_empty_slice() {
-        let k = StreamKernel;
+        let k = StreamKernel::default();
         // Absurd over-subscription must still yield a usable buffer per thread.
         let mut state = k.setup(&SetupCtx {
             threads: TOTAL_ELEMENTS * 2,
             thread_index: TOTAL_ELEMENTS * 2 - 1,
+            pages: PagePolicy::Default,
         });
         state.run(1);
     }
@@ -0,0 +1,164 @@
+//! TLB reach — the latency chase with one node per page.
+//!
+//! The latency sweep puts a node on every cache line, so a working set
+//! outgrows the TLBs at the same time as it outgrows the caches and the two
+//! costs arrive mixed into one step. This probe spaces the nodes a page apart
+//! instead, using the same single-cycle permutation as
+//! [`latency`](crate::latency), so that every hop lands on a different page and
+//! the chase touches one line of each. Walked from a handful of pages to tens
+//! of thousands, its latency steps up where the first-level data TLB runs out,
+//! again where the second-level TLB does, and what is left is the page walk.
+//!
+//! # Keeping the caches out of it
+//!
+//! One line per page is still a line, and ten thousand of them no longer fit
+//! an L1d. So each point is measured twice: once a page apart, and once as a
+//! baseline with the same number of nodes packed a line apart, on as few
+//! pages as huge pages allow. Both chases hit the same data caches in the
+//! same proportion, and only the first needs a translation per hop; the
+//! difference between them is what translation costs, and
+//! [`threadstone_core::hierarchy::tlb_reach`] reads the levels off that.
+//!
+//! # Page sizes
+//!
+//! The 4 KiB probe is backed with small pages only, so transparent huge pages
+//! cannot quietly make it a 2 MiB probe. The 2 MiB probe asks for transparent
+//! huge pages and is given up, rather than reported, if any of its buffers is
+//! not granted them. Each baseline asks for huge pages, so that it needs as
+//! few translations as possible. Linux only: elsewhere neither backing can be
+//! chosen.
+
+use threadstone_core::pages::{self, PagePolicy, HUGE_PAGE};
+use threadstone_core::stats::Summary;
+use threadstone_core::sweep::TlbPoint;
+
+use crate::latency::{self, Chase, LINE_BYTES, SEED};
+
+/// Size of a small page, as the 4 KiB probe strides.
+pub const SMALL_PAGE: usize = 4096;
+
+/// Fewest pages a probe starts from.
+const FIRST_PAGES: usize = 4;
+
+/// Page sizes to probe: 4 KiB, and 2 MiB.
+pub const PAGE_SIZES: [usize; 2] = [SMALL_PAGE, HUGE_PAGE];
+
+/// Page counts from four up to `max_bytes` of address space, no two
+/// neighbours more than `step` apart, cutting each octave as
+/// [`latency::sweep_sizes`] does and refusing a `step` below
+/// [`latency::MIN_STEP`] as it does.
+pub fn page_counts(page_bytes: usize, step: f64, max_bytes: usize) -> Vec<usize> {
+    let floor = latency::MIN_STEP;
+    assert!(step >= floor, "a sweep step must be at least {floor}");
+    let parts = (1.0 / (step - 1.0)).ceil().max(1.0) as usize;
+    let most = max_bytes / page_bytes;
+    let mut counts: Vec<usize> = (0..)
+        .map(|shift| FIRST_PAGES << shift)
+        .take_while(|&base| base <= most)
+        .flat_map(|base| (0..parts).map(move |i| base + base * i / parts))
+        .filter(|&pages| pages <= most)
+        .collect();
+    counts.dedup();
+    counts
+}
+
+/// Measure the chase a page apart at each of `counts`, with its baseline.
+///
+/// `None` when the pages asked for could not be had: a 2 MiB probe whose
+/// buffers were not backed by huge pages would be measuring small ones.
+pub fn probe(
+    page_bytes: usize,
+    counts: &[usize],
+    min_millis: u64,
+    samples: usize,
+    progress: &mut dyn FnMut(usize),
+) -> Option<Vec<TlbPoint>> {
+    let policy = if page_bytes == SMALL_PAGE {
+        PagePolicy::Small
+    } else {
+        PagePolicy::Thp
+    };
+    let samples = samples.max(1);
+    let mut points = Vec::with_capacity(counts.len());
+    for &pages in counts {
+        progress(pages);
+        let before = pages::huge_pages();
+        let mut strided = Chase::spaced(pages, page_bytes, SEED, policy);
+        if policy == PagePolicy::Thp && !granted(before, pages * page_bytes) {
+            return None;
+        }
+        let latencies = latency::time(&mut strided, min_millis, samples);
+        drop(strided);
+
+        let mut packed = Chase::spaced(pages, LINE_BYTES, SEED, PagePolicy::Thp);
+        let baseline = latency::time(&mut packed, min_millis, samples);
+
+        let stats = Summary::new(&latencies).expect("chase windows are finite");
+        points.push(TlbPoint {
+            pages,
+            latency_ns: stats.median,
+            samples: latencies,
+            stats,
+            baseline_ns: threadstone_core::stats::median(&baseline)
+                .expect("chase windows are finite"),
+        });
+    }
+    Some(points)
+}
+
+/// Whether the huge-page footprint grew by at least `bytes` since `before`.
+fn granted(before: Option<pages::HugePages>, bytes: usize) -> bool {
+    match (before, pages::huge_pages()) {
+        (Some(before), Some(after)) => {
+            (after.total_kib().saturating_sub(before.total_kib()) << 10) >= bytes as u64
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_counts_start_at_four_and_stop_at_the_span() {
+        let small = page_counts(SMALL_PAGE, 2.0, 256 << 20);
+        assert_eq!(small.first(), Some(&4));
+        assert_eq!(small.last(), Some(&65536));
+        assert!(small.windows(2).all(|w| w[1] == 2 * w[0]));
+
+        assert_eq!(
+            page_counts(HUGE_PAGE, 2.0, 256 << 20),
+            [4, 8, 16, 32, 64, 128]
+        );
+        let quarters = page_counts(SMALL_PAGE, 1.25, 1 << 20);
+        assert_eq!(&quarters[..5], &[4, 5, 6, 7, 8]);
+        assert!(quarters.contains(&96) && quarters.ends_with(&[224, 256]));
+        assert!(page_counts(HUGE_PAGE, 2.0, 4 << 20).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "at least")]
+    fn a_step_finer_than_the_floor_is_refused() {
+        page_counts(SMALL_PAGE, 1.000_001, 1 << 20);
+    }
+
+    #[test]
+    fn a_small_page_probe_measures_every_count_against_its_baseline() {
+        let mut seen = Vec::new();
+        let points = probe(SMALL_PAGE, &[4, 4096], 10, 3, &mut |p| seen.push(p))
+            .expect("small pages are always available");
+        assert_eq!(seen, [4, 4096]);
+        for p in &points {
+            assert_eq!(p.samples.len(), 3);
+            assert_eq!(p.latency_ns, p.stats.median);
+            assert!(p.latency_ns > 0.0 && p.baseline_ns > 0.0);
+        }
+        assert!(
+            points[1].translation_ns() > points[0].translation_ns(),
+            "4096 pages ({:.1} ns over baseline) should cost more to translate than 4 ({:.1} ns)",
+            points[1].translation_ns(),
+            points[0].translation_ns()
+        );
+    }
+}